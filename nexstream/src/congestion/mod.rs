@@ -0,0 +1,35 @@
+//! Pluggable congestion-control algorithms.
+
+pub mod cubic;
+
+/// A congestion-control algorithm, driven by packet-send/ack/loss events
+/// and queried for the current window.
+pub trait CongestionController: Send {
+    /// Record that a packet carrying `bytes` was sent.
+    fn on_packet_sent(&mut self, bytes: usize);
+
+    /// Record that `bytes` were acknowledged.
+    fn on_ack(&mut self, bytes: usize);
+
+    /// Record a loss of `bytes`: most algorithms multiplicatively shrink
+    /// the window in response.
+    fn on_loss(&mut self, bytes: usize);
+
+    /// Current congestion window, in bytes.
+    fn window(&self) -> usize;
+
+    /// Current bytes in flight (sent but not yet acknowledged or declared
+    /// lost).
+    fn bytes_in_flight(&self) -> usize;
+
+    /// Record an ECN congestion-experienced (CE) signal (see
+    /// [`crate::connection::Connection::on_ecn_counts_received`]): react as
+    /// to a loss event -- most algorithms shrink the window the same way --
+    /// but without touching bytes in flight, since no packet was actually
+    /// lost or retransmitted. The default forwards to [`CongestionController::on_loss`]
+    /// with zero bytes, which is exactly that for every implementation in
+    /// this crate.
+    fn on_ecn_congestion_event(&mut self) {
+        self.on_loss(0);
+    }
+}
@@ -1,9 +1,10 @@
 //! Connection state machine.
 //!
 //! Manages the lifecycle of a NexStream connection:
-//! Idle -> Connecting -> Open -> Closing -> Closed.
+//! Idle -> Connecting -> Open -> Closing -> Draining -> Closed.
 
 use std::fmt;
+use std::time::{Duration, Instant};
 
 use bytes::Bytes;
 
@@ -13,6 +14,7 @@ use crate::error::{NexStreamError, Result};
 use crate::flow_control::FlowController;
 use crate::loss_detection::LossDetector;
 use crate::mux::{Multiplexer, StreamId};
+use crate::qlog::{self, Event, EventKind, EventLog};
 use crate::rtt::RttEstimator;
 use crate::transport::TransportMode;
 
@@ -25,8 +27,17 @@ pub enum ConnectionState {
     Connecting,
     /// Connection is established and ready for streams.
     Open,
-    /// Connection is shutting down.
+    /// Connection is shutting down; new streams are rejected but the
+    /// transition to `Draining` happens immediately, so this state is not
+    /// normally observed outside of `close`/`close_with_error` itself.
     Closing,
+    /// Connection has sent (or decided on) a close reason and is waiting
+    /// out [`Connection::drain_timeout`] so any in-flight acknowledgements
+    /// or retransmissions of the close can still be absorbed, mirroring
+    /// QUIC's draining state (RFC 9000 section 10.2). New streams are
+    /// rejected; call [`Connection::poll_drain`] to advance to `Closed`
+    /// once the timeout has elapsed.
+    Draining,
     /// Connection is fully closed.
     Closed,
 }
@@ -38,11 +49,80 @@ impl fmt::Display for ConnectionState {
             ConnectionState::Connecting => write!(f, "Connecting"),
             ConnectionState::Open => write!(f, "Open"),
             ConnectionState::Closing => write!(f, "Closing"),
+            ConnectionState::Draining => write!(f, "Draining"),
             ConnectionState::Closed => write!(f, "Closed"),
         }
     }
 }
 
+/// Which error-code namespace a [`CloseReason`] belongs to, mirroring
+/// QUIC's split between transport-level and application-level
+/// CONNECTION_CLOSE frames (RFC 9000 section 19.19).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CloseErrorNamespace {
+    /// The error originated in the transport itself (framing, flow control,
+    /// protocol violations).
+    Transport,
+    /// The error was raised by the application running over the
+    /// connection.
+    Application,
+}
+
+/// A structured connection-close reason, recorded by
+/// [`Connection::close_with_error`] and surfaced via
+/// [`Connection::close_reason`] (and to `recv` callers, once the peer's
+/// close has been observed).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CloseReason {
+    /// Which namespace `code` is drawn from.
+    pub namespace: CloseErrorNamespace,
+    /// The numeric error code, scoped to `namespace`.
+    pub code: u32,
+    /// A human-readable reason string.
+    pub reason: String,
+}
+
+/// Multiple of the smoothed RTT a connection in `Draining` waits before
+/// [`Connection::poll_drain`] may move it to `Closed`, mirroring QUIC's
+/// recommended drain period of three times the current PTO (RFC 9000
+/// section 10.2).
+const DRAIN_RTT_MULTIPLIER: u32 = 3;
+
+/// Floor on the drain timeout for a connection with little or no RTT
+/// history yet.
+const MIN_DRAIN_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// The IP-ECN codepoint to mark an outgoing packet with (RFC 3168 section
+/// 5). A caller building the IP header for a packet reads
+/// [`Connection::ecn_codepoint`] to decide which of these to set, the same
+/// way it reads [`Connection::congestion`] to size the packet it's about to
+/// send.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EcnCodepoint {
+    /// Not ECN-Capable Transport.
+    NotEct,
+    /// ECN-Capable Transport, codepoint 0.
+    Ect0,
+    /// ECN-Capable Transport, codepoint 1.
+    Ect1,
+    /// Congestion Experienced.
+    Ce,
+}
+
+/// Per-connection counts of acknowledgements carrying each ECN codepoint,
+/// as echoed back by the peer -- mirrors neqo's `EcnCount`. Only ECT(0),
+/// ECT(1), and CE are tracked; `NotEct` acknowledgements aren't ECN
+/// feedback and so aren't counted here.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EcnCounts {
+    /// Acknowledgements the peer reported as ECT(0).
+    pub ect0: u64,
+    /// Acknowledgements the peer reported as ECT(1).
+    pub ect1: u64,
+    /// Acknowledgements the peer reported as CE (congestion experienced).
+    pub ce: u64,
+}
+
 /// Configuration for a connection.
 #[derive(Debug, Clone)]
 pub struct ConnectionConfig {
@@ -66,6 +146,9 @@ impl Default for ConnectionConfig {
 
 /// A NexStream connection.
 pub struct Connection {
+    /// Identifier assigned at construction time, used to attribute qlog
+    /// events to this connection (see [`crate::qlog`]).
+    conn_id: u64,
     /// Current connection state.
     state: ConnectionState,
     /// Stream multiplexer.
@@ -78,12 +161,30 @@ pub struct Connection {
     loss_detector: LossDetector,
     /// Flow controller.
     flow_control: FlowController,
+    /// Optional structured event sink; see [`Connection::set_event_log`].
+    event_log: Option<Box<dyn EventLog>>,
+    /// The close reason recorded by [`Connection::close_with_error`], if
+    /// any. `None` after a graceful [`Connection::close`].
+    close_reason: Option<CloseReason>,
+    /// When this connection entered `Draining`, used by
+    /// [`Connection::poll_drain`] to tell whether [`Connection::drain_timeout`]
+    /// has elapsed.
+    draining_since: Option<Instant>,
+    /// Whether this connection is still attempting ECN. Starts `true`
+    /// (optimistic, like QUIC's own ECN validation) and latches to `false`
+    /// the first time [`Connection::on_ecn_counts_received`] observes
+    /// non-monotonic counts, as a black-holing defense.
+    ecn_enabled: bool,
+    /// The last validated ECN counts echoed by the peer; see
+    /// [`Connection::on_ecn_counts_received`].
+    ecn_counts: EcnCounts,
 }
 
 impl Connection {
     /// Create a new connection from the given config.
     pub fn new(config: ConnectionConfig) -> Self {
         Self {
+            conn_id: qlog::next_connection_id(),
             state: ConnectionState::Idle,
             mux: Multiplexer::new(config.max_streams),
             congestion: Box::new(Cubic::new()),
@@ -93,14 +194,70 @@ impl Connection {
                 config.connection_window,
                 config.stream_window,
             ),
+            event_log: None,
+            close_reason: None,
+            draining_since: None,
+            ecn_enabled: true,
+            ecn_counts: EcnCounts::default(),
+        }
+    }
+
+    /// Install a structured event sink (see [`crate::qlog`]). Pass `None` to
+    /// stop logging.
+    pub fn set_event_log(&mut self, event_log: Option<Box<dyn EventLog>>) {
+        self.event_log = event_log;
+    }
+
+    /// Report a congestion-controller decision made by the caller (e.g.
+    /// after driving [`Connection::congestion_mut`]) through this
+    /// connection's event log, if one is installed.
+    pub fn log_congestion_update(&mut self, cwnd: usize, bytes_in_flight: usize) {
+        self.emit(
+            None,
+            EventKind::CongestionUpdate {
+                cwnd,
+                bytes_in_flight,
+            },
+        );
+    }
+
+    /// Report an RTT sample made by the caller (e.g. after driving
+    /// [`Connection::rtt_mut`]) through this connection's event log, if one
+    /// is installed.
+    pub fn log_rtt_sample(&mut self, latest_micros: u64, smoothed_micros: u64) {
+        self.emit(
+            None,
+            EventKind::RttSample {
+                latest_micros,
+                smoothed_micros,
+            },
+        );
+    }
+
+    /// Report a loss-detection event made by the caller (e.g. after driving
+    /// [`Connection::loss_detector_mut`]) through this connection's event
+    /// log, if one is installed.
+    pub fn log_packet_lost(&mut self, seq: u64) {
+        self.emit(None, EventKind::PacketLost { seq });
+    }
+
+    fn emit(&mut self, stream_id: Option<StreamId>, kind: EventKind) {
+        if let Some(event_log) = self.event_log.as_mut() {
+            event_log.log(Event::new(self.conn_id, stream_id, kind));
         }
     }
 
+    fn transition(&mut self, to: ConnectionState) {
+        let from = self.state;
+        self.state = to;
+        self.emit(None, EventKind::StateTransition { from, to });
+    }
+
     /// Initiate a connection (client side).
     pub fn connect(&mut self) -> Result<()> {
         match self.state {
             ConnectionState::Idle => {
-                self.state = ConnectionState::Connecting;
+                self.transition(ConnectionState::Connecting);
                 Ok(())
             }
             _ => Err(NexStreamError::InvalidStateTransition {
@@ -114,7 +271,7 @@ impl Connection {
     pub fn accept(&mut self) -> Result<()> {
         match self.state {
             ConnectionState::Idle | ConnectionState::Connecting => {
-                self.state = ConnectionState::Open;
+                self.transition(ConnectionState::Open);
                 Ok(())
             }
             _ => Err(NexStreamError::InvalidStateTransition {
@@ -131,6 +288,12 @@ impl Connection {
         }
         let sid = self.mux.create_stream(mode)?;
         self.flow_control.add_stream(sid);
+        self.emit(
+            Some(sid),
+            EventKind::StreamOpened {
+                mode: format!("{mode:?}"),
+            },
+        );
         Ok(sid)
     }
 
@@ -143,23 +306,73 @@ impl Connection {
     }
 
     /// Receive data from a stream.
+    ///
+    /// Once the connection has a recorded [`CloseReason`] (whether from our
+    /// own [`Connection::close_with_error`] or a peer's close observed via
+    /// [`Connection::note_peer_close`]), this surfaces that reason instead
+    /// of a bare `ConnectionClosed`, so callers can tell a graceful shutdown
+    /// apart from a protocol error.
     pub fn recv(&mut self, stream_id: StreamId) -> Result<Option<Bytes>> {
         if self.state != ConnectionState::Open {
+            if let Some(reason) = &self.close_reason {
+                return Err(NexStreamError::ConnectionClosedWithReason {
+                    code: reason.code,
+                    reason: reason.reason.clone(),
+                });
+            }
             return Err(NexStreamError::ConnectionClosed);
         }
         self.mux.recv(stream_id)
     }
 
-    /// Close the connection gracefully.
+    /// Close the connection gracefully, with no error code recorded (see
+    /// [`Connection::close_with_error`] for a protocol-error close). Moves
+    /// to `Draining`; call [`Connection::poll_drain`] to finish the
+    /// transition to `Closed` once [`Connection::drain_timeout`] elapses.
     pub fn close(&mut self) -> Result<()> {
         match self.state {
             ConnectionState::Open => {
-                self.state = ConnectionState::Closing;
-                // In a real implementation we would send CONN_CLOSE and wait.
-                self.state = ConnectionState::Closed;
+                self.draining_since = Some(Instant::now());
+                self.transition(ConnectionState::Closing);
+                self.transition(ConnectionState::Draining);
+                Ok(())
+            }
+            ConnectionState::Closing | ConnectionState::Draining | ConnectionState::Closed => {
+                Ok(())
+            }
+            _ => Err(NexStreamError::InvalidStateTransition {
+                from: self.state.to_string(),
+                to: "Closing".into(),
+            }),
+        }
+    }
+
+    /// Close the connection with a structured error, following QUIC's
+    /// CONNECTION_CLOSE model: `namespace`/`code` identify what went wrong
+    /// and `reason` is a human-readable explanation, both recorded and
+    /// retrievable via [`Connection::close_reason`]. Like [`Connection::close`],
+    /// moves to `Draining` rather than `Closed` directly.
+    pub fn close_with_error(
+        &mut self,
+        namespace: CloseErrorNamespace,
+        code: u32,
+        reason: impl Into<String>,
+    ) -> Result<()> {
+        match self.state {
+            ConnectionState::Open => {
+                self.close_reason = Some(CloseReason {
+                    namespace,
+                    code,
+                    reason: reason.into(),
+                });
+                self.draining_since = Some(Instant::now());
+                self.transition(ConnectionState::Closing);
+                self.transition(ConnectionState::Draining);
+                Ok(())
+            }
+            ConnectionState::Closing | ConnectionState::Draining | ConnectionState::Closed => {
                 Ok(())
             }
-            ConnectionState::Closing | ConnectionState::Closed => Ok(()),
             _ => Err(NexStreamError::InvalidStateTransition {
                 from: self.state.to_string(),
                 to: "Closing".into(),
@@ -167,11 +380,126 @@ impl Connection {
         }
     }
 
+    /// Record that the peer sent a CONNECTION_CLOSE with `reason`. This
+    /// crate doesn't ship a wire codec for one, so a caller that decodes an
+    /// incoming close frame calls this to fold it in; the connection moves
+    /// straight to `Draining` exactly as a local [`Connection::close_with_error`]
+    /// would, since the peer has already decided to close.
+    pub fn note_peer_close(&mut self, reason: CloseReason) {
+        if self.state == ConnectionState::Closed {
+            return;
+        }
+        self.close_reason = Some(reason);
+        self.draining_since = Some(Instant::now());
+        if self.state != ConnectionState::Draining {
+            self.transition(ConnectionState::Closing);
+            self.transition(ConnectionState::Draining);
+        }
+    }
+
+    /// Returns the close reason recorded by [`Connection::close_with_error`]
+    /// or [`Connection::note_peer_close`]. `None` if the connection is still
+    /// active or was closed gracefully via [`Connection::close`].
+    pub fn close_reason(&self) -> Option<&CloseReason> {
+        self.close_reason.as_ref()
+    }
+
+    /// How long a connection in `Draining` waits before
+    /// [`Connection::poll_drain`] may move it to `Closed`: three times the
+    /// current smoothed RTT (mirroring QUIC's recommended drain period of
+    /// three times the current PTO, RFC 9000 section 10.2), floored at
+    /// [`MIN_DRAIN_TIMEOUT`] for a connection with little or no RTT history.
+    pub fn drain_timeout(&self) -> Duration {
+        (self.rtt.smoothed_rtt() * DRAIN_RTT_MULTIPLIER).max(MIN_DRAIN_TIMEOUT)
+    }
+
+    /// Advance the `Draining` timer. No-op outside of `Draining`. Once
+    /// [`Connection::drain_timeout`] has elapsed since entering `Draining`,
+    /// moves the connection to `Closed`.
+    pub fn poll_drain(&mut self, now: Instant) -> Result<()> {
+        if self.state != ConnectionState::Draining {
+            return Ok(());
+        }
+        if let Some(since) = self.draining_since {
+            if now.duration_since(since) >= self.drain_timeout() {
+                self.transition(ConnectionState::Closed);
+            }
+        }
+        Ok(())
+    }
+
+    /// The ECN codepoint to mark the next outgoing packet with: `Ect0` while
+    /// ECN is still being attempted, `NotEct` once
+    /// [`Connection::on_ecn_counts_received`] has disabled it. A caller
+    /// builds its packet's IP header from this the same way it builds
+    /// packet sizing from [`Connection::congestion`].
+    pub fn ecn_codepoint(&self) -> EcnCodepoint {
+        if self.ecn_enabled {
+            EcnCodepoint::Ect0
+        } else {
+            EcnCodepoint::NotEct
+        }
+    }
+
+    /// Whether this connection is still attempting ECN (see
+    /// [`Connection::on_ecn_counts_received`]).
+    pub fn ecn_enabled(&self) -> bool {
+        self.ecn_enabled
+    }
+
+    /// The last validated ECN counts echoed by the peer.
+    pub fn ecn_counts(&self) -> EcnCounts {
+        self.ecn_counts
+    }
+
+    /// Fold in a fresh set of ECN counts echoed by the peer's
+    /// acknowledgements, mirroring neqo's `EcnCount` validation.
+    ///
+    /// A no-op if ECN has already been disabled. Otherwise: the echoed
+    /// counts must be monotonically non-decreasing in every field, since a
+    /// genuine peer only ever reports packets it has actually seen; a
+    /// decrease means something on the path is mangling or fabricating the
+    /// ECN field, so ECN is disabled for the rest of the connection as a
+    /// black-holing defense (RFC 9000 section 13.4.2). Otherwise, if the
+    /// CE count increased, the peer observed new congestion marks since the
+    /// last report, so this reacts as to a loss -- shrinking the congestion
+    /// window via [`CongestionController::on_ecn_congestion_event`] -- but
+    /// without touching bytes in flight, since no packet was actually lost
+    /// or needs retransmitting.
+    pub fn on_ecn_counts_received(&mut self, echoed: EcnCounts) {
+        if !self.ecn_enabled {
+            return;
+        }
+        if echoed.ect0 < self.ecn_counts.ect0
+            || echoed.ect1 < self.ecn_counts.ect1
+            || echoed.ce < self.ecn_counts.ce
+        {
+            self.ecn_enabled = false;
+            return;
+        }
+        if echoed.ce > self.ecn_counts.ce {
+            self.congestion.on_ecn_congestion_event();
+            self.emit(
+                None,
+                EventKind::CongestionUpdate {
+                    cwnd: self.congestion.window(),
+                    bytes_in_flight: self.congestion.bytes_in_flight(),
+                },
+            );
+        }
+        self.ecn_counts = echoed;
+    }
+
     /// Returns the current connection state.
     pub fn state(&self) -> ConnectionState {
         self.state
     }
 
+    /// Returns this connection's qlog-style identifier (see [`crate::qlog`]).
+    pub fn id(&self) -> u64 {
+        self.conn_id
+    }
+
     /// Returns a reference to the RTT estimator.
     pub fn rtt(&self) -> &RttEstimator {
         &self.rtt
@@ -239,6 +567,9 @@ mod tests {
         assert_eq!(conn.state(), ConnectionState::Open);
 
         conn.close().unwrap();
+        assert_eq!(conn.state(), ConnectionState::Draining);
+
+        conn.poll_drain(Instant::now() + conn.drain_timeout()).unwrap();
         assert_eq!(conn.state(), ConnectionState::Closed);
     }
 
@@ -272,4 +603,260 @@ mod tests {
         conn.close().unwrap();
         conn.close().unwrap(); // should not error
     }
+
+    #[test]
+    fn graceful_close_drains_before_closing() {
+        let mut conn = Connection::new(ConnectionConfig::default());
+        conn.connect().unwrap();
+        conn.accept().unwrap();
+
+        conn.close().unwrap();
+        assert_eq!(conn.state(), ConnectionState::Draining);
+        assert!(conn.close_reason().is_none());
+
+        // Not enough time has passed yet.
+        conn.poll_drain(Instant::now()).unwrap();
+        assert_eq!(conn.state(), ConnectionState::Draining);
+
+        conn.poll_drain(Instant::now() + conn.drain_timeout()).unwrap();
+        assert_eq!(conn.state(), ConnectionState::Closed);
+    }
+
+    #[test]
+    fn close_with_error_records_reason_and_rejects_new_streams() {
+        let mut conn = Connection::new(ConnectionConfig::default());
+        conn.connect().unwrap();
+        conn.accept().unwrap();
+
+        conn.close_with_error(CloseErrorNamespace::Application, 7, "bad request")
+            .unwrap();
+        assert_eq!(conn.state(), ConnectionState::Draining);
+
+        let reason = conn.close_reason().unwrap();
+        assert_eq!(reason.namespace, CloseErrorNamespace::Application);
+        assert_eq!(reason.code, 7);
+        assert_eq!(reason.reason, "bad request");
+
+        assert!(conn.open_stream(TransportMode::BestEffort).is_err());
+    }
+
+    #[test]
+    fn recv_surfaces_close_reason_once_closing() {
+        let mut conn = Connection::new(ConnectionConfig::default());
+        conn.connect().unwrap();
+        conn.accept().unwrap();
+        let sid = conn.open_stream(TransportMode::BestEffort).unwrap();
+
+        conn.close_with_error(CloseErrorNamespace::Transport, 10, "flow control violation")
+            .unwrap();
+
+        let err = conn.recv(sid).unwrap_err();
+        match err {
+            NexStreamError::ConnectionClosedWithReason { code, reason } => {
+                assert_eq!(code, 10);
+                assert_eq!(reason, "flow control violation");
+            }
+            other => panic!("expected ConnectionClosedWithReason, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn note_peer_close_drains_like_a_local_close() {
+        let mut conn = Connection::new(ConnectionConfig::default());
+        conn.connect().unwrap();
+        conn.accept().unwrap();
+
+        conn.note_peer_close(CloseReason {
+            namespace: CloseErrorNamespace::Transport,
+            code: 1,
+            reason: "peer protocol violation".into(),
+        });
+
+        assert_eq!(conn.state(), ConnectionState::Draining);
+        assert_eq!(conn.close_reason().unwrap().code, 1);
+
+        conn.poll_drain(Instant::now() + conn.drain_timeout()).unwrap();
+        assert_eq!(conn.state(), ConnectionState::Closed);
+    }
+
+    struct RecordingLog {
+        events: Vec<Event>,
+    }
+
+    impl EventLog for RecordingLog {
+        fn log(&mut self, event: Event) {
+            self.events.push(event);
+        }
+    }
+
+    struct SharedLog(std::sync::Arc<std::sync::Mutex<Vec<Event>>>);
+
+    impl EventLog for SharedLog {
+        fn log(&mut self, event: Event) {
+            self.0.lock().unwrap().push(event);
+        }
+    }
+
+    #[test]
+    fn lifecycle_emits_state_transitions_and_stream_opened() {
+        let events = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut conn = Connection::new(ConnectionConfig::default());
+        conn.set_event_log(Some(Box::new(SharedLog(events.clone()))));
+
+        conn.connect().unwrap();
+        conn.accept().unwrap();
+        conn.open_stream(TransportMode::BestEffort).unwrap();
+        conn.close().unwrap();
+        conn.poll_drain(Instant::now() + conn.drain_timeout()).unwrap();
+
+        let recorded = events.lock().unwrap();
+        assert!(recorded
+            .iter()
+            .any(|e| matches!(e.kind, EventKind::StateTransition {
+                from: ConnectionState::Idle,
+                to: ConnectionState::Connecting
+            })));
+        assert!(recorded
+            .iter()
+            .any(|e| matches!(&e.kind, EventKind::StreamOpened { mode } if mode == "BestEffort")));
+        assert!(recorded
+            .iter()
+            .any(|e| matches!(e.kind, EventKind::StateTransition {
+                to: ConnectionState::Closed,
+                ..
+            })));
+    }
+
+    #[test]
+    fn manual_log_helpers_forward_to_event_log() {
+        let events = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut conn = Connection::new(ConnectionConfig::default());
+        conn.set_event_log(Some(Box::new(SharedLog(events.clone()))));
+        conn.log_congestion_update(12000, 4000);
+        conn.log_rtt_sample(50_000, 48_000);
+        conn.log_packet_lost(7);
+        // The end-to-end event shape is covered by the qlog module's own
+        // tests; this confirms Connection wires each call through.
+        let recorded = events.lock().unwrap();
+        assert_eq!(recorded.len(), 3);
+        assert!(matches!(
+            recorded[0].kind,
+            EventKind::CongestionUpdate {
+                cwnd: 12000,
+                bytes_in_flight: 4000
+            }
+        ));
+        assert!(matches!(
+            recorded[1].kind,
+            EventKind::RttSample {
+                latest_micros: 50_000,
+                smoothed_micros: 48_000
+            }
+        ));
+        assert!(matches!(recorded[2].kind, EventKind::PacketLost { seq: 7 }));
+    }
+
+    #[test]
+    fn ecn_counts_start_enabled_at_ect0() {
+        let conn = Connection::new(ConnectionConfig::default());
+        assert!(conn.ecn_enabled());
+        assert_eq!(conn.ecn_codepoint(), EcnCodepoint::Ect0);
+        assert_eq!(conn.ecn_counts(), EcnCounts::default());
+    }
+
+    #[test]
+    fn monotonic_ecn_updates_are_accepted() {
+        let mut conn = Connection::new(ConnectionConfig::default());
+        conn.on_ecn_counts_received(EcnCounts {
+            ect0: 5,
+            ect1: 0,
+            ce: 0,
+        });
+        assert!(conn.ecn_enabled());
+        assert_eq!(conn.ecn_counts().ect0, 5);
+
+        conn.on_ecn_counts_received(EcnCounts {
+            ect0: 9,
+            ect1: 0,
+            ce: 0,
+        });
+        assert!(conn.ecn_enabled());
+        assert_eq!(conn.ecn_counts().ect0, 9);
+    }
+
+    #[test]
+    fn ce_increase_shrinks_the_congestion_window() {
+        let mut conn = Connection::new(ConnectionConfig::default());
+        conn.congestion_mut().on_packet_sent(20_000);
+        let window_before = conn.congestion().window();
+
+        conn.on_ecn_counts_received(EcnCounts {
+            ect0: 3,
+            ect1: 0,
+            ce: 1,
+        });
+
+        assert!(conn.congestion().window() < window_before);
+        assert_eq!(conn.ecn_counts().ce, 1);
+    }
+
+    #[test]
+    fn non_monotonic_ecn_counts_disable_ecn() {
+        let mut conn = Connection::new(ConnectionConfig::default());
+        conn.on_ecn_counts_received(EcnCounts {
+            ect0: 5,
+            ect1: 0,
+            ce: 2,
+        });
+        assert!(conn.ecn_enabled());
+
+        // A decrease in any field looks like a mangled or fabricated ECN
+        // field -- disable ECN rather than trust it.
+        conn.on_ecn_counts_received(EcnCounts {
+            ect0: 5,
+            ect1: 0,
+            ce: 1,
+        });
+
+        assert!(!conn.ecn_enabled());
+        assert_eq!(conn.ecn_codepoint(), EcnCodepoint::NotEct);
+        // The stale, pre-disable counts are left in place rather than
+        // overwritten by the untrusted report.
+        assert_eq!(conn.ecn_counts().ce, 2);
+    }
+
+    #[test]
+    fn disabled_ecn_ignores_further_reports() {
+        let mut conn = Connection::new(ConnectionConfig::default());
+        conn.on_ecn_counts_received(EcnCounts {
+            ect0: 1,
+            ect1: 0,
+            ce: 5,
+        });
+        conn.on_ecn_counts_received(EcnCounts {
+            ect0: 0,
+            ect1: 0,
+            ce: 0,
+        });
+        assert!(!conn.ecn_enabled());
+
+        let window_before = conn.congestion().window();
+        conn.on_ecn_counts_received(EcnCounts {
+            ect0: 99,
+            ect1: 0,
+            ce: 99,
+        });
+        assert_eq!(conn.congestion().window(), window_before);
+        assert_eq!(conn.ecn_counts().ce, 5);
+    }
+
+    #[test]
+    fn without_an_event_log_nothing_is_recorded() {
+        let mut conn = Connection::new(ConnectionConfig::default());
+        conn.connect().unwrap();
+        conn.accept().unwrap();
+        conn.open_stream(TransportMode::BestEffort).unwrap();
+        conn.close().unwrap();
+        // No event_log installed -- nothing to assert beyond "it didn't panic".
+    }
 }
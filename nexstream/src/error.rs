@@ -24,6 +24,9 @@ pub enum NexStreamError {
     #[error("connection is closed")]
     ConnectionClosed,
 
+    #[error("connection closed with code {code}: {reason}")]
+    ConnectionClosedWithReason { code: u32, reason: String },
+
     #[error("connection timeout")]
     ConnectionTimeout,
 
@@ -13,17 +13,23 @@ pub mod flow_control;
 pub mod frame;
 pub mod loss_detection;
 pub mod mux;
+pub mod qlog;
 pub mod retransmission;
 pub mod rtt;
 pub mod stream;
 pub mod transport;
 
 // Re-export key public types at crate root.
-pub use connection::{Connection, ConnectionConfig, ConnectionState};
+pub use congestion::CongestionController;
+pub use connection::{
+    CloseErrorNamespace, CloseReason, Connection, ConnectionConfig, ConnectionState, EcnCodepoint,
+    EcnCounts,
+};
 pub use error::{NexStreamError, Result};
 pub use flow_control::FlowController;
 pub use frame::Frame;
 pub use mux::Multiplexer;
+pub use qlog::{Event, EventKind, EventLog, NdjsonEventLog};
 pub use rtt::RttEstimator;
 pub use stream::{Stream, StreamState};
 pub use transport::TransportMode;
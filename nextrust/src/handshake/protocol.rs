@@ -7,6 +7,7 @@
 //     |==== encrypted channel ===|
 
 use crate::crypto::aead::AeadCipher;
+use crate::crypto::hash::hash_sha256;
 use crate::crypto::keys::{derive_node_id, IdentityKeyPair};
 use crate::crypto::x25519::{derive_session_keys, X25519KeyPair};
 use crate::error::{NexTrustError, Result};
@@ -30,18 +31,56 @@ fn node_id_from_mic(mic: &MIC) -> [u8; 16] {
     }
 }
 
-/// A fixed nonce used for the handshake confirmation messages.
-/// In production, these would be derived uniquely; here we use a simple scheme:
-/// message 2 uses nonce [0,0,...,1], message 3 uses nonce [0,0,...,2].
-fn handshake_nonce(msg_num: u8) -> [u8; 12] {
-    let mut n = [0u8; 12];
-    n[11] = msg_num;
-    n
-}
-
 /// The confirmation message encrypted inside TRUST_ACCEPT and TRUST_FINISH.
+/// Its content no longer needs to carry the channel binding itself -- the
+/// transcript hash does that as both AEAD associated data and HKDF context
+/// -- so it stays a fixed marker both sides can cheaply compare against.
 const FINISHED_MSG: &[u8] = b"nexus handshake finished";
 
+/// A MIC's full wire encoding: the signed content followed by its signature.
+/// Used to fold a peer's MIC into the handshake transcript hash; there is no
+/// standalone MIC serializer in this crate, so this mirrors exactly what
+/// [`crate::mic::validator::validate`] checks the signature over.
+fn mic_wire_bytes(mic: &MIC) -> Vec<u8> {
+    let mut bytes = mic.signable_bytes();
+    bytes.extend_from_slice(&mic.signature);
+    bytes
+}
+
+/// Incremental SHA-256 transcript hash over the handshake so far.
+///
+/// `transcript_hash(None, init_ephemeral_pub, initiator_mic)` is the
+/// transcript after TRUST_HELLO; feeding that result back in as `prior`
+/// along with the responder's own ephemeral key and MIC extends it to the
+/// transcript after TRUST_ACCEPT. Both finished messages are then encrypted
+/// under associated data and an HKDF context derived from this transcript,
+/// so tampering with any prior message is detected by the next finished tag
+/// failing to authenticate, and traffic keys from one session can never be
+/// confused with another's.
+fn transcript_hash(prior: Option<&[u8; 32]>, ephemeral_pub: &[u8; 32], mic: &MIC) -> [u8; 32] {
+    let mut buf = Vec::with_capacity(32 + 32 + 256);
+    if let Some(prior) = prior {
+        buf.extend_from_slice(prior);
+    }
+    buf.extend_from_slice(ephemeral_pub);
+    buf.extend_from_slice(&mic_wire_bytes(mic));
+    hash_sha256(&buf)
+}
+
+/// Derive a per-direction, per-session AEAD nonce: `direction` distinguishes
+/// the responder's confirmation from the initiator's (instead of the old
+/// fixed `[0,...,1]`/`[0,...,2]` scheme), and `transcript` salts it so the
+/// same direction byte never reuses a nonce across two different sessions.
+fn finished_nonce(transcript: &[u8; 32], direction: u8) -> [u8; 12] {
+    let mut buf = Vec::with_capacity(33);
+    buf.extend_from_slice(transcript);
+    buf.push(direction);
+    let digest = hash_sha256(&buf);
+    let mut nonce = [0u8; 12];
+    nonce.copy_from_slice(&digest[..12]);
+    nonce
+}
+
 // ── Initiator ────────────────────────────────────────────────────────────
 
 /// Client-side (initiator) of the NexTrust handshake.
@@ -103,7 +142,7 @@ impl Initiator {
         response: HandshakeResponse,
         now: u64,
     ) -> Result<HandshakeComplete> {
-        let (ephemeral_secret, _ephemeral_public) = match &self.state {
+        let (ephemeral_secret, ephemeral_public) = match &self.state {
             HandshakeState::InitSent {
                 ephemeral_secret,
                 ephemeral_public,
@@ -123,17 +162,29 @@ impl Initiator {
         let our_ephemeral = X25519KeyPair::from_secret_bytes(ephemeral_secret);
         let shared_secret = our_ephemeral.diffie_hellman(&response.ephemeral_pub);
 
-        // Derive session keys (we are the client)
+        // Recompute the transcript up through TRUST_ACCEPT: both sides hash
+        // the same two (ephemeral_pub, MIC) pairs in the same order, so a
+        // tampered TRUST_HELLO or TRUST_ACCEPT yields a transcript neither
+        // peer's finished tag authenticates under.
+        let transcript_after_init = transcript_hash(None, &ephemeral_public, &self.mic);
+        let transcript = transcript_hash(
+            Some(&transcript_after_init),
+            &response.ephemeral_pub,
+            &response.responder_mic,
+        );
+
+        // Derive session keys (we are the client), bound to the transcript.
         let client_node_id = node_id_from_mic(&self.mic);
         let server_node_id = node_id_from_mic(&response.responder_mic);
-        let session_keys = derive_session_keys(&shared_secret, &client_node_id, &server_node_id)?;
+        let session_keys =
+            derive_session_keys(&shared_secret, &client_node_id, &server_node_id, &transcript)?;
 
-        // Verify the responder's encrypted payload (decrypt with server_write_key)
+        // Verify the responder's encrypted payload (decrypt with server_write_key).
         let server_cipher = AeadCipher::new(session_keys.server_write_key);
         let decrypted = server_cipher.decrypt(
-            &handshake_nonce(2),
+            &finished_nonce(&transcript, 2),
             &response.encrypted_payload,
-            b"",
+            &transcript,
         )?;
         if decrypted != FINISHED_MSG {
             return Err(NexTrustError::Handshake(
@@ -141,12 +192,13 @@ impl Initiator {
             ));
         }
 
-        // Encrypt our own finished message with client_write_key
+        // Encrypt our own finished message with client_write_key, under the
+        // same transcript-bound nonce/AAD scheme.
         let client_cipher = AeadCipher::new(session_keys.client_write_key);
         let encrypted_payload = client_cipher.encrypt(
-            &handshake_nonce(3),
+            &finished_nonce(&transcript, 3),
             FINISHED_MSG,
-            b"",
+            &transcript,
         )?;
 
         self.state = HandshakeState::Complete {
@@ -182,6 +234,10 @@ pub struct Responder {
     state: HandshakeState,
     /// Stored session keys after processing init
     session_keys_cache: Option<(/* client_write_key */ [u8; 32], /* server_write_key */ [u8; 32])>,
+    /// Transcript hash through TRUST_ACCEPT, cached alongside the session
+    /// keys so `process_complete` can re-derive the same finished nonce/AAD
+    /// to check the client's finished tag against.
+    transcript_cache: Option<[u8; 32]>,
 }
 
 impl Responder {
@@ -192,6 +248,7 @@ impl Responder {
             mic,
             state: HandshakeState::Idle,
             session_keys_cache: None,
+            transcript_cache: None,
         }
     }
 
@@ -224,20 +281,28 @@ impl Responder {
         // Perform DH
         let shared_secret = ephemeral.diffie_hellman(&init.ephemeral_pub);
 
-        // Derive session keys (the initiator is the client, we are the server)
+        // Transcript through TRUST_ACCEPT: the initiator's (ephemeral_pub,
+        // MIC) followed by our own, matching the order `Initiator` recomputes
+        // in `process_response`.
+        let transcript_after_init = transcript_hash(None, &init.ephemeral_pub, &init.initiator_mic);
+        let transcript = transcript_hash(Some(&transcript_after_init), &ephemeral_pub, &self.mic);
+
+        // Derive session keys (the initiator is the client, we are the server), bound to the transcript.
         let client_node_id = node_id_from_mic(&init.initiator_mic);
         let server_node_id = node_id_from_mic(&self.mic);
-        let session_keys = derive_session_keys(&shared_secret, &client_node_id, &server_node_id)?;
+        let session_keys =
+            derive_session_keys(&shared_secret, &client_node_id, &server_node_id, &transcript)?;
 
-        // Encrypt server finished message
+        // Encrypt server finished message, bound to the transcript.
         let server_cipher = AeadCipher::new(session_keys.server_write_key);
         let encrypted_payload = server_cipher.encrypt(
-            &handshake_nonce(2),
+            &finished_nonce(&transcript, 2),
             FINISHED_MSG,
-            b"",
+            &transcript,
         )?;
 
         self.session_keys_cache = Some((session_keys.client_write_key, session_keys.server_write_key));
+        self.transcript_cache = Some(transcript);
 
         self.state = HandshakeState::ResponseReceived {
             session_key: session_keys.server_write_key,
@@ -267,13 +332,16 @@ impl Responder {
         let (client_write_key, server_write_key) = self
             .session_keys_cache
             .ok_or_else(|| NexTrustError::Handshake("no cached session keys".into()))?;
+        let transcript = self
+            .transcript_cache
+            .ok_or_else(|| NexTrustError::Handshake("no cached transcript".into()))?;
 
-        // Decrypt and verify client finished message
+        // Decrypt and verify client finished message, bound to the transcript.
         let client_cipher = AeadCipher::new(client_write_key);
         let decrypted = client_cipher.decrypt(
-            &handshake_nonce(3),
+            &finished_nonce(&transcript, 3),
             &complete.encrypted_payload,
-            b"",
+            &transcript,
         )?;
         if decrypted != FINISHED_MSG {
             return Err(NexTrustError::Handshake(
@@ -347,4 +415,23 @@ mod tests {
         assert!(initiator.completed_state().is_some());
         assert!(responder.completed_state().is_some());
     }
+
+    #[test]
+    fn tampered_transcript_is_rejected() {
+        let (client_kp, client_mic) = make_identity_and_mic();
+        let (server_kp, server_mic) = make_identity_and_mic();
+
+        let mut initiator = Initiator::new(client_kp, client_mic);
+        let mut responder = Responder::new(server_kp, server_mic);
+
+        let now = 5000u64;
+        let init_msg = initiator.create_init().unwrap();
+        let mut response_msg = responder.process_init(init_msg, now).unwrap();
+        // Flip a bit in the responder's ephemeral key after TRUST_ACCEPT is
+        // sent: the initiator recomputes a different transcript and the
+        // cached server finished tag no longer authenticates under it.
+        response_msg.ephemeral_pub[0] ^= 0xFF;
+
+        assert!(initiator.process_response(response_msg, now).is_err());
+    }
 }
@@ -6,7 +6,7 @@
 //   - Multiplexer dispatch throughput
 
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use bytes::Bytes;
 use strandstream::congestion::cubic::Cubic;
@@ -204,6 +204,33 @@ fn bench_mux_dispatch(c: &mut Criterion) {
             let _ = mux.recv(sid);
         });
     });
+
+    // Paced vs. unpaced dispatch: same send/drain workload, but routed
+    // through `drain_frames_paced` with a generous rate so nothing is
+    // actually held back -- isolates the `Pacer::check` bookkeeping cost
+    // from drain-frame contention the unpaced path doesn't pay.
+    c.bench_function("mux_send_drain_unpaced", |b| {
+        let mut mux = Multiplexer::new(1024);
+        let sid = mux.create_stream(TransportMode::BestEffort).unwrap();
+
+        b.iter(|| {
+            mux.send(sid, Bytes::from_static(b"benchmark payload for pacing comparison"))
+                .unwrap();
+            black_box(mux.drain_frames());
+        });
+    });
+
+    c.bench_function("mux_send_drain_paced", |b| {
+        let mut mux = Multiplexer::new(1024);
+        mux.set_pacer(Some(strandstream::congestion::pacer::Pacer::default()));
+        let sid = mux.create_stream(TransportMode::BestEffort).unwrap();
+
+        b.iter(|| {
+            mux.send(sid, Bytes::from_static(b"benchmark payload for pacing comparison"))
+                .unwrap();
+            black_box(mux.drain_frames_paced(Instant::now(), 100_000_000.0));
+        });
+    });
 }
 
 // ---------------------------------------------------------------------------
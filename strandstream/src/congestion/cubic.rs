@@ -0,0 +1,742 @@
+//! CUBIC congestion control (RFC 8312).
+//!
+//! Slow start: cwnd += MSS per ACK (approximately doubles per RTT).
+//! Congestion avoidance: W(t) = C * (t - K)^3 + w_max
+//!   where C = 0.4, K = cbrt(w_max * (1 - beta) / C), beta = 0.7
+//! TCP-friendly region: also track a Reno-equivalent estimate `w_est`, reset
+//! to `ssthresh` at the start of each congestion-avoidance epoch and grown
+//! on every ACK by `ALPHA * (acked_bytes / cwnd) * MSS` (the standard Reno
+//! AIMD increase, `ALPHA = 3 * (1 - beta) / (1 + beta)`), and grow towards
+//! whichever of `w_cubic`/`w_est` is larger, so CUBIC never grows slower
+//! than Reno would on the same path (RFC 8312 section 4.3).
+//! On loss: ssthresh = cwnd * beta, cwnd = ssthresh, and w_max is set per
+//! RFC 8312 section 4.7's fast convergence: if this loss lands before cwnd
+//! has climbed back to the w_max remembered from the *previous* loss (i.e.
+//! the path is already more congested than last time), w_max shrinks to
+//! `cwnd * (1 + beta) / 2` instead of plain `cwnd`, so the next epoch's
+//! `K` -- and therefore the cubic curve's ceiling -- backs off further and
+//! releases bandwidth to competing flows faster. Otherwise w_max = cwnd.
+//! PMTUD (see `crate::transport::pmtud::Pmtud`): `MSS` below is only the
+//! *default* segment size a freshly constructed `Cubic` uses; the value
+//! actually driving slow-start increments, the minimum-window floor, and
+//! congestion-avoidance increase math is the per-controller `mss` field
+//! (`Cubic::with_mss`/`Cubic::set_mss`), so a controller tracks PMTUD raising
+//! or lowering the path's usable MTU without restarting from scratch.
+//! HyStart++ (RFC 9406, opt-in via `Cubic::with_hystart`): exits slow start
+//! early, before a loss-driven exit overshoots the pipe. Tracks the minimum
+//! RTT seen in the previous and current "round" (a round is `cwnd / MSS`
+//! ACKs); if the current round's minimum has inflated by more than
+//! `clamp(last_round_min_rtt / 8, 4ms, 16ms)` over at least
+//! `HYSTART_MIN_SAMPLES` RTT samples, the pipe is judged full: `ssthresh`
+//! is set to the current `cwnd` and slow start hands off to "conservative
+//! slow start" (CSS), which keeps growing cwnd -- by `MSS / L` per ACK,
+//! `L = 8` -- until it reaches `ssthresh`, then enters ordinary CUBIC
+//! congestion avoidance.
+//! PRR (RFC 6937): `on_loss` still drops `cwnd` to `ssthresh` instantly, but
+//! actual send credit during the resulting recovery episode comes from
+//! `can_send_bytes` (overridden below) rather than `cwnd - in_flight`, so the
+//! instant drop doesn't stall the sender until `in_flight` drains below the
+//! new `cwnd`, nor burst once it does. `recover_fs` (what's left in flight
+//! once the lost bytes are removed) and `ssthresh` bound a `sndcnt` that
+//! grows with `prr_delivered` (bytes ACKed this episode): proportionally,
+//! `ceil(prr_delivered * ssthresh / recover_fs) - prr_out`, while
+//! `in_flight > ssthresh`, then by the more permissive slow-start-reduction
+//! bound `max(prr_delivered - prr_out, newly_acked) + MSS` once flight has
+//! drained below `ssthresh`. The episode ends once `prr_delivered` reaches
+//! `recover_fs` -- i.e. everything that was in flight after the loss has now
+//! been ACKed.
+
+use std::time::{Duration, Instant};
+
+use crate::congestion::CongestionController;
+
+/// CUBIC constants.
+const C: f64 = 0.4;
+const BETA: f64 = 0.7;
+
+/// TCP-friendly region growth rate: the fraction of a segment Reno/NewReno
+/// would add to `cwnd` per ACKed RTT's worth of bytes (RFC 8312 section 4.3).
+const ALPHA: f64 = 3.0 * (1.0 - BETA) / (1.0 + BETA);
+
+/// HyStart++ (RFC 9406) minimum number of RTT samples in a round before its
+/// inflation check is trusted.
+const HYSTART_MIN_SAMPLES: usize = 8;
+
+/// HyStart++ conservative-slow-start growth divisor `L`: cwnd grows by
+/// `MSS / HYSTART_CSS_GROWTH_DIVISOR` per ACK while in CSS, instead of the
+/// full `MSS` per ACK exponential slow-start growth.
+const HYSTART_CSS_GROWTH_DIVISOR: f64 = 8.0;
+
+/// HyStart++ RTT-inflation threshold clamp range, in milliseconds.
+const HYSTART_MIN_RTT_THRESH_MS: f64 = 4.0;
+const HYSTART_MAX_RTT_THRESH_MS: f64 = 16.0;
+
+/// Default maximum segment size.
+const MSS: usize = 1200;
+
+/// Default initial congestion window: 10 * MSS (per RFC 6928).
+const INITIAL_WINDOW: usize = 10 * MSS;
+
+/// Maximum congestion window: 1 GiB. Clamps cwnd to prevent integer overflow
+/// and unbounded memory pressure on extremely high-bandwidth links.
+const MAX_CWND: usize = 1024 * 1024 * 1024;
+
+/// CUBIC congestion controller.
+#[derive(Debug)]
+pub struct Cubic {
+    /// Current congestion window in bytes.
+    cwnd: usize,
+    /// Slow-start threshold.
+    ssthresh: usize,
+    /// Window size just before the last loss event.
+    w_max: f64,
+    /// Time when the current congestion avoidance epoch started.
+    epoch_start: Option<Instant>,
+    /// Precomputed K value for the current epoch.
+    k: f64,
+    /// Bytes in flight.
+    in_flight: usize,
+    /// Count of ACKed bytes in the current slow-start/CA cycle
+    /// used for window increase calculation.
+    ack_accum: usize,
+    /// TCP-friendly (Reno-equivalent) window estimate for the current
+    /// congestion-avoidance epoch. Reset to `ssthresh` when the epoch
+    /// starts and grown on every ACK (see `on_ack`); the epoch's growth
+    /// target is `w_cubic.max(w_est)` so CUBIC never falls behind Reno.
+    w_est: f64,
+    /// Whether HyStart++ early slow-start exit is enabled (see
+    /// `Cubic::with_hystart`).
+    hystart_enabled: bool,
+    /// Whether we've exited slow start into HyStart++'s conservative slow
+    /// start, growing towards `ssthresh` before entering CUBIC CA.
+    in_css: bool,
+    /// Minimum RTT sample observed during the previous HyStart++ round.
+    last_round_min_rtt: Option<Duration>,
+    /// Minimum RTT sample observed so far during the current round.
+    curr_round_min_rtt: Option<Duration>,
+    /// RTT samples taken during the current round.
+    round_samples: usize,
+    /// Target ACK count for the current round, frozen from `cwnd / MSS` when
+    /// the round began (cwnd keeps growing during the round, so this must
+    /// not be recomputed from the live `cwnd` or the round would never end).
+    round_target: usize,
+    /// ACKs received during the current round; a round ends once this
+    /// reaches `round_target`.
+    round_acks: usize,
+    /// Whether a PRR (RFC 6937) recovery episode is in progress.
+    in_recovery: bool,
+    /// Flight size recorded when the current recovery episode began.
+    recover_fs: usize,
+    /// Bytes ACKed so far during the current recovery episode.
+    prr_delivered: usize,
+    /// Bytes sent so far during the current recovery episode.
+    prr_out: usize,
+    /// Bytes the current recovery episode permits sending right now, beyond
+    /// `prr_out` (see `can_send_bytes`).
+    sndcnt: usize,
+    /// Maximum segment size in bytes, used in place of the `MSS` constant
+    /// for slow-start increments, the minimum-window floor, and
+    /// congestion-avoidance increase math. Defaults to `MSS`; updated at
+    /// runtime via `set_mss` as `crate::transport::pmtud::Pmtud` discovers
+    /// the path's usable MTU.
+    mss: usize,
+}
+
+impl Cubic {
+    /// Create a new CUBIC controller with default parameters and HyStart++
+    /// disabled (slow start only ever exits on loss).
+    pub fn new() -> Self {
+        Self {
+            cwnd: INITIAL_WINDOW,
+            ssthresh: usize::MAX,
+            w_max: 0.0,
+            epoch_start: None,
+            k: 0.0,
+            in_flight: 0,
+            ack_accum: 0,
+            w_est: 0.0,
+            hystart_enabled: false,
+            in_css: false,
+            last_round_min_rtt: None,
+            curr_round_min_rtt: None,
+            round_samples: 0,
+            round_target: (INITIAL_WINDOW / MSS).max(1),
+            round_acks: 0,
+            in_recovery: false,
+            recover_fs: 0,
+            prr_delivered: 0,
+            prr_out: 0,
+            sndcnt: 0,
+            mss: MSS,
+        }
+    }
+
+    /// Create a CUBIC controller with HyStart++ (RFC 9406) early slow-start
+    /// exit enabled: RTT inflation ends slow start before a loss-driven exit
+    /// overshoots the pipe.
+    pub fn with_hystart() -> Self {
+        Self {
+            hystart_enabled: true,
+            ..Self::new()
+        }
+    }
+
+    /// Create a CUBIC controller for a path whose maximum segment size
+    /// differs from the default 1200-byte `MSS` -- e.g. seeded from
+    /// `crate::transport::pmtud::Pmtud`'s base PLPMTU before a single probe
+    /// has completed. The initial window and first HyStart++ round both
+    /// scale with `mss`, same as `Cubic::new` does with the default.
+    pub fn with_mss(mss: usize) -> Self {
+        let mss = mss.max(1);
+        Self {
+            mss,
+            cwnd: 10 * mss,
+            round_target: 10,
+            ..Self::new()
+        }
+    }
+
+    /// Update the maximum segment size used by slow-start increments, the
+    /// minimum-window floor, and congestion-avoidance increase math, without
+    /// otherwise disturbing the controller's current state -- e.g. when
+    /// `crate::transport::pmtud::Pmtud` raises or lowers the path's usable
+    /// MTU mid-connection. If the new `mss` would leave `cwnd` below the
+    /// resulting minimum window (`2 * mss`), `cwnd` is raised to meet it.
+    pub fn set_mss(&mut self, mss: usize) {
+        self.mss = mss.max(1);
+        self.cwnd = self.cwnd.max(self.min_window());
+    }
+
+    /// Current maximum segment size (see `set_mss`).
+    pub fn mss(&self) -> usize {
+        self.mss
+    }
+
+    /// Minimum congestion window for the current `mss`: `2 * mss`.
+    fn min_window(&self) -> usize {
+        2 * self.mss
+    }
+
+    /// Returns whether we are in slow start (including HyStart++'s
+    /// conservative slow start, which is still exponential-start-adjacent
+    /// rather than full CUBIC congestion avoidance).
+    pub fn in_slow_start(&self) -> bool {
+        self.cwnd < self.ssthresh || self.in_css
+    }
+
+    /// Compute the CUBIC target window at time `t` seconds since epoch start.
+    fn cubic_window(&self, t: f64) -> f64 {
+        let dt = t - self.k;
+        C * dt * dt * dt + self.w_max
+    }
+
+    /// Start a new congestion-avoidance epoch: (re)compute `K` from the
+    /// current `w_max` and reset `w_est` to `ssthresh`, the Reno-equivalent
+    /// starting point for this epoch's TCP-friendly growth.
+    fn start_epoch(&mut self, now: Instant) {
+        self.epoch_start = Some(now);
+        self.k = ((self.w_max * (1.0 - BETA)) / C).cbrt();
+        self.w_est = self.ssthresh as f64;
+        self.ack_accum = 0;
+    }
+
+    /// HyStart++ (RFC 9406) per-ACK round bookkeeping, called once per ACK
+    /// while in (non-conservative) slow start. A round is `cwnd / MSS` ACKs;
+    /// once a round completes, compare its minimum RTT against the previous
+    /// round's and exit slow start into conservative slow start if the pipe
+    /// looks full.
+    fn hystart_on_ack(&mut self) {
+        self.round_acks += 1;
+        if self.round_acks < self.round_target {
+            return;
+        }
+
+        if let (Some(curr), Some(last)) = (self.curr_round_min_rtt, self.last_round_min_rtt) {
+            if self.round_samples >= HYSTART_MIN_SAMPLES {
+                let thresh_ms =
+                    (last.as_secs_f64() * 1000.0 / 8.0).clamp(HYSTART_MIN_RTT_THRESH_MS, HYSTART_MAX_RTT_THRESH_MS);
+                if curr >= last + Duration::from_secs_f64(thresh_ms / 1000.0) {
+                    self.ssthresh = self.cwnd;
+                    self.in_css = true;
+                }
+            }
+        }
+
+        if self.curr_round_min_rtt.is_some() {
+            self.last_round_min_rtt = self.curr_round_min_rtt;
+        }
+        self.curr_round_min_rtt = None;
+        self.round_samples = 0;
+        self.round_acks = 0;
+        // Freeze the next round's length from cwnd as it stands right now;
+        // cwnd keeps growing *during* a round, so recomputing the target
+        // from a live cwnd on every ACK (instead of freezing it once per
+        // round) would make the round length grow just as fast as
+        // round_acks and the round would never end.
+        self.round_target = (self.cwnd / self.mss).max(1);
+    }
+
+    /// PRR (RFC 6937) per-ACK bookkeeping, called once per ACK while a
+    /// recovery episode is in progress. Recomputes `sndcnt` from the
+    /// episode's running totals and ends the episode once everything that
+    /// was in flight at the loss has been ACKed.
+    fn prr_on_ack(&mut self, newly_acked: usize) {
+        self.prr_delivered += newly_acked;
+
+        self.sndcnt = if self.in_flight > self.ssthresh {
+            // Proportional phase: grow sent bytes in step with delivered
+            // bytes, scaled down to the new (post-loss) ssthresh.
+            let limit = self.prr_delivered * self.ssthresh;
+            let limit = limit.div_ceil(self.recover_fs);
+            limit.saturating_sub(self.prr_out)
+        } else {
+            // Slow-start reduction: flight has already drained below
+            // ssthresh, so allow a bit more than pure conservation to avoid
+            // under-utilizing the now-smaller window.
+            self.prr_delivered.saturating_sub(self.prr_out).max(newly_acked) + self.mss
+        };
+
+        if self.prr_delivered >= self.recover_fs {
+            self.in_recovery = false;
+        }
+    }
+}
+
+impl Default for Cubic {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CongestionController for Cubic {
+    fn on_packet_sent(&mut self, bytes: usize) {
+        self.in_flight += bytes;
+        if self.in_recovery {
+            self.prr_out += bytes;
+            self.sndcnt = self.sndcnt.saturating_sub(bytes);
+        }
+    }
+
+    fn on_ack(&mut self, bytes: usize) {
+        self.in_flight = self.in_flight.saturating_sub(bytes);
+
+        if self.in_recovery {
+            self.prr_on_ack(bytes);
+        }
+
+        if self.in_css {
+            // HyStart++ conservative slow start: grow gently towards the
+            // `ssthresh` HyStart++ already picked, then hand off to CUBIC CA.
+            let increase = (self.mss as f64 / HYSTART_CSS_GROWTH_DIVISOR).max(1.0) as usize;
+            self.cwnd = self.cwnd.saturating_add(increase).min(self.ssthresh);
+            if self.cwnd >= self.ssthresh {
+                self.in_css = false;
+                self.start_epoch(Instant::now());
+            }
+        } else if self.in_slow_start() {
+            // Slow start: increase cwnd by one MSS per ACK.
+            self.cwnd = self.cwnd.saturating_add(self.mss).min(MAX_CWND);
+
+            if self.hystart_enabled {
+                self.hystart_on_ack();
+            }
+
+            if self.cwnd >= self.ssthresh {
+                // Loss-free HyStart++ exit missed it (or HyStart++ is off) and
+                // cwnd caught up with ssthresh on its own: start CA directly.
+                self.start_epoch(Instant::now());
+            }
+        } else {
+            // Congestion avoidance (CUBIC, with a TCP-friendly/Reno-equivalent
+            // floor so CUBIC never falls behind standard Reno).
+            let now = Instant::now();
+            if self.epoch_start.is_none() {
+                self.start_epoch(now);
+            }
+
+            // Reno-friendly growth: a standard Reno flow grows its window by
+            // roughly ALPHA * MSS per RTT-worth of ACKed bytes (RFC 8312
+            // §4.2); accumulate that continuously so `w_est` tracks Reno
+            // regardless of how the caller batches ACKs.
+            self.w_est += ALPHA * (bytes as f64 / self.cwnd as f64) * self.mss as f64;
+
+            let t = now
+                .duration_since(self.epoch_start.unwrap())
+                .as_secs_f64();
+            let w_cubic = self.cubic_window(t);
+            let target = w_cubic.max(self.w_est).max(self.cwnd as f64);
+
+            // Increase cwnd towards target.
+            self.ack_accum += bytes;
+            if self.ack_accum >= self.cwnd {
+                let increase = ((target - self.cwnd as f64) / (self.cwnd as f64 / self.mss as f64))
+                    .max(0.0) as usize;
+                self.cwnd = self.cwnd.saturating_add(increase.max(1)).min(MAX_CWND);
+                self.ack_accum = 0;
+            }
+        }
+    }
+
+    fn on_loss(&mut self, bytes: usize) {
+        self.in_flight = self.in_flight.saturating_sub(bytes);
+
+        // PRR (RFC 6937): remember what's left in flight at the start of
+        // this loss as the episode's `recover_fs` -- everything that has to
+        // be ACKed before the episode is considered over. A loss that
+        // leaves nothing in flight has nothing to pace out, so there's no
+        // episode to start.
+        self.recover_fs = self.in_flight;
+        self.in_recovery = self.recover_fs > 0;
+        self.prr_delivered = 0;
+        self.prr_out = 0;
+        self.sndcnt = 0;
+
+        // Fast convergence (RFC 8312 §4.7): `self.w_max` still holds the
+        // value remembered from the *previous* loss at this point. If we're
+        // losing again before climbing back to it, shrink the new target
+        // further than a plain multiplicative decrease would.
+        let cwnd = self.cwnd as f64;
+        if cwnd < self.w_max {
+            self.w_max = cwnd * (1.0 + BETA) / 2.0;
+        } else {
+            self.w_max = cwnd;
+        }
+        self.ssthresh = ((self.cwnd as f64 * BETA) as usize).max(self.min_window());
+        self.cwnd = self.ssthresh;
+
+        // Reset epoch.
+        self.epoch_start = None;
+        self.k = ((self.w_max * (1.0 - BETA)) / C).cbrt();
+        self.ack_accum = 0;
+
+        // HyStart++ only applies within a single slow-start run; a loss means
+        // we're leaving slow start (whether or not HyStart++ caught it first).
+        self.in_css = false;
+        self.last_round_min_rtt = None;
+        self.curr_round_min_rtt = None;
+        self.round_samples = 0;
+        self.round_target = 0;
+        self.round_acks = 0;
+    }
+
+    fn window(&self) -> usize {
+        self.cwnd
+    }
+
+    fn bytes_in_flight(&self) -> usize {
+        self.in_flight
+    }
+
+    fn can_send_bytes(&self) -> usize {
+        if self.in_recovery {
+            self.sndcnt
+        } else {
+            self.cwnd.saturating_sub(self.in_flight)
+        }
+    }
+
+    fn on_rtt_sample(&mut self, rtt: Duration) {
+        if !self.hystart_enabled || self.in_css || !self.in_slow_start() {
+            return;
+        }
+        self.curr_round_min_rtt = Some(match self.curr_round_min_rtt {
+            Some(m) => m.min(rtt),
+            None => rtt,
+        });
+        self.round_samples += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Minimum congestion window at the default `MSS`, i.e. `2 * MSS`.
+    const MIN_WINDOW: usize = 2 * MSS;
+
+    #[test]
+    fn initial_window() {
+        let c = Cubic::new();
+        assert_eq!(c.window(), INITIAL_WINDOW);
+        assert!(c.in_slow_start());
+    }
+
+    #[test]
+    fn slow_start_increases_cwnd() {
+        let mut c = Cubic::new();
+        let initial = c.window();
+        c.on_packet_sent(MSS);
+        c.on_ack(MSS);
+        assert!(c.window() > initial);
+    }
+
+    #[test]
+    fn loss_reduces_window() {
+        let mut c = Cubic::new();
+        // Pump up the window.
+        for _ in 0..20 {
+            c.on_packet_sent(MSS);
+            c.on_ack(MSS);
+        }
+        let pre_loss = c.window();
+        c.on_loss(MSS);
+        assert!(c.window() < pre_loss);
+    }
+
+    #[test]
+    fn loss_sets_ssthresh_using_beta() {
+        let mut c = Cubic::new();
+        // Set cwnd to a known value via slow start.
+        while c.window() < 100 * MSS {
+            c.on_packet_sent(MSS);
+            c.on_ack(MSS);
+        }
+        let pre_loss = c.window();
+        c.on_loss(MSS);
+        let expected_ssthresh = ((pre_loss as f64 * BETA) as usize).max(MIN_WINDOW);
+        assert_eq!(c.window(), expected_ssthresh);
+    }
+
+    #[test]
+    fn min_window_enforced() {
+        let mut c = Cubic::new();
+        // Trigger many losses to push window down.
+        for _ in 0..50 {
+            c.on_loss(MSS);
+        }
+        assert!(c.window() >= MIN_WINDOW);
+    }
+
+    #[test]
+    fn w_est_resets_to_ssthresh_at_epoch_start() {
+        let mut c = Cubic::new();
+        for _ in 0..30 {
+            c.on_packet_sent(MSS);
+            c.on_ack(MSS);
+        }
+        c.on_loss(MSS);
+        let ssthresh = c.ssthresh as f64;
+        // Next ACK lazily starts the new CA epoch; w_est should seed from
+        // the fresh ssthresh, not linger at whatever it was last epoch.
+        c.on_packet_sent(MSS);
+        c.on_ack(MSS);
+        assert_eq!(c.w_est, ssthresh + ALPHA * (MSS as f64 / c.ssthresh as f64) * MSS as f64);
+    }
+
+    #[test]
+    fn w_est_dominates_small_window_just_after_loss() {
+        // Immediately after a loss, cubic_window(t) sits near its t=0 floor
+        // (w_max * beta, i.e. a small window) and barely moves over the tiny
+        // wall-clock gap a test loop takes, while w_est grows deterministically
+        // by a fixed amount per ACK regardless of elapsed time. So a handful
+        // of ACKs into the new epoch, w_est -- the Reno-equivalent estimate --
+        // must have overtaken the cubic term.
+        let mut c = Cubic::new();
+        for _ in 0..30 {
+            c.on_packet_sent(MSS);
+            c.on_ack(MSS);
+        }
+        c.on_loss(MSS);
+        for _ in 0..5 {
+            c.on_packet_sent(MSS);
+            c.on_ack(MSS);
+        }
+        let t = c.epoch_start.unwrap().elapsed().as_secs_f64();
+        assert!(
+            c.w_est > c.cubic_window(t),
+            "w_est ({}) should dominate the cubic term ({}) just after a loss",
+            c.w_est,
+            c.cubic_window(t)
+        );
+    }
+
+    #[test]
+    fn fast_convergence_shrinks_w_max_on_repeated_loss_before_recovery() {
+        let mut c = Cubic::new();
+        for _ in 0..20 {
+            c.on_packet_sent(MSS);
+            c.on_ack(MSS);
+        }
+        // First loss: w_max was still 0.0, so this takes the plain
+        // `w_max = cwnd` path.
+        c.on_loss(MSS);
+        let cwnd_before_second_loss = c.window() as f64;
+
+        // Second loss strikes while cwnd is still below the w_max just
+        // recorded -- we haven't recovered to the prior peak -- so this one
+        // should take the fast-convergence path instead.
+        c.on_loss(MSS);
+        assert_eq!(c.w_max, cwnd_before_second_loss * (1.0 + BETA) / 2.0);
+        assert!(
+            c.w_max < cwnd_before_second_loss,
+            "fast convergence should shrink w_max below a plain cwnd reset"
+        );
+    }
+
+    #[test]
+    fn max_window_clamped() {
+        let mut c = Cubic::new();
+        // Drive many ACKs to grow cwnd as large as possible.
+        for _ in 0..1_000_000 {
+            c.on_packet_sent(MSS);
+            c.on_ack(MSS);
+        }
+        // cwnd must never exceed MAX_CWND regardless of ACK count.
+        assert!(c.window() <= MAX_CWND, "cwnd {} exceeds MAX_CWND {}", c.window(), MAX_CWND);
+    }
+
+    #[test]
+    fn bandwidth_estimate_is_always_none() {
+        let mut c = Cubic::new();
+        c.on_packet_sent(MSS);
+        c.on_ack(MSS);
+        assert_eq!(c.bandwidth_estimate(), None);
+    }
+
+    #[test]
+    fn hystart_exits_slow_start_early_on_rtt_inflation() {
+        let mut c = Cubic::with_hystart();
+
+        // Round 1: low, stable RTT establishes `last_round_min_rtt`.
+        for _ in 0..(INITIAL_WINDOW / MSS) {
+            c.on_rtt_sample(Duration::from_millis(20));
+            c.on_packet_sent(MSS);
+            c.on_ack(MSS);
+        }
+        assert_eq!(
+            c.ssthresh,
+            usize::MAX,
+            "a single round alone must not trigger an early exit"
+        );
+
+        // Round 2: RTT has clearly inflated, meaning the pipe is filling up.
+        // This should end slow start without any packet loss at all.
+        let round_len = c.cwnd / MSS;
+        for _ in 0..round_len {
+            c.on_rtt_sample(Duration::from_millis(40));
+            c.on_packet_sent(MSS);
+            c.on_ack(MSS);
+        }
+
+        assert!(
+            c.ssthresh < usize::MAX,
+            "HyStart++ should have exited slow start on RTT inflation"
+        );
+        assert!(
+            c.in_slow_start(),
+            "conservative slow start is still a (non-exponential) form of slow start"
+        );
+        assert!(
+            c.window() <= c.ssthresh,
+            "conservative slow start must not overshoot ssthresh"
+        );
+    }
+
+    #[test]
+    fn loss_with_nothing_in_flight_does_not_start_a_recovery_episode() {
+        let mut c = Cubic::new();
+        c.on_loss(MSS);
+        assert!(!c.in_recovery);
+        // No PRR pacing in effect: send credit is the plain cwnd - in_flight.
+        assert_eq!(c.can_send_bytes(), c.window());
+    }
+
+    #[test]
+    fn prr_paces_sends_instead_of_a_cliff() {
+        let mut c = Cubic::new();
+        // Fill the pipe with several packets in flight, then lose one.
+        let in_flight_packets = 10;
+        for _ in 0..in_flight_packets {
+            c.on_packet_sent(MSS);
+        }
+        c.on_loss(MSS);
+        assert!(c.in_recovery);
+
+        // The instant drop to ssthresh would make a bare cwnd-in_flight
+        // check stall (in_flight is still far above the new, smaller cwnd),
+        // but PRR should still grant a small amount of credit on each ACK
+        // so the sender can keep trickling data out rather than going
+        // completely silent until in_flight drains.
+        let mut granted_mid_recovery = false;
+        for _ in 0..(in_flight_packets - 1) {
+            c.on_ack(MSS);
+            if c.in_recovery && c.can_send_bytes() > 0 {
+                granted_mid_recovery = true;
+            }
+        }
+        assert!(
+            granted_mid_recovery,
+            "PRR should grant non-zero send credit during recovery, not stall until in_flight drains"
+        );
+    }
+
+    #[test]
+    fn prr_exits_recovery_once_pre_loss_flight_is_fully_acked() {
+        let mut c = Cubic::new();
+        let in_flight_packets = 5;
+        for _ in 0..in_flight_packets {
+            c.on_packet_sent(MSS);
+        }
+        c.on_loss(MSS);
+        assert!(c.in_recovery);
+
+        // recover_fs was captured after this loss's bytes were subtracted,
+        // so acking the rest of the pre-loss flight should retire it.
+        for _ in 0..(in_flight_packets - 1) {
+            c.on_ack(MSS);
+        }
+        assert!(!c.in_recovery, "recovery should end once all pre-loss flight is acked");
+    }
+
+    #[test]
+    fn with_mss_scales_initial_window_and_round_target() {
+        let c = Cubic::with_mss(1400);
+        assert_eq!(c.window(), 10 * 1400);
+        assert_eq!(c.mss(), 1400);
+        assert_eq!(c.round_target, c.window() / c.mss());
+    }
+
+    #[test]
+    fn set_mss_changes_slow_start_increment() {
+        let mut c = Cubic::new();
+        c.set_mss(1400);
+        let before = c.window();
+        c.on_packet_sent(1400);
+        c.on_ack(1400);
+        assert_eq!(c.window(), before + 1400, "slow start should grow by the new mss, not the default");
+    }
+
+    #[test]
+    fn set_mss_raises_cwnd_to_the_new_minimum_window_if_needed() {
+        let mut c = Cubic::new();
+        for _ in 0..50 {
+            c.on_loss(MSS);
+        }
+        assert_eq!(c.window(), MIN_WINDOW);
+
+        // Raising mss should lift cwnd to the new, larger minimum window
+        // rather than leaving it stuck below 2 * mss.
+        c.set_mss(9000);
+        assert_eq!(c.window(), 2 * 9000);
+    }
+
+    #[test]
+    fn hystart_disabled_by_default_ignores_rtt_inflation() {
+        let mut c = Cubic::new();
+        for _ in 0..(INITIAL_WINDOW / MSS) {
+            c.on_rtt_sample(Duration::from_millis(20));
+            c.on_packet_sent(MSS);
+            c.on_ack(MSS);
+        }
+        for _ in 0..50 {
+            c.on_rtt_sample(Duration::from_millis(200));
+            c.on_packet_sent(MSS);
+            c.on_ack(MSS);
+        }
+        assert_eq!(
+            c.ssthresh,
+            usize::MAX,
+            "HyStart++ must be opt-in via Cubic::with_hystart"
+        );
+    }
+}
@@ -0,0 +1,247 @@
+//! Delivery-rate sampling (draft-cheng-iccrg-delivery-rate-estimation).
+//!
+//! `CongestionController::on_ack(bytes)` carries no send or delivery
+//! timestamps, so a rate-based controller like [`bbr::Bbr`](crate::congestion::bbr::Bbr)
+//! cannot derive a true bandwidth sample from it alone. `DeliveryRateEstimator`
+//! fixes that: for each sent packet it snapshots the connection's cumulative
+//! delivered-bytes counter and delivery timestamp at send time; once that
+//! packet is ACKed, it computes `ack_rate = (delivered - delivered_at_send) /
+//! (now - delivered_time_at_send)` and `send_rate = bytes / (sent_time -
+//! prior_sent_time)`, and reports `min(ack_rate, send_rate)` as the sample
+//! (the slower of "how fast the path delivered this data" and "how fast we
+//! could even send it" bounds the true rate). Samples taken while the
+//! application had no more data to send are still reported, but excluded
+//! from the windowed max-filter so an idle sender can't manufacture an
+//! artificially low bandwidth estimate.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Number of non-app-limited samples the bandwidth max-filter is taken over
+/// (matches `bbr::BTLBW_FILTER_ROUNDS`; one sample per ACK).
+const FILTER_WINDOW: usize = 10;
+
+/// Connection-level delivery state snapshotted when a packet is sent, used
+/// to compute that packet's rate sample once it is ACKed.
+#[derive(Debug, Clone, Copy)]
+struct SentPacketState {
+    bytes: usize,
+    sent_time: Instant,
+    prior_sent_time: Option<Instant>,
+    delivered_at_send: usize,
+    delivered_time_at_send: Instant,
+    app_limited: bool,
+}
+
+/// A single delivery-rate sample produced by an ACK.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateSample {
+    /// `min(ack_rate, send_rate)` for the ACKed packet, in bytes/second.
+    pub delivery_rate: f64,
+    /// Whether this packet was sent while the application had no more data
+    /// queued. Such samples cannot be trusted to reflect the path's true
+    /// capacity, so callers must not let them raise a bandwidth filter.
+    pub app_limited: bool,
+}
+
+/// Delivery-rate estimator feeding rate-based congestion controllers (e.g.
+/// [`bbr::Bbr`](crate::congestion::bbr::Bbr)) with bandwidth samples that
+/// `CongestionController::on_ack`'s plain byte count cannot produce alone.
+///
+/// Mirrors `CongestionController::on_ack`'s contract of carrying no
+/// per-packet identity: `on_ack` assumes packets are acknowledged in the
+/// order they were sent, matching every other consumer of that trait.
+#[derive(Debug)]
+pub struct DeliveryRateEstimator {
+    /// Cumulative bytes delivered (ACKed) over the life of the connection.
+    delivered: usize,
+    /// Wall-clock time of the last delivery (the last `on_ack` call).
+    delivered_time: Instant,
+    /// Sent time of the most recently sent packet, used as the
+    /// `prior_sent_time` for the next packet's send-rate denominator.
+    last_sent_time: Option<Instant>,
+    /// State for packets sent but not yet ACKed, oldest first.
+    in_flight: VecDeque<SentPacketState>,
+    /// Max-filtered bandwidth estimate over the trailing `FILTER_WINDOW`
+    /// non-app-limited samples, in bytes/second.
+    windowed_max: VecDeque<f64>,
+}
+
+impl DeliveryRateEstimator {
+    /// Create a new estimator with no delivery history yet.
+    pub fn new() -> Self {
+        let now = Instant::now();
+        Self {
+            delivered: 0,
+            delivered_time: now,
+            last_sent_time: None,
+            in_flight: VecDeque::new(),
+            windowed_max: VecDeque::new(),
+        }
+    }
+
+    /// Record that `bytes` were sent. `app_limited` marks that the
+    /// application had no more data queued at send time -- the pipe wasn't
+    /// necessarily full, so this packet's eventual rate sample must not be
+    /// allowed to raise the bandwidth filter.
+    pub fn on_packet_sent(&mut self, bytes: usize, app_limited: bool) {
+        let now = Instant::now();
+        self.in_flight.push_back(SentPacketState {
+            bytes,
+            sent_time: now,
+            prior_sent_time: self.last_sent_time,
+            delivered_at_send: self.delivered,
+            delivered_time_at_send: self.delivered_time,
+            app_limited,
+        });
+        self.last_sent_time = Some(now);
+    }
+
+    /// Record that `bytes` were ACKed and return the resulting rate sample,
+    /// or `None` if there was no matching in-flight packet or the sample's
+    /// elapsed time was zero. The very first packet, with no prior send to
+    /// measure a send interval against, uses its ACK interval in its place.
+    pub fn on_ack(&mut self, bytes: usize) -> Option<RateSample> {
+        let sent = self.in_flight.pop_front()?;
+        let now = Instant::now();
+
+        self.delivered += bytes;
+        self.delivered_time = now;
+
+        let ack_elapsed = now.duration_since(sent.delivered_time_at_send);
+        let send_elapsed = sent
+            .prior_sent_time
+            .map(|prior| sent.sent_time.duration_since(prior))
+            .unwrap_or(ack_elapsed);
+
+        let ack_rate = rate(self.delivered - sent.delivered_at_send, ack_elapsed)?;
+        let send_rate = rate(sent.bytes, send_elapsed)?;
+        let delivery_rate = ack_rate.min(send_rate);
+
+        if !sent.app_limited {
+            self.windowed_max.push_back(delivery_rate);
+            if self.windowed_max.len() > FILTER_WINDOW {
+                self.windowed_max.pop_front();
+            }
+        }
+
+        Some(RateSample {
+            delivery_rate,
+            app_limited: sent.app_limited,
+        })
+    }
+
+    /// Max-filtered bandwidth estimate over the trailing window of
+    /// non-app-limited samples, in bytes/second, or `None` if none has been
+    /// recorded yet.
+    pub fn bandwidth_estimate(&self) -> Option<f64> {
+        self.windowed_max.iter().copied().fold(None, |acc, s| {
+            Some(acc.map_or(s, |a: f64| a.max(s)))
+        })
+    }
+}
+
+impl Default for DeliveryRateEstimator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// `bytes / elapsed`, or `None` if `elapsed` is zero (the rate would be
+/// infinite/undefined).
+fn rate(bytes: usize, elapsed: Duration) -> Option<f64> {
+    if elapsed.is_zero() {
+        None
+    } else {
+        Some(bytes as f64 / elapsed.as_secs_f64())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread::sleep;
+
+    #[test]
+    fn ack_rate_and_send_rate_take_the_minimum() {
+        // Two back-to-back sends, then an ACK arrives much later than the
+        // send interval would suggest -- ack_rate (gated by the slow ACK)
+        // should be lower than send_rate, so the sample is ack_rate.
+        let mut est = DeliveryRateEstimator::new();
+        est.on_packet_sent(1000, false);
+        sleep(Duration::from_millis(5));
+        est.on_packet_sent(1000, false);
+
+        sleep(Duration::from_millis(50));
+        let sample1 = est.on_ack(1000).unwrap();
+        sleep(Duration::from_millis(50));
+        let sample2 = est.on_ack(1000).unwrap();
+
+        // send_rate for the second packet is ~1000 bytes / 5ms = 200,000 B/s,
+        // far higher than the ~50ms-gated ack_rate, so min() must pick the
+        // (lower) ack_rate.
+        assert!(sample2.delivery_rate < 1000.0 / 0.005);
+        assert!(!sample1.app_limited);
+    }
+
+    #[test]
+    fn app_limited_sample_reported_but_excluded_from_filter() {
+        let mut est = DeliveryRateEstimator::new();
+
+        // A healthy, non-app-limited sample establishes the filter. Drain
+        // both sends fully so the next send is the only thing in flight.
+        est.on_packet_sent(1000, false);
+        sleep(Duration::from_millis(5));
+        est.on_packet_sent(1000, false);
+        sleep(Duration::from_millis(5));
+        est.on_ack(1000);
+        est.on_ack(1000);
+        let baseline = est.bandwidth_estimate();
+        assert!(baseline.is_some());
+
+        // An app-limited send, ACKed after an unusually long (idle) gap --
+        // if this were allowed into the filter it would crater the max.
+        est.on_packet_sent(1000, true);
+        sleep(Duration::from_millis(200));
+        let sample = est.on_ack(1000).unwrap();
+        assert!(sample.app_limited);
+
+        assert_eq!(
+            est.bandwidth_estimate(),
+            baseline,
+            "an app-limited sample must not lower (or raise) the bandwidth filter"
+        );
+    }
+
+    #[test]
+    fn bandwidth_estimate_none_before_any_sample() {
+        let est = DeliveryRateEstimator::new();
+        assert_eq!(est.bandwidth_estimate(), None);
+    }
+
+    #[test]
+    fn filter_keeps_the_max_over_the_trailing_window() {
+        let mut est = DeliveryRateEstimator::new();
+        // A fast sample first...
+        est.on_packet_sent(10_000, false);
+        sleep(Duration::from_millis(1));
+        est.on_packet_sent(1000, false);
+        sleep(Duration::from_millis(1));
+        est.on_ack(1000);
+        let fast = est.bandwidth_estimate().unwrap();
+
+        // ...then a much slower one. The max-filter must keep reporting the
+        // faster sample rather than being overwritten by the latest one.
+        est.on_packet_sent(1000, false);
+        sleep(Duration::from_millis(80));
+        est.on_ack(1000);
+
+        assert_eq!(est.bandwidth_estimate(), Some(fast));
+    }
+
+    #[test]
+    fn on_ack_without_a_prior_send_returns_none() {
+        let mut est = DeliveryRateEstimator::new();
+        assert_eq!(est.on_ack(1000), None);
+    }
+}
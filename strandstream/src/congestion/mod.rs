@@ -1,9 +1,21 @@
 //! Congestion control module.
 //!
-//! Defines the `CongestionController` trait and provides CUBIC as the default
-//! algorithm.
+//! Defines the `CongestionController` trait and provides CUBIC (loss-based)
+//! and BBR (model-based) as pluggable algorithms, plus a shared
+//! `DeliveryRateEstimator` rate-based controllers can use as their
+//! bandwidth-sampling input, and a `pacer::Pacer` that spaces a controller's
+//! admitted sends out over time instead of releasing them in a single
+//! line-rate burst.
 
+use std::time::Duration;
+
+pub mod bbr;
 pub mod cubic;
+pub mod delivery_rate;
+pub mod new_reno;
+pub mod pacer;
+
+use delivery_rate::RateSample;
 
 /// Trait for pluggable congestion control algorithms.
 ///
@@ -16,6 +28,20 @@ pub trait CongestionController: Send {
     /// Notify the controller that `bytes` were acknowledged.
     fn on_ack(&mut self, bytes: usize);
 
+    /// Notify the controller that `bytes` were acknowledged, carrying a
+    /// [`RateSample`](delivery_rate::RateSample) from a
+    /// [`DeliveryRateEstimator`](delivery_rate::DeliveryRateEstimator) for
+    /// controllers that want a real bandwidth measurement instead of
+    /// deriving one from wall-clock time between `on_ack` calls.
+    ///
+    /// The default ignores the sample and defers to `on_ack`; loss-based
+    /// controllers like CUBIC have no use for a bandwidth sample and need
+    /// not override this. Rate-based controllers like BBR override it to
+    /// fold `sample` into their own bandwidth filter.
+    fn on_ack_ex(&mut self, bytes: usize, _sample: Option<RateSample>) {
+        self.on_ack(bytes);
+    }
+
     /// Notify the controller that `bytes` were declared lost.
     fn on_loss(&mut self, bytes: usize);
 
@@ -27,6 +53,92 @@ pub trait CongestionController: Send {
 
     /// Whether the controller allows sending `bytes` more data.
     fn can_send(&self, bytes: usize) -> bool {
-        self.bytes_in_flight() + bytes <= self.window()
+        bytes <= self.can_send_bytes()
+    }
+
+    /// Bytes currently permitted to be sent right now.
+    ///
+    /// Defaults to the plain `window() - bytes_in_flight()` credit. Loss
+    /// recovery schemes that pace sends out independently of `window()` --
+    /// e.g. PRR (RFC 6937, see `cubic::Cubic`), which doles out credit
+    /// proportionally to delivery during a recovery episode instead of
+    /// letting an instantly-dropped `cwnd` either stall the sender until
+    /// `bytes_in_flight` drains or burst once it does -- override this
+    /// instead of `can_send`.
+    fn can_send_bytes(&self) -> usize {
+        self.window().saturating_sub(self.bytes_in_flight())
+    }
+
+    /// Notify the controller of a fresh RTT sample.
+    ///
+    /// Loss-based controllers like CUBIC have no use for this and may ignore
+    /// it; model-based controllers like BBR use it to track round-trip
+    /// propagation time.
+    fn on_rtt_sample(&mut self, _rtt: Duration) {}
+
+    /// Target pacing rate in bytes/second, given the current RTT estimate.
+    ///
+    /// The default spreads the congestion window evenly across one RTT.
+    /// Controllers with their own bandwidth model (e.g. BBR) override this
+    /// to pace off that model instead of the window/RTT ratio.
+    fn pacing_rate(&self, rtt: Duration) -> f64 {
+        if rtt.is_zero() {
+            f64::INFINITY
+        } else {
+            self.window() as f64 / rtt.as_secs_f64()
+        }
+    }
+
+    /// Current bottleneck-bandwidth estimate in bytes/second, if this
+    /// controller maintains one.
+    ///
+    /// Loss-based controllers like CUBIC have no bandwidth model and return
+    /// `None`; model-based controllers like BBR override this to expose
+    /// their filtered bandwidth estimate once sampled.
+    fn bandwidth_estimate(&self) -> Option<f64> {
+        None
+    }
+}
+
+/// A congestion controller that never constrains sending.
+///
+/// Used for Probabilistic and Sequenced streams, which have no
+/// retransmission or ordering guarantees worth protecting with a congestion
+/// window in the first place. BestEffort streams get a `Cubic` instead.
+#[derive(Debug, Default)]
+pub struct NoopController;
+
+impl CongestionController for NoopController {
+    fn on_packet_sent(&mut self, _bytes: usize) {}
+
+    fn on_ack(&mut self, _bytes: usize) {}
+
+    fn on_loss(&mut self, _bytes: usize) {}
+
+    fn window(&self) -> usize {
+        usize::MAX
+    }
+
+    fn bytes_in_flight(&self) -> usize {
+        0
+    }
+
+    fn can_send(&self, _bytes: usize) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn noop_controller_never_blocks() {
+        let mut c = NoopController;
+        c.on_packet_sent(usize::MAX / 2);
+        assert!(c.can_send(usize::MAX / 2));
+        c.on_ack(1);
+        c.on_loss(1);
+        assert_eq!(c.bytes_in_flight(), 0);
     }
 }
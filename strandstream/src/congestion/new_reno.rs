@@ -0,0 +1,172 @@
+//! New Reno congestion control (RFC 6582).
+//!
+//! Slow start: cwnd += MSS per ACK (approximately doubles per RTT) while
+//! cwnd < ssthresh. Congestion avoidance: cwnd += MSS*MSS/cwnd per ACK,
+//! approximating one MSS of growth per round trip. On loss: ssthresh =
+//! max(cwnd/2, 2*MSS), cwnd = ssthresh.
+
+use crate::congestion::CongestionController;
+
+/// Default maximum segment size (matches [`cubic`](crate::congestion::cubic)
+/// and [`bbr`](crate::congestion::bbr)).
+const MSS: usize = 1200;
+
+/// Default initial congestion window: 10 * MSS (per RFC 6928).
+const INITIAL_WINDOW: usize = 10 * MSS;
+
+/// Maximum congestion window: 1 GiB. Clamps cwnd to prevent integer overflow
+/// and unbounded memory pressure on extremely high-bandwidth links.
+const MAX_CWND: usize = 1024 * 1024 * 1024;
+
+/// New Reno congestion controller -- the default for reliable streams.
+#[derive(Debug)]
+pub struct NewReno {
+    /// Current congestion window in bytes.
+    cwnd: usize,
+    /// Slow-start threshold.
+    ssthresh: usize,
+    /// Bytes in flight.
+    in_flight: usize,
+}
+
+impl NewReno {
+    /// Create a new New Reno controller with default parameters.
+    pub fn new() -> Self {
+        Self {
+            cwnd: INITIAL_WINDOW,
+            ssthresh: usize::MAX,
+            in_flight: 0,
+        }
+    }
+
+    /// Returns whether we are in slow start.
+    pub fn in_slow_start(&self) -> bool {
+        self.cwnd < self.ssthresh
+    }
+}
+
+impl Default for NewReno {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CongestionController for NewReno {
+    fn on_packet_sent(&mut self, bytes: usize) {
+        self.in_flight += bytes;
+    }
+
+    fn on_ack(&mut self, bytes: usize) {
+        self.in_flight = self.in_flight.saturating_sub(bytes);
+
+        if self.in_slow_start() {
+            // Slow start: one MSS of growth per ACK.
+            self.cwnd = self.cwnd.saturating_add(MSS).min(MAX_CWND);
+        } else {
+            // Congestion avoidance: roughly one MSS of growth per RTT.
+            let increase = ((MSS * MSS) as f64 / self.cwnd as f64).max(1.0) as usize;
+            self.cwnd = self.cwnd.saturating_add(increase).min(MAX_CWND);
+        }
+    }
+
+    fn on_loss(&mut self, bytes: usize) {
+        self.in_flight = self.in_flight.saturating_sub(bytes);
+
+        // Multiplicative decrease.
+        self.ssthresh = (self.cwnd / 2).max(2 * MSS);
+        self.cwnd = self.ssthresh;
+    }
+
+    fn window(&self) -> usize {
+        self.cwnd
+    }
+
+    fn bytes_in_flight(&self) -> usize {
+        self.in_flight
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Minimum congestion window at the default `MSS`, i.e. `2 * MSS`
+    /// (mirrors the floor `on_loss` applies via `(self.cwnd / 2).max(2 * MSS)`).
+    const MIN_WINDOW: usize = 2 * MSS;
+
+    #[test]
+    fn initial_window() {
+        let r = NewReno::new();
+        assert_eq!(r.window(), INITIAL_WINDOW);
+        assert!(r.in_slow_start());
+    }
+
+    #[test]
+    fn slow_start_increases_cwnd_by_mss_per_ack() {
+        let mut r = NewReno::new();
+        let initial = r.window();
+        r.on_packet_sent(MSS);
+        r.on_ack(MSS);
+        assert_eq!(r.window(), initial + MSS);
+    }
+
+    #[test]
+    fn congestion_avoidance_grows_slower_than_slow_start() {
+        let mut r = NewReno::new();
+        // Force ssthresh low so the next ACK lands in congestion avoidance.
+        r.on_packet_sent(MSS);
+        r.on_loss(MSS);
+        let avoidance_ssthresh = r.ssthresh;
+        assert_eq!(r.window(), avoidance_ssthresh);
+
+        let before = r.window();
+        r.on_packet_sent(MSS);
+        r.on_ack(MSS);
+        assert!(r.window() > before);
+        assert!(r.window() - before < MSS);
+    }
+
+    #[test]
+    fn loss_sets_ssthresh_to_half_cwnd() {
+        let mut r = NewReno::new();
+        for _ in 0..20 {
+            r.on_packet_sent(MSS);
+            r.on_ack(MSS);
+        }
+        let pre_loss = r.window();
+        r.on_loss(MSS);
+        assert_eq!(r.window(), (pre_loss / 2).max(2 * MSS));
+        assert_eq!(r.ssthresh, r.window());
+    }
+
+    #[test]
+    fn min_window_enforced() {
+        let mut r = NewReno::new();
+        for _ in 0..50 {
+            r.on_loss(MSS);
+        }
+        assert!(r.window() >= MIN_WINDOW);
+    }
+
+    #[test]
+    fn bytes_in_flight_tracking() {
+        let mut r = NewReno::new();
+        r.on_packet_sent(1000);
+        assert_eq!(r.bytes_in_flight(), 1000);
+        r.on_packet_sent(500);
+        assert_eq!(r.bytes_in_flight(), 1500);
+        r.on_ack(600);
+        assert_eq!(r.bytes_in_flight(), 900);
+        r.on_loss(400);
+        assert_eq!(r.bytes_in_flight(), 500);
+    }
+
+    #[test]
+    fn can_send_respects_window() {
+        let mut r = NewReno::new();
+        assert!(r.can_send(INITIAL_WINDOW));
+        assert!(!r.can_send(INITIAL_WINDOW + 1));
+        r.on_packet_sent(INITIAL_WINDOW);
+        assert!(!r.can_send(1));
+    }
+}
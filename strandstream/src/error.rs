@@ -1,8 +1,8 @@
 use thiserror::Error;
 
-/// All errors produced by the NexStream transport layer.
+/// All errors produced by the StrandStream transport layer.
 #[derive(Debug, Error)]
-pub enum NexStreamError {
+pub enum StrandStreamError {
     #[error("frame too short: expected at least {expected} bytes, got {actual}")]
     FrameTooShort { expected: usize, actual: usize },
 
@@ -12,6 +12,9 @@ pub enum NexStreamError {
     #[error("invalid transport mode: 0x{0:02x}")]
     InvalidTransportMode(u8),
 
+    #[error("stream {0} is not BestEffort: datagrams require BestEffort mode")]
+    DatagramRequiresBestEffort(u32),
+
     #[error("stream {0} not found")]
     StreamNotFound(u32),
 
@@ -21,6 +24,16 @@ pub enum NexStreamError {
     #[error("stream {0} is closed")]
     StreamClosed(u32),
 
+    #[error("stream reset by peer with error code {code}")]
+    StreamReset { code: u32 },
+
+    #[error("stream {stream_id} reset final_size {final_size} is less than the {received} bytes already received")]
+    ResetFinalSizeMismatch {
+        stream_id: u32,
+        final_size: u64,
+        received: u64,
+    },
+
     #[error("connection is closed")]
     ConnectionClosed,
 
@@ -33,6 +46,9 @@ pub enum NexStreamError {
     #[error("invalid stream id: 0x{0:08x}")]
     InvalidStreamId(u32),
 
+    #[error("replayed or too-old frame: seq {0}")]
+    ReplayedFrame(u32),
+
     #[error("retransmit buffer full: {inflight} bytes inflight exceeds max {max}")]
     RetransmitBufferFull { inflight: usize, max: usize },
 
@@ -45,6 +61,15 @@ pub enum NexStreamError {
     #[error("flow control violation: send exceeds available window")]
     FlowControlViolation,
 
+    #[error("connection-level flow control window exhausted")]
+    ConnectionFlowControlBlocked,
+
+    #[error("connection-level flow control violation: send exceeds available window")]
+    ConnectionFlowControlViolation,
+
+    #[error("stream {stream_id} send side stopped by peer with error code {error_code}")]
+    StreamSendStopped { stream_id: u32, error_code: u32 },
+
     #[error("invalid state transition from {from} to {to}")]
     InvalidStateTransition { from: String, to: String },
 
@@ -58,4 +83,4 @@ pub enum NexStreamError {
     Internal(String),
 }
 
-pub type Result<T> = std::result::Result<T, NexStreamError>;
+pub type Result<T> = std::result::Result<T, StrandStreamError>;
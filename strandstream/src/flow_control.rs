@@ -2,8 +2,15 @@
 //!
 //! Each stream has an independent send window. There is also a connection-level
 //! window that caps the total across all streams.
+//!
+//! `auto_tune` additionally implements QUIC-style receive-window
+//! auto-tuning: if the application is draining a stream faster than roughly
+//! one window per RTT, the static default would throttle a high
+//! bandwidth-delay-product link for no reason, so the stream's window (and,
+//! if an ceiling is configured, the connection window) doubles instead.
 
 use std::collections::HashMap;
+use std::time::{Duration, Instant};
 
 use crate::error::{StrandStreamError, Result};
 
@@ -11,15 +18,31 @@ use crate::error::{StrandStreamError, Result};
 const DEFAULT_STREAM_WINDOW: usize = 64 * 1024;
 /// Default connection window: 1 MB.
 const DEFAULT_CONNECTION_WINDOW: usize = 1024 * 1024;
+/// Default ceiling `auto_tune` will grow a stream's window to: 16 MiB,
+/// comfortably covering a fat long-haul link without letting one stream
+/// monopolize unbounded memory.
+const DEFAULT_MAX_STREAM_WINDOW: usize = 16 * 1024 * 1024;
 
 /// Manages flow control windows for a connection and its streams.
 pub struct FlowController {
     /// Per-stream available window (bytes).
     stream_windows: HashMap<u32, usize>,
+    /// Per-stream current window ceiling (bytes), grown by `auto_tune`.
+    stream_ceilings: HashMap<u32, usize>,
+    /// Time `auto_tune` was last called for a stream, to measure the
+    /// consumption rate against `rtt`.
+    last_tuned: HashMap<u32, Instant>,
     /// Connection-level available window (bytes).
     connection_window: usize,
     /// Default initial window for new streams.
     default_stream_window: usize,
+    /// Ceiling `auto_tune` will grow a stream's window to.
+    max_stream_window: usize,
+    /// If set, `auto_tune` also grows the connection window (up to this
+    /// ceiling) by the same increment as any stream it grows, so the
+    /// connection window scales with the sum of active stream windows
+    /// instead of bottlenecking them back down to its static default.
+    max_connection_window: Option<usize>,
 }
 
 impl FlowController {
@@ -27,8 +50,12 @@ impl FlowController {
     pub fn new() -> Self {
         Self {
             stream_windows: HashMap::new(),
+            stream_ceilings: HashMap::new(),
+            last_tuned: HashMap::new(),
             connection_window: DEFAULT_CONNECTION_WINDOW,
             default_stream_window: DEFAULT_STREAM_WINDOW,
+            max_stream_window: DEFAULT_MAX_STREAM_WINDOW,
+            max_connection_window: None,
         }
     }
 
@@ -36,21 +63,42 @@ impl FlowController {
     pub fn with_windows(connection_window: usize, default_stream_window: usize) -> Self {
         Self {
             stream_windows: HashMap::new(),
+            stream_ceilings: HashMap::new(),
+            last_tuned: HashMap::new(),
             connection_window,
             default_stream_window,
+            max_stream_window: DEFAULT_MAX_STREAM_WINDOW,
+            max_connection_window: None,
         }
     }
 
+    /// Set the ceiling `auto_tune` will grow a stream's window to.
+    pub fn set_max_stream_window(&mut self, max: usize) {
+        self.max_stream_window = max;
+    }
+
+    /// Set (or clear, with `None`) the ceiling `auto_tune` will grow the
+    /// connection window to as it grows stream windows. Left unset, the
+    /// connection window is never auto-grown and stays at its static value.
+    pub fn set_max_connection_window(&mut self, max: Option<usize>) {
+        self.max_connection_window = max;
+    }
+
     /// Register a stream with its initial window.
     pub fn add_stream(&mut self, stream_id: u32) {
         self.stream_windows
             .entry(stream_id)
             .or_insert(self.default_stream_window);
+        self.stream_ceilings
+            .entry(stream_id)
+            .or_insert(self.default_stream_window);
     }
 
     /// Remove a stream from tracking.
     pub fn remove_stream(&mut self, stream_id: u32) {
         self.stream_windows.remove(&stream_id);
+        self.stream_ceilings.remove(&stream_id);
+        self.last_tuned.remove(&stream_id);
     }
 
     /// Update (increase or decrease) a stream's window by `delta` bytes.
@@ -109,6 +157,66 @@ impl FlowController {
     pub fn connection_available(&self) -> usize {
         self.connection_window
     }
+
+    /// Auto-tune `stream_id`'s window against how fast the application is
+    /// draining it relative to `rtt`.
+    ///
+    /// `bytes_consumed_since_last_update` is the amount read off the stream
+    /// since the previous call; `now` is compared against the previous
+    /// call's timestamp to derive a consumption rate. If that rate exceeds
+    /// roughly one window per RTT -- `bytes_consumed / elapsed >
+    /// ceiling / rtt`, cross-multiplied to avoid dividing by a possibly-zero
+    /// `rtt` -- the window doubles, capped by `max_stream_window` (and, if
+    /// `max_connection_window` is set, the connection window grows by the
+    /// same increment). Returns the window increment applied (0 if no growth
+    /// was warranted, including on the first call for a stream, which only
+    /// establishes the baseline timestamp).
+    ///
+    /// Errs with `StreamNotFound` if `stream_id` hasn't been registered via
+    /// `add_stream`.
+    pub fn auto_tune(
+        &mut self,
+        stream_id: u32,
+        rtt: Duration,
+        bytes_consumed_since_last_update: usize,
+        now: Instant,
+    ) -> Result<usize> {
+        let ceiling = *self
+            .stream_ceilings
+            .get(&stream_id)
+            .ok_or(StrandStreamError::StreamNotFound(stream_id))?;
+
+        let previous = self.last_tuned.insert(stream_id, now);
+        let Some(previous) = previous else {
+            return Ok(0);
+        };
+        let elapsed = now.saturating_duration_since(previous);
+        if elapsed.is_zero() || rtt.is_zero() {
+            return Ok(0);
+        }
+
+        let consumed_rate = bytes_consumed_since_last_update as u128 * rtt.as_nanos();
+        let one_window_per_rtt = ceiling as u128 * elapsed.as_nanos();
+        if consumed_rate <= one_window_per_rtt {
+            return Ok(0);
+        }
+
+        let new_ceiling = ceiling.saturating_mul(2).min(self.max_stream_window);
+        let increment = new_ceiling - ceiling;
+        if increment == 0 {
+            return Ok(0);
+        }
+
+        self.stream_ceilings.insert(stream_id, new_ceiling);
+        self.update_window(stream_id, increment as isize)?;
+
+        if let Some(max_connection_window) = self.max_connection_window {
+            self.connection_window =
+                (self.connection_window + increment).min(max_connection_window);
+        }
+
+        Ok(increment)
+    }
 }
 
 impl Default for FlowController {
@@ -173,4 +281,97 @@ mod tests {
         assert_eq!(fc.available(1), expected);
         assert!(fc.available(1) >= before);
     }
+
+    #[test]
+    fn auto_tune_first_call_only_establishes_baseline() {
+        let mut fc = FlowController::new();
+        fc.add_stream(1);
+        let now = Instant::now();
+        // No prior call to compare against, so nothing grows yet even
+        // though the consumption looks fast.
+        let grown = fc
+            .auto_tune(1, Duration::from_millis(50), DEFAULT_STREAM_WINDOW, now)
+            .unwrap();
+        assert_eq!(grown, 0);
+    }
+
+    #[test]
+    fn auto_tune_grows_window_when_drained_faster_than_one_window_per_rtt() {
+        let mut fc = FlowController::new();
+        fc.add_stream(1);
+        let t0 = Instant::now();
+        fc.auto_tune(1, Duration::from_millis(50), 0, t0).unwrap();
+
+        // The whole window was consumed in 10ms, far faster than the 50ms
+        // RTT -- this should double the window.
+        let t1 = t0 + Duration::from_millis(10);
+        let grown = fc
+            .auto_tune(1, Duration::from_millis(50), DEFAULT_STREAM_WINDOW, t1)
+            .unwrap();
+        assert_eq!(grown, DEFAULT_STREAM_WINDOW);
+        assert_eq!(fc.available(1), DEFAULT_STREAM_WINDOW * 2);
+    }
+
+    #[test]
+    fn auto_tune_does_not_grow_on_slow_consumption() {
+        let mut fc = FlowController::new();
+        fc.add_stream(1);
+        let t0 = Instant::now();
+        fc.auto_tune(1, Duration::from_millis(50), 0, t0).unwrap();
+
+        // Only a trickle consumed over a long interval -- well under one
+        // window per RTT.
+        let t1 = t0 + Duration::from_secs(1);
+        let grown = fc.auto_tune(1, Duration::from_millis(50), 100, t1).unwrap();
+        assert_eq!(grown, 0);
+        assert_eq!(fc.available(1), DEFAULT_STREAM_WINDOW);
+    }
+
+    #[test]
+    fn auto_tune_caps_growth_at_max_stream_window() {
+        let mut fc = FlowController::with_windows(usize::MAX, 100);
+        fc.set_max_stream_window(150);
+        fc.add_stream(1);
+        let t0 = Instant::now();
+        fc.auto_tune(1, Duration::from_millis(10), 0, t0).unwrap();
+
+        let t1 = t0 + Duration::from_millis(1);
+        let grown = fc.auto_tune(1, Duration::from_millis(10), 100, t1).unwrap();
+        // Doubling 100 would give 200, but the ceiling caps it at 150.
+        assert_eq!(grown, 50);
+        assert_eq!(fc.available(1), 150);
+
+        // Already at the ceiling -- a further fast-consumption call can't
+        // grow it any more.
+        let t2 = t1 + Duration::from_millis(1);
+        let grown = fc.auto_tune(1, Duration::from_millis(10), 150, t2).unwrap();
+        assert_eq!(grown, 0);
+    }
+
+    #[test]
+    fn auto_tune_scales_connection_window_when_ceiling_configured() {
+        let mut fc = FlowController::with_windows(100, 100);
+        fc.set_max_connection_window(Some(1000));
+        fc.add_stream(1);
+        let t0 = Instant::now();
+        fc.auto_tune(1, Duration::from_millis(10), 0, t0).unwrap();
+
+        let t1 = t0 + Duration::from_millis(1);
+        let grown = fc.auto_tune(1, Duration::from_millis(10), 100, t1).unwrap();
+        assert_eq!(grown, 100);
+        // The connection window grew by the same increment as the stream.
+        assert_eq!(fc.connection_available(), 200);
+    }
+
+    #[test]
+    fn auto_tune_unknown_stream_errs() {
+        let mut fc = FlowController::new();
+        let err = fc
+            .auto_tune(99, Duration::from_millis(50), 0, Instant::now())
+            .unwrap_err();
+        match err {
+            StrandStreamError::StreamNotFound(id) => assert_eq!(id, 99),
+            other => panic!("expected StreamNotFound, got {other:?}"),
+        }
+    }
 }
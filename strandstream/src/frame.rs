@@ -1,16 +1,17 @@
 use bytes::{Buf, BufMut, Bytes, BytesMut};
 
-use crate::error::{NexStreamError, Result};
+use crate::error::{StrandStreamError, Result};
+use crate::varint::{get_varint, put_varint, varint_len};
 
-/// Frame type identifiers carried inside NexStream.
+/// Frame type identifiers carried inside StrandStream.
 ///
-/// Values 0x01–0x08 are data-path frames. Values 0x10–0x13 are connection
+/// Values 0x01–0x09 are data-path frames. Values 0x10–0x18 are connection
 /// lifecycle control frames. 0x40 is the congestion-signalling frame.
-/// All wire values match the spec (§4.3 NexStream Control Frame Types).
+/// All wire values match the spec (§4.3 StrandStream Control Frame Types).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[repr(u8)]
 pub enum FrameType {
-    // Data-path frames (0x01–0x08)
+    // Data-path frames (0x01–0x09)
     Data = 0x01,
     Ack = 0x02,
     Nack = 0x03,
@@ -19,17 +20,35 @@ pub enum FrameType {
     Ping = 0x06,
     Pong = 0x07,
     WindowUpdate = 0x08,
-    // Connection lifecycle control frames (0x10–0x13)
+    /// Unreliable, unordered payload carrying no stream association (see
+    /// [`Frame::Datagram`]).
+    Datagram = 0x09,
+    // Connection lifecycle control frames (0x10–0x18)
     StreamOpen = 0x10,
     StreamAck = 0x11,
     StreamClose = 0x12,
     StreamReset = 0x13,
+    StreamDataBlocked = 0x14,
+    DataBlocked = 0x15,
+    StreamsBlocked = 0x16,
+    /// Graceful connection shutdown (see [`Frame::GoAway`]). Assigned 0x17
+    /// rather than the 0x14 an earlier draft of this frame suggested, since
+    /// 0x14 was already claimed by `StreamDataBlocked` by the time this was
+    /// added.
+    GoAway = 0x17,
+    /// Stop the peer from sending further data on a stream (see
+    /// [`Frame::StopSending`]). Assigned 0x18 rather than the 0x15 an
+    /// earlier draft of this frame suggested, since 0x15 was already claimed
+    /// by `DataBlocked` by the time this was added.
+    StopSending = 0x18,
     // Congestion-signalling frame (0x40)
     Congestion = 0x40,
+    // Length-obfuscation frame (0x41)
+    Padding = 0x41,
 }
 
 impl TryFrom<u8> for FrameType {
-    type Error = NexStreamError;
+    type Error = StrandStreamError;
 
     fn try_from(value: u8) -> Result<Self> {
         match value {
@@ -41,12 +60,19 @@ impl TryFrom<u8> for FrameType {
             0x06 => Ok(FrameType::Ping),
             0x07 => Ok(FrameType::Pong),
             0x08 => Ok(FrameType::WindowUpdate),
+            0x09 => Ok(FrameType::Datagram),
             0x10 => Ok(FrameType::StreamOpen),
             0x11 => Ok(FrameType::StreamAck),
             0x12 => Ok(FrameType::StreamClose),
             0x13 => Ok(FrameType::StreamReset),
+            0x14 => Ok(FrameType::StreamDataBlocked),
+            0x15 => Ok(FrameType::DataBlocked),
+            0x16 => Ok(FrameType::StreamsBlocked),
+            0x17 => Ok(FrameType::GoAway),
+            0x18 => Ok(FrameType::StopSending),
             0x40 => Ok(FrameType::Congestion),
-            other => Err(NexStreamError::UnknownFrameType(other)),
+            0x41 => Ok(FrameType::Padding),
+            other => Err(StrandStreamError::UnknownFrameType(other)),
         }
     }
 }
@@ -59,12 +85,45 @@ impl DataFlags {
     pub const NONE: Self = Self(0x00);
     pub const FIN: Self = Self(0x01);
     pub const KEY_FRAME: Self = Self(0x02);
+    /// Payload is a Reed-Solomon FEC shard: a [`crate::transport::fec::FecGroup`]
+    /// header is prefixed to the payload bytes. Set by
+    /// `ProbabilisticSender` when FEC is enabled.
+    pub const FEC: Self = Self(0x04);
 
     pub fn contains(self, flag: DataFlags) -> bool {
         (self.0 & flag.0) == flag.0
     }
 }
 
+/// Which field-encoding scheme [`Frame::encode_into`]/[`Frame::decode`] (and
+/// their `_versioned` counterparts) use on the wire.
+///
+/// `V1Fixed` is the original encoding: every ID/length field is a fixed
+/// 2-byte `u16` (range counts), 4-byte `u32` or 8-byte `u64`, regardless of
+/// its actual value. `V2Varint`
+/// instead encodes those same fields as QUIC-style varints (see
+/// [`crate::varint`]), which costs as little as 1 byte for the common case
+/// of small stream IDs and short payloads. Both versions can be produced and
+/// parsed side by side during a migration -- nothing here auto-negotiates
+/// which one a peer understands, so that's left to whatever handshake or
+/// connection-level capability exchange calls into this module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WireVersion {
+    /// Fixed-width fields, as encoded by plain `encode`/`decode`.
+    #[default]
+    V1Fixed,
+    /// QUIC-style varint fields.
+    V2Varint,
+}
+
+/// Maximum length of a `Frame::GoAway` debug blob accepted by `decode`.
+/// `GoAway` is meant to carry a short human-readable reason, not an
+/// arbitrary payload, so a peer can't use it to smuggle unbounded data past
+/// flow control; anything past this is truncated on decode rather than
+/// rejected, since the debug text is diagnostic only and losing its tail
+/// doesn't change the frame's shutdown semantics.
+pub const MAX_GOAWAY_DEBUG_LEN: usize = 256;
+
 /// A range of sequence numbers used in NACK frames (selective negative ACK).
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct SeqRange {
@@ -72,7 +131,7 @@ pub struct SeqRange {
     pub end: u32,
 }
 
-/// NexStream wire frame.
+/// StrandStream wire frame.
 ///
 /// Binary layout (all fields big-endian):
 ///
@@ -106,10 +165,14 @@ pub enum Frame {
     Fin {
         stream_id: u32,
     },
-    /// RST: stream_id(4) + error_code(4)
+    /// RST: stream_id(4) + error_code(4) + final_size(8)
     Rst {
         stream_id: u32,
         error_code: u32,
+        /// Total byte offset the resetting side had definitively committed
+        /// (i.e. `Stream::send_bytes_sent` at reset time), so the peer can
+        /// reconcile its flow-control accounting for this stream.
+        final_size: u64,
     },
     /// PING: ping_id(8)
     Ping {
@@ -124,6 +187,16 @@ pub enum Frame {
         stream_id: u32,
         window_increment: u32,
     },
+    /// DATAGRAM: flags(1) + payload_len(4) + payload(N). Carries no
+    /// `stream_id` or `seq` -- it isn't associated with any stream's
+    /// ordering/flow-control state, is never retransmitted, and is never
+    /// ACKed. Used by `TransportMode::BestEffort` streams that want real
+    /// unreliable delivery instead of just relaxed ordering (see
+    /// `Stream::send_datagram`).
+    Datagram {
+        flags: DataFlags,
+        payload: Bytes,
+    },
     /// STREAM_OPEN: stream_id(4) + transport_mode(1)
     StreamOpen {
         stream_id: u32,
@@ -142,12 +215,58 @@ pub enum Frame {
         stream_id: u32,
         error_code: u32,
     },
+    /// STREAM_DATA_BLOCKED: stream_id(4) + limit(8). Sent when this side has
+    /// data to write on `stream_id` but `Stream::send_max_offset` has no
+    /// more room for it, so the peer can tell a starved stream apart from an
+    /// idle one instead of just seeing silence.
+    StreamDataBlocked {
+        stream_id: u32,
+        limit: u64,
+    },
+    /// DATA_BLOCKED: limit(8). Connection-wide counterpart of
+    /// `StreamDataBlocked`, sent when `Multiplexer::conn_send_max_offset` is
+    /// what's stalling sends rather than any one stream's own window.
+    DataBlocked {
+        limit: u64,
+    },
+    /// STREAMS_BLOCKED: max_streams(4). Sent when a new stream was wanted
+    /// but `Multiplexer::max_streams` has already been reached.
+    StreamsBlocked {
+        max_streams: u32,
+    },
+    /// GO_AWAY: last_stream_id(4) + error_code(4) + debug_len(4) + debug(N).
+    /// Announces that this side is shutting down the connection, but has
+    /// processed (and will still finish serving) every stream up to and
+    /// including `last_stream_id`; the peer must not open anything higher.
+    /// `debug` is a short human-readable reason, capped at
+    /// [`MAX_GOAWAY_DEBUG_LEN`] on decode.
+    GoAway {
+        last_stream_id: u32,
+        error_code: u32,
+        debug: Bytes,
+    },
+    /// STOP_SENDING: stream_id(4) + error_code(4). Tells the peer "I am no
+    /// longer reading this stream; please cease transmitting," without
+    /// discarding the sender's own ability to finish its write side -- the
+    /// stream remains open for the recipient's own FIN flow, unlike the hard
+    /// shutdown a [`Frame::Rst`] signals.
+    StopSending {
+        stream_id: u32,
+        error_code: u32,
+    },
     /// CONGESTION: stream_id(4) + cwnd(4) + rtt_us(4)
     Congestion {
         stream_id: u32,
         cwnd: u32,
         rtt_us: u32,
     },
+    /// PADDING: len(4) + filler(len), zero-filled. Carries no application
+    /// data; emitted only to reshape record sizes on the wire (see
+    /// [`crate::padding`]) and discarded by [`crate::mux::Multiplexer::poll`]
+    /// before it ever reaches a stream.
+    Padding {
+        len: u32,
+    },
 }
 
 impl Frame {
@@ -162,23 +281,52 @@ impl Frame {
             Frame::Ping { .. } => FrameType::Ping,
             Frame::Pong { .. } => FrameType::Pong,
             Frame::WindowUpdate { .. } => FrameType::WindowUpdate,
+            Frame::Datagram { .. } => FrameType::Datagram,
             Frame::StreamOpen { .. } => FrameType::StreamOpen,
             Frame::StreamAck { .. } => FrameType::StreamAck,
             Frame::StreamClose { .. } => FrameType::StreamClose,
             Frame::StreamReset { .. } => FrameType::StreamReset,
+            Frame::StreamDataBlocked { .. } => FrameType::StreamDataBlocked,
+            Frame::DataBlocked { .. } => FrameType::DataBlocked,
+            Frame::StreamsBlocked { .. } => FrameType::StreamsBlocked,
+            Frame::GoAway { .. } => FrameType::GoAway,
+            Frame::StopSending { .. } => FrameType::StopSending,
             Frame::Congestion { .. } => FrameType::Congestion,
+            Frame::Padding { .. } => FrameType::Padding,
         }
     }
 
-    /// Encode this frame into a byte buffer.
+    /// Encode this frame into a byte buffer using the fixed-width `V1Fixed`
+    /// wire format. See [`Frame::encode_versioned`] to pick a version
+    /// explicitly.
     pub fn encode(&self) -> Bytes {
-        let mut buf = BytesMut::with_capacity(self.encoded_len());
-        self.encode_into(&mut buf);
-        buf.freeze()
+        self.encode_versioned(WireVersion::V1Fixed)
     }
 
-    /// Encode into a pre-allocated `BytesMut`.
+    /// Encode into a pre-allocated `BytesMut` using the fixed-width
+    /// `V1Fixed` wire format.
     pub fn encode_into(&self, buf: &mut BytesMut) {
+        self.encode_into_versioned(buf, WireVersion::V1Fixed)
+    }
+
+    /// Encode this frame into a byte buffer using `version`'s field encoding
+    /// (see [`WireVersion`]).
+    pub fn encode_versioned(&self, version: WireVersion) -> Bytes {
+        let mut buf = BytesMut::with_capacity(self.encoded_len_versioned(version));
+        self.encode_into_versioned(&mut buf, version);
+        buf.freeze()
+    }
+
+    /// Encode into a pre-allocated `BytesMut` using `version`'s field
+    /// encoding (see [`WireVersion`]).
+    pub fn encode_into_versioned(&self, buf: &mut BytesMut, version: WireVersion) {
+        match version {
+            WireVersion::V1Fixed => self.encode_into_fixed(buf),
+            WireVersion::V2Varint => self.encode_into_varint(buf),
+        }
+    }
+
+    fn encode_into_fixed(&self, buf: &mut BytesMut) {
         match self {
             Frame::Data {
                 stream_id,
@@ -223,10 +371,12 @@ impl Frame {
             Frame::Rst {
                 stream_id,
                 error_code,
+                final_size,
             } => {
                 buf.put_u8(FrameType::Rst as u8);
                 buf.put_u32(*stream_id);
                 buf.put_u32(*error_code);
+                buf.put_u64(*final_size);
             }
             Frame::Ping { ping_id } => {
                 buf.put_u8(FrameType::Ping as u8);
@@ -244,6 +394,12 @@ impl Frame {
                 buf.put_u32(*stream_id);
                 buf.put_u32(*window_increment);
             }
+            Frame::Datagram { flags, payload } => {
+                buf.put_u8(FrameType::Datagram as u8);
+                buf.put_u8(flags.0);
+                buf.put_u32(payload.len() as u32);
+                buf.put_slice(payload);
+            }
             Frame::StreamOpen {
                 stream_id,
                 transport_mode,
@@ -268,6 +424,38 @@ impl Frame {
                 buf.put_u32(*stream_id);
                 buf.put_u32(*error_code);
             }
+            Frame::StreamDataBlocked { stream_id, limit } => {
+                buf.put_u8(FrameType::StreamDataBlocked as u8);
+                buf.put_u32(*stream_id);
+                buf.put_u64(*limit);
+            }
+            Frame::DataBlocked { limit } => {
+                buf.put_u8(FrameType::DataBlocked as u8);
+                buf.put_u64(*limit);
+            }
+            Frame::StreamsBlocked { max_streams } => {
+                buf.put_u8(FrameType::StreamsBlocked as u8);
+                buf.put_u32(*max_streams);
+            }
+            Frame::GoAway {
+                last_stream_id,
+                error_code,
+                debug,
+            } => {
+                buf.put_u8(FrameType::GoAway as u8);
+                buf.put_u32(*last_stream_id);
+                buf.put_u32(*error_code);
+                buf.put_u32(debug.len() as u32);
+                buf.put_slice(debug);
+            }
+            Frame::StopSending {
+                stream_id,
+                error_code,
+            } => {
+                buf.put_u8(FrameType::StopSending as u8);
+                buf.put_u32(*stream_id);
+                buf.put_u32(*error_code);
+            }
             Frame::Congestion {
                 stream_id,
                 cwnd,
@@ -278,33 +466,316 @@ impl Frame {
                 buf.put_u32(*cwnd);
                 buf.put_u32(*rtt_us);
             }
+            Frame::Padding { len } => {
+                buf.put_u8(FrameType::Padding as u8);
+                buf.put_u32(*len);
+                buf.put_bytes(0, *len as usize);
+            }
+        }
+    }
+
+    /// Same field set as [`Frame::encode_into_fixed`], but every ID/length
+    /// field is written with [`put_varint`] instead of a fixed-width
+    /// integer. Single-byte fields (the type tag, `DataFlags`,
+    /// `transport_mode`) are unaffected -- a varint already is one byte for
+    /// values that small.
+    fn encode_into_varint(&self, buf: &mut BytesMut) {
+        match self {
+            Frame::Data {
+                stream_id,
+                seq,
+                flags,
+                payload,
+            } => {
+                buf.put_u8(FrameType::Data as u8);
+                put_varint(buf, *stream_id as u64);
+                put_varint(buf, *seq as u64);
+                buf.put_u8(flags.0);
+                put_varint(buf, payload.len() as u64);
+                buf.put_slice(payload);
+            }
+            Frame::Ack {
+                stream_id,
+                ack_seq,
+                ranges,
+            } => {
+                buf.put_u8(FrameType::Ack as u8);
+                put_varint(buf, *stream_id as u64);
+                put_varint(buf, *ack_seq as u64);
+                put_varint(buf, ranges.len() as u64);
+                for r in ranges {
+                    put_varint(buf, r.start as u64);
+                    put_varint(buf, r.end as u64);
+                }
+            }
+            Frame::Nack { stream_id, ranges } => {
+                buf.put_u8(FrameType::Nack as u8);
+                put_varint(buf, *stream_id as u64);
+                put_varint(buf, ranges.len() as u64);
+                for r in ranges {
+                    put_varint(buf, r.start as u64);
+                    put_varint(buf, r.end as u64);
+                }
+            }
+            Frame::Fin { stream_id } => {
+                buf.put_u8(FrameType::Fin as u8);
+                put_varint(buf, *stream_id as u64);
+            }
+            Frame::Rst {
+                stream_id,
+                error_code,
+                final_size,
+            } => {
+                buf.put_u8(FrameType::Rst as u8);
+                put_varint(buf, *stream_id as u64);
+                put_varint(buf, *error_code as u64);
+                put_varint(buf, *final_size);
+            }
+            Frame::Ping { ping_id } => {
+                buf.put_u8(FrameType::Ping as u8);
+                put_varint(buf, *ping_id);
+            }
+            Frame::Pong { ping_id } => {
+                buf.put_u8(FrameType::Pong as u8);
+                put_varint(buf, *ping_id);
+            }
+            Frame::WindowUpdate {
+                stream_id,
+                window_increment,
+            } => {
+                buf.put_u8(FrameType::WindowUpdate as u8);
+                put_varint(buf, *stream_id as u64);
+                put_varint(buf, *window_increment as u64);
+            }
+            Frame::Datagram { flags, payload } => {
+                buf.put_u8(FrameType::Datagram as u8);
+                buf.put_u8(flags.0);
+                put_varint(buf, payload.len() as u64);
+                buf.put_slice(payload);
+            }
+            Frame::StreamOpen {
+                stream_id,
+                transport_mode,
+            } => {
+                buf.put_u8(FrameType::StreamOpen as u8);
+                put_varint(buf, *stream_id as u64);
+                buf.put_u8(*transport_mode);
+            }
+            Frame::StreamAck { stream_id } => {
+                buf.put_u8(FrameType::StreamAck as u8);
+                put_varint(buf, *stream_id as u64);
+            }
+            Frame::StreamClose { stream_id } => {
+                buf.put_u8(FrameType::StreamClose as u8);
+                put_varint(buf, *stream_id as u64);
+            }
+            Frame::StreamReset {
+                stream_id,
+                error_code,
+            } => {
+                buf.put_u8(FrameType::StreamReset as u8);
+                put_varint(buf, *stream_id as u64);
+                put_varint(buf, *error_code as u64);
+            }
+            Frame::StreamDataBlocked { stream_id, limit } => {
+                buf.put_u8(FrameType::StreamDataBlocked as u8);
+                put_varint(buf, *stream_id as u64);
+                put_varint(buf, *limit);
+            }
+            Frame::DataBlocked { limit } => {
+                buf.put_u8(FrameType::DataBlocked as u8);
+                put_varint(buf, *limit);
+            }
+            Frame::StreamsBlocked { max_streams } => {
+                buf.put_u8(FrameType::StreamsBlocked as u8);
+                put_varint(buf, *max_streams as u64);
+            }
+            Frame::GoAway {
+                last_stream_id,
+                error_code,
+                debug,
+            } => {
+                buf.put_u8(FrameType::GoAway as u8);
+                put_varint(buf, *last_stream_id as u64);
+                put_varint(buf, *error_code as u64);
+                put_varint(buf, debug.len() as u64);
+                buf.put_slice(debug);
+            }
+            Frame::StopSending {
+                stream_id,
+                error_code,
+            } => {
+                buf.put_u8(FrameType::StopSending as u8);
+                put_varint(buf, *stream_id as u64);
+                put_varint(buf, *error_code as u64);
+            }
+            Frame::Congestion {
+                stream_id,
+                cwnd,
+                rtt_us,
+            } => {
+                buf.put_u8(FrameType::Congestion as u8);
+                put_varint(buf, *stream_id as u64);
+                put_varint(buf, *cwnd as u64);
+                put_varint(buf, *rtt_us as u64);
+            }
+            Frame::Padding { len } => {
+                buf.put_u8(FrameType::Padding as u8);
+                put_varint(buf, *len as u64);
+                buf.put_bytes(0, *len as usize);
+            }
         }
     }
 
-    /// The total number of bytes this frame will occupy when encoded.
+    /// The total number of bytes this frame will occupy when encoded with
+    /// the fixed-width `V1Fixed` wire format. See
+    /// [`Frame::encoded_len_versioned`] to size for a specific version.
     pub fn encoded_len(&self) -> usize {
+        self.encoded_len_versioned(WireVersion::V1Fixed)
+    }
+
+    /// The total number of bytes this frame will occupy when encoded with
+    /// `version`'s field encoding (see [`WireVersion`]).
+    pub fn encoded_len_versioned(&self, version: WireVersion) -> usize {
+        match version {
+            WireVersion::V1Fixed => self.encoded_len_fixed(),
+            WireVersion::V2Varint => self.encoded_len_varint(),
+        }
+    }
+
+    fn encoded_len_fixed(&self) -> usize {
         // 1 byte for type tag in every variant
         1 + match self {
             Frame::Data { payload, .. } => 4 + 4 + 1 + 4 + payload.len(),
             Frame::Ack { ranges, .. } => 4 + 4 + 2 + ranges.len() * 8,
             Frame::Nack { ranges, .. } => 4 + 2 + ranges.len() * 8,
             Frame::Fin { .. } => 4,
-            Frame::Rst { .. } => 4 + 4,
+            Frame::Rst { .. } => 4 + 4 + 8,
             Frame::Ping { .. } => 8,
             Frame::Pong { .. } => 8,
             Frame::WindowUpdate { .. } => 4 + 4,
+            Frame::Datagram { payload, .. } => 1 + 4 + payload.len(),
             Frame::StreamOpen { .. } => 4 + 1,
             Frame::StreamAck { .. } => 4,
             Frame::StreamClose { .. } => 4,
             Frame::StreamReset { .. } => 4 + 4,
+            Frame::StreamDataBlocked { .. } => 4 + 8,
+            Frame::DataBlocked { .. } => 8,
+            Frame::StreamsBlocked { .. } => 4,
+            Frame::GoAway { debug, .. } => 4 + 4 + 4 + debug.len(),
+            Frame::StopSending { .. } => 4 + 4,
             Frame::Congestion { .. } => 4 + 4 + 4,
+            Frame::Padding { len } => 4 + *len as usize,
         }
     }
 
-    /// Decode a frame from the given byte buffer.
-    pub fn decode(mut data: &[u8]) -> Result<Self> {
+    fn encoded_len_varint(&self) -> usize {
+        // 1 byte for type tag in every variant
+        1 + match self {
+            Frame::Data {
+                stream_id,
+                seq,
+                payload,
+                ..
+            } => {
+                varint_len(*stream_id as u64)
+                    + varint_len(*seq as u64)
+                    + 1
+                    + varint_len(payload.len() as u64)
+                    + payload.len()
+            }
+            Frame::Ack {
+                stream_id,
+                ack_seq,
+                ranges,
+            } => {
+                varint_len(*stream_id as u64)
+                    + varint_len(*ack_seq as u64)
+                    + varint_len(ranges.len() as u64)
+                    + ranges
+                        .iter()
+                        .map(|r| varint_len(r.start as u64) + varint_len(r.end as u64))
+                        .sum::<usize>()
+            }
+            Frame::Nack { stream_id, ranges } => {
+                varint_len(*stream_id as u64)
+                    + varint_len(ranges.len() as u64)
+                    + ranges
+                        .iter()
+                        .map(|r| varint_len(r.start as u64) + varint_len(r.end as u64))
+                        .sum::<usize>()
+            }
+            Frame::Fin { stream_id } => varint_len(*stream_id as u64),
+            Frame::Rst {
+                stream_id,
+                error_code,
+                final_size,
+            } => {
+                varint_len(*stream_id as u64)
+                    + varint_len(*error_code as u64)
+                    + varint_len(*final_size)
+            }
+            Frame::Ping { ping_id } => varint_len(*ping_id),
+            Frame::Pong { ping_id } => varint_len(*ping_id),
+            Frame::WindowUpdate {
+                stream_id,
+                window_increment,
+            } => varint_len(*stream_id as u64) + varint_len(*window_increment as u64),
+            Frame::Datagram { payload, .. } => 1 + varint_len(payload.len() as u64) + payload.len(),
+            Frame::StreamOpen { stream_id, .. } => varint_len(*stream_id as u64) + 1,
+            Frame::StreamAck { stream_id } => varint_len(*stream_id as u64),
+            Frame::StreamClose { stream_id } => varint_len(*stream_id as u64),
+            Frame::StreamReset {
+                stream_id,
+                error_code,
+            } => varint_len(*stream_id as u64) + varint_len(*error_code as u64),
+            Frame::StreamDataBlocked { stream_id, limit } => {
+                varint_len(*stream_id as u64) + varint_len(*limit)
+            }
+            Frame::DataBlocked { limit } => varint_len(*limit),
+            Frame::StreamsBlocked { max_streams } => varint_len(*max_streams as u64),
+            Frame::GoAway {
+                last_stream_id,
+                error_code,
+                debug,
+            } => {
+                varint_len(*last_stream_id as u64)
+                    + varint_len(*error_code as u64)
+                    + varint_len(debug.len() as u64)
+                    + debug.len()
+            }
+            Frame::StopSending {
+                stream_id,
+                error_code,
+            } => varint_len(*stream_id as u64) + varint_len(*error_code as u64),
+            Frame::Congestion {
+                stream_id,
+                cwnd,
+                rtt_us,
+            } => varint_len(*stream_id as u64) + varint_len(*cwnd as u64) + varint_len(*rtt_us as u64),
+            Frame::Padding { len } => varint_len(*len as u64) + *len as usize,
+        }
+    }
+
+    /// Decode a frame from the given byte buffer, assuming the fixed-width
+    /// `V1Fixed` wire format. See [`Frame::decode_versioned`] to pick a
+    /// version explicitly.
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        Self::decode_versioned(data, WireVersion::V1Fixed)
+    }
+
+    /// Decode a frame from `data`, assuming `version`'s field encoding (see
+    /// [`WireVersion`]).
+    pub fn decode_versioned(data: &[u8], version: WireVersion) -> Result<Self> {
+        match version {
+            WireVersion::V1Fixed => Self::decode_fixed(data),
+            WireVersion::V2Varint => Self::decode_varint(data),
+        }
+    }
+
+    fn decode_fixed(mut data: &[u8]) -> Result<Self> {
         if data.is_empty() {
-            return Err(NexStreamError::FrameTooShort {
+            return Err(StrandStreamError::FrameTooShort {
                 expected: 1,
                 actual: 0,
             });
@@ -359,12 +830,14 @@ impl Frame {
                 Ok(Frame::Fin { stream_id })
             }
             FrameType::Rst => {
-                Self::ensure_len(data, 8, "RST")?;
+                Self::ensure_len(data, 16, "RST")?; // 4+4+8
                 let stream_id = (&data[0..4]).get_u32();
                 let error_code = (&data[4..8]).get_u32();
+                let final_size = (&data[8..16]).get_u64();
                 Ok(Frame::Rst {
                     stream_id,
                     error_code,
+                    final_size,
                 })
             }
             FrameType::Ping => {
@@ -386,6 +859,15 @@ impl Frame {
                     window_increment,
                 })
             }
+            FrameType::Datagram => {
+                Self::ensure_len(data, 5, "DATAGRAM")?; // 1+4
+                let flags = DataFlags(data[0]);
+                let payload_len = (&data[1..5]).get_u32() as usize;
+                let data = &data[5..];
+                Self::ensure_len(data, payload_len, "DATAGRAM payload")?;
+                let payload = Bytes::copy_from_slice(&data[..payload_len]);
+                Ok(Frame::Datagram { flags, payload })
+            }
             FrameType::StreamOpen => {
                 Self::ensure_len(data, 5, "STREAM_OPEN")?;
                 let stream_id = (&data[0..4]).get_u32();
@@ -414,6 +896,46 @@ impl Frame {
                     error_code,
                 })
             }
+            FrameType::StreamDataBlocked => {
+                Self::ensure_len(data, 12, "STREAM_DATA_BLOCKED")?; // 4+8
+                let stream_id = (&data[0..4]).get_u32();
+                let limit = (&data[4..12]).get_u64();
+                Ok(Frame::StreamDataBlocked { stream_id, limit })
+            }
+            FrameType::DataBlocked => {
+                Self::ensure_len(data, 8, "DATA_BLOCKED")?;
+                let limit = (&data[0..8]).get_u64();
+                Ok(Frame::DataBlocked { limit })
+            }
+            FrameType::StreamsBlocked => {
+                Self::ensure_len(data, 4, "STREAMS_BLOCKED")?;
+                let max_streams = (&data[0..4]).get_u32();
+                Ok(Frame::StreamsBlocked { max_streams })
+            }
+            FrameType::GoAway => {
+                Self::ensure_len(data, 12, "GO_AWAY")?; // 4+4+4
+                let last_stream_id = (&data[0..4]).get_u32();
+                let error_code = (&data[4..8]).get_u32();
+                let debug_len = (&data[8..12]).get_u32() as usize;
+                let data = &data[12..];
+                Self::ensure_len(data, debug_len, "GO_AWAY debug")?;
+                let truncated = debug_len.min(MAX_GOAWAY_DEBUG_LEN);
+                let debug = Bytes::copy_from_slice(&data[..truncated]);
+                Ok(Frame::GoAway {
+                    last_stream_id,
+                    error_code,
+                    debug,
+                })
+            }
+            FrameType::StopSending => {
+                Self::ensure_len(data, 8, "STOP_SENDING")?;
+                let stream_id = (&data[0..4]).get_u32();
+                let error_code = (&data[4..8]).get_u32();
+                Ok(Frame::StopSending {
+                    stream_id,
+                    error_code,
+                })
+            }
             FrameType::Congestion => {
                 Self::ensure_len(data, 12, "CONGESTION")?;
                 let stream_id = (&data[0..4]).get_u32();
@@ -425,12 +947,195 @@ impl Frame {
                     rtt_us,
                 })
             }
+            FrameType::Padding => {
+                Self::ensure_len(data, 4, "PADDING")?;
+                let len = (&data[0..4]).get_u32();
+                let data = &data[4..];
+                Self::ensure_len(data, len as usize, "PADDING filler")?;
+                Ok(Frame::Padding { len })
+            }
+        }
+    }
+
+    fn decode_varint(mut data: &[u8]) -> Result<Self> {
+        if data.is_empty() {
+            return Err(StrandStreamError::FrameTooShort {
+                expected: 1,
+                actual: 0,
+            });
+        }
+
+        let frame_type = FrameType::try_from(data[0])?;
+        data = &data[1..];
+
+        match frame_type {
+            FrameType::Data => {
+                let stream_id = get_varint(&mut data)? as u32;
+                let seq = get_varint(&mut data)? as u32;
+                Self::ensure_len(data, 1, "DATA flags")?;
+                let flags = DataFlags(data[0]);
+                data = &data[1..];
+                let payload_len = get_varint(&mut data)? as usize;
+                Self::ensure_len(data, payload_len, "DATA payload")?;
+                let payload = Bytes::copy_from_slice(&data[..payload_len]);
+                Ok(Frame::Data {
+                    stream_id,
+                    seq,
+                    flags,
+                    payload,
+                })
+            }
+            FrameType::Ack => {
+                let stream_id = get_varint(&mut data)? as u32;
+                let ack_seq = get_varint(&mut data)? as u32;
+                let range_count = get_varint(&mut data)? as usize;
+                let ranges = Self::decode_ranges_varint(&mut data, range_count)?;
+                Ok(Frame::Ack {
+                    stream_id,
+                    ack_seq,
+                    ranges,
+                })
+            }
+            FrameType::Nack => {
+                let stream_id = get_varint(&mut data)? as u32;
+                let range_count = get_varint(&mut data)? as usize;
+                let ranges = Self::decode_ranges_varint(&mut data, range_count)?;
+                Ok(Frame::Nack { stream_id, ranges })
+            }
+            FrameType::Fin => {
+                let stream_id = get_varint(&mut data)? as u32;
+                Ok(Frame::Fin { stream_id })
+            }
+            FrameType::Rst => {
+                let stream_id = get_varint(&mut data)? as u32;
+                let error_code = get_varint(&mut data)? as u32;
+                let final_size = get_varint(&mut data)?;
+                Ok(Frame::Rst {
+                    stream_id,
+                    error_code,
+                    final_size,
+                })
+            }
+            FrameType::Ping => {
+                let ping_id = get_varint(&mut data)?;
+                Ok(Frame::Ping { ping_id })
+            }
+            FrameType::Pong => {
+                let ping_id = get_varint(&mut data)?;
+                Ok(Frame::Pong { ping_id })
+            }
+            FrameType::WindowUpdate => {
+                let stream_id = get_varint(&mut data)? as u32;
+                let window_increment = get_varint(&mut data)? as u32;
+                Ok(Frame::WindowUpdate {
+                    stream_id,
+                    window_increment,
+                })
+            }
+            FrameType::Datagram => {
+                Self::ensure_len(data, 1, "DATAGRAM flags")?;
+                let flags = DataFlags(data[0]);
+                data = &data[1..];
+                let payload_len = get_varint(&mut data)? as usize;
+                Self::ensure_len(data, payload_len, "DATAGRAM payload")?;
+                let payload = Bytes::copy_from_slice(&data[..payload_len]);
+                Ok(Frame::Datagram { flags, payload })
+            }
+            FrameType::StreamOpen => {
+                let stream_id = get_varint(&mut data)? as u32;
+                Self::ensure_len(data, 1, "STREAM_OPEN transport_mode")?;
+                let transport_mode = data[0];
+                Ok(Frame::StreamOpen {
+                    stream_id,
+                    transport_mode,
+                })
+            }
+            FrameType::StreamAck => {
+                let stream_id = get_varint(&mut data)? as u32;
+                Ok(Frame::StreamAck { stream_id })
+            }
+            FrameType::StreamClose => {
+                let stream_id = get_varint(&mut data)? as u32;
+                Ok(Frame::StreamClose { stream_id })
+            }
+            FrameType::StreamReset => {
+                let stream_id = get_varint(&mut data)? as u32;
+                let error_code = get_varint(&mut data)? as u32;
+                Ok(Frame::StreamReset {
+                    stream_id,
+                    error_code,
+                })
+            }
+            FrameType::StreamDataBlocked => {
+                let stream_id = get_varint(&mut data)? as u32;
+                let limit = get_varint(&mut data)?;
+                Ok(Frame::StreamDataBlocked { stream_id, limit })
+            }
+            FrameType::DataBlocked => {
+                let limit = get_varint(&mut data)?;
+                Ok(Frame::DataBlocked { limit })
+            }
+            FrameType::StreamsBlocked => {
+                let max_streams = get_varint(&mut data)? as u32;
+                Ok(Frame::StreamsBlocked { max_streams })
+            }
+            FrameType::GoAway => {
+                let last_stream_id = get_varint(&mut data)? as u32;
+                let error_code = get_varint(&mut data)? as u32;
+                let debug_len = get_varint(&mut data)? as usize;
+                Self::ensure_len(data, debug_len, "GO_AWAY debug")?;
+                let truncated = debug_len.min(MAX_GOAWAY_DEBUG_LEN);
+                let debug = Bytes::copy_from_slice(&data[..truncated]);
+                Ok(Frame::GoAway {
+                    last_stream_id,
+                    error_code,
+                    debug,
+                })
+            }
+            FrameType::StopSending => {
+                let stream_id = get_varint(&mut data)? as u32;
+                let error_code = get_varint(&mut data)? as u32;
+                Ok(Frame::StopSending {
+                    stream_id,
+                    error_code,
+                })
+            }
+            FrameType::Congestion => {
+                let stream_id = get_varint(&mut data)? as u32;
+                let cwnd = get_varint(&mut data)? as u32;
+                let rtt_us = get_varint(&mut data)? as u32;
+                Ok(Frame::Congestion {
+                    stream_id,
+                    cwnd,
+                    rtt_us,
+                })
+            }
+            FrameType::Padding => {
+                let len = get_varint(&mut data)? as u32;
+                Self::ensure_len(data, len as usize, "PADDING filler")?;
+                Ok(Frame::Padding { len })
+            }
+        }
+    }
+
+    /// `count` comes straight off the wire as a varint (up to `2^62 - 1`),
+    /// so it's not trustworthy as a `Vec::with_capacity` argument -- a
+    /// handful of bytes could otherwise claim billions of ranges and abort
+    /// the process on the allocation. Growing one push at a time instead
+    /// means a truncated `data` simply fails the next `get_varint` call.
+    fn decode_ranges_varint(data: &mut &[u8], count: usize) -> Result<Vec<SeqRange>> {
+        let mut ranges = Vec::new();
+        for _ in 0..count {
+            let start = get_varint(data)? as u32;
+            let end = get_varint(data)? as u32;
+            ranges.push(SeqRange { start, end });
         }
+        Ok(ranges)
     }
 
     fn ensure_len(data: &[u8], needed: usize, context: &str) -> Result<()> {
         if data.len() < needed {
-            Err(NexStreamError::FrameTooShort {
+            Err(StrandStreamError::FrameTooShort {
                 expected: needed,
                 actual: data.len(),
             })
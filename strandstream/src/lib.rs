@@ -0,0 +1,33 @@
+//! StrandStream -- Layer 3 Hybrid Transport Protocol for the Strand Protocol stack.
+//!
+//! Provides four delivery modes multiplexed over a single connection:
+//! - **Reliable-Ordered**: TCP-equivalent in-order, exactly-once delivery
+//! - **Reliable-Unordered**: exactly-once delivery without ordering
+//! - **Best-Effort**: fire-and-forget, no guarantees
+//! - **Probabilistic**: configurable delivery probability
+
+pub mod congestion;
+pub mod error;
+pub mod flow_control;
+pub mod frame;
+pub mod mux;
+pub mod padding;
+pub mod recovery;
+pub mod replay;
+pub mod rtt;
+pub mod scheduler;
+pub mod stream;
+pub mod transport;
+pub mod varint;
+
+// Re-export key public types at crate root.
+pub use error::{Result, StrandStreamError};
+pub use flow_control::FlowController;
+pub use frame::Frame;
+pub use mux::Multiplexer;
+pub use scheduler::{ChunkScheduler, TransmissionPriority};
+pub use recovery::{LossDetector, RetransmissionEngine, RttEstimate};
+pub use rtt::RttEstimator;
+pub use stream::{Stream, StreamReceiver, StreamSender, StreamState};
+pub use transport::split::{split, RecvHalf, SendHalf};
+pub use transport::TransportMode;
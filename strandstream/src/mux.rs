@@ -4,17 +4,55 @@
 //! incoming frames to the appropriate stream and collects outgoing data.
 
 use std::collections::HashMap;
+use std::time::Instant;
 
 use bytes::Bytes;
 
+use crate::congestion::pacer::{PacingDecision, Pacer};
 use crate::error::{StrandStreamError, Result};
 use crate::frame::Frame;
-use crate::stream::{Stream, StreamState};
+use crate::padding::PaddingPolicy;
+use crate::replay::{ReplayWindow, DEFAULT_WINDOW_WIDTH};
+use crate::stream::{Stream, StreamReceiver, StreamSender, StreamState, DEFAULT_STREAM_WEIGHT};
 use crate::transport::TransportMode;
 
 /// Type alias for stream identifiers.
 pub type StreamId = u32;
 
+/// Base quantum unit for weighted deficit round-robin scheduling, in bytes.
+/// Each ready stream's per-round quantum is `weight * BASE_QUANTUM`.
+pub const BASE_QUANTUM: usize = 1500;
+
+/// Default connection-wide aggregate flow-control window, in bytes, shared
+/// by every stream in addition to each stream's own per-stream window (see
+/// `crate::stream::DEFAULT_RECV_WINDOW`). Modeled on QUIC's connection-level
+/// `MAX_DATA`, this bounds total buffering across *all* streams so a fleet
+/// of small, well-behaved streams can't collectively exhaust memory even
+/// though no single one would trip its own per-stream limit.
+pub const DEFAULT_CONN_WINDOW: u64 = 1024 * 1024;
+
+/// Reserved stream ID used to carry connection-level flow-control credit,
+/// i.e. a `Frame::WindowUpdate { stream_id: CONNECTION_STREAM_ID, .. }`
+/// raises `conn_send_max_offset` instead of crediting a real stream. ID 0 is
+/// otherwise reserved (see `validate_stream_id`), so it can't collide with
+/// an application stream.
+pub const CONNECTION_STREAM_ID: StreamId = 0;
+
+/// Scheduling mode used by [`Multiplexer::next_sendable`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SchedulingMode {
+    /// Weighted deficit round-robin: each ready stream's weight-proportional
+    /// quantum is added to its deficit every round, and it may emit whole
+    /// frames up to that deficit, carrying any remainder into the next call
+    /// so low-weight streams still make progress.
+    #[default]
+    WeightedFair,
+    /// Strict priority: only the highest-weight class of ready streams is
+    /// serviced; lower-priority streams get nothing until the top class has
+    /// no more data or the budget runs out.
+    StrictPriority,
+}
+
 /// Multiplexer managing all streams on a connection.
 pub struct Multiplexer {
     /// Active streams keyed by stream ID.
@@ -23,22 +61,197 @@ pub struct Multiplexer {
     next_client_stream_id: u32,
     /// Maximum allowed concurrent streams.
     max_streams: u32,
+    /// Per-stream anti-replay sliding windows, keyed by stream ID.
+    replay_windows: HashMap<StreamId, ReplayWindow>,
+    /// Width of each stream's replay window, in sequence numbers.
+    replay_window_width: u32,
+    /// Per-stream deficit counters for weighted deficit round-robin
+    /// scheduling, persisted across `next_sendable()` calls.
+    stream_deficits: HashMap<StreamId, usize>,
+    /// Per-stream deficit counters for `drain_frames()`'s own deficit
+    /// round-robin pass, persisted across calls. Kept separate from
+    /// `stream_deficits` since `drain_frames()` and `next_sendable()` are
+    /// independent scheduling paths (wire-size-weighted vs. payload-budget-
+    /// weighted) that a caller may use one or the other of, or interleave.
+    drain_deficits: HashMap<StreamId, usize>,
+    /// Scheduling mode used by `next_sendable()`.
+    scheduling_mode: SchedulingMode,
+    /// Optional length-obfuscation policy consulted by `drain_frames()`.
+    padding_policy: Option<Box<dyn PaddingPolicy>>,
+    /// Connection-wide aggregate send window: the highest cumulative byte
+    /// offset, summed across every stream's `send()` calls, this side is
+    /// permitted to send. Raised by an inbound
+    /// `Frame::WindowUpdate { stream_id: CONNECTION_STREAM_ID, .. }`.
+    conn_send_max_offset: u64,
+    /// Cumulative bytes queued via `send()` across all streams so far.
+    conn_send_bytes_sent: u64,
+    /// Size of the credit re-issued by a connection-level `WindowUpdate`,
+    /// mirroring `Stream::recv_window`.
+    conn_recv_window: u64,
+    /// Highest cumulative inbound byte offset, summed across every stream's
+    /// `Frame::Data`, we will accept without rejecting the frame for
+    /// connection-level flow control.
+    conn_recv_max_offset: u64,
+    /// Cumulative bytes offered to `poll()` as `Frame::Data` across all
+    /// streams so far.
+    conn_recv_bytes_received: u64,
+    /// Cumulative bytes handed to the application via `recv()` across all
+    /// streams.
+    conn_recv_bytes_consumed: u64,
+    /// Connection-level control frames (the connection-level
+    /// `Frame::WindowUpdate`, plus frames reinstated by `on_timeout`/a
+    /// `Frame::Nack` via a stream's `retransmit()`) queued for the next
+    /// `drain_frames()`, kept separate from any one stream's `pending_frames`
+    /// so recovery traffic skips the weighted deficit pass and goes out with
+    /// the next `drain_frames()` call regardless of scheduling fairness.
+    conn_pending_frames: Vec<Frame>,
+    /// Total number of frames re-sent across all streams via `on_timeout` or
+    /// a `Frame::Nack`, for observability (see `retransmit_count`).
+    retransmit_count: u64,
+    /// Packet pacer consulted by `drain_frames_paced`. `None` (the default)
+    /// means pacing is disabled and callers should use the unpaced
+    /// `drain_frames`, mirroring `padding_policy`'s opt-in shape.
+    pacer: Option<Pacer>,
+    /// `conn_send_max_offset` value a connection-level `Frame::DataBlocked`
+    /// was last queued for, mirroring `Stream::data_blocked_limit_sent` so
+    /// repeated `send()` calls stalled on the same limit announce it once.
+    conn_data_blocked_limit_sent: Option<u64>,
+    /// Whether a `Frame::StreamsBlocked` has already been queued for the
+    /// current `max_streams` (which never changes after construction), so a
+    /// caller retrying `create_stream` against a full connection doesn't
+    /// queue a duplicate announcement on every attempt.
+    streams_blocked_sent: bool,
+    /// Set by `go_away()`: once draining, `create_stream_with_priority`
+    /// refuses to open any further stream, mirroring the `Frame::GoAway`
+    /// just queued for the peer.
+    draining: bool,
+    /// `last_stream_id` from the most recent inbound `Frame::GoAway`, if
+    /// any -- the peer has told us it will not process anything past this
+    /// ID, so a caller should stop calling `create_stream` once this is set
+    /// (see `peer_goaway`).
+    peer_goaway: Option<u32>,
 }
 
 impl Multiplexer {
-    /// Create a new multiplexer.
+    /// Create a new multiplexer with the default replay window width.
     pub fn new(max_streams: u32) -> Self {
+        Self::with_replay_window(max_streams, DEFAULT_WINDOW_WIDTH)
+    }
+
+    /// Create a new multiplexer with a non-default connection-wide aggregate
+    /// flow-control window (see `DEFAULT_CONN_WINDOW`), e.g. for tests that
+    /// want to exercise connection-level backpressure without sending
+    /// megabytes of data.
+    pub fn with_conn_window(max_streams: u32, conn_window: u64) -> Self {
+        let mut mux = Self::new(max_streams);
+        mux.conn_send_max_offset = conn_window;
+        mux.conn_recv_window = conn_window;
+        mux.conn_recv_max_offset = conn_window;
+        mux
+    }
+
+    /// Create a new multiplexer with an explicit anti-replay window width
+    /// (clamped to `1..=64`; see [`ReplayWindow`]).
+    pub fn with_replay_window(max_streams: u32, replay_window_width: u32) -> Self {
         Self {
             streams: HashMap::new(),
             next_client_stream_id: 1,
             max_streams,
+            replay_windows: HashMap::new(),
+            replay_window_width,
+            stream_deficits: HashMap::new(),
+            drain_deficits: HashMap::new(),
+            scheduling_mode: SchedulingMode::WeightedFair,
+            padding_policy: None,
+            conn_send_max_offset: DEFAULT_CONN_WINDOW,
+            conn_send_bytes_sent: 0,
+            conn_recv_window: DEFAULT_CONN_WINDOW,
+            conn_recv_max_offset: DEFAULT_CONN_WINDOW,
+            conn_recv_bytes_received: 0,
+            conn_recv_bytes_consumed: 0,
+            conn_pending_frames: Vec::new(),
+            retransmit_count: 0,
+            pacer: None,
+            conn_data_blocked_limit_sent: None,
+            streams_blocked_sent: false,
+            draining: false,
+            peer_goaway: None,
         }
     }
 
-    /// Create a new stream with the given transport mode.
-    /// Returns the stream ID.
+    /// Set the scheduling mode used by `next_sendable()`.
+    pub fn set_scheduling_mode(&mut self, mode: SchedulingMode) {
+        self.scheduling_mode = mode;
+    }
+
+    /// Set (or clear, with `None`) the padding policy consulted by
+    /// `drain_frames()` to reshape outgoing records for traffic-analysis
+    /// resistance. See [`crate::padding`].
+    pub fn set_padding_policy(&mut self, policy: Option<Box<dyn PaddingPolicy>>) {
+        self.padding_policy = policy;
+    }
+
+    /// Set (or clear, with `None`) the packet pacer consulted by
+    /// `drain_frames_paced()`. Pacing is off by default (mirroring a
+    /// `no_pacing` option) -- call this with `Some(Pacer::default())` to
+    /// enable it, or `None` to go back to releasing whatever the congestion
+    /// window admits in one shot via the plain `drain_frames()`.
+    pub fn set_pacer(&mut self, pacer: Option<Pacer>) {
+        self.pacer = pacer;
+    }
+
+    /// Begin an orderly shutdown: queue a `Frame::GoAway` naming the highest
+    /// currently-open stream ID as `last_stream_id` (0 if there are none)
+    /// and refuse any further `create_stream`/`create_stream_with_priority`
+    /// call. Existing streams are left untouched so they can drain normally;
+    /// only opening *new* ones is blocked.
+    ///
+    /// Returns the `last_stream_id` that was announced.
+    pub fn go_away(&mut self, error_code: u32, debug: Bytes) -> StreamId {
+        let last_stream_id = self.streams.keys().copied().max().unwrap_or(0);
+        self.draining = true;
+        self.conn_pending_frames.push(Frame::GoAway {
+            last_stream_id,
+            error_code,
+            debug,
+        });
+        last_stream_id
+    }
+
+    /// Whether `go_away()` has been called on this side.
+    pub fn is_draining(&self) -> bool {
+        self.draining
+    }
+
+    /// The `last_stream_id` from the most recent inbound `Frame::GoAway`,
+    /// if the peer has asked us to stop opening new streams.
+    pub fn peer_goaway(&self) -> Option<u32> {
+        self.peer_goaway
+    }
+
+    /// Create a new stream with the given transport mode and the default
+    /// scheduling weight ([`DEFAULT_STREAM_WEIGHT`]). Returns the stream ID.
     pub fn create_stream(&mut self, mode: TransportMode) -> Result<StreamId> {
+        self.create_stream_with_priority(mode, DEFAULT_STREAM_WEIGHT)
+    }
+
+    /// Create a new stream with an explicit scheduling weight (see
+    /// [`Multiplexer::next_sendable`]). Returns the stream ID.
+    pub fn create_stream_with_priority(
+        &mut self,
+        mode: TransportMode,
+        weight: u32,
+    ) -> Result<StreamId> {
+        if self.draining {
+            return Err(StrandStreamError::ConnectionClosed);
+        }
         if self.streams.len() as u32 >= self.max_streams {
+            if !self.streams_blocked_sent {
+                self.streams_blocked_sent = true;
+                self.conn_pending_frames.push(Frame::StreamsBlocked {
+                    max_streams: self.max_streams,
+                });
+            }
             return Err(StrandStreamError::MaxStreamsExceeded(self.max_streams));
         }
 
@@ -46,45 +259,284 @@ impl Multiplexer {
         self.next_client_stream_id = self.next_client_stream_id.wrapping_add(2); // odd IDs
 
         let mut stream = Stream::new(id, mode);
+        stream.set_priority(weight);
         stream.open()?;
         self.streams.insert(id, stream);
+        self.replay_windows
+            .insert(id, ReplayWindow::new(self.replay_window_width));
+        self.stream_deficits.insert(id, 0);
         Ok(id)
     }
 
+    /// Set a stream's scheduling weight (used by both `WeightedFair` and
+    /// `StrictPriority` modes).
+    pub fn set_priority(&mut self, stream_id: StreamId, weight: u32) -> Result<()> {
+        let stream = self
+            .streams
+            .get_mut(&stream_id)
+            .ok_or(StrandStreamError::StreamNotFound(stream_id))?;
+        stream.set_priority(weight);
+        Ok(())
+    }
+
     /// Queue data for sending on the given stream.
     ///
-    /// Delegates to the stream's mode-specific `TransportSender`, which assigns
-    /// a sequence number and buffers the resulting `Frame` for network dispatch.
-    /// Call `drain_frames()` to retrieve the ready-to-send frames.
+    /// Checked against the connection-wide aggregate send window first
+    /// (`conn_send_max_offset`, see `DEFAULT_CONN_WINDOW`) -- exceeding it
+    /// returns `ConnectionFlowControlViolation` without touching the stream
+    /// at all, so a single fast stream (or many small ones together) can't
+    /// buffer unbounded data even if each stays under its own per-stream
+    /// window. Delegates to the stream's mode-specific `TransportSender`,
+    /// which assigns a sequence number and buffers the resulting `Frame` for
+    /// network dispatch; that call applies the *per-stream* window and can
+    /// itself return `FlowControlViolation`. Call `drain_frames()` to
+    /// retrieve the ready-to-send frames.
     pub fn send(&mut self, stream_id: StreamId, data: Bytes) -> Result<()> {
+        let len = data.len() as u64;
+        if self.conn_send_bytes_sent.saturating_add(len) > self.conn_send_max_offset {
+            if self.conn_data_blocked_limit_sent != Some(self.conn_send_max_offset) {
+                self.conn_data_blocked_limit_sent = Some(self.conn_send_max_offset);
+                self.conn_pending_frames.push(Frame::DataBlocked {
+                    limit: self.conn_send_max_offset,
+                });
+            }
+            return Err(StrandStreamError::ConnectionFlowControlViolation);
+        }
         let stream = self
             .streams
             .get_mut(&stream_id)
             .ok_or(StrandStreamError::StreamNotFound(stream_id))?;
-        stream.send(data)
+        stream.send(data)?;
+        self.conn_send_bytes_sent += len;
+        Ok(())
     }
 
     /// Receive data from the given stream (returns None if no data available).
+    ///
+    /// Bytes handed back are also charged against the connection-wide
+    /// aggregate receive window's consumed counter, which may queue a
+    /// connection-level `Frame::WindowUpdate` for the next `drain_frames()`
+    /// (see `maybe_emit_conn_window_update`) the same way draining a
+    /// stream's own window queues a per-stream one.
     pub fn recv(&mut self, stream_id: StreamId) -> Result<Option<Bytes>> {
         let stream = self
             .streams
             .get_mut(&stream_id)
             .ok_or(StrandStreamError::StreamNotFound(stream_id))?;
-        stream.recv()
+        let popped = stream.recv()?;
+        if let Some(data) = &popped {
+            self.conn_recv_bytes_consumed += data.len() as u64;
+            self.maybe_emit_conn_window_update();
+        }
+        Ok(popped)
+    }
+
+    /// Re-open the connection-level receive window once consumption has
+    /// eaten into half of it, mirroring `Stream::maybe_emit_window_update`
+    /// but for the aggregate window shared across all streams.
+    fn maybe_emit_conn_window_update(&mut self) {
+        let unused = self
+            .conn_recv_max_offset
+            .saturating_sub(self.conn_recv_bytes_consumed);
+        if unused >= self.conn_recv_window / 2 {
+            return;
+        }
+        let new_max_offset = self.conn_recv_bytes_consumed + self.conn_recv_window;
+        if new_max_offset <= self.conn_recv_max_offset {
+            return;
+        }
+        let increment = (new_max_offset - self.conn_recv_max_offset).min(u32::MAX as u64) as u32;
+        self.conn_recv_max_offset = new_max_offset;
+        self.conn_pending_frames.push(Frame::WindowUpdate {
+            stream_id: CONNECTION_STREAM_ID,
+            window_increment: increment,
+        });
+    }
+
+    /// The connection-wide aggregate send window remaining, in bytes.
+    pub fn conn_send_window_remaining(&self) -> u64 {
+        self.conn_send_max_offset.saturating_sub(self.conn_send_bytes_sent)
+    }
+
+    /// The connection-wide aggregate receive offset currently accepted.
+    pub fn conn_recv_max_offset(&self) -> u64 {
+        self.conn_recv_max_offset
+    }
+
+    /// Total bytes currently in flight across every stream's congestion
+    /// controller (see `Stream::bytes_in_flight`).
+    pub fn bytes_in_flight(&self) -> usize {
+        self.streams.values().map(Stream::bytes_in_flight).sum()
     }
 
-    /// Drain all outbound frames produced by `send()` calls on all streams.
+    /// Total number of frames re-sent so far via `on_timeout` or an inbound
+    /// `Frame::Nack`.
+    pub fn retransmit_count(&self) -> u64 {
+        self.retransmit_count
+    }
+
+    /// Check every stream's RTO-gated retransmit timer and re-queue whatever
+    /// frames are now overdue.
+    ///
+    /// Each stream's `Stream::retransmit` decides internally (via its
+    /// mode-specific `TransportSender`'s own backoff timer) which frames, if
+    /// any, are due; overdue frames are pushed into `conn_pending_frames` so
+    /// the next `drain_frames()` resends them ahead of fresh data, and the
+    /// charge against the stream's congestion window (`on_loss`) has already
+    /// happened inside `retransmit()`.
+    ///
+    /// Returns the IDs of streams that gave up on a frame (exceeded their
+    /// maximum retransmission attempts) -- callers should treat those as
+    /// fatal for the stream, e.g. by resetting it.
+    pub fn on_timeout(&mut self) -> Vec<StreamId> {
+        let mut given_up = Vec::new();
+        for (&stream_id, stream) in self.streams.iter_mut() {
+            match stream.retransmit() {
+                Ok(frames) => {
+                    self.retransmit_count += frames.len() as u64;
+                    self.conn_pending_frames.extend(frames);
+                }
+                Err(_) => given_up.push(stream_id),
+            }
+        }
+        given_up
+    }
+
+    /// Drain all outbound frames produced by `send()` calls on all streams,
+    /// plus any queued connection-level control frames (the connection-level
+    /// `Frame::WindowUpdate` from `maybe_emit_conn_window_update`, and
+    /// `Frame::DataBlocked`/`Frame::StreamsBlocked` queued when `send()`/
+    /// `create_stream` hit a connection-wide limit).
     ///
     /// Returns a flat `Vec<Frame>` ready to be serialised and sent to the
-    /// network layer.  The order within the vector is stream-creation order
-    /// (HashMap iteration), which is non-deterministic but acceptable since
-    /// each stream maintains its own per-stream sequence numbering.
+    /// network layer. Connection-level control frames come first, then every
+    /// stream's pending frames interleaved by deficit round-robin (see
+    /// `drain_stream_frames`) in ascending stream-ID order, so output is
+    /// reproducible and a high-weight stream (e.g. an interactive control
+    /// stream) doesn't wait behind an unrelated stream's entire backlog the
+    /// way unordered `HashMap` iteration did.
+    ///
+    /// If a [`PaddingPolicy`](crate::padding::PaddingPolicy) is configured
+    /// (see [`Self::set_padding_policy`]), the real frames are followed by
+    /// standalone `Frame::Padding` frames sized to reshape the output per
+    /// the policy, including a standalone padding frame when this call would
+    /// otherwise return empty.
     pub fn drain_frames(&mut self) -> Vec<Frame> {
-        let mut frames = Vec::new();
-        for stream in self.streams.values_mut() {
-            frames.extend(stream.drain_frames());
+        let mut frames = std::mem::take(&mut self.conn_pending_frames);
+        frames.extend(self.drain_stream_frames());
+        self.apply_padding(frames)
+    }
+
+    /// Like `drain_frames`, but additionally gated by the configured
+    /// `Pacer` (see `set_pacer`): frames beyond what the pacing budget
+    /// admits at `now` are held back and re-queued ahead of everything else
+    /// for the next call, instead of going out in the same burst
+    /// `drain_frames` would produce. With no pacer set, this is exactly
+    /// `drain_frames`.
+    ///
+    /// `rate` is the pacing rate in bytes/second to budget against (see
+    /// `CongestionController::pacing_rate`); like `on_timeout`'s RTO and
+    /// `RetransmissionEngine`'s explicit `srtt` parameter, the multiplexer
+    /// doesn't keep its own RTT estimate, so the caller -- who already has
+    /// one -- supplies it.
+    pub fn drain_frames_paced(&mut self, now: Instant, rate: f64) -> Vec<Frame> {
+        if self.pacer.is_none() {
+            return self.drain_frames();
         }
-        frames
+
+        let frames = {
+            let mut frames = std::mem::take(&mut self.conn_pending_frames);
+            frames.extend(self.drain_stream_frames());
+            self.apply_padding(frames)
+        };
+
+        let pacer = self.pacer.as_mut().unwrap();
+        let mut admitted = Vec::with_capacity(frames.len());
+        let mut held = Vec::new();
+        for frame in frames {
+            match pacer.check(now, frame.encoded_len(), rate) {
+                PacingDecision::SendNow => admitted.push(frame),
+                PacingDecision::Delay(_) => held.push(frame),
+            }
+        }
+
+        // Re-queue whatever got paced out ahead of anything queued since,
+        // so the next call picks it up first.
+        self.conn_pending_frames.splice(0..0, held);
+        admitted
+    }
+
+    /// Drain every stream's pending frames in deficit round-robin order,
+    /// weighted by [`Stream::priority`] (see [`Multiplexer::set_priority`]).
+    ///
+    /// Unlike `next_sendable`, this has no byte budget -- it always drains
+    /// every ready stream completely -- so the deficit pass only determines
+    /// *interleaving order*, not how much is sent. Each ready stream's
+    /// deficit (persisted in `drain_deficits` across calls) is topped up by
+    /// `weight * BASE_QUANTUM` every round; a stream emits frames while its
+    /// deficit covers the next frame's on-wire size
+    /// ([`Frame::encoded_len`]), carrying any leftover into the next round.
+    fn drain_stream_frames(&mut self) -> Vec<Frame> {
+        let ready = self.ready_stream_ids();
+        if ready.is_empty() {
+            return Vec::new();
+        }
+
+        for &id in &ready {
+            let weight = self.streams.get(&id).map(|s| s.priority()).unwrap_or(1);
+            let deficit = self.drain_deficits.entry(id).or_insert(0);
+            *deficit = deficit.saturating_add(weight as usize * BASE_QUANTUM);
+        }
+
+        let mut out = Vec::new();
+        let mut progressed = true;
+        while progressed {
+            progressed = false;
+            for &id in &ready {
+                let deficit = self.drain_deficits.entry(id).or_insert(0);
+                let stream = match self.streams.get_mut(&id) {
+                    Some(stream) => stream,
+                    None => continue,
+                };
+                while let Some(len) = stream.peek_pending_frame_encoded_len() {
+                    if len > *deficit {
+                        break;
+                    }
+                    if let Some(frame) = stream.pop_pending_frame() {
+                        *deficit -= len;
+                        out.push(frame);
+                        progressed = true;
+                    }
+                }
+            }
+        }
+
+        out
+    }
+
+    /// Reshape `frames` according to the configured padding policy, if any.
+    fn apply_padding(&mut self, frames: Vec<Frame>) -> Vec<Frame> {
+        let Some(policy) = self.padding_policy.as_deref_mut() else {
+            return frames;
+        };
+
+        if frames.is_empty() {
+            return match policy.idle_padding() {
+                Some(len) => vec![Frame::Padding { len: len as u32 }],
+                None => frames,
+            };
+        }
+
+        let mut out = Vec::with_capacity(frames.len() * 2);
+        for frame in frames {
+            let real_len = frame.encoded_len();
+            let pad_len = policy.pad_len_for(real_len);
+            out.push(frame);
+            if pad_len > 0 {
+                out.push(Frame::Padding { len: pad_len as u32 });
+            }
+        }
+        out
     }
 
     /// Close a stream.
@@ -96,26 +548,67 @@ impl Multiplexer {
         stream.close()
     }
 
+    /// Abruptly reset a stream with an application error code, queuing a
+    /// `Frame::Rst` for `drain_frames()` to hand to the network layer so the
+    /// peer learns why (see `Stream::reset_with`).
+    pub fn reset_stream(&mut self, stream_id: StreamId, code: u32) -> Result<()> {
+        let stream = self
+            .streams
+            .get_mut(&stream_id)
+            .ok_or(StrandStreamError::StreamNotFound(stream_id))?;
+        stream.reset_with(code);
+        Ok(())
+    }
+
     /// Dispatch an incoming frame to the appropriate stream.
     ///
-    /// For DATA frames, the frame is forwarded to the stream's mode-specific
-    /// `TransportReceiver` via `transport_receive()`.  The receiver applies
-    /// mode semantics (ordering reassembly for RO, deduplication for RU,
-    /// probabilistic drop for PR, unconditional delivery for BE) and enqueues
-    /// any ready payloads into the stream's application receive buffer.
+    /// For DATA frames, `seq` is first checked against the stream's anti-replay
+    /// sliding window (see [`ReplayWindow`]); a too-old or duplicate sequence
+    /// number is rejected with `ReplayedFrame` before it ever reaches the
+    /// transport-mode receiver. Frames that pass are forwarded to the stream's
+    /// mode-specific `TransportReceiver` via `transport_receive()`. The
+    /// receiver applies mode semantics (ordering reassembly for RO,
+    /// deduplication for RU, probabilistic drop for PR, unconditional
+    /// delivery for BE) and enqueues any ready payloads into the stream's
+    /// application receive buffer.
     ///
     /// For FIN frames, marks the remote side as closed.
     /// For RST frames, resets the stream and removes it from the map.
+    /// For GO_AWAY frames, records the peer's `last_stream_id` (see
+    /// `peer_goaway`); existing streams are unaffected.
+    /// For WINDOW_UPDATE frames addressed to `CONNECTION_STREAM_ID`, raises
+    /// the connection-wide aggregate send window (`conn_send_max_offset`);
+    /// otherwise raises the target stream's send-side flow control limit
+    /// (see `Stream::apply_window_update`).
     pub fn poll(&mut self, frame: &Frame) -> Result<()> {
         match frame {
-            Frame::Data { stream_id, .. } => {
+            Frame::Data { stream_id, seq, payload, .. } => {
                 Self::validate_stream_id(*stream_id)?;
+                if !self.streams.contains_key(stream_id) {
+                    return Err(StrandStreamError::StreamNotFound(*stream_id));
+                }
+
+                let incoming = self
+                    .conn_recv_bytes_received
+                    .saturating_add(payload.len() as u64);
+                if incoming > self.conn_recv_max_offset {
+                    return Err(StrandStreamError::ConnectionFlowControlBlocked);
+                }
+
+                let window_width = self.replay_window_width;
+                let window = self
+                    .replay_windows
+                    .entry(*stream_id)
+                    .or_insert_with(|| ReplayWindow::new(window_width));
+                window.check_and_update(*seq)?;
+
                 let stream = self
                     .streams
                     .get_mut(stream_id)
                     .ok_or(StrandStreamError::StreamNotFound(*stream_id))?;
                 // Delegate to the mode-specific receiver for ordering / dedup.
                 stream.transport_receive(frame)?;
+                self.conn_recv_bytes_received = incoming;
                 Ok(())
             }
             Frame::Fin { stream_id } => {
@@ -127,20 +620,98 @@ impl Multiplexer {
                 stream.remote_close();
                 Ok(())
             }
-            Frame::Rst { stream_id, .. } => {
+            Frame::Rst { stream_id, error_code, final_size } => {
                 Self::validate_stream_id(*stream_id)?;
                 let stream = self
                     .streams
                     .get_mut(stream_id)
                     .ok_or(StrandStreamError::StreamNotFound(*stream_id))?;
-                stream.reset();
+                stream.on_reset(*error_code, *final_size)?;
                 // Remove immediately: RST terminates the stream in both directions.
                 self.streams.remove(stream_id);
+                self.replay_windows.remove(stream_id);
+                self.stream_deficits.remove(stream_id);
+                Ok(())
+            }
+            Frame::Padding { .. } => {
+                // Length-obfuscation filler: discarded here so it never
+                // reaches a stream's receive buffer (see `crate::padding`).
+                Ok(())
+            }
+            Frame::WindowUpdate { stream_id, window_increment } => {
+                if *stream_id == CONNECTION_STREAM_ID {
+                    self.conn_send_max_offset =
+                        self.conn_send_max_offset.saturating_add(*window_increment as u64);
+                    self.conn_data_blocked_limit_sent = None;
+                    return Ok(());
+                }
+                Self::validate_stream_id(*stream_id)?;
+                let stream = self
+                    .streams
+                    .get_mut(stream_id)
+                    .ok_or(StrandStreamError::StreamNotFound(*stream_id))?;
+                stream.apply_window_update(*window_increment);
+                Ok(())
+            }
+            Frame::Ack { stream_id, ack_seq, ranges } => {
+                // Feeds `Stream::on_ack`, which charges the acked bytes to
+                // the stream's `CongestionController` (see `crate::congestion`) in
+                // addition to clearing the retransmit buffer -- this is the
+                // only path that advances a reliable stream's `cwnd`, so
+                // acks that never reach here leave the stream stuck at its
+                // initial window.
+                Self::validate_stream_id(*stream_id)?;
+                let stream = self
+                    .streams
+                    .get_mut(stream_id)
+                    .ok_or(StrandStreamError::StreamNotFound(*stream_id))?;
+                stream.on_ack(*ack_seq);
+                for range in ranges {
+                    for seq in range.start..=range.end {
+                        stream.on_ack(seq);
+                    }
+                }
+                Ok(())
+            }
+            Frame::Nack { stream_id, ranges } => {
+                // A NACK is an explicit loss signal: prompt the stream's own
+                // RTO-gated retransmit check rather than waiting for
+                // `on_timeout` to notice independently. We don't have a way
+                // to force-resend only the named `ranges` -- `retransmit()`
+                // is driven by the sender's own backoff timer -- so an empty
+                // `ranges` (or one that names only already-acked frames)
+                // legitimately yields nothing here.
+                Self::validate_stream_id(*stream_id)?;
+                let stream = self
+                    .streams
+                    .get_mut(stream_id)
+                    .ok_or(StrandStreamError::StreamNotFound(*stream_id))?;
+                if !ranges.is_empty() {
+                    let frames = stream.retransmit()?;
+                    self.retransmit_count += frames.len() as u64;
+                    self.conn_pending_frames.extend(frames);
+                }
+                Ok(())
+            }
+            Frame::GoAway { last_stream_id, .. } => {
+                self.peer_goaway = Some(*last_stream_id);
+                Ok(())
+            }
+            Frame::StopSending { stream_id, error_code } => {
+                Self::validate_stream_id(*stream_id)?;
+                let stream = self
+                    .streams
+                    .get_mut(stream_id)
+                    .ok_or(StrandStreamError::StreamNotFound(*stream_id))?;
+                stream.on_stop_sending(*error_code)?;
                 Ok(())
             }
             _ => {
-                // Other frame types (ACK, NACK, Ping, Pong, WindowUpdate)
-                // are handled by the connection layer, not the mux.
+                // Other frame types (Ping, Pong) carry no per-stream state
+                // the mux needs to update. `Frame::Datagram` also lands here:
+                // it carries no `stream_id` to dispatch on, so delivering it
+                // is the caller's job via `Stream::transport_receive` on
+                // whichever BestEffort stream it owns, not `Multiplexer::poll`.
                 Ok(())
             }
         }
@@ -165,6 +736,131 @@ impl Multiplexer {
     pub fn remove_closed_streams(&mut self) {
         self.streams
             .retain(|_, s| s.state() != StreamState::Closed);
+        let live_ids = &self.streams;
+        self.replay_windows.retain(|id, _| live_ids.contains_key(id));
+        self.stream_deficits.retain(|id, _| live_ids.contains_key(id));
+        self.drain_deficits.retain(|id, _| live_ids.contains_key(id));
+    }
+
+    /// Select which pending `Data` frames to send next, distributing
+    /// `budget` bytes across ready streams according to the configured
+    /// [`SchedulingMode`].
+    ///
+    /// A stream is "ready" if it has at least one pending outbound frame. Frames are dequeued whole, never split mid-frame: in
+    /// `WeightedFair` mode a frame leaves a stream's queue once its
+    /// weight-proportional quantum covers the frame's size, with any unused
+    /// deficit carried into the next call so low-weight streams still make
+    /// progress; in `StrictPriority` mode, only the highest-weight class of
+    /// ready streams is serviced at all.
+    pub fn next_sendable(&mut self, budget: usize) -> Vec<(StreamId, Bytes)> {
+        match self.scheduling_mode {
+            SchedulingMode::WeightedFair => self.next_sendable_weighted(budget),
+            SchedulingMode::StrictPriority => self.next_sendable_strict_priority(budget),
+        }
+    }
+
+    /// Stream IDs with at least one pending outbound frame, in ascending
+    /// order for deterministic round-robin iteration.
+    fn ready_stream_ids(&self) -> Vec<StreamId> {
+        let mut ids: Vec<StreamId> = self
+            .streams
+            .iter()
+            .filter(|(_, s)| s.has_pending_frames())
+            .map(|(id, _)| *id)
+            .collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    fn next_sendable_weighted(&mut self, mut budget: usize) -> Vec<(StreamId, Bytes)> {
+        let mut out = Vec::new();
+        let ready = self.ready_stream_ids();
+        if ready.is_empty() {
+            return out;
+        }
+
+        // Top up each ready stream's carried-over deficit with its
+        // weight-proportional quantum for this round.
+        for &id in &ready {
+            let weight = self.streams.get(&id).map(|s| s.priority()).unwrap_or(1);
+            let deficit = self.stream_deficits.entry(id).or_insert(0);
+            *deficit = deficit.saturating_add(weight as usize * BASE_QUANTUM);
+        }
+
+        // Round-robin over ready streams, dequeuing whole frames while they
+        // fit both the stream's deficit and the remaining budget, until a
+        // full pass makes no further progress.
+        let mut progressed = true;
+        while budget > 0 && progressed {
+            progressed = false;
+            for &id in &ready {
+                if budget == 0 {
+                    break;
+                }
+                let deficit = self.stream_deficits.entry(id).or_insert(0);
+                let stream = match self.streams.get_mut(&id) {
+                    Some(stream) => stream,
+                    None => continue,
+                };
+                while let Some(len) = stream.peek_pending_frame_len() {
+                    if len > *deficit || len > budget {
+                        break;
+                    }
+                    if let Some(Frame::Data { payload, .. }) = stream.pop_pending_frame() {
+                        *deficit -= payload.len();
+                        budget -= payload.len();
+                        out.push((id, payload));
+                        progressed = true;
+                    }
+                }
+            }
+        }
+
+        out
+    }
+
+    fn next_sendable_strict_priority(&mut self, mut budget: usize) -> Vec<(StreamId, Bytes)> {
+        let mut out = Vec::new();
+        let ready = self.ready_stream_ids();
+        let max_weight = ready
+            .iter()
+            .filter_map(|id| self.streams.get(id).map(|s| s.priority()))
+            .max();
+        let max_weight = match max_weight {
+            Some(weight) => weight,
+            None => return out,
+        };
+
+        let top_class: Vec<StreamId> = ready
+            .into_iter()
+            .filter(|id| self.streams.get(id).map(|s| s.priority()) == Some(max_weight))
+            .collect();
+
+        let mut progressed = true;
+        while budget > 0 && progressed {
+            progressed = false;
+            for &id in &top_class {
+                if budget == 0 {
+                    break;
+                }
+                let stream = match self.streams.get_mut(&id) {
+                    Some(stream) => stream,
+                    None => continue,
+                };
+                while let Some(len) = stream.peek_pending_frame_len() {
+                    if len > budget {
+                        break;
+                    }
+                    if let Some(Frame::Data { payload, .. }) = stream.pop_pending_frame() {
+                        budget -= payload.len();
+                        out.push((id, payload));
+                        progressed = true;
+                    }
+                }
+            }
+        }
+
+        out
     }
 
     /// Returns a reference to a stream by ID.
@@ -177,6 +873,30 @@ impl Multiplexer {
         self.streams.get_mut(&stream_id)
     }
 
+    /// Detach a stream from the multiplexer and split it into independent
+    /// send/receive halves (see [`Stream::split`]).
+    ///
+    /// The stream is removed from this multiplexer entirely -- along with
+    /// its replay window and scheduler deficits -- since `get_stream`/
+    /// `get_stream_mut`/`poll`/`drain_frames`/`next_sendable` all operate on
+    /// streams still owned by `self.streams`. After this call, the caller
+    /// drives the returned [`StreamSender`]/[`StreamReceiver`] directly:
+    /// inbound frames addressed to `stream_id` must be routed to the
+    /// `StreamReceiver` (or `StreamSender`, for `Frame::Ack`/
+    /// `Frame::WindowUpdate`) by the caller instead of `Multiplexer::poll`,
+    /// and outbound frames are collected via `StreamSender::drain_frames`
+    /// instead of `Multiplexer::drain_frames`.
+    pub fn split_stream(&mut self, stream_id: StreamId) -> Result<(StreamSender, StreamReceiver)> {
+        let stream = self
+            .streams
+            .remove(&stream_id)
+            .ok_or(StrandStreamError::StreamNotFound(stream_id))?;
+        self.replay_windows.remove(&stream_id);
+        self.stream_deficits.remove(&stream_id);
+        self.drain_deficits.remove(&stream_id);
+        Ok(stream.split())
+    }
+
     /// Returns the number of active (non-closed) streams.
     pub fn active_stream_count(&self) -> usize {
         self.streams
@@ -261,6 +981,7 @@ mod tests {
         let frame = Frame::Rst {
             stream_id: sid,
             error_code: 42,
+            final_size: 0,
         };
         mux.poll(&frame).unwrap();
 
@@ -269,6 +990,26 @@ mod tests {
         assert!(mux.get_stream(sid).is_none());
     }
 
+    #[test]
+    fn inbound_stop_sending_stops_local_send_without_removing_stream() {
+        let mut mux = Multiplexer::new(100);
+        let sid = mux.create_stream(TransportMode::ReliableOrdered).unwrap();
+
+        let frame = Frame::StopSending {
+            stream_id: sid,
+            error_code: 7,
+        };
+        mux.poll(&frame).unwrap();
+
+        // Unlike RST, the stream stays put -- only its local send side stops.
+        assert_eq!(mux.stream_count(), 1);
+        let err = mux.send(sid, Bytes::from_static(b"x")).unwrap_err();
+        assert!(matches!(
+            err,
+            StrandStreamError::StreamSendStopped { stream_id, error_code: 7 } if stream_id == sid
+        ));
+    }
+
     #[test]
     fn remove_closed_streams_cleans_up() {
         let mut mux = Multiplexer::new(100);
@@ -287,6 +1028,257 @@ mod tests {
         assert_eq!(mux.stream_count(), 0);
     }
 
+    #[test]
+    fn on_timeout_requeues_overdue_frame_and_counts_retransmit() {
+        use std::thread::sleep;
+        use std::time::Duration;
+
+        let mut mux = Multiplexer::new(100);
+        let sid = mux.create_stream(TransportMode::ReliableOrdered).unwrap();
+        mux.send(sid, Bytes::from_static(b"hello")).unwrap();
+        // Move the frame out of `pending_frames` into flight, as a real
+        // sender would after dispatching it to the network.
+        mux.drain_frames();
+
+        sleep(Duration::from_millis(30));
+        let given_up = mux.on_timeout();
+        assert!(given_up.is_empty());
+        assert_eq!(mux.retransmit_count(), 1);
+
+        let redrained = mux.drain_frames();
+        assert_eq!(redrained.len(), 1);
+        assert!(matches!(redrained[0], Frame::Data { .. }));
+    }
+
+    #[test]
+    fn nack_frame_triggers_retransmit() {
+        let mut mux = Multiplexer::new(100);
+        let sid = mux.create_stream(TransportMode::ReliableOrdered).unwrap();
+        mux.send(sid, Bytes::from_static(b"hello")).unwrap();
+        mux.drain_frames();
+
+        // No RTO has elapsed yet, so even an explicit NACK finds nothing
+        // overdue -- `retransmit()` is still gated by the sender's own timer.
+        let nack = Frame::Nack {
+            stream_id: sid,
+            ranges: vec![crate::frame::SeqRange { start: 0, end: 0 }],
+        };
+        mux.poll(&nack).unwrap();
+        assert_eq!(mux.retransmit_count(), 0);
+    }
+
+    #[test]
+    fn bytes_in_flight_reflects_unacked_stream_data() {
+        let mut mux = Multiplexer::new(100);
+        let sid = mux.create_stream(TransportMode::ReliableOrdered).unwrap();
+        assert_eq!(mux.bytes_in_flight(), 0);
+
+        mux.send(sid, Bytes::from_static(b"hello")).unwrap();
+        assert_eq!(mux.bytes_in_flight(), 5);
+
+        let ack = Frame::Ack {
+            stream_id: sid,
+            ack_seq: 0,
+            ranges: Vec::new(),
+        };
+        mux.poll(&ack).unwrap();
+        assert_eq!(mux.bytes_in_flight(), 0);
+    }
+
+    #[test]
+    fn ack_frame_advances_stream_congestion_window() {
+        let mut mux = Multiplexer::new(100);
+        let sid = mux.create_stream(TransportMode::ReliableOrdered).unwrap();
+        mux.send(sid, Bytes::from_static(b"hello")).unwrap();
+
+        let stream = mux.get_stream_mut(sid).unwrap();
+        let before = stream.congestion_window();
+        let sent = stream.drain_send();
+        assert_eq!(sent.len(), 1);
+
+        let ack = Frame::Ack {
+            stream_id: sid,
+            ack_seq: 0,
+            ranges: Vec::new(),
+        };
+        mux.poll(&ack).unwrap();
+
+        let stream = mux.get_stream_mut(sid).unwrap();
+        assert!(stream.congestion_window() > before);
+        assert_eq!(stream.bytes_in_flight(), 0);
+    }
+
+    #[test]
+    fn ack_frame_for_unknown_stream_errs() {
+        let mut mux = Multiplexer::new(100);
+        let ack = Frame::Ack {
+            stream_id: 7,
+            ack_seq: 0,
+            ranges: Vec::new(),
+        };
+        assert!(mux.poll(&ack).is_err());
+    }
+
+    #[test]
+    fn replayed_data_frame_rejected() {
+        let mut mux = Multiplexer::new(100);
+        let sid = mux.create_stream(TransportMode::ReliableOrdered).unwrap();
+
+        let frame = Frame::Data {
+            stream_id: sid,
+            seq: 0,
+            flags: crate::frame::DataFlags::NONE,
+            payload: Bytes::from_static(b"first"),
+        };
+        mux.poll(&frame).unwrap();
+
+        // A replay of the same seq must be rejected, not delivered again.
+        let replay = frame.clone();
+        assert!(mux.poll(&replay).is_err());
+    }
+
+    #[test]
+    fn old_data_frame_outside_window_rejected() {
+        let mut mux = Multiplexer::with_replay_window(100, 8);
+        let sid = mux.create_stream(TransportMode::ReliableOrdered).unwrap();
+
+        let advance = Frame::Data {
+            stream_id: sid,
+            seq: 100,
+            flags: crate::frame::DataFlags::NONE,
+            payload: Bytes::from_static(b"advance"),
+        };
+        mux.poll(&advance).unwrap();
+
+        let too_old = Frame::Data {
+            stream_id: sid,
+            seq: 10,
+            flags: crate::frame::DataFlags::NONE,
+            payload: Bytes::from_static(b"stale"),
+        };
+        assert!(mux.poll(&too_old).is_err());
+    }
+
+    #[test]
+    fn next_sendable_drains_a_single_stream() {
+        let mut mux = Multiplexer::new(100);
+        let sid = mux.create_stream(TransportMode::BestEffort).unwrap();
+        mux.send(sid, Bytes::from_static(b"hello")).unwrap();
+
+        let sent = mux.next_sendable(1024);
+        assert_eq!(sent, vec![(sid, Bytes::from_static(b"hello"))]);
+        // Nothing left to send.
+        assert!(mux.next_sendable(1024).is_empty());
+    }
+
+    #[test]
+    fn next_sendable_weighted_favours_higher_priority() {
+        let mut mux = Multiplexer::new(100);
+        // weight 2 -> quantum 3000, weight 8 -> quantum 12000.
+        let low = mux
+            .create_stream_with_priority(TransportMode::BestEffort, 2)
+            .unwrap();
+        let high = mux
+            .create_stream_with_priority(TransportMode::BestEffort, 8)
+            .unwrap();
+
+        // Queue far more 1000-byte frames than either quantum can cover in
+        // one round, so each stream's output is bounded by its own deficit
+        // rather than by supply.
+        for _ in 0..20 {
+            mux.send(low, Bytes::from(vec![0u8; 1000])).unwrap();
+            mux.send(high, Bytes::from(vec![0u8; 1000])).unwrap();
+        }
+
+        let sent = mux.next_sendable(usize::MAX);
+        let high_count = sent.iter().filter(|(id, _)| *id == high).count();
+        let low_count = sent.iter().filter(|(id, _)| *id == low).count();
+        assert_eq!(low_count, 3); // 3000 / 1000
+        assert_eq!(high_count, 12); // 12000 / 1000
+    }
+
+    #[test]
+    fn next_sendable_weighted_splits_proportionally_across_three_streams() {
+        let mut mux = Multiplexer::new(100);
+        // Quanta: 1*1500, 2*1500, 4*1500 -- 1, 3 and 6 whole 1000-byte frames.
+        let a = mux
+            .create_stream_with_priority(TransportMode::BestEffort, 1)
+            .unwrap();
+        let b = mux
+            .create_stream_with_priority(TransportMode::BestEffort, 2)
+            .unwrap();
+        let c = mux
+            .create_stream_with_priority(TransportMode::BestEffort, 4)
+            .unwrap();
+
+        for _ in 0..30 {
+            mux.send(a, Bytes::from(vec![0u8; 1000])).unwrap();
+            mux.send(b, Bytes::from(vec![0u8; 1000])).unwrap();
+            mux.send(c, Bytes::from(vec![0u8; 1000])).unwrap();
+        }
+
+        let sent = mux.next_sendable(usize::MAX);
+        let count = |id: StreamId| sent.iter().filter(|(sid, _)| *sid == id).count();
+        assert_eq!(count(a), 1); // 1500 / 1000, rounded down
+        assert_eq!(count(b), 3); // 3000 / 1000
+        assert_eq!(count(c), 6); // 6000 / 1000
+        // Weights carry through proportionally even with a third stream
+        // sharing the round, not just in the two-stream case.
+        assert!(count(c) > count(b) && count(b) > count(a));
+    }
+
+    #[test]
+    fn next_sendable_carries_deficit_forward() {
+        let mut mux = Multiplexer::new(100);
+        let sid = mux.create_stream(TransportMode::BestEffort).unwrap();
+        mux.set_priority(sid, 1).unwrap();
+        // A single frame larger than one round's quantum.
+        mux.send(sid, Bytes::from(vec![0u8; BASE_QUANTUM + 1])).unwrap();
+
+        // First call: quantum doesn't cover the frame yet.
+        assert!(mux.next_sendable(usize::MAX).is_empty());
+        // Second call: the carried-over deficit plus this round's quantum does.
+        let sent = mux.next_sendable(usize::MAX);
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].0, sid);
+    }
+
+    #[test]
+    fn next_sendable_strict_priority_starves_lower_class() {
+        let mut mux = Multiplexer::new(100);
+        mux.set_scheduling_mode(SchedulingMode::StrictPriority);
+
+        let low = mux.create_stream(TransportMode::BestEffort).unwrap();
+        let high = mux
+            .create_stream_with_priority(TransportMode::BestEffort, 64)
+            .unwrap();
+
+        mux.send(low, Bytes::from_static(b"low")).unwrap();
+        mux.send(high, Bytes::from_static(b"high")).unwrap();
+
+        let sent = mux.next_sendable(1024);
+        assert_eq!(sent, vec![(high, Bytes::from_static(b"high"))]);
+
+        // The low-priority stream is untouched until the high one has nothing left.
+        let sent = mux.next_sendable(1024);
+        assert_eq!(sent, vec![(low, Bytes::from_static(b"low"))]);
+    }
+
+    #[test]
+    fn window_update_frame_raises_send_max_offset() {
+        let mut mux = Multiplexer::new(100);
+        let sid = mux.create_stream(TransportMode::BestEffort).unwrap();
+        let before = mux.get_stream(sid).unwrap().send_max_offset();
+
+        let frame = Frame::WindowUpdate {
+            stream_id: sid,
+            window_increment: 4096,
+        };
+        mux.poll(&frame).unwrap();
+
+        assert_eq!(mux.get_stream(sid).unwrap().send_max_offset(), before + 4096);
+    }
+
     #[test]
     fn reserved_stream_ids_rejected() {
         let mut mux = Multiplexer::new(100);
@@ -304,7 +1296,257 @@ mod tests {
         let frame_max = Frame::Rst {
             stream_id: 0xFFFF_FFFF,
             error_code: 0,
+            final_size: 0,
         };
         assert!(mux.poll(&frame_max).is_err());
     }
+
+    #[test]
+    fn send_blocked_once_connection_window_exhausted() {
+        let mut mux = Multiplexer::with_conn_window(100, 16);
+        let a = mux.create_stream(TransportMode::BestEffort).unwrap();
+        let b = mux.create_stream(TransportMode::BestEffort).unwrap();
+
+        // Two streams together exhaust the connection-wide window even
+        // though neither comes close to its own per-stream window.
+        mux.send(a, Bytes::from(vec![0u8; 8])).unwrap();
+        mux.send(b, Bytes::from(vec![0u8; 8])).unwrap();
+        assert_eq!(mux.conn_send_window_remaining(), 0);
+
+        let err = mux.send(a, Bytes::from_static(b"x")).unwrap_err();
+        assert!(matches!(err, StrandStreamError::ConnectionFlowControlViolation));
+    }
+
+    #[test]
+    fn connection_window_exhaustion_queues_data_blocked() {
+        let mut mux = Multiplexer::with_conn_window(100, 16);
+        let a = mux.create_stream(TransportMode::BestEffort).unwrap();
+        mux.send(a, Bytes::from(vec![0u8; 16])).unwrap();
+
+        assert!(mux.send(a, Bytes::from_static(b"x")).is_err());
+        assert!(mux.send(a, Bytes::from_static(b"y")).is_err());
+
+        // Only one DataBlocked should be queued for the same limit, even
+        // though the connection was blocked on two separate send() calls.
+        let frames = mux.drain_frames();
+        let blocked: Vec<&Frame> = frames
+            .iter()
+            .filter(|f| matches!(f, Frame::DataBlocked { .. }))
+            .collect();
+        assert_eq!(blocked.len(), 1);
+        assert!(matches!(blocked[0], Frame::DataBlocked { limit: 16 }));
+    }
+
+    #[test]
+    fn max_streams_exceeded_queues_streams_blocked() {
+        let mut mux = Multiplexer::new(1);
+        mux.create_stream(TransportMode::BestEffort).unwrap();
+
+        assert!(mux.create_stream(TransportMode::BestEffort).is_err());
+        assert!(mux.create_stream(TransportMode::BestEffort).is_err());
+
+        let frames = mux.drain_frames();
+        let blocked: Vec<&Frame> = frames
+            .iter()
+            .filter(|f| matches!(f, Frame::StreamsBlocked { .. }))
+            .collect();
+        assert_eq!(blocked.len(), 1);
+        assert!(matches!(blocked[0], Frame::StreamsBlocked { max_streams: 1 }));
+    }
+
+    #[test]
+    fn go_away_blocks_new_streams_and_names_last_stream_id() {
+        let mut mux = Multiplexer::new(100);
+        let a = mux.create_stream(TransportMode::BestEffort).unwrap();
+        let b = mux.create_stream(TransportMode::BestEffort).unwrap();
+
+        let last_stream_id = mux.go_away(0, Bytes::from_static(b"shutting down"));
+        assert_eq!(last_stream_id, a.max(b));
+        assert!(mux.is_draining());
+
+        let err = mux.create_stream(TransportMode::BestEffort).unwrap_err();
+        assert!(matches!(err, StrandStreamError::ConnectionClosed));
+
+        let frames = mux.drain_frames();
+        assert!(frames.iter().any(|f| matches!(
+            f,
+            Frame::GoAway {
+                last_stream_id: ls,
+                error_code: 0,
+                ..
+            } if *ls == a.max(b)
+        )));
+    }
+
+    #[test]
+    fn inbound_go_away_records_peer_last_stream_id() {
+        let mut mux = Multiplexer::new(100);
+        assert_eq!(mux.peer_goaway(), None);
+
+        mux.poll(&Frame::GoAway {
+            last_stream_id: 7,
+            error_code: 0,
+            debug: Bytes::new(),
+        })
+        .unwrap();
+
+        assert_eq!(mux.peer_goaway(), Some(7));
+    }
+
+    #[test]
+    fn connection_window_update_credits_aggregate_send_window() {
+        let mut mux = Multiplexer::with_conn_window(100, 8);
+        let sid = mux.create_stream(TransportMode::BestEffort).unwrap();
+        mux.send(sid, Bytes::from(vec![0u8; 8])).unwrap();
+        assert!(mux.send(sid, Bytes::from_static(b"x")).is_err());
+
+        let frame = Frame::WindowUpdate {
+            stream_id: CONNECTION_STREAM_ID,
+            window_increment: 1,
+        };
+        mux.poll(&frame).unwrap();
+
+        mux.send(sid, Bytes::from_static(b"x")).unwrap();
+        assert_eq!(mux.conn_send_window_remaining(), 0);
+    }
+
+    #[test]
+    fn data_exceeding_connection_window_rejected() {
+        let mut mux = Multiplexer::with_conn_window(100, 16);
+        let a = mux.create_stream(TransportMode::BestEffort).unwrap();
+        let b = mux.create_stream(TransportMode::BestEffort).unwrap();
+
+        let big = Frame::Data {
+            stream_id: a,
+            seq: 0,
+            flags: crate::frame::DataFlags::NONE,
+            payload: Bytes::from(vec![0u8; 16]),
+        };
+        mux.poll(&big).unwrap();
+
+        let overflow = Frame::Data {
+            stream_id: b,
+            seq: 0,
+            flags: crate::frame::DataFlags::NONE,
+            payload: Bytes::from_static(b"x"),
+        };
+        let err = mux.poll(&overflow).unwrap_err();
+        assert!(matches!(err, StrandStreamError::ConnectionFlowControlBlocked));
+    }
+
+    #[test]
+    fn recv_emits_connection_window_update_once_half_consumed() {
+        let mut mux = Multiplexer::with_conn_window(100, 16);
+        let sid = mux.create_stream(TransportMode::BestEffort).unwrap();
+
+        let frame = Frame::Data {
+            stream_id: sid,
+            seq: 0,
+            flags: crate::frame::DataFlags::NONE,
+            payload: Bytes::from(vec![0u8; 9]),
+        };
+        mux.poll(&frame).unwrap();
+        mux.recv(sid).unwrap();
+
+        let drained = mux.drain_frames();
+        assert!(drained.iter().any(|f| matches!(
+            f,
+            Frame::WindowUpdate { stream_id, .. } if *stream_id == CONNECTION_STREAM_ID
+        )));
+    }
+
+    #[test]
+    fn drain_frames_splits_proportionally_by_priority() {
+        let mut mux = Multiplexer::new(100);
+        // Each frame's encoded length is 14 + 86 = 100 bytes, so
+        // weight 1 -> quantum 1500 -> 15 frames/round, weight 4 -> quantum
+        // 6000 -> 60 frames/round.
+        let low = mux
+            .create_stream_with_priority(TransportMode::BestEffort, 1)
+            .unwrap();
+        let high = mux
+            .create_stream_with_priority(TransportMode::BestEffort, 4)
+            .unwrap();
+
+        for _ in 0..100 {
+            mux.send(low, Bytes::from(vec![0u8; 86])).unwrap();
+            mux.send(high, Bytes::from(vec![0u8; 86])).unwrap();
+        }
+
+        let drained = mux.drain_frames();
+        let low_count = drained
+            .iter()
+            .filter(|f| matches!(f, Frame::Data { stream_id, .. } if *stream_id == low))
+            .count();
+        let high_count = drained
+            .iter()
+            .filter(|f| matches!(f, Frame::Data { stream_id, .. } if *stream_id == high))
+            .count();
+        assert_eq!(low_count, 15);
+        assert_eq!(high_count, 60);
+    }
+
+    #[test]
+    fn drain_frames_order_is_deterministic_across_calls() {
+        let mut mux = Multiplexer::new(100);
+        let a = mux.create_stream(TransportMode::BestEffort).unwrap();
+        let b = mux.create_stream(TransportMode::BestEffort).unwrap();
+
+        for _ in 0..4 {
+            mux.send(a, Bytes::from_static(b"x")).unwrap();
+            mux.send(b, Bytes::from_static(b"y")).unwrap();
+        }
+        let first = mux.drain_frames();
+
+        for _ in 0..4 {
+            mux.send(a, Bytes::from_static(b"x")).unwrap();
+            mux.send(b, Bytes::from_static(b"y")).unwrap();
+        }
+        let second = mux.drain_frames();
+
+        let ids = |frames: &[Frame]| -> Vec<StreamId> {
+            frames
+                .iter()
+                .map(|f| match f {
+                    Frame::Data { stream_id, .. } => *stream_id,
+                    other => panic!("unexpected frame: {other:?}"),
+                })
+                .collect()
+        };
+        assert_eq!(ids(&first), ids(&second));
+    }
+
+    #[test]
+    fn unpaced_by_default_drain_frames_paced_matches_drain_frames() {
+        let mut mux = Multiplexer::new(100);
+        let sid = mux.create_stream(TransportMode::BestEffort).unwrap();
+        mux.send(sid, Bytes::from_static(b"hello")).unwrap();
+
+        let frames = mux.drain_frames_paced(Instant::now(), 1_000_000.0);
+        assert_eq!(frames.len(), 1);
+    }
+
+    #[test]
+    fn pacer_holds_back_frames_beyond_the_burst_allowance() {
+        use std::time::Duration;
+
+        let mut mux = Multiplexer::new(100);
+        mux.set_pacer(Some(crate::congestion::pacer::Pacer::new(Duration::ZERO)));
+        let sid = mux.create_stream(TransportMode::BestEffort).unwrap();
+        for _ in 0..5 {
+            mux.send(sid, Bytes::from_static(b"x")).unwrap();
+        }
+
+        let now = Instant::now();
+        // A slow rate means only the very first frame fits within a
+        // zero-burst budget; the rest should be held for a later call
+        // instead of going out in the same burst.
+        let first = mux.drain_frames_paced(now, 1000.0);
+        assert_eq!(first.len(), 1);
+
+        // Once enough wall-clock time has passed to pay off the schedule,
+        // the held frames should start draining again.
+        let later = mux.drain_frames_paced(now + Duration::from_secs(1), 1000.0);
+        assert!(!later.is_empty());
+    }
 }
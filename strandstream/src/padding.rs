@@ -0,0 +1,177 @@
+//! Length-obfuscating padding for traffic-analysis resistance.
+//!
+//! Strand frames otherwise map payload sizes 1:1 onto the wire, leaking
+//! message boundaries and sizes to an on-path observer. A [`PaddingPolicy`]
+//! lets the [`crate::mux::Multiplexer`] reshape outgoing records by
+//! following each real frame with a standalone [`crate::frame::Frame::Padding`]
+//! frame, so the real frame's size disappears into a bucket (or a sampled
+//! distribution) instead of appearing on the wire verbatim. Padding frames
+//! carry no application data and are stripped by
+//! [`crate::mux::Multiplexer::poll`] before dispatch.
+
+/// Decides how much padding to add to outgoing records.
+pub trait PaddingPolicy: Send {
+    /// Given a just-emitted real frame's encoded length, return the length
+    /// of a standalone padding frame to follow it with (0 for none).
+    fn pad_len_for(&mut self, real_frame_len: usize) -> usize;
+
+    /// Called once per `drain` when there was no real traffic to send.
+    /// Returns `Some(len)` for a standalone padding frame to emit so the
+    /// flow doesn't stall to silence, or `None` to stay quiet this round.
+    fn idle_padding(&mut self) -> Option<usize>;
+}
+
+/// Pads every outgoing record up to a fixed size bucket, so real frame sizes
+/// are only ever observed as one of a small number of bucket sizes.
+///
+/// With no explicit buckets configured, rounds up to the next power of two
+/// (the classic "pad to power of two" scheme). Never emits standalone
+/// padding during idle gaps -- there is no traffic whose size needs hiding.
+pub struct BucketPadding {
+    /// Sorted ascending bucket sizes. Empty means "round to next power of two".
+    buckets: Vec<usize>,
+}
+
+impl BucketPadding {
+    /// Pad every record up to the next power of two.
+    pub fn power_of_two() -> Self {
+        Self { buckets: Vec::new() }
+    }
+
+    /// Pad every record up to the smallest configured bucket that fits it.
+    /// Records larger than every bucket are left unpadded.
+    pub fn with_buckets(mut buckets: Vec<usize>) -> Self {
+        buckets.sort_unstable();
+        Self { buckets }
+    }
+
+    fn bucket_for(&self, real_len: usize) -> usize {
+        if self.buckets.is_empty() {
+            real_len.next_power_of_two()
+        } else {
+            self.buckets
+                .iter()
+                .copied()
+                .find(|&b| b >= real_len)
+                .unwrap_or(real_len)
+        }
+    }
+}
+
+impl PaddingPolicy for BucketPadding {
+    fn pad_len_for(&mut self, real_frame_len: usize) -> usize {
+        self.bucket_for(real_frame_len).saturating_sub(real_frame_len)
+    }
+
+    fn idle_padding(&mut self) -> Option<usize> {
+        None
+    }
+}
+
+/// Draws padding-frame lengths from a weighted table, the kind of
+/// inter-arrival-time / length distribution obfuscated transports (e.g.
+/// obfs4's `iat-mode`) ship to mimic some other protocol's traffic shape.
+/// Unlike [`BucketPadding`], this also emits standalone padding frames
+/// during idle gaps, since a distribution-shaped flow going silent when
+/// real traffic dries up is itself a distinguishing signal.
+pub struct DistributionPadding {
+    /// `(length, weight)` pairs; weights need not be normalised.
+    table: Vec<(usize, f64)>,
+    total_weight: f64,
+}
+
+impl DistributionPadding {
+    /// Build a distribution from `(length, weight)` pairs. Panics if `table`
+    /// is empty or every weight is non-positive.
+    pub fn new(table: Vec<(usize, f64)>) -> Self {
+        let total_weight: f64 = table.iter().map(|(_, w)| w).sum();
+        assert!(
+            !table.is_empty() && total_weight > 0.0,
+            "DistributionPadding requires at least one entry with positive weight"
+        );
+        Self { table, total_weight }
+    }
+
+    /// Sample a length from the configured distribution.
+    fn sample_len(&self) -> usize {
+        let mut roll = rand::random::<f64>() * self.total_weight;
+        for &(len, weight) in &self.table {
+            if roll < weight {
+                return len;
+            }
+            roll -= weight;
+        }
+        // Floating-point rounding: fall back to the last entry.
+        self.table.last().map(|(len, _)| *len).unwrap_or(0)
+    }
+}
+
+impl PaddingPolicy for DistributionPadding {
+    fn pad_len_for(&mut self, real_frame_len: usize) -> usize {
+        self.sample_len().saturating_sub(real_frame_len)
+    }
+
+    fn idle_padding(&mut self) -> Option<usize> {
+        Some(self.sample_len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bucket_pads_up_to_next_power_of_two() {
+        let mut policy = BucketPadding::power_of_two();
+        assert_eq!(policy.pad_len_for(5), 3); // 5 -> 8
+        assert_eq!(policy.pad_len_for(8), 0); // already a power of two
+        assert_eq!(policy.pad_len_for(0), 1); // 0 -> 1, the smallest power of two
+    }
+
+    #[test]
+    fn bucket_pads_up_to_configured_bucket() {
+        let mut policy = BucketPadding::with_buckets(vec![64, 256, 1024]);
+        assert_eq!(policy.pad_len_for(10), 54);
+        assert_eq!(policy.pad_len_for(64), 0);
+        assert_eq!(policy.pad_len_for(100), 156);
+    }
+
+    #[test]
+    fn bucket_leaves_oversized_records_unpadded() {
+        let mut policy = BucketPadding::with_buckets(vec![64]);
+        assert_eq!(policy.pad_len_for(1000), 0);
+    }
+
+    #[test]
+    fn bucket_never_emits_idle_padding() {
+        let mut policy = BucketPadding::power_of_two();
+        assert_eq!(policy.idle_padding(), None);
+    }
+
+    #[test]
+    fn distribution_only_samples_configured_lengths() {
+        let mut policy = DistributionPadding::new(vec![(100, 1.0), (500, 1.0)]);
+        for _ in 0..100 {
+            let sampled = policy.sample_len();
+            assert!(sampled == 100 || sampled == 500);
+        }
+    }
+
+    #[test]
+    fn distribution_emits_idle_padding() {
+        let mut policy = DistributionPadding::new(vec![(200, 1.0)]);
+        assert_eq!(policy.idle_padding(), Some(200));
+    }
+
+    #[test]
+    fn distribution_pad_len_never_negative_for_oversized_real_frame() {
+        let mut policy = DistributionPadding::new(vec![(10, 1.0)]);
+        assert_eq!(policy.pad_len_for(1000), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn distribution_rejects_empty_table() {
+        DistributionPadding::new(vec![]);
+    }
+}
@@ -0,0 +1,152 @@
+//! Coalesced sets of acknowledged sequence ranges.
+//!
+//! Mirrors how QUIC ACK frames report delivery: instead of one sequence
+//! number per ACK, a peer reports contiguous ranges, so acknowledging a burst
+//! of N delivered packets costs `RetransmissionEngine`/`LossDetector` one pass
+//! over the covered ranges instead of N individual `on_ack` calls.
+
+use std::ops::RangeInclusive;
+
+/// A sorted, non-overlapping, non-adjacent set of inclusive `u64` ranges.
+///
+/// Ranges that touch or overlap on insertion are merged, so `ranges()` always
+/// yields the minimal representation (e.g. inserting `5..=7` then `8..=10`
+/// yields the single range `5..=10`).
+#[derive(Debug, Clone, Default)]
+pub struct AckRanges {
+    ranges: Vec<RangeInclusive<u64>>,
+}
+
+impl AckRanges {
+    pub fn new() -> Self {
+        Self { ranges: Vec::new() }
+    }
+
+    /// Insert a range, merging it with any existing range it overlaps or is
+    /// adjacent to. A reversed range (`start > end`) is ignored.
+    pub fn insert(&mut self, range: RangeInclusive<u64>) {
+        let (start, end) = (*range.start(), *range.end());
+        if start > end {
+            return;
+        }
+
+        // First existing range that could possibly overlap or touch
+        // [start, end]: every range before it ends more than one below `start`.
+        let i = self
+            .ranges
+            .partition_point(|r| (*r.end()).saturating_add(1) < start);
+
+        let mut new_start = start;
+        let mut new_end = end;
+        let mut merge_count = 0;
+        for existing in &self.ranges[i..] {
+            if *existing.start() > new_end.saturating_add(1) {
+                break;
+            }
+            new_start = new_start.min(*existing.start());
+            new_end = new_end.max(*existing.end());
+            merge_count += 1;
+        }
+        self.ranges
+            .splice(i..i + merge_count, [new_start..=new_end]);
+    }
+
+    /// Returns `true` if `seq` falls within one of the tracked ranges.
+    pub fn contains(&self, seq: u64) -> bool {
+        self.ranges
+            .binary_search_by(|r| {
+                if *r.end() < seq {
+                    std::cmp::Ordering::Less
+                } else if *r.start() > seq {
+                    std::cmp::Ordering::Greater
+                } else {
+                    std::cmp::Ordering::Equal
+                }
+            })
+            .is_ok()
+    }
+
+    /// The ranges in ascending order.
+    pub fn ranges(&self) -> impl Iterator<Item = &RangeInclusive<u64>> {
+        self.ranges.iter()
+    }
+
+    /// The top of the highest (last) range, i.e. the largest acknowledged
+    /// sequence number covered by this set.
+    pub fn highest(&self) -> Option<u64> {
+        self.ranges.last().map(|r| *r.end())
+    }
+
+    /// Returns `true` if no ranges have been inserted.
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_single_range() {
+        let mut r = AckRanges::new();
+        r.insert(5..=10);
+        assert!(r.contains(5));
+        assert!(r.contains(10));
+        assert!(!r.contains(4));
+        assert!(!r.contains(11));
+    }
+
+    #[test]
+    fn insert_merges_adjacent_ranges() {
+        let mut r = AckRanges::new();
+        r.insert(8..=10);
+        r.insert(5..=7);
+        assert_eq!(r.ranges().collect::<Vec<_>>(), vec![&(5..=10)]);
+    }
+
+    #[test]
+    fn insert_merges_overlapping_ranges() {
+        let mut r = AckRanges::new();
+        r.insert(1..=5);
+        r.insert(3..=8);
+        assert_eq!(r.ranges().collect::<Vec<_>>(), vec![&(1..=8)]);
+    }
+
+    #[test]
+    fn insert_keeps_disjoint_ranges_separate() {
+        let mut r = AckRanges::new();
+        r.insert(1..=2);
+        r.insert(10..=12);
+        assert_eq!(r.ranges().collect::<Vec<_>>(), vec![&(1..=2), &(10..=12)]);
+    }
+
+    #[test]
+    fn insert_bridges_gap_between_two_ranges() {
+        let mut r = AckRanges::new();
+        r.insert(1..=2);
+        r.insert(10..=12);
+        r.insert(3..=9);
+        assert_eq!(r.ranges().collect::<Vec<_>>(), vec![&(1..=12)]);
+    }
+
+    #[test]
+    fn reversed_range_is_ignored() {
+        let mut r = AckRanges::new();
+        r.insert(10..=5);
+        assert!(r.is_empty());
+    }
+
+    #[test]
+    fn highest_returns_top_of_last_range() {
+        let mut r = AckRanges::new();
+        r.insert(1..=2);
+        r.insert(20..=25);
+        assert_eq!(r.highest(), Some(25));
+    }
+
+    #[test]
+    fn highest_is_none_when_empty() {
+        assert_eq!(AckRanges::new().highest(), None);
+    }
+}
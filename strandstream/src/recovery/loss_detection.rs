@@ -0,0 +1,248 @@
+//! Loss detection using packet-threshold and time-threshold methods
+//! as specified in RFC 9002 section 6.1.
+//!
+//! A packet is declared lost if:
+//! - **packet_threshold**: at least 3 packets with higher sequence numbers
+//!   have been acknowledged, OR
+//! - **time_threshold**: more than max(SRTT * 9/8, 1ms) has elapsed since
+//!   the packet was sent, where SRTT comes from a live `RttEstimate`.
+
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use crate::recovery::ack_ranges::AckRanges;
+
+/// Number of later-acknowledged packets before a packet is declared lost.
+const PACKET_THRESHOLD: u64 = 3;
+/// Minimum time threshold for loss detection.
+const MIN_TIME_THRESHOLD: Duration = Duration::from_millis(1);
+
+/// Tracks sent packets and detects losses.
+pub struct LossDetector {
+    /// Maps sequence number -> time the packet was sent.
+    sent_packets: BTreeMap<u64, Instant>,
+    /// The largest acknowledged sequence number.
+    largest_acked: Option<u64>,
+}
+
+impl LossDetector {
+    pub fn new() -> Self {
+        Self {
+            sent_packets: BTreeMap::new(),
+            largest_acked: None,
+        }
+    }
+
+    /// Record that a packet with the given sequence number was sent.
+    pub fn on_packet_sent(&mut self, seq: u64, sent_time: Instant) {
+        self.sent_packets.insert(seq, sent_time);
+    }
+
+    /// Process an ACK and return the set of sequence numbers now considered lost.
+    ///
+    /// `ack_seq` is the sequence number being acknowledged. `srtt` is the
+    /// current smoothed RTT (typically `RttEstimate::smoothed_rtt()`). `now`
+    /// is the current time.
+    pub fn on_ack_received(&mut self, ack_seq: u64, srtt: Duration, now: Instant) -> Vec<u64> {
+        // Remove the acknowledged packet.
+        self.sent_packets.remove(&ack_seq);
+
+        // Update largest acked.
+        self.largest_acked = Some(match self.largest_acked {
+            Some(prev) => prev.max(ack_seq),
+            None => ack_seq,
+        });
+
+        self.detect_losses(srtt, now)
+    }
+
+    /// Process a coalesced range of ACKs and return the set of sequence
+    /// numbers now considered lost, like `on_ack_received` but for a burst of
+    /// acknowledgements delivered as `ranges` (see `AckRanges`) instead of one
+    /// call per sequence number.
+    ///
+    /// `largest_acked` is updated once from the top of the highest range
+    /// (`ranges.highest()`) rather than per-seq, and the packet-/time-
+    /// threshold checks then run once over the remaining `sent_packets`.
+    pub fn on_ack_ranges(&mut self, ranges: &AckRanges, srtt: Duration, now: Instant) -> Vec<u64> {
+        for range in ranges.ranges() {
+            let matched: Vec<u64> = self.sent_packets.range(range.clone()).map(|(&seq, _)| seq).collect();
+            for seq in matched {
+                self.sent_packets.remove(&seq);
+            }
+        }
+
+        if let Some(top) = ranges.highest() {
+            self.largest_acked = Some(match self.largest_acked {
+                Some(prev) => prev.max(top),
+                None => top,
+            });
+        }
+
+        self.detect_losses(srtt, now)
+    }
+
+    /// Run the packet-threshold and time-threshold checks over `sent_packets`
+    /// against the current `largest_acked`, removing and returning any
+    /// sequence numbers now considered lost.
+    fn detect_losses(&mut self, srtt: Duration, now: Instant) -> Vec<u64> {
+        let Some(largest) = self.largest_acked else {
+            return Vec::new();
+        };
+
+        // Time threshold: max(SRTT * 9/8, 1ms)
+        let time_threshold = std::cmp::max(srtt * 9 / 8, MIN_TIME_THRESHOLD);
+
+        let mut lost = Vec::new();
+        let seqs: Vec<u64> = self.sent_packets.keys().copied().collect();
+
+        for seq in seqs {
+            // Packet threshold: 3 packets with higher seq numbers were ACKed.
+            let packet_lost = largest >= seq + PACKET_THRESHOLD;
+
+            // Time threshold: sent more than time_threshold ago.
+            let time_lost = if let Some(&sent_time) = self.sent_packets.get(&seq) {
+                now.duration_since(sent_time) > time_threshold
+            } else {
+                false
+            };
+
+            if packet_lost || time_lost {
+                self.sent_packets.remove(&seq);
+                lost.push(seq);
+            }
+        }
+
+        lost
+    }
+
+    /// Returns the number of packets still in flight (sent but not acknowledged
+    /// or declared lost).
+    pub fn in_flight(&self) -> usize {
+        self.sent_packets.len()
+    }
+}
+
+impl Default for LossDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_loss_when_all_acked_in_order() {
+        let mut ld = LossDetector::new();
+        let now = Instant::now();
+
+        for i in 0..5 {
+            ld.on_packet_sent(i, now);
+        }
+
+        for i in 0..5 {
+            let lost = ld.on_ack_received(i, Duration::from_millis(100), now);
+            assert!(lost.is_empty(), "unexpected loss at ack {i}");
+        }
+
+        assert_eq!(ld.in_flight(), 0);
+    }
+
+    #[test]
+    fn packet_threshold_loss() {
+        let mut ld = LossDetector::new();
+        let now = Instant::now();
+
+        for i in 0..6 {
+            ld.on_packet_sent(i, now);
+        }
+
+        let _ = ld.on_ack_received(1, Duration::from_millis(100), now);
+        let _ = ld.on_ack_received(2, Duration::from_millis(100), now);
+        let lost = ld.on_ack_received(3, Duration::from_millis(100), now);
+        assert!(lost.contains(&0), "packet 0 should be declared lost");
+    }
+
+    #[test]
+    fn time_threshold_loss() {
+        let mut ld = LossDetector::new();
+        let start = Instant::now();
+
+        ld.on_packet_sent(0, start);
+        ld.on_packet_sent(1, start);
+
+        let srtt = Duration::from_millis(100);
+        let later = start + Duration::from_millis(200); // well past 112.5ms threshold
+
+        let lost = ld.on_ack_received(1, srtt, later);
+        assert!(lost.contains(&0), "packet 0 should be time-threshold lost");
+    }
+
+    #[test]
+    fn srtt_sourced_from_rtt_estimate() {
+        use crate::recovery::rtt::RttEstimate;
+
+        let mut est = RttEstimate::new(Duration::from_millis(0));
+        est.update(Duration::from_millis(100), Duration::from_millis(0));
+
+        let mut ld = LossDetector::new();
+        let start = Instant::now();
+        ld.on_packet_sent(0, start);
+        ld.on_packet_sent(1, start);
+
+        let later = start + Duration::from_millis(200);
+        let lost = ld.on_ack_received(1, est.smoothed_rtt(), later);
+        assert!(lost.contains(&0));
+    }
+
+    #[test]
+    fn ack_ranges_clears_all_covered_packets() {
+        use crate::recovery::ack_ranges::AckRanges;
+
+        let mut ld = LossDetector::new();
+        let now = Instant::now();
+        for i in 0..5 {
+            ld.on_packet_sent(i, now);
+        }
+
+        let mut ranges = AckRanges::new();
+        ranges.insert(0..=2);
+        let lost = ld.on_ack_ranges(&ranges, Duration::from_millis(100), now);
+        assert!(lost.is_empty());
+        assert_eq!(ld.in_flight(), 2); // seqs 3, 4 remain
+    }
+
+    #[test]
+    fn ack_ranges_packet_threshold_loss() {
+        use crate::recovery::ack_ranges::AckRanges;
+
+        let mut ld = LossDetector::new();
+        let now = Instant::now();
+        for i in 0..6 {
+            ld.on_packet_sent(i, now);
+        }
+
+        let mut ranges = AckRanges::new();
+        ranges.insert(1..=3);
+        let lost = ld.on_ack_ranges(&ranges, Duration::from_millis(100), now);
+        assert!(lost.contains(&0), "packet 0 should be declared lost");
+    }
+
+    #[test]
+    fn ack_ranges_updates_largest_acked_from_top_of_range() {
+        use crate::recovery::ack_ranges::AckRanges;
+
+        let mut ld = LossDetector::new();
+        let now = Instant::now();
+        for i in 0..3 {
+            ld.on_packet_sent(i, now);
+        }
+
+        let mut ranges = AckRanges::new();
+        ranges.insert(0..=2);
+        ld.on_ack_ranges(&ranges, Duration::from_millis(100), now);
+        assert_eq!(ld.largest_acked, Some(2));
+    }
+}
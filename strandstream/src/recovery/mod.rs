@@ -0,0 +1,18 @@
+//! Loss recovery: RTT estimation feeding retransmission scheduling and loss
+//! detection.
+//!
+//! `RttEstimate` is the single source of timing for `RetransmissionEngine`
+//! (seeds each packet's retransmit timer via `rto()`) and `LossDetector`
+//! (supplies `smoothed_rtt()` for the time-threshold check).
+
+pub mod ack_ranges;
+pub mod loss_detection;
+pub mod retransmission;
+pub mod rtt;
+pub mod seq_range_set;
+
+pub use ack_ranges::AckRanges;
+pub use loss_detection::LossDetector;
+pub use retransmission::RetransmissionEngine;
+pub use rtt::RttEstimate;
+pub use seq_range_set::SeqRangeSet;
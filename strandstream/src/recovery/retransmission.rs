@@ -0,0 +1,713 @@
+//! Retransmission engine using a BinaryHeap ordered by retransmit time.
+//!
+//! Supports exponential backoff (rto *= 2) on each retransmission, with a
+//! maximum of 3 retries per packet. Callers seed each `push` with an `rto`
+//! drawn from a live `RttEstimate` rather than a fixed constant.
+
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BinaryHeap, HashMap};
+use std::time::{Duration, Instant};
+
+use bytes::Bytes;
+
+use crate::congestion::CongestionController;
+use crate::error::{Result, StrandStreamError};
+use crate::recovery::ack_ranges::AckRanges;
+
+/// Maximum number of retransmission attempts before giving up.
+const MAX_RETRIES: u32 = 3;
+
+/// Default maximum bytes held in the retransmit buffer across all in-flight
+/// packets. 64 MiB prevents unbounded memory growth under sustained loss.
+const MAX_INFLIGHT_BYTES: usize = 64 * 1024 * 1024;
+
+/// Maximum number of probe packets sent per PTO, per RFC 9002 section 6.2.
+const MAX_PTO_PROBES: usize = 2;
+
+/// An entry in the retransmission queue.
+#[derive(Debug, Clone)]
+struct RetransmitEntry {
+    /// Sequence number.
+    seq: u64,
+    /// Data to retransmit.
+    data: Bytes,
+    /// When this packet should be retransmitted.
+    retransmit_at: Instant,
+    /// Current RTO for this packet.
+    rto: Duration,
+    /// Number of retransmission attempts so far.
+    attempts: u32,
+}
+
+// BinaryHeap is a max-heap; we want the *earliest* retransmit_at first,
+// so we reverse the ordering.
+impl PartialEq for RetransmitEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.retransmit_at == other.retransmit_at
+    }
+}
+
+impl Eq for RetransmitEntry {}
+
+impl PartialOrd for RetransmitEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for RetransmitEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reverse ordering so that the earliest deadline is popped first.
+        other.retransmit_at.cmp(&self.retransmit_at)
+    }
+}
+
+/// A packet that has exceeded its maximum retransmission attempts.
+#[derive(Debug)]
+pub struct GivenUp {
+    pub seq: u64,
+    pub data: Bytes,
+    pub attempts: u32,
+}
+
+/// A packet ready for retransmission.
+#[derive(Debug)]
+pub struct RetransmitPacket {
+    pub seq: u64,
+    pub data: Bytes,
+}
+
+/// Retransmission engine.
+///
+/// Packets are pushed with an initial RTO (typically `RttEstimate::rto()`).
+/// `poll_expired` returns packets whose timer has fired. Exponential backoff
+/// is applied on each retransmit.  After `MAX_RETRIES` the packet is
+/// reported as given up.
+///
+/// The total in-flight byte count is capped at `max_bytes` (default 64 MiB)
+/// to prevent unbounded memory growth under sustained packet loss. Attach a
+/// [`CongestionController`] with [`RetransmissionEngine::with_congestion_controller`]
+/// to drive admission from an actual congestion window (NewReno, CUBIC, ...)
+/// instead of this fixed cap -- `push` then rejects once the controller's
+/// `window()` is full rather than once `max_bytes` is reached, and acks/
+/// losses observed by the engine are forwarded to the controller.
+///
+/// Optionally, [`RetransmissionEngine::enable_pto`] switches the engine to
+/// the RFC 9002 section 6.2 Probe Timeout model: a single connection-wide
+/// timer, armed via [`RetransmissionEngine::arm_pto`], that re-sends the
+/// earliest unacknowledged packets as probes via
+/// [`RetransmissionEngine::poll_pto`] instead of giving up after a fixed
+/// number of doublings.
+pub struct RetransmissionEngine {
+    heap: BinaryHeap<RetransmitEntry>,
+    /// Track which sequences are still pending and their payload size.
+    pending: HashMap<u64, usize>,
+    /// Pending payloads kept in sequence order, so PTO mode can pick the
+    /// earliest unacknowledged packets to probe with.
+    order: BTreeMap<u64, Bytes>,
+    /// Total bytes currently held in the retransmit buffer.
+    inflight_bytes: usize,
+    /// Maximum allowed in-flight bytes.
+    max_bytes: usize,
+    /// Whether PTO mode (instead of the fixed MAX_RETRIES backoff) is active.
+    pto_enabled: bool,
+    /// Number of consecutive PTOs tolerated before giving up.
+    pto_ceiling: u32,
+    /// Number of consecutive PTOs since the last new largest-acked packet.
+    pto_count: u32,
+    /// `rto() + max_ack_delay`, as last supplied via `arm_pto`.
+    base_pto: Duration,
+    /// When the PTO timer next fires, if armed.
+    pto_deadline: Option<Instant>,
+    /// The largest sequence number acknowledged so far (PTO mode only).
+    largest_acked: Option<u64>,
+    /// Congestion controller driving admission, if attached. When present,
+    /// this takes over from `max_bytes` entirely.
+    congestion: Option<Box<dyn CongestionController>>,
+}
+
+impl RetransmissionEngine {
+    pub fn new() -> Self {
+        Self {
+            heap: BinaryHeap::new(),
+            pending: HashMap::new(),
+            order: BTreeMap::new(),
+            inflight_bytes: 0,
+            max_bytes: MAX_INFLIGHT_BYTES,
+            pto_enabled: false,
+            pto_ceiling: 0,
+            pto_count: 0,
+            base_pto: Duration::ZERO,
+            pto_deadline: None,
+            largest_acked: None,
+            congestion: None,
+        }
+    }
+
+    /// Create an engine with a custom in-flight byte limit.
+    pub fn with_max_bytes(max_bytes: usize) -> Self {
+        Self {
+            max_bytes,
+            ..Self::new()
+        }
+    }
+
+    /// Attach a congestion controller to drive admission instead of
+    /// `max_bytes`. `push` will reject once `controller.can_send` returns
+    /// false, and acks/losses observed by the engine are forwarded to it.
+    pub fn with_congestion_controller(controller: Box<dyn CongestionController>) -> Self {
+        Self {
+            congestion: Some(controller),
+            ..Self::new()
+        }
+    }
+
+    /// Current congestion window in bytes, if a congestion controller is
+    /// attached.
+    pub fn cwnd(&self) -> Option<usize> {
+        self.congestion.as_ref().map(|c| c.window())
+    }
+
+    /// Bytes the attached congestion controller considers in flight, if any.
+    pub fn congestion_bytes_in_flight(&self) -> Option<usize> {
+        self.congestion.as_ref().map(|c| c.bytes_in_flight())
+    }
+
+    /// Switch to PTO-driven recovery instead of the fixed MAX_RETRIES
+    /// backoff. `ceiling` is the number of consecutive PTOs (without a new
+    /// largest-acked packet) tolerated before pending packets are given up.
+    pub fn enable_pto(&mut self, ceiling: u32) {
+        self.pto_enabled = true;
+        self.pto_ceiling = ceiling;
+    }
+
+    /// Register a packet for potential retransmission, scheduled `rto` from
+    /// now (seed this from `RttEstimate::rto()` for a live timer).
+    ///
+    /// Returns `Err(RetransmitBufferFull)` if adding this packet would exceed
+    /// the configured in-flight byte limit.
+    pub fn push(&mut self, seq: u64, data: Bytes, rto: Duration) -> Result<()> {
+        let len = data.len();
+        if let Some(congestion) = &self.congestion {
+            if !congestion.can_send(len) {
+                return Err(StrandStreamError::RetransmitBufferFull {
+                    inflight: congestion.bytes_in_flight(),
+                    max: congestion.window(),
+                });
+            }
+        } else {
+            let new_inflight = self.inflight_bytes.saturating_add(len);
+            if new_inflight > self.max_bytes {
+                return Err(StrandStreamError::RetransmitBufferFull {
+                    inflight: self.inflight_bytes,
+                    max: self.max_bytes,
+                });
+            }
+        }
+
+        self.inflight_bytes = self.inflight_bytes.saturating_add(len);
+        if let Some(congestion) = &mut self.congestion {
+            congestion.on_packet_sent(len);
+        }
+        self.pending.insert(seq, len);
+        self.order.insert(seq, data.clone());
+        let entry = RetransmitEntry {
+            seq,
+            data,
+            retransmit_at: Instant::now() + rto,
+            rto,
+            attempts: 0,
+        };
+        self.heap.push(entry);
+        Ok(())
+    }
+
+    /// (Re)arm the PTO timer from a fresh `rto`/`max_ack_delay` sample, e.g.
+    /// right after a `push`. No-op unless PTO mode is enabled.
+    pub fn arm_pto(&mut self, rto: Duration, max_ack_delay: Duration, now: Instant) {
+        if !self.pto_enabled {
+            return;
+        }
+        self.base_pto = rto + max_ack_delay;
+        self.pto_deadline = Some(now + self.base_pto * 2u32.pow(self.pto_count));
+    }
+
+    /// Acknowledge a packet, removing it from the retransmission queue.
+    ///
+    /// Returns `true` if the packet was still pending. In PTO mode, a new
+    /// largest-acked sequence number resets `pto_count`.
+    pub fn on_ack(&mut self, seq: u64) -> bool {
+        self.order.remove(&seq);
+        if self.pto_enabled {
+            let is_new_largest = match self.largest_acked {
+                Some(largest) => seq > largest,
+                None => true,
+            };
+            if is_new_largest {
+                self.largest_acked = Some(seq);
+                self.pto_count = 0;
+            }
+        }
+        if let Some(len) = self.pending.remove(&seq) {
+            self.inflight_bytes = self.inflight_bytes.saturating_sub(len);
+            if let Some(congestion) = &mut self.congestion {
+                congestion.on_ack(len);
+            }
+            true
+        } else {
+            false
+        }
+        // The entry may still be in the heap but will be skipped by poll_expired.
+    }
+
+    /// Acknowledge every pending sequence number covered by `ranges` in one
+    /// pass, like a QUIC ACK frame's range list rather than N individual
+    /// `on_ack` calls.
+    ///
+    /// Drains `order`/`pending` for each covered range via a `BTreeMap` range
+    /// query and adjusts `inflight_bytes`/the congestion controller once per
+    /// acked packet. In PTO mode, `largest_acked` is updated from
+    /// `ranges.highest()` instead of per-seq. Returns the sequence numbers
+    /// that were still pending and are now acknowledged.
+    pub fn on_ack_ranges(&mut self, ranges: &AckRanges) -> Vec<u64> {
+        let mut acked = Vec::new();
+        for range in ranges.ranges() {
+            let matched: Vec<u64> = self.order.range(range.clone()).map(|(&seq, _)| seq).collect();
+            for seq in matched {
+                self.order.remove(&seq);
+                if let Some(len) = self.pending.remove(&seq) {
+                    self.inflight_bytes = self.inflight_bytes.saturating_sub(len);
+                    if let Some(congestion) = &mut self.congestion {
+                        congestion.on_ack(len);
+                    }
+                    acked.push(seq);
+                }
+            }
+        }
+
+        if self.pto_enabled {
+            if let Some(top) = ranges.highest() {
+                let is_new_largest = match self.largest_acked {
+                    Some(largest) => top > largest,
+                    None => true,
+                };
+                if is_new_largest {
+                    self.largest_acked = Some(top);
+                    self.pto_count = 0;
+                }
+            }
+        }
+
+        acked
+    }
+
+    /// Poll the PTO timer (only meaningful once [`Self::enable_pto`] and
+    /// [`Self::arm_pto`] have been called). If it has fired, returns up to
+    /// [`MAX_PTO_PROBES`] probe packets -- the earliest still-unacknowledged
+    /// payloads -- and reschedules the timer at `base_pto * 2^pto_count`.
+    /// Once `pto_count` exceeds `pto_ceiling`, all still-pending packets are
+    /// given up instead of probed.
+    pub fn poll_pto(&mut self, now: Instant) -> (Vec<RetransmitPacket>, Vec<GivenUp>) {
+        if !self.pto_enabled {
+            return (Vec::new(), Vec::new());
+        }
+        let Some(deadline) = self.pto_deadline else {
+            return (Vec::new(), Vec::new());
+        };
+        if now < deadline {
+            return (Vec::new(), Vec::new());
+        }
+
+        self.pto_count += 1;
+
+        if self.pto_count > self.pto_ceiling {
+            let given_up: Vec<GivenUp> = self
+                .order
+                .iter()
+                .map(|(&seq, data)| GivenUp {
+                    seq,
+                    data: data.clone(),
+                    attempts: self.pto_count,
+                })
+                .collect();
+            for entry in &given_up {
+                self.pending.remove(&entry.seq);
+                self.inflight_bytes = self.inflight_bytes.saturating_sub(entry.data.len());
+            }
+            self.order.clear();
+            self.pto_deadline = None;
+            return (Vec::new(), given_up);
+        }
+
+        let probes: Vec<RetransmitPacket> = self
+            .order
+            .iter()
+            .take(MAX_PTO_PROBES)
+            .map(|(&seq, data)| RetransmitPacket {
+                seq,
+                data: data.clone(),
+            })
+            .collect();
+
+        self.pto_deadline = Some(now + self.base_pto * 2u32.pow(self.pto_count));
+        (probes, Vec::new())
+    }
+
+    /// Poll for packets whose retransmission timer has expired.
+    ///
+    /// Returns packets to retransmit and packets that have exceeded the max
+    /// retry count.
+    pub fn poll_expired(&mut self, now: Instant) -> (Vec<RetransmitPacket>, Vec<GivenUp>) {
+        let mut to_retransmit = Vec::new();
+        let mut given_up = Vec::new();
+
+        while let Some(entry) = self.heap.peek() {
+            if entry.retransmit_at > now {
+                break;
+            }
+
+            let entry = self.heap.pop().unwrap();
+
+            // Skip if already ACKed.
+            if !self.pending.contains_key(&entry.seq) {
+                continue;
+            }
+
+            if entry.attempts >= MAX_RETRIES {
+                let len = self.pending.remove(&entry.seq).unwrap_or(0);
+                self.inflight_bytes = self.inflight_bytes.saturating_sub(len);
+                self.order.remove(&entry.seq);
+                given_up.push(GivenUp {
+                    seq: entry.seq,
+                    data: entry.data,
+                    attempts: entry.attempts,
+                });
+            } else {
+                // Signal loss to the congestion controller once per packet,
+                // on its first retransmission -- subsequent backoff firings
+                // of the same packet are not distinct loss events.
+                if entry.attempts == 0 {
+                    if let Some(congestion) = &mut self.congestion {
+                        congestion.on_loss(entry.data.len());
+                    }
+                }
+
+                to_retransmit.push(RetransmitPacket {
+                    seq: entry.seq,
+                    data: entry.data.clone(),
+                });
+
+                // Re-enqueue with exponential backoff. inflight_bytes unchanged.
+                let new_rto = entry.rto * 2;
+                self.heap.push(RetransmitEntry {
+                    seq: entry.seq,
+                    data: entry.data,
+                    retransmit_at: now + new_rto,
+                    rto: new_rto,
+                    attempts: entry.attempts + 1,
+                });
+            }
+        }
+
+        (to_retransmit, given_up)
+    }
+
+    /// Number of packets still pending retransmission.
+    pub fn pending_count(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Total bytes currently held in the retransmit buffer.
+    pub fn inflight_bytes(&self) -> usize {
+        self.inflight_bytes
+    }
+}
+
+impl Default for RetransmissionEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push_and_ack() {
+        let mut engine = RetransmissionEngine::new();
+        engine
+            .push(1, Bytes::from_static(b"hello"), Duration::from_millis(100))
+            .unwrap();
+        assert_eq!(engine.pending_count(), 1);
+        assert_eq!(engine.inflight_bytes(), 5);
+        assert!(engine.on_ack(1));
+        assert_eq!(engine.pending_count(), 0);
+        assert_eq!(engine.inflight_bytes(), 0);
+    }
+
+    #[test]
+    fn poll_before_expiry_returns_nothing() {
+        let mut engine = RetransmissionEngine::new();
+        let now = Instant::now();
+        engine
+            .push(1, Bytes::from_static(b"A"), Duration::from_secs(10))
+            .unwrap();
+
+        let (retx, given) = engine.poll_expired(now);
+        assert!(retx.is_empty());
+        assert!(given.is_empty());
+    }
+
+    #[test]
+    fn poll_after_expiry_returns_packet() {
+        let mut engine = RetransmissionEngine::new();
+        engine
+            .push(1, Bytes::from_static(b"A"), Duration::from_millis(10))
+            .unwrap();
+
+        let later = Instant::now() + Duration::from_millis(50);
+        let (retx, given) = engine.poll_expired(later);
+        assert_eq!(retx.len(), 1);
+        assert_eq!(retx[0].seq, 1);
+        assert!(given.is_empty());
+    }
+
+    #[test]
+    fn exponential_backoff_and_give_up() {
+        let mut engine = RetransmissionEngine::new();
+        let rto = Duration::from_millis(10);
+        engine.push(1, Bytes::from_static(b"A"), rto).unwrap();
+
+        let t1 = Instant::now() + Duration::from_millis(50);
+        let (retx, _) = engine.poll_expired(t1);
+        assert_eq!(retx.len(), 1);
+
+        let t2 = t1 + Duration::from_millis(50);
+        let (retx, _) = engine.poll_expired(t2);
+        assert_eq!(retx.len(), 1);
+
+        let t3 = t2 + Duration::from_millis(100);
+        let (retx, _) = engine.poll_expired(t3);
+        assert_eq!(retx.len(), 1);
+
+        let t4 = t3 + Duration::from_millis(200);
+        let (retx, given) = engine.poll_expired(t4);
+        assert!(retx.is_empty());
+        assert_eq!(given.len(), 1);
+        assert_eq!(given[0].seq, 1);
+        assert_eq!(engine.pending_count(), 0);
+        assert_eq!(engine.inflight_bytes(), 0);
+    }
+
+    #[test]
+    fn retransmit_buffer_limit_rejects_overflow() {
+        let mut engine = RetransmissionEngine::with_max_bytes(16);
+
+        engine
+            .push(1, Bytes::from(vec![0u8; 10]), Duration::from_secs(10))
+            .unwrap();
+
+        let result = engine.push(2, Bytes::from(vec![0u8; 10]), Duration::from_secs(10));
+        assert!(matches!(
+            result,
+            Err(StrandStreamError::RetransmitBufferFull { .. })
+        ));
+
+        assert!(engine.on_ack(1));
+        engine
+            .push(2, Bytes::from(vec![0u8; 10]), Duration::from_secs(10))
+            .unwrap();
+    }
+
+    #[test]
+    fn push_seeded_from_rtt_estimate() {
+        use crate::recovery::rtt::RttEstimate;
+
+        let mut est = RttEstimate::new(Duration::from_millis(25));
+        est.update(Duration::from_millis(100), Duration::from_millis(0));
+
+        let mut engine = RetransmissionEngine::new();
+        engine
+            .push(1, Bytes::from_static(b"A"), est.rto())
+            .unwrap();
+        assert_eq!(engine.pending_count(), 1);
+    }
+
+    #[test]
+    fn pto_probes_earliest_unacked_packets() {
+        let mut engine = RetransmissionEngine::new();
+        engine.enable_pto(5);
+
+        engine.push(1, Bytes::from_static(b"A"), Duration::from_millis(10)).unwrap();
+        engine.push(2, Bytes::from_static(b"B"), Duration::from_millis(10)).unwrap();
+        engine.push(3, Bytes::from_static(b"C"), Duration::from_millis(10)).unwrap();
+
+        let now = Instant::now();
+        engine.arm_pto(Duration::from_millis(10), Duration::from_millis(0), now);
+
+        let later = now + Duration::from_millis(20);
+        let (probes, given_up) = engine.poll_pto(later);
+        assert!(given_up.is_empty());
+        assert_eq!(probes.len(), MAX_PTO_PROBES);
+        assert_eq!(probes[0].seq, 1);
+        assert_eq!(probes[1].seq, 2);
+    }
+
+    #[test]
+    fn pto_does_nothing_before_deadline() {
+        let mut engine = RetransmissionEngine::new();
+        engine.enable_pto(5);
+        engine.push(1, Bytes::from_static(b"A"), Duration::from_millis(10)).unwrap();
+
+        let now = Instant::now();
+        engine.arm_pto(Duration::from_secs(10), Duration::from_millis(0), now);
+
+        let (probes, given_up) = engine.poll_pto(now);
+        assert!(probes.is_empty());
+        assert!(given_up.is_empty());
+    }
+
+    #[test]
+    fn pto_count_resets_on_new_largest_acked() {
+        let mut engine = RetransmissionEngine::new();
+        engine.enable_pto(5);
+        engine.push(1, Bytes::from_static(b"A"), Duration::from_millis(10)).unwrap();
+        engine.push(2, Bytes::from_static(b"B"), Duration::from_millis(10)).unwrap();
+
+        let now = Instant::now();
+        engine.arm_pto(Duration::from_millis(10), Duration::from_millis(0), now);
+        let t1 = now + Duration::from_millis(20);
+        engine.poll_pto(t1);
+        assert_eq!(engine.pto_count, 1);
+
+        // A new largest-acked packet resets the PTO count.
+        engine.on_ack(2);
+        assert_eq!(engine.pto_count, 0);
+    }
+
+    #[test]
+    fn congestion_controller_gates_push() {
+        use crate::congestion::new_reno::NewReno;
+
+        // NewReno's initial window is 10 * 1200 bytes; push more than that
+        // in one packet to force rejection.
+        let mut engine =
+            RetransmissionEngine::with_congestion_controller(Box::new(NewReno::new()));
+        let huge = Bytes::from(vec![0u8; 12_001]);
+        let result = engine.push(1, huge, Duration::from_secs(1));
+        assert!(matches!(
+            result,
+            Err(StrandStreamError::RetransmitBufferFull { .. })
+        ));
+    }
+
+    #[test]
+    fn congestion_controller_tracks_acks_and_loss() {
+        use crate::congestion::new_reno::NewReno;
+
+        let mut engine =
+            RetransmissionEngine::with_congestion_controller(Box::new(NewReno::new()));
+        let initial_cwnd = engine.cwnd().unwrap();
+
+        engine
+            .push(1, Bytes::from_static(b"A"), Duration::from_millis(10))
+            .unwrap();
+        assert_eq!(engine.congestion_bytes_in_flight(), Some(1));
+
+        assert!(engine.on_ack(1));
+        assert_eq!(engine.congestion_bytes_in_flight(), Some(0));
+        // Slow start: cwnd grows by one MSS on ack.
+        assert!(engine.cwnd().unwrap() > initial_cwnd);
+
+        engine
+            .push(2, Bytes::from_static(b"B"), Duration::from_millis(10))
+            .unwrap();
+        let pre_loss_cwnd = engine.cwnd().unwrap();
+        let later = Instant::now() + Duration::from_millis(50);
+        let (retx, _) = engine.poll_expired(later);
+        assert_eq!(retx.len(), 1);
+        // First retransmission signals a loss event: cwnd should drop.
+        assert!(engine.cwnd().unwrap() < pre_loss_cwnd);
+    }
+
+    #[test]
+    fn pto_gives_up_once_ceiling_exceeded() {
+        let mut engine = RetransmissionEngine::new();
+        engine.enable_pto(1);
+        engine.push(1, Bytes::from_static(b"A"), Duration::from_millis(10)).unwrap();
+
+        let now = Instant::now();
+        engine.arm_pto(Duration::from_millis(10), Duration::from_millis(0), now);
+
+        // First PTO: within ceiling, probes only.
+        let t1 = now + Duration::from_millis(20);
+        let (probes, given_up) = engine.poll_pto(t1);
+        assert_eq!(probes.len(), 1);
+        assert!(given_up.is_empty());
+
+        // Second PTO: pto_count (2) exceeds ceiling (1), give up.
+        let t2 = engine.pto_deadline.unwrap() + Duration::from_millis(1);
+        let (probes, given_up) = engine.poll_pto(t2);
+        assert!(probes.is_empty());
+        assert_eq!(given_up.len(), 1);
+        assert_eq!(given_up[0].seq, 1);
+        assert_eq!(engine.pending_count(), 0);
+    }
+
+    #[test]
+    fn on_ack_ranges_drains_covered_seqs() {
+        let mut engine = RetransmissionEngine::new();
+        for seq in 1..=5u64 {
+            engine
+                .push(seq, Bytes::from_static(b"x"), Duration::from_secs(10))
+                .unwrap();
+        }
+
+        let mut ranges = AckRanges::new();
+        ranges.insert(1..=3);
+        let acked = engine.on_ack_ranges(&ranges);
+
+        assert_eq!(acked, vec![1, 2, 3]);
+        assert_eq!(engine.pending_count(), 2);
+        assert_eq!(engine.inflight_bytes(), 2);
+    }
+
+    #[test]
+    fn on_ack_ranges_ignores_unpending_seqs() {
+        let mut engine = RetransmissionEngine::new();
+        engine
+            .push(1, Bytes::from_static(b"x"), Duration::from_secs(10))
+            .unwrap();
+
+        let mut ranges = AckRanges::new();
+        ranges.insert(1..=10); // only seq 1 is actually pending
+        let acked = engine.on_ack_ranges(&ranges);
+
+        assert_eq!(acked, vec![1]);
+        assert_eq!(engine.pending_count(), 0);
+    }
+
+    #[test]
+    fn on_ack_ranges_updates_largest_acked_in_pto_mode() {
+        let mut engine = RetransmissionEngine::new();
+        engine.enable_pto(5);
+        for seq in 1..=3u64 {
+            engine
+                .push(seq, Bytes::from_static(b"x"), Duration::from_millis(10))
+                .unwrap();
+        }
+
+        let now = Instant::now();
+        engine.arm_pto(Duration::from_millis(10), Duration::from_millis(0), now);
+        let t1 = now + Duration::from_millis(20);
+        engine.poll_pto(t1);
+        assert_eq!(engine.pto_count, 1);
+
+        let mut ranges = AckRanges::new();
+        ranges.insert(1..=3);
+        engine.on_ack_ranges(&ranges);
+        assert_eq!(engine.largest_acked, Some(3));
+        assert_eq!(engine.pto_count, 0);
+    }
+}
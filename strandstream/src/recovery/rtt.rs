@@ -0,0 +1,153 @@
+//! RFC 9002 (QUIC) RTT estimator.
+//!
+//! Unlike the RFC 6298 `RttEstimator` in `crate::rtt`, this estimator tracks
+//! `min_rtt` and discounts the peer-reported `ack_delay` from each sample
+//! before smoothing, and its `rto()` folds in `max_ack_delay` directly. It is
+//! the single source of timing for `RetransmissionEngine` and `LossDetector`.
+
+use std::time::Duration;
+
+/// Timer granularity floor for the variance component, per RFC 9002 6.2.1.
+const TIMER_GRANULARITY: Duration = Duration::from_millis(1);
+
+/// RTT estimate implementing the RFC 9002 smoothing algorithm.
+#[derive(Debug, Clone)]
+pub struct RttEstimate {
+    /// Most recent RTT sample, prior to ack-delay adjustment.
+    latest_rtt: Duration,
+    /// Lowest RTT observed over the life of the connection.
+    min_rtt: Duration,
+    /// Smoothed RTT (SRTT).
+    smoothed_rtt: Duration,
+    /// RTT variance.
+    rttvar: Duration,
+    /// Peer-advertised maximum ack delay, added to every `rto()` computation.
+    max_ack_delay: Duration,
+    /// Whether a sample has been recorded yet.
+    has_sample: bool,
+}
+
+impl RttEstimate {
+    /// Create a new estimator. `max_ack_delay` is the peer's advertised
+    /// maximum ack delay, folded into `rto()`.
+    pub fn new(max_ack_delay: Duration) -> Self {
+        Self {
+            latest_rtt: Duration::ZERO,
+            min_rtt: Duration::MAX,
+            smoothed_rtt: Duration::ZERO,
+            rttvar: Duration::ZERO,
+            max_ack_delay,
+            has_sample: false,
+        }
+    }
+
+    /// Record a new RTT sample along with the peer's reported `ack_delay`
+    /// for that acknowledgement.
+    pub fn update(&mut self, latest_rtt: Duration, ack_delay: Duration) {
+        self.latest_rtt = latest_rtt;
+        self.min_rtt = self.min_rtt.min(latest_rtt);
+
+        let adjusted_rtt = if latest_rtt >= self.min_rtt + ack_delay {
+            latest_rtt - ack_delay
+        } else {
+            latest_rtt
+        };
+
+        if !self.has_sample {
+            self.smoothed_rtt = adjusted_rtt;
+            self.rttvar = adjusted_rtt / 2;
+            self.has_sample = true;
+            return;
+        }
+
+        let diff = self.smoothed_rtt.abs_diff(adjusted_rtt);
+        self.rttvar = (self.rttvar * 3 + diff) / 4;
+        self.smoothed_rtt = (self.smoothed_rtt * 7 + adjusted_rtt) / 8;
+    }
+
+    /// Most recent raw RTT sample.
+    pub fn latest_rtt(&self) -> Duration {
+        self.latest_rtt
+    }
+
+    /// Lowest RTT observed so far, or `Duration::MAX` if no sample yet.
+    pub fn min_rtt(&self) -> Duration {
+        self.min_rtt
+    }
+
+    /// Current smoothed RTT.
+    pub fn smoothed_rtt(&self) -> Duration {
+        self.smoothed_rtt
+    }
+
+    /// Current RTT variance.
+    pub fn rttvar(&self) -> Duration {
+        self.rttvar
+    }
+
+    /// Retransmission timeout: `smoothed_rtt + max(4 * rttvar, timer_granularity) + max_ack_delay`.
+    pub fn rto(&self) -> Duration {
+        self.smoothed_rtt + (self.rttvar * 4).max(TIMER_GRANULARITY) + self.max_ack_delay
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_sample_sets_srtt_and_half_rttvar() {
+        let mut est = RttEstimate::new(Duration::from_millis(25));
+        est.update(Duration::from_millis(100), Duration::from_millis(0));
+        assert_eq!(est.smoothed_rtt(), Duration::from_millis(100));
+        assert_eq!(est.rttvar(), Duration::from_millis(50));
+        assert_eq!(est.min_rtt(), Duration::from_millis(100));
+    }
+
+    #[test]
+    fn subsequent_sample_discounts_ack_delay() {
+        let mut est = RttEstimate::new(Duration::from_millis(0));
+        est.update(Duration::from_millis(100), Duration::from_millis(0));
+        // adjusted_rtt = 120 - 10 = 110ms since 120 >= min_rtt(100) + 10.
+        est.update(Duration::from_millis(120), Duration::from_millis(10));
+
+        // SRTT = 7/8 * 100 + 1/8 * 110 = 87.5 + 13.75 = 101.25ms
+        let srtt = est.smoothed_rtt();
+        assert!(
+            srtt.as_millis() >= 101 && srtt.as_millis() <= 102,
+            "srtt = {:?}",
+            srtt
+        );
+        assert_eq!(est.min_rtt(), Duration::from_millis(100));
+    }
+
+    #[test]
+    fn ack_delay_ignored_when_it_would_go_below_min_rtt() {
+        let mut est = RttEstimate::new(Duration::from_millis(0));
+        est.update(Duration::from_millis(100), Duration::from_millis(0));
+        // latest_rtt(100) < min_rtt(100) + ack_delay(50), so ack_delay is not
+        // subtracted and adjusted_rtt stays 100ms.
+        est.update(Duration::from_millis(100), Duration::from_millis(50));
+
+        // SRTT = 7/8 * 100 + 1/8 * 100 = 100ms.
+        assert_eq!(est.smoothed_rtt(), Duration::from_millis(100));
+    }
+
+    #[test]
+    fn min_rtt_tracks_lowest_sample() {
+        let mut est = RttEstimate::new(Duration::from_millis(0));
+        est.update(Duration::from_millis(100), Duration::from_millis(0));
+        est.update(Duration::from_millis(50), Duration::from_millis(0));
+        est.update(Duration::from_millis(80), Duration::from_millis(0));
+        assert_eq!(est.min_rtt(), Duration::from_millis(50));
+    }
+
+    #[test]
+    fn rto_includes_max_ack_delay_and_granularity_floor() {
+        let mut est = RttEstimate::new(Duration::from_millis(25));
+        est.update(Duration::from_millis(100), Duration::from_millis(0));
+        // rttvar = 50ms -> 4*rttvar = 200ms (dominates the 1ms floor).
+        // rto = 100 + 200 + 25 = 325ms.
+        assert_eq!(est.rto(), Duration::from_millis(325));
+    }
+}
@@ -0,0 +1,265 @@
+//! Accumulates individually observed sequence numbers into the minimal set
+//! of coalesced ranges, for building `Frame::Ack`/`Frame::Nack` payloads.
+//!
+//! Distinct from `crate::recovery::AckRanges`: that type takes whole ranges
+//! at a time (for coalescing retransmission bookkeeping over `u64` packet
+//! sequence numbers), while this one takes one `u32` sequence number at a time -- the
+//! shape a receiver actually has available as frames arrive -- and hands
+//! back `crate::frame::SeqRange`, the wire type `Ack`/`Nack` already carry.
+
+use crate::frame::SeqRange;
+
+/// A sorted, non-overlapping, non-adjacent set of inclusive `u32` sequence
+/// ranges, built one sequence number at a time via `insert`.
+///
+/// Ranges that become adjacent or overlapping on insertion are merged, so
+/// `iter()` always yields the minimal representation (e.g. inserting `5`,
+/// `6`, then `7` yields the single range `5..=7`).
+#[derive(Debug, Clone)]
+pub struct SeqRangeSet {
+    ranges: Vec<SeqRange>,
+    /// Maximum number of ranges to retain. Once exceeded, the two
+    /// lowest-sequence ranges are merged into one, collapsing the oldest gap
+    /// rather than growing unboundedly -- an adversarial pattern that keeps
+    /// leaving single-sequence gaps can otherwise force one range per gap.
+    cap: Option<usize>,
+}
+
+impl SeqRangeSet {
+    /// Create an unbounded set.
+    pub fn new() -> Self {
+        Self {
+            ranges: Vec::new(),
+            cap: None,
+        }
+    }
+
+    /// Create a set that collapses its oldest gap once it would otherwise
+    /// hold more than `cap` ranges.
+    pub fn with_cap(cap: usize) -> Self {
+        Self {
+            ranges: Vec::new(),
+            cap: Some(cap),
+        }
+    }
+
+    /// Record `seq` as observed, extending or merging an adjacent range if
+    /// one exists, otherwise inserting a new singleton range.
+    pub fn insert(&mut self, seq: u32) {
+        // First existing range that could possibly overlap or touch
+        // [seq, seq]: every range before it ends more than one below `seq`.
+        let i = self
+            .ranges
+            .partition_point(|r| r.end.saturating_add(1) < seq);
+
+        let mut new_start = seq;
+        let mut new_end = seq;
+        let mut merge_count = 0;
+        for existing in &self.ranges[i..] {
+            if existing.start > new_end.saturating_add(1) {
+                break;
+            }
+            new_start = new_start.min(existing.start);
+            new_end = new_end.max(existing.end);
+            merge_count += 1;
+        }
+        self.ranges.splice(
+            i..i + merge_count,
+            [SeqRange {
+                start: new_start,
+                end: new_end,
+            }],
+        );
+
+        if let Some(cap) = self.cap {
+            while self.ranges.len() > cap {
+                let second = self.ranges.remove(1);
+                self.ranges[0].end = second.end;
+            }
+        }
+    }
+
+    /// Returns `true` if `seq` falls within one of the tracked ranges.
+    pub fn contains(&self, seq: u32) -> bool {
+        self.ranges
+            .binary_search_by(|r| {
+                if r.end < seq {
+                    std::cmp::Ordering::Less
+                } else if r.start > seq {
+                    std::cmp::Ordering::Greater
+                } else {
+                    std::cmp::Ordering::Equal
+                }
+            })
+            .is_ok()
+    }
+
+    /// The tracked ranges in ascending order, ready to drop into an ACK frame.
+    pub fn iter(&self) -> impl Iterator<Item = &SeqRange> {
+        self.ranges.iter()
+    }
+
+    /// Returns `true` if no sequence numbers have been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// The complement of the tracked ranges within `0..=up_to`, i.e. the
+    /// unacknowledged gaps a NACK should name.
+    pub fn gaps(&self, up_to: u32) -> Vec<SeqRange> {
+        let mut gaps = Vec::new();
+        let mut cursor = 0u32;
+        for range in &self.ranges {
+            if cursor > up_to {
+                return gaps;
+            }
+            if range.start > cursor {
+                gaps.push(SeqRange {
+                    start: cursor,
+                    end: (range.start - 1).min(up_to),
+                });
+            }
+            cursor = match range.end.checked_add(1) {
+                Some(next) => next,
+                None => return gaps,
+            };
+        }
+        if cursor <= up_to {
+            gaps.push(SeqRange {
+                start: cursor,
+                end: up_to,
+            });
+        }
+        gaps
+    }
+}
+
+impl Default for SeqRangeSet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_single_seq() {
+        let mut s = SeqRangeSet::new();
+        s.insert(5);
+        assert!(s.contains(5));
+        assert!(!s.contains(4));
+        assert!(!s.contains(6));
+    }
+
+    #[test]
+    fn insert_merges_adjacent_ascending() {
+        let mut s = SeqRangeSet::new();
+        s.insert(5);
+        s.insert(6);
+        s.insert(7);
+        assert_eq!(
+            s.iter().collect::<Vec<_>>(),
+            vec![&SeqRange { start: 5, end: 7 }]
+        );
+    }
+
+    #[test]
+    fn insert_merges_adjacent_descending() {
+        let mut s = SeqRangeSet::new();
+        s.insert(7);
+        s.insert(6);
+        s.insert(5);
+        assert_eq!(
+            s.iter().collect::<Vec<_>>(),
+            vec![&SeqRange { start: 5, end: 7 }]
+        );
+    }
+
+    #[test]
+    fn insert_keeps_disjoint_ranges_separate() {
+        let mut s = SeqRangeSet::new();
+        s.insert(1);
+        s.insert(10);
+        assert_eq!(
+            s.iter().collect::<Vec<_>>(),
+            vec![&SeqRange { start: 1, end: 1 }, &SeqRange { start: 10, end: 10 }]
+        );
+    }
+
+    #[test]
+    fn insert_bridges_gap_between_two_ranges() {
+        let mut s = SeqRangeSet::new();
+        s.insert(1);
+        s.insert(10);
+        for seq in 2..10 {
+            s.insert(seq);
+        }
+        assert_eq!(
+            s.iter().collect::<Vec<_>>(),
+            vec![&SeqRange { start: 1, end: 10 }]
+        );
+    }
+
+    #[test]
+    fn duplicate_insert_is_a_no_op() {
+        let mut s = SeqRangeSet::new();
+        s.insert(5);
+        s.insert(5);
+        assert_eq!(s.iter().collect::<Vec<_>>(), vec![&SeqRange { start: 5, end: 5 }]);
+    }
+
+    #[test]
+    fn gaps_reports_complement_up_to_bound() {
+        let mut s = SeqRangeSet::new();
+        s.insert(2);
+        s.insert(3);
+        s.insert(7);
+
+        assert_eq!(
+            s.gaps(10),
+            vec![
+                SeqRange { start: 0, end: 1 },
+                SeqRange { start: 4, end: 6 },
+                SeqRange { start: 8, end: 10 },
+            ]
+        );
+    }
+
+    #[test]
+    fn gaps_empty_when_fully_covered() {
+        let mut s = SeqRangeSet::new();
+        for seq in 0..=5 {
+            s.insert(seq);
+        }
+        assert!(s.gaps(5).is_empty());
+    }
+
+    #[test]
+    fn gaps_on_empty_set_is_one_full_range() {
+        let s = SeqRangeSet::new();
+        assert_eq!(s.gaps(3), vec![SeqRange { start: 0, end: 3 }]);
+    }
+
+    #[test]
+    fn cap_collapses_oldest_gap() {
+        let mut s = SeqRangeSet::with_cap(2);
+        s.insert(1);
+        s.insert(10);
+        s.insert(20);
+        // Three singleton ranges would exceed the cap of 2, so the two
+        // lowest-sequence ranges (1 and 10) collapse into one, even though
+        // the gap between them was never actually filled.
+        assert_eq!(
+            s.iter().collect::<Vec<_>>(),
+            vec![&SeqRange { start: 1, end: 10 }, &SeqRange { start: 20, end: 20 }]
+        );
+    }
+
+    #[test]
+    fn is_empty_reports_no_ranges() {
+        let s = SeqRangeSet::new();
+        assert!(s.is_empty());
+    }
+}
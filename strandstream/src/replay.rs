@@ -0,0 +1,150 @@
+//! Per-stream anti-replay sliding window.
+//!
+//! A DTLS/IPsec-style bitmap that rejects frames whose sequence number is
+//! either older than the window or a duplicate already seen within it, so a
+//! replayed or reordered encrypted record is never delivered twice.
+
+use crate::error::{Result, StrandStreamError};
+
+/// Default window width in sequence numbers.
+pub const DEFAULT_WINDOW_WIDTH: u32 = 64;
+
+/// A sliding-window replay filter keyed on a single stream's `seq` space.
+///
+/// Tracks a `highest_seq` high-water mark plus a bitmap of the `width` most
+/// recent sequence numbers at or below it. A sequence number is accepted
+/// exactly once: either it raises the high-water mark (shifting the window
+/// forward), or it falls inside the current window and its bit was not yet
+/// set.
+#[derive(Debug, Clone)]
+pub struct ReplayWindow {
+    width: u32,
+    highest_seq: Option<u32>,
+    bitmap: u64,
+}
+
+impl ReplayWindow {
+    /// Create a new window. `width` is clamped to `1..=64` since the bitmap
+    /// backing the window is a single `u64`.
+    pub fn new(width: u32) -> Self {
+        Self {
+            width: width.clamp(1, 64),
+            highest_seq: None,
+            bitmap: 0,
+        }
+    }
+
+    /// The configured window width.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Check `seq` against the window and, if accepted, record it.
+    ///
+    /// Returns `Err(StrandStreamError::ReplayedFrame(seq))` if `seq` is older
+    /// than the window or a duplicate of one already seen.
+    pub fn check_and_update(&mut self, seq: u32) -> Result<()> {
+        let highest_seq = match self.highest_seq {
+            None => {
+                self.highest_seq = Some(seq);
+                self.bitmap = 1;
+                return Ok(());
+            }
+            Some(h) => h,
+        };
+
+        if seq > highest_seq {
+            // New high-water mark: shift the window forward and set bit 0.
+            let shift = u64::from(seq - highest_seq);
+            self.bitmap = if shift >= 64 { 0 } else { self.bitmap << shift };
+            self.bitmap |= 1;
+            self.highest_seq = Some(seq);
+            Ok(())
+        } else {
+            let age = u64::from(highest_seq - seq);
+            if age >= u64::from(self.width) {
+                return Err(StrandStreamError::ReplayedFrame(seq));
+            }
+            let bit = 1u64 << age;
+            if self.bitmap & bit != 0 {
+                return Err(StrandStreamError::ReplayedFrame(seq));
+            }
+            self.bitmap |= bit;
+            Ok(())
+        }
+    }
+}
+
+impl Default for ReplayWindow {
+    fn default() -> Self {
+        Self::new(DEFAULT_WINDOW_WIDTH)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_frame_always_accepted() {
+        let mut w = ReplayWindow::new(64);
+        assert!(w.check_and_update(100).is_ok());
+    }
+
+    #[test]
+    fn in_order_frames_accepted() {
+        let mut w = ReplayWindow::new(64);
+        for seq in 0..10 {
+            assert!(w.check_and_update(seq).is_ok());
+        }
+    }
+
+    #[test]
+    fn exact_duplicate_rejected() {
+        let mut w = ReplayWindow::new(64);
+        w.check_and_update(5).unwrap();
+        let err = w.check_and_update(5);
+        assert!(matches!(err, Err(StrandStreamError::ReplayedFrame(5))));
+    }
+
+    #[test]
+    fn reordered_frame_inside_window_accepted_once() {
+        let mut w = ReplayWindow::new(64);
+        w.check_and_update(10).unwrap();
+        w.check_and_update(8).unwrap();
+        let err = w.check_and_update(8);
+        assert!(matches!(err, Err(StrandStreamError::ReplayedFrame(8))));
+    }
+
+    #[test]
+    fn frame_older_than_window_rejected() {
+        let mut w = ReplayWindow::new(8);
+        w.check_and_update(100).unwrap();
+        let err = w.check_and_update(91); // age 9 >= width 8
+        assert!(matches!(err, Err(StrandStreamError::ReplayedFrame(91))));
+    }
+
+    #[test]
+    fn frame_just_inside_small_window_accepted() {
+        let mut w = ReplayWindow::new(8);
+        w.check_and_update(100).unwrap();
+        assert!(w.check_and_update(93).is_ok()); // age 7 < width 8
+    }
+
+    #[test]
+    fn large_forward_jump_resets_window() {
+        let mut w = ReplayWindow::new(8);
+        w.check_and_update(10).unwrap();
+        w.check_and_update(1000).unwrap();
+        // Old sequence numbers are now far outside the window.
+        assert!(w.check_and_update(10).is_err());
+        // But the new high-water mark's own neighbourhood still works.
+        assert!(w.check_and_update(999).is_ok());
+    }
+
+    #[test]
+    fn width_is_clamped_to_64() {
+        let w = ReplayWindow::new(1000);
+        assert_eq!(w.width(), 64);
+    }
+}
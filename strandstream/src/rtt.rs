@@ -45,11 +45,7 @@ impl RttEstimator {
             }
             Some(srtt) => {
                 // RTTVAR = 3/4 * RTTVAR + 1/4 * |SRTT - sample|
-                let diff = if srtt > sample {
-                    srtt - sample
-                } else {
-                    sample - srtt
-                };
+                let diff = srtt.abs_diff(sample);
                 let rttvar = self.rttvar.unwrap_or(diff);
                 let new_rttvar = (rttvar * 3 + diff) / 4;
                 self.rttvar = Some(new_rttvar);
@@ -0,0 +1,256 @@
+//! Priority-aware round-robin chunk scheduler.
+//!
+//! Sits above the [`TransportSender`](crate::transport::TransportSender)
+//! layer: splits large payloads into fixed-size chunks and multiplexes them
+//! across streams by [`TransmissionPriority`]. Strictly higher-priority
+//! streams drain first; among streams at the same priority, one chunk each
+//! is emitted per round-robin pass so a large bulk transfer cannot starve a
+//! small interactive one sharing the same class.
+//!
+//! This complements [`crate::mux::Multiplexer`]'s weighted/strict-priority
+//! byte scheduling -- that one distributes already-framed `Data` payloads
+//! across streams with proportional weights, while `ChunkScheduler` is the
+//! layer that turns one large `enqueue()` call into the fixed-size pieces a
+//! frame's MTU can carry in the first place.
+
+use std::collections::{BTreeMap, VecDeque};
+
+use bytes::Bytes;
+
+/// Default chunk size for large payloads: 16 KiB.
+pub const DEFAULT_CHUNK_SIZE: usize = 16 * 1024;
+
+/// Relative transmission priority for a stream's queued chunks.
+///
+/// Declared lowest-to-highest so that `Ord` orders `Critical` above `Low`;
+/// [`ChunkScheduler::poll_frames`] always drains every ready stream at the
+/// highest active priority before touching a lower one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum TransmissionPriority {
+    Low,
+    #[default]
+    Normal,
+    High,
+    Important,
+    Critical,
+}
+
+/// Per-stream queue of pending chunks at a single priority.
+struct StreamQueue {
+    priority: TransmissionPriority,
+    chunks: VecDeque<Bytes>,
+}
+
+/// Splits payloads into fixed-size chunks and multiplexes them across
+/// streams by [`TransmissionPriority`].
+pub struct ChunkScheduler {
+    chunk_size: usize,
+    queues: BTreeMap<u32, StreamQueue>,
+    /// Stream IDs in first-seen order, for deterministic round-robin service
+    /// within a priority class.
+    order: VecDeque<u32>,
+}
+
+impl ChunkScheduler {
+    /// Create a scheduler using the default 16 KiB chunk size.
+    pub fn new() -> Self {
+        Self::with_chunk_size(DEFAULT_CHUNK_SIZE)
+    }
+
+    /// Create a scheduler with an explicit chunk size (clamped to at least 1
+    /// byte so `enqueue` never loops forever on a non-empty payload).
+    pub fn with_chunk_size(chunk_size: usize) -> Self {
+        Self {
+            chunk_size: chunk_size.max(1),
+            queues: BTreeMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    /// Split `data` into `chunk_size` pieces and enqueue them for
+    /// `stream_id` at `priority`.
+    ///
+    /// A later call for the same `stream_id` updates its priority for any
+    /// chunks still queued -- a stream doesn't get to keep stale priority
+    /// after being reclassified.
+    pub fn enqueue(&mut self, stream_id: u32, data: Bytes, priority: TransmissionPriority) {
+        if !self.queues.contains_key(&stream_id) {
+            self.order.push_back(stream_id);
+        }
+        let queue = self.queues.entry(stream_id).or_insert_with(|| StreamQueue {
+            priority,
+            chunks: VecDeque::new(),
+        });
+        queue.priority = priority;
+
+        if data.is_empty() {
+            queue.chunks.push_back(data);
+            return;
+        }
+        let mut offset = 0;
+        while offset < data.len() {
+            let end = (offset + self.chunk_size).min(data.len());
+            queue.chunks.push_back(data.slice(offset..end));
+            offset = end;
+        }
+    }
+
+    /// Drop all pending chunks for `stream_id` (e.g. once it's closed/reset).
+    pub fn remove_stream(&mut self, stream_id: u32) {
+        self.queues.remove(&stream_id);
+        self.order.retain(|&id| id != stream_id);
+    }
+
+    /// Returns the highest priority with at least one stream holding pending
+    /// chunks, or `None` if every queue is empty.
+    fn highest_active_priority(&self) -> Option<TransmissionPriority> {
+        self.queues
+            .values()
+            .filter(|q| !q.chunks.is_empty())
+            .map(|q| q.priority)
+            .max()
+    }
+
+    /// Dequeue up to `budget` bytes' worth of chunks, respecting priority
+    /// and round-robin fairness within a class.
+    ///
+    /// Every chunk at the highest active priority is drained (one per
+    /// stream per round-robin pass) before any lower-priority stream is
+    /// touched at all. A chunk larger than the remaining budget is left in
+    /// place for a future call rather than split further.
+    pub fn poll_frames(&mut self, mut budget: usize) -> Vec<(u32, Bytes)> {
+        let mut out = Vec::new();
+        loop {
+            let Some(priority) = self.highest_active_priority() else {
+                break;
+            };
+            let ready: Vec<u32> = self
+                .order
+                .iter()
+                .copied()
+                .filter(|id| {
+                    self.queues
+                        .get(id)
+                        .is_some_and(|q| q.priority == priority && !q.chunks.is_empty())
+                })
+                .collect();
+            if ready.is_empty() {
+                break;
+            }
+
+            let mut progressed = false;
+            for id in ready {
+                if budget == 0 {
+                    break;
+                }
+                let Some(queue) = self.queues.get_mut(&id) else {
+                    continue;
+                };
+                let fits = queue.chunks.front().is_some_and(|c| c.len() <= budget);
+                if !fits {
+                    continue;
+                }
+                let chunk = queue.chunks.pop_front().expect("checked above");
+                budget -= chunk.len();
+                out.push((id, chunk));
+                progressed = true;
+            }
+            if !progressed || budget == 0 {
+                break;
+            }
+        }
+        out
+    }
+
+    /// Returns `true` if any stream has a chunk waiting to be polled.
+    pub fn has_pending(&self) -> bool {
+        self.queues.values().any(|q| !q.chunks.is_empty())
+    }
+}
+
+impl Default for ChunkScheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn small_payload_is_a_single_chunk() {
+        let mut sched = ChunkScheduler::new();
+        sched.enqueue(1, Bytes::from_static(b"hello"), TransmissionPriority::Normal);
+        let out = sched.poll_frames(usize::MAX);
+        assert_eq!(out, vec![(1, Bytes::from_static(b"hello"))]);
+    }
+
+    #[test]
+    fn large_payload_splits_into_fixed_size_chunks() {
+        let mut sched = ChunkScheduler::with_chunk_size(4);
+        sched.enqueue(1, Bytes::from_static(b"abcdefghij"), TransmissionPriority::Normal);
+        let out = sched.poll_frames(usize::MAX);
+        let chunks: Vec<&[u8]> = out.iter().map(|(_, c)| &c[..]).collect();
+        assert_eq!(chunks, vec![b"abcd".as_slice(), b"efgh".as_slice(), b"ij".as_slice()]);
+    }
+
+    #[test]
+    fn higher_priority_drains_before_lower() {
+        let mut sched = ChunkScheduler::with_chunk_size(4);
+        sched.enqueue(1, Bytes::from(vec![0u8; 100]), TransmissionPriority::Low);
+        sched.enqueue(2, Bytes::from(vec![1u8; 8]), TransmissionPriority::Critical);
+
+        let out = sched.poll_frames(usize::MAX);
+        // All of stream 2's (critical) chunks come first, then stream 1's.
+        let first_low_idx = out.iter().position(|(id, _)| *id == 1).unwrap();
+        let last_critical_idx = out.iter().rposition(|(id, _)| *id == 2).unwrap();
+        assert!(last_critical_idx < first_low_idx);
+    }
+
+    #[test]
+    fn same_priority_round_robins_one_chunk_each() {
+        let mut sched = ChunkScheduler::with_chunk_size(1);
+        sched.enqueue(1, Bytes::from_static(b"AAA"), TransmissionPriority::Normal);
+        sched.enqueue(2, Bytes::from_static(b"BBB"), TransmissionPriority::Normal);
+
+        let out = sched.poll_frames(usize::MAX);
+        let ids: Vec<u32> = out.iter().map(|(id, _)| *id).collect();
+        assert_eq!(ids, vec![1, 2, 1, 2, 1, 2]);
+    }
+
+    #[test]
+    fn budget_limits_chunks_emitted_per_call() {
+        let mut sched = ChunkScheduler::with_chunk_size(4);
+        sched.enqueue(1, Bytes::from_static(b"abcdefgh"), TransmissionPriority::Normal);
+
+        let out = sched.poll_frames(4);
+        assert_eq!(out, vec![(1, Bytes::from_static(b"abcd"))]);
+        assert!(sched.has_pending());
+
+        let out = sched.poll_frames(4);
+        assert_eq!(out, vec![(1, Bytes::from_static(b"efgh"))]);
+        assert!(!sched.has_pending());
+    }
+
+    #[test]
+    fn remove_stream_drops_pending_chunks() {
+        let mut sched = ChunkScheduler::new();
+        sched.enqueue(1, Bytes::from_static(b"gone"), TransmissionPriority::Normal);
+        sched.remove_stream(1);
+        assert!(!sched.has_pending());
+        assert!(sched.poll_frames(usize::MAX).is_empty());
+    }
+
+    #[test]
+    fn reenqueue_updates_priority() {
+        let mut sched = ChunkScheduler::with_chunk_size(1);
+        sched.enqueue(1, Bytes::from_static(b"a"), TransmissionPriority::Low);
+        sched.enqueue(1, Bytes::from_static(b"b"), TransmissionPriority::Critical);
+
+        // Both of stream 1's chunks are now tagged Critical.
+        assert_eq!(sched.queues[&1].priority, TransmissionPriority::Critical);
+        let out = sched.poll_frames(usize::MAX);
+        assert_eq!(out.len(), 2);
+    }
+}
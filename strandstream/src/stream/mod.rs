@@ -0,0 +1,1707 @@
+//! Individual stream state and operations.
+//!
+//! Each stream has an ID, a transport mode (immutable), and a state machine:
+//! Idle -> Open -> HalfClosedLocal / HalfClosedRemote -> Closed.
+//!
+//! The send and receive paths are delegated to mode-specific `TransportSender`
+//! and `TransportReceiver` objects (see `crate::transport`). This ensures that
+//! RU, BE, and Probabilistic streams use their proper deduplication, congestion,
+//! and ordering semantics rather than a generic `VecDeque`.
+
+pub mod split;
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::time::{Duration, Instant};
+
+pub use split::{StreamReceiver, StreamSender};
+
+use bytes::{Bytes, BytesMut};
+
+use crate::congestion::cubic::Cubic;
+use crate::congestion::new_reno::NewReno;
+use crate::congestion::{CongestionController, NoopController};
+use crate::error::{StrandStreamError, Result};
+use crate::frame::{DataFlags, Frame};
+use crate::transport::best_effort::{BestEffortReceiver, BestEffortSender};
+use crate::transport::probabilistic::{ProbabilisticReceiver, ProbabilisticSender};
+use crate::transport::reliable_ordered::{ReliableOrderedReceiver, ReliableOrderedSender};
+use crate::transport::reliable_unordered::{ReliableUnorderedReceiver, ReliableUnorderedSender};
+use crate::transport::replay_filter::ReplayFilteredReceiver;
+use crate::transport::sequenced::{SequencedReceiver, SequencedSender};
+use crate::transport::{TransportMode, TransportReceiver, TransportSender};
+
+/// Default scheduling weight assigned to a stream at creation.
+///
+/// Used by `Multiplexer::next_sendable`'s weighted deficit round-robin
+/// scheduler; a stream with twice the weight of another gets roughly twice
+/// the share of the send budget.
+pub const DEFAULT_STREAM_WEIGHT: u32 = 16;
+
+/// Default initial per-stream receive window, in bytes, before any
+/// `Frame::WindowUpdate` frames have been exchanged with the peer.
+pub const DEFAULT_RECV_WINDOW: u64 = 64 * 1024;
+
+/// Maximum payload bytes per `Frame::Data` chunk produced by
+/// `Stream::send_stream`/`poll_send_ready`.
+pub const DEFAULT_MSS: usize = 1200;
+
+/// Stream state machine states.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamState {
+    /// Stream has been allocated but not yet opened.
+    Idle,
+    /// Stream is fully open for bidirectional communication.
+    Open,
+    /// Local side has sent FIN; can still receive.
+    HalfClosedLocal,
+    /// Remote side has sent FIN; can still send.
+    HalfClosedRemote,
+    /// Stream is fully closed.
+    Closed,
+}
+
+/// Events that `Stream::poll_timeout` can surface to the mux layer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamEvent {
+    /// The stream had no send/receive activity for longer than its
+    /// configured idle timeout and has been reset (see `Stream::reset`).
+    IdleTimeout {
+        /// The stream that was reset.
+        stream_id: u32,
+    },
+}
+
+impl fmt::Display for StreamState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamState::Idle => write!(f, "Idle"),
+            StreamState::Open => write!(f, "Open"),
+            StreamState::HalfClosedLocal => write!(f, "HalfClosedLocal"),
+            StreamState::HalfClosedRemote => write!(f, "HalfClosedRemote"),
+            StreamState::Closed => write!(f, "Closed"),
+        }
+    }
+}
+
+/// A single multiplexed stream.
+///
+/// The outbound path goes through a mode-specific `TransportSender` that
+/// produces wire-ready `Frame` values (with sequence numbers assigned).
+/// The inbound path goes through a mode-specific `TransportReceiver` that
+/// applies mode semantics (ordering, deduplication, congestion gating) and
+/// returns zero or more application-visible `Bytes` payloads.
+///
+/// A plain `VecDeque<Bytes>` is still kept as the **application-level receive
+/// queue** — the receiver converts `Frame`s into `Bytes` and those are pushed
+/// here so that `Stream::recv()` remains a simple pop operation.
+pub struct Stream {
+    /// Stream identifier.
+    id: u32,
+    /// The delivery mode for this stream (set at creation, immutable).
+    mode: TransportMode,
+    /// Current state.
+    state: StreamState,
+    /// Mode-specific sender: assigns sequence numbers, buffers for retransmit.
+    sender: Box<dyn TransportSender>,
+    /// Mode-specific receiver: applies ordering / dedup / probability filter.
+    receiver: Box<dyn TransportReceiver>,
+    /// Outbound frames ready to be handed to the network layer.
+    pending_frames: Vec<Frame>,
+    /// Application-level receive queue: payloads extracted by the receiver.
+    recv_buf: VecDeque<Bytes>,
+    /// Scheduling weight used by `Multiplexer::next_sendable` (see
+    /// `DEFAULT_STREAM_WEIGHT`).
+    priority: u32,
+    /// Size of the credit re-issued by a `Frame::WindowUpdate`, in bytes.
+    /// Set once at construction time (see `with_recv_window`).
+    recv_window: u64,
+    /// Highest cumulative inbound byte offset we will accept without
+    /// rejecting the frame for flow control.
+    recv_max_offset: u64,
+    /// Cumulative bytes offered to `transport_receive` so far (i.e. the
+    /// current inbound byte offset), used to enforce `recv_max_offset`.
+    recv_bytes_received: u64,
+    /// Cumulative bytes handed to the application via `recv()`.
+    recv_bytes_consumed: u64,
+    /// Highest cumulative byte offset we are permitted to send, per the
+    /// peer's most recent `Frame::WindowUpdate`.
+    send_max_offset: u64,
+    /// Cumulative bytes queued via `send()` so far.
+    send_bytes_sent: u64,
+    /// `send_max_offset` value a `Frame::StreamDataBlocked` was last queued
+    /// for, so a stream stalled on the same limit across repeated
+    /// `poll_send_ready()` calls announces it once rather than every call.
+    /// Cleared by `apply_window_update` once the limit actually moves.
+    data_blocked_limit_sent: Option<u64>,
+    /// Congestion controller gating how many unacknowledged bytes this
+    /// stream may have outstanding. New Reno for ReliableOrdered/
+    /// ReliableUnordered, Cubic for BestEffort, and a no-op for
+    /// Probabilistic/Sequenced (see `default_congestion_controller`).
+    congestion: Box<dyn CongestionController>,
+    /// Frames produced by `sender.send()` that the congestion window does
+    /// not yet admit; released into `pending_frames` as `on_ack` opens room.
+    cwnd_queue: VecDeque<Frame>,
+    /// Payload length of each in-flight `Data` frame, keyed by `seq`, so
+    /// `on_ack` can report the right byte count to `congestion`.
+    inflight_lens: HashMap<u32, usize>,
+    /// Timestamp of the most recent `send()`, `transport_receive()`, or
+    /// data-producing `recv()` call. `None` until the first such call.
+    last_activity: Option<Instant>,
+    /// How long this stream may go without activity before `poll_timeout`
+    /// resets it. `None` (the default) disables idle timeout entirely.
+    idle_timeout: Option<Duration>,
+    /// Error code from an inbound `Frame::Rst` (see `on_reset`), surfaced to
+    /// the application by `recv()` as `StreamReset` instead of the generic
+    /// `StreamClosed`. `None` for streams closed any other way.
+    reset_code: Option<u32>,
+    /// Error code from an inbound `Frame::StopSending` (see
+    /// `on_stop_sending`), once set `send_with_flags` rejects further sends
+    /// with `StreamSendStopped` instead of chunking them out. Unlike
+    /// `reset_code` this does not close the stream or touch any buffered
+    /// receive-side data -- the peer is only refusing to read our writes,
+    /// not tearing down the stream.
+    stop_sending_code: Option<u32>,
+    /// Bytes queued via `send_stream()` not yet chunked into `pending_frames`
+    /// -- held here until `can_send()` credit allows more, so a multi-chunk
+    /// body can be pushed in without buffering it all downstream at once.
+    stream_body: Bytes,
+    /// Whether `stream_body` ends the streamed body: once it's fully
+    /// chunked out, the terminal `Frame::Data` carries `DataFlags::FIN`.
+    stream_fin: bool,
+    /// Chunk size `send_stream()`/`poll_send_ready()` slice `stream_body`
+    /// into, in bytes. Defaults to `DEFAULT_MSS`; raise it via `set_mss`
+    /// once path MTU discovery (`crate::transport::pmtud::Pmtud`) confirms a
+    /// larger `effective_mtu` (which never drops below the 1200-byte base).
+    mss: usize,
+}
+
+/// The default congestion controller for a given transport mode.
+///
+/// BestEffort uses `Cubic` rather than the no-op controller: unlike RO/RU it
+/// has no retransmission to keep bytes-in-flight bounded, so a real window is
+/// what caps how much unacknowledged data a BE stream can have outstanding.
+/// `Stream::on_ack`/`Stream::retransmit` keep `BestEffortSender` in sync via
+/// `TransportSender::set_cwnd_hint`.
+fn default_congestion_controller(mode: TransportMode) -> Box<dyn CongestionController> {
+    match mode {
+        TransportMode::ReliableOrdered | TransportMode::ReliableUnordered => {
+            Box::new(NewReno::new())
+        }
+        TransportMode::BestEffort => Box::new(Cubic::new()),
+        TransportMode::Probabilistic | TransportMode::Sequenced => Box::new(NoopController),
+    }
+}
+
+impl Stream {
+    /// Create a new stream in the Idle state.
+    ///
+    /// The correct `TransportSender` / `TransportReceiver` pair is instantiated
+    /// based on `mode`.  Probabilistic streams use a 50% delivery probability
+    /// by default; callers that need a different probability should use
+    /// `new_probabilistic()`.
+    pub fn new(id: u32, mode: TransportMode) -> Self {
+        Self::with_recv_window(id, mode, DEFAULT_RECV_WINDOW)
+    }
+
+    /// Create a new stream with a non-default initial receive window (see
+    /// `recv_max_offset`/`DEFAULT_RECV_WINDOW`). The send-side window starts
+    /// out equal to `recv_window` as well, on the assumption that the peer
+    /// applies the same default; it is corrected by the peer's first
+    /// `Frame::WindowUpdate` if that assumption doesn't hold.
+    pub fn with_recv_window(id: u32, mode: TransportMode, recv_window: u64) -> Self {
+        let (sender, receiver): (Box<dyn TransportSender>, Box<dyn TransportReceiver>) =
+            match mode {
+                TransportMode::ReliableOrdered => (
+                    Box::new(ReliableOrderedSender::new()),
+                    Box::new(ReliableOrderedReceiver::new()),
+                ),
+                TransportMode::ReliableUnordered => (
+                    Box::new(ReliableUnorderedSender::new()),
+                    Box::new(ReliableUnorderedReceiver::new()),
+                ),
+                TransportMode::BestEffort => (
+                    Box::new(BestEffortSender::new()),
+                    Box::new(BestEffortReceiver::new()),
+                ),
+                TransportMode::Probabilistic => (
+                    Box::new(ProbabilisticSender::new()),
+                    // Default probability 0.5 — override with `new_probabilistic`.
+                    Box::new(ProbabilisticReceiver::new(0.5)),
+                ),
+                TransportMode::Sequenced => (
+                    Box::new(SequencedSender::new()),
+                    Box::new(SequencedReceiver::new()),
+                ),
+            };
+
+        Self {
+            id,
+            mode,
+            state: StreamState::Idle,
+            sender,
+            receiver,
+            pending_frames: Vec::new(),
+            recv_buf: VecDeque::new(),
+            priority: DEFAULT_STREAM_WEIGHT,
+            recv_window,
+            recv_max_offset: recv_window,
+            recv_bytes_received: 0,
+            recv_bytes_consumed: 0,
+            send_max_offset: recv_window,
+            send_bytes_sent: 0,
+            data_blocked_limit_sent: None,
+            congestion: default_congestion_controller(mode),
+            cwnd_queue: VecDeque::new(),
+            inflight_lens: HashMap::new(),
+            last_activity: None,
+            idle_timeout: None,
+            reset_code: None,
+            stop_sending_code: None,
+            stream_body: Bytes::new(),
+            stream_fin: false,
+            mss: DEFAULT_MSS,
+        }
+    }
+
+    /// Create a `BestEffort` or `ReliableUnordered` stream whose receiver is
+    /// wrapped in a sliding-window anti-replay filter (see
+    /// `crate::transport::replay_filter::ReplayFilteredReceiver`), dropping
+    /// stale or duplicate `seq`s in O(1) instead of relying on the mode's own
+    /// (nonexistent, for BE) or unbounded-buffering (for RU) dedup.
+    ///
+    /// `window_width` is clamped to `1..=64`; see `crate::replay::ReplayWindow`.
+    /// Other modes already reject duplicates via their ordering logic, so for
+    /// them this is equivalent to `Stream::new`.
+    pub fn with_replay_filter(id: u32, mode: TransportMode, window_width: u32) -> Self {
+        let mut s = Self::new(id, mode);
+        s.receiver = match mode {
+            TransportMode::BestEffort => Box::new(ReplayFilteredReceiver::with_window_width(
+                BestEffortReceiver::new(),
+                window_width,
+            )),
+            TransportMode::ReliableUnordered => {
+                Box::new(ReplayFilteredReceiver::with_window_width(
+                    ReliableUnorderedReceiver::new(),
+                    window_width,
+                ))
+            }
+            TransportMode::ReliableOrdered | TransportMode::Probabilistic | TransportMode::Sequenced => {
+                return s
+            }
+        };
+        s
+    }
+
+    /// Create a new Probabilistic stream with a custom delivery probability.
+    pub fn new_probabilistic(id: u32, probability: f64) -> Self {
+        let sender: Box<dyn TransportSender> = Box::new(ProbabilisticSender::new());
+        let receiver: Box<dyn TransportReceiver> =
+            Box::new(ProbabilisticReceiver::new(probability));
+        Self {
+            id,
+            mode: TransportMode::Probabilistic,
+            state: StreamState::Idle,
+            sender,
+            receiver,
+            pending_frames: Vec::new(),
+            recv_buf: VecDeque::new(),
+            priority: DEFAULT_STREAM_WEIGHT,
+            recv_window: DEFAULT_RECV_WINDOW,
+            recv_max_offset: DEFAULT_RECV_WINDOW,
+            recv_bytes_received: 0,
+            recv_bytes_consumed: 0,
+            send_max_offset: DEFAULT_RECV_WINDOW,
+            send_bytes_sent: 0,
+            data_blocked_limit_sent: None,
+            congestion: Box::new(NoopController),
+            cwnd_queue: VecDeque::new(),
+            inflight_lens: HashMap::new(),
+            last_activity: None,
+            idle_timeout: None,
+            reset_code: None,
+            stop_sending_code: None,
+            stream_body: Bytes::new(),
+            stream_fin: false,
+            mss: DEFAULT_MSS,
+        }
+    }
+
+    /// Returns the stream ID.
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// Returns the transport mode.
+    pub fn mode(&self) -> TransportMode {
+        self.mode
+    }
+
+    /// Returns the current state.
+    pub fn state(&self) -> StreamState {
+        self.state
+    }
+
+    /// Returns the stream's scheduling weight.
+    pub fn priority(&self) -> u32 {
+        self.priority
+    }
+
+    /// Set the stream's scheduling weight, used by
+    /// `Multiplexer::next_sendable`.
+    pub fn set_priority(&mut self, weight: u32) {
+        self.priority = weight;
+    }
+
+    /// The stream's configured idle timeout, if any (see `set_idle_timeout`).
+    pub fn idle_timeout(&self) -> Option<Duration> {
+        self.idle_timeout
+    }
+
+    /// Set the idle timeout: if no activity (`send()`, `transport_receive()`,
+    /// or a data-producing `recv()`) occurs within `timeout`, `poll_timeout`
+    /// resets the stream. Opt-in -- unset by default, meaning no timeout.
+    pub fn set_idle_timeout(&mut self, timeout: Duration) {
+        self.idle_timeout = Some(timeout);
+    }
+
+    /// Disable the idle timeout.
+    pub fn clear_idle_timeout(&mut self) {
+        self.idle_timeout = None;
+    }
+
+    /// The chunk size `send_stream()` slices a streamed body into.
+    pub fn mss(&self) -> usize {
+        self.mss
+    }
+
+    /// Set the chunk size `send_stream()`/`poll_send_ready()` use, in place
+    /// of `DEFAULT_MSS`. Intended to be fed from `Pmtud::effective_mtu()`
+    /// once path MTU discovery confirms a (usually larger) usable segment
+    /// size; does not retroactively re-chunk a chunk already queued in
+    /// `pending_frames`.
+    pub fn set_mss(&mut self, mss: usize) {
+        self.mss = mss.max(1);
+    }
+
+    /// The deadline at which this stream would time out if left idle, i.e.
+    /// `last_activity + idle_timeout`. Returns `None` if no idle timeout is
+    /// configured or no activity has been recorded yet. The mux layer can
+    /// take the minimum of this across all streams to drive a single
+    /// connection-level timer instead of polling every stream.
+    pub fn next_deadline(&self) -> Option<Instant> {
+        Some(self.last_activity? + self.idle_timeout?)
+    }
+
+    /// Check whether this stream has been idle past its configured timeout
+    /// as of `now`, resetting it and returning `StreamEvent::IdleTimeout` if
+    /// so. Returns `None` if no idle timeout is set, no activity has been
+    /// recorded yet, or the timeout hasn't elapsed.
+    pub fn poll_timeout(&mut self, now: Instant) -> Option<StreamEvent> {
+        let deadline = self.next_deadline()?;
+        if now < deadline {
+            return None;
+        }
+        self.reset();
+        Some(StreamEvent::IdleTimeout { stream_id: self.id })
+    }
+
+    /// The highest cumulative byte offset we currently accept from the peer.
+    pub fn recv_max_offset(&self) -> u64 {
+        self.recv_max_offset
+    }
+
+    /// The highest cumulative byte offset the peer currently allows us to send.
+    pub fn send_max_offset(&self) -> u64 {
+        self.send_max_offset
+    }
+
+    /// Bytes remaining in the send window before `send()` would be rejected.
+    pub fn send_window_remaining(&self) -> u64 {
+        self.send_max_offset.saturating_sub(self.send_bytes_sent)
+    }
+
+    /// Transition the stream to the Open state.
+    pub fn open(&mut self) -> Result<()> {
+        match self.state {
+            StreamState::Idle => {
+                self.state = StreamState::Open;
+                Ok(())
+            }
+            _ => Err(StrandStreamError::InvalidStateTransition {
+                from: self.state.to_string(),
+                to: "Open".into(),
+            }),
+        }
+    }
+
+    /// Queue data for sending on this stream.
+    ///
+    /// The data is passed to the mode-specific `TransportSender` which assigns
+    /// a sequence number and returns the wire frame(s). Each frame is then
+    /// admitted into `pending_frames` only if `congestion` has room for it;
+    /// frames that don't fit under the current congestion window wait in
+    /// `cwnd_queue` and are released as `on_ack` opens room (see
+    /// `release_cwnd_queue`).
+    ///
+    /// Rejected with `FlowControlViolation` if this send would push
+    /// `send_bytes_sent` past the peer's last-advertised `send_max_offset`;
+    /// the peer raises that limit by sending a `Frame::WindowUpdate`
+    /// (applied via `apply_window_update`).
+    pub fn send(&mut self, data: Bytes) -> Result<()> {
+        self.send_with_flags(data, DataFlags::NONE)
+    }
+
+    /// Queue `data` as an unreliable `Frame::Datagram`, bypassing the
+    /// mode-specific `TransportSender`, flow control, and the congestion
+    /// window entirely -- it goes straight onto `pending_frames`. Only valid
+    /// for `TransportMode::BestEffort` streams, since this exists precisely
+    /// to skip the sequencing/buffering overhead that mode's ordered `send()`
+    /// still pays for.
+    pub fn send_datagram(&mut self, data: Bytes) -> Result<()> {
+        if self.mode != TransportMode::BestEffort {
+            return Err(StrandStreamError::DatagramRequiresBestEffort(self.id));
+        }
+        match self.state {
+            StreamState::Open | StreamState::HalfClosedRemote => {
+                self.pending_frames.push(Frame::Datagram {
+                    flags: DataFlags::NONE,
+                    payload: data,
+                });
+                self.last_activity = Some(Instant::now());
+                Ok(())
+            }
+            StreamState::HalfClosedLocal | StreamState::Closed => {
+                Err(StrandStreamError::StreamClosed(self.id))
+            }
+            StreamState::Idle => Err(StrandStreamError::InvalidStateTransition {
+                from: "Idle".into(),
+                to: "send".into(),
+            }),
+        }
+    }
+
+    /// Shared implementation behind `send()` and the chunks `poll_send_ready`
+    /// produces for `send_stream()`; `flags` lets the latter mark the
+    /// terminal chunk of a streamed body with `DataFlags::FIN`.
+    fn send_with_flags(&mut self, data: Bytes, flags: DataFlags) -> Result<()> {
+        match self.state {
+            StreamState::Open | StreamState::HalfClosedRemote => {
+                if let Some(error_code) = self.stop_sending_code {
+                    return Err(StrandStreamError::StreamSendStopped {
+                        stream_id: self.id,
+                        error_code,
+                    });
+                }
+                let len = data.len() as u64;
+                if self.send_bytes_sent.saturating_add(len) > self.send_max_offset {
+                    self.note_send_blocked();
+                    return Err(StrandStreamError::FlowControlViolation);
+                }
+                let frames = self.sender.send(self.id, data, flags)?;
+                self.send_bytes_sent += len;
+                for frame in frames {
+                    self.admit_or_queue(frame);
+                }
+                self.last_activity = Some(Instant::now());
+                Ok(())
+            }
+            StreamState::HalfClosedLocal | StreamState::Closed => {
+                Err(StrandStreamError::StreamClosed(self.id))
+            }
+            StreamState::Idle => Err(StrandStreamError::InvalidStateTransition {
+                from: "Idle".into(),
+                to: "send".into(),
+            }),
+        }
+    }
+
+    /// Current send credit, in bytes: the smaller of the remaining
+    /// flow-control send window (`send_window_remaining`) and the
+    /// congestion controller's current send credit (`congestion.can_send_bytes()`,
+    /// ordinarily `window() - bytes_in_flight()`, but e.g. PRR-paced during a
+    /// `Cubic` loss recovery episode). This is how much
+    /// `send_stream()`/`poll_send_ready()` can chunk out right now without
+    /// blocking; callers streaming a large body can also consult it directly
+    /// to size their next push.
+    pub fn can_send(&self) -> usize {
+        let flow_credit = self.send_window_remaining().min(usize::MAX as u64) as usize;
+        let cwnd_credit = self.congestion.can_send_bytes();
+        flow_credit.min(cwnd_credit)
+    }
+
+    /// Queue `data` as (a segment of) a body to stream out over this stream.
+    ///
+    /// Unlike `send()`, which hands the whole payload to the transport
+    /// sender in one `Frame::Data`, this chunks `data` into `mss()`-sized
+    /// pieces (`DEFAULT_MSS` unless overridden via `set_mss`) so a
+    /// multi-megabyte body doesn't have to be materialized as a single
+    /// frame. Only as much as `can_send()`
+    /// currently allows is admitted into `pending_frames`/`cwnd_queue`
+    /// right away (see `poll_send_ready`); any remainder is buffered in
+    /// `stream_body` and sent by a later `poll_send_ready()` call -- e.g.
+    /// once an `on_ack` or inbound `Frame::WindowUpdate` frees more credit
+    /// -- so a caller never has to hold the whole body downstream at once.
+    ///
+    /// Pass `fin = true` once `data` is the last segment of the body; the
+    /// terminal chunk then carries `DataFlags::FIN`. Call this with an
+    /// empty `data` and `fin = true` to close out a body with no further
+    /// bytes to send.
+    pub fn send_stream(&mut self, data: Bytes, fin: bool) -> Result<()> {
+        match self.state {
+            StreamState::Open | StreamState::HalfClosedRemote => {}
+            StreamState::HalfClosedLocal | StreamState::Closed => {
+                return Err(StrandStreamError::StreamClosed(self.id));
+            }
+            StreamState::Idle => {
+                return Err(StrandStreamError::InvalidStateTransition {
+                    from: "Idle".into(),
+                    to: "send".into(),
+                });
+            }
+        }
+
+        if self.stream_body.is_empty() {
+            self.stream_body = data;
+        } else {
+            let mut combined = BytesMut::with_capacity(self.stream_body.len() + data.len());
+            combined.extend_from_slice(&self.stream_body);
+            combined.extend_from_slice(&data);
+            self.stream_body = combined.freeze();
+        }
+        self.stream_fin = self.stream_fin || fin;
+        self.poll_send_ready()
+    }
+
+    /// Resume chunking out any `send_stream()` body buffered in
+    /// `stream_body`, admitting `mss()`-sized `Frame::Data` chunks one at a
+    /// time as long as `can_send()` reports credit. Stops (without
+    /// error) as soon as credit runs out, leaving the rest of the body
+    /// buffered for the next call -- the mux layer calls this again after
+    /// events that free up credit (`on_ack`, an inbound `Frame::WindowUpdate`).
+    ///
+    /// Once `stream_body` is fully drained and `fin` was requested, emits a
+    /// final `DataFlags::FIN`-carrying chunk: the last body chunk carries
+    /// FIN itself, and an empty FIN chunk is sent only when no body bytes
+    /// were left to carry it.
+    pub fn poll_send_ready(&mut self) -> Result<()> {
+        loop {
+            if self.stream_body.is_empty() {
+                if self.stream_fin {
+                    self.send_with_flags(Bytes::new(), DataFlags::FIN)?;
+                    self.stream_fin = false;
+                }
+                return Ok(());
+            }
+
+            let credit = self.can_send();
+            if credit == 0 {
+                if self.send_window_remaining() == 0 {
+                    self.note_send_blocked();
+                }
+                return Ok(());
+            }
+
+            let take = credit.min(self.mss).min(self.stream_body.len());
+            if take == 0 {
+                return Ok(());
+            }
+            let chunk = self.stream_body.split_to(take);
+            let is_final_chunk = self.stream_body.is_empty() && self.stream_fin;
+            let flags = if is_final_chunk { DataFlags::FIN } else { DataFlags::NONE };
+            self.send_with_flags(chunk, flags)?;
+            if is_final_chunk {
+                self.stream_fin = false;
+            }
+        }
+    }
+
+    /// Admit `frame` into `pending_frames` if the congestion window allows
+    /// it right now, otherwise hold it in `cwnd_queue`.
+    ///
+    /// BestEffort is the one exception: it always admits immediately.  Its
+    /// `Cubic` controller (see `default_congestion_controller`) still tracks
+    /// real window dynamics fed by `on_ack`/`retransmit` and drives
+    /// `BestEffortSender` via `set_cwnd_hint`, but fire-and-forget delivery
+    /// means BE never buffers -- a congested window shows up as the sender
+    /// silently dropping frames, not as queuing here.
+    fn admit_or_queue(&mut self, frame: Frame) {
+        let len = Self::frame_payload_len(&frame);
+        if self.mode == TransportMode::BestEffort || self.congestion.can_send(len) {
+            self.admit_frame(frame, len);
+        } else {
+            self.cwnd_queue.push_back(frame);
+        }
+    }
+
+    /// Move `frame` into `pending_frames`, charging its bytes against the
+    /// congestion window and recording its length for the eventual `on_ack`.
+    fn admit_frame(&mut self, frame: Frame, len: usize) {
+        self.congestion.on_packet_sent(len);
+        if let Frame::Data { seq, .. } = &frame {
+            self.inflight_lens.insert(*seq, len);
+        }
+        self.pending_frames.push(frame);
+    }
+
+    /// Release as many `cwnd_queue` frames as the congestion window now
+    /// admits, in FIFO order, stopping at the first one that still doesn't fit.
+    fn release_cwnd_queue(&mut self) {
+        while let Some(frame) = self.cwnd_queue.front() {
+            let len = Self::frame_payload_len(frame);
+            if !self.congestion.can_send(len) {
+                break;
+            }
+            let frame = self.cwnd_queue.pop_front().expect("front just peeked");
+            self.admit_frame(frame, len);
+        }
+    }
+
+    /// Apply an inbound `Frame::WindowUpdate`, raising `send_max_offset` by
+    /// `window_increment` bytes so `send()` can make further progress.
+    pub fn apply_window_update(&mut self, window_increment: u32) {
+        self.send_max_offset = self.send_max_offset.saturating_add(window_increment as u64);
+        self.data_blocked_limit_sent = None;
+    }
+
+    /// Queue a `Frame::StreamDataBlocked` announcing `send_max_offset` as the
+    /// limit stalling this stream's sends, unless one was already queued for
+    /// the same limit (cleared by `apply_window_update` once it moves) --
+    /// otherwise a stalled `poll_send_ready()` would re-announce the same
+    /// limit on every call.
+    fn note_send_blocked(&mut self) {
+        if self.data_blocked_limit_sent != Some(self.send_max_offset) {
+            self.data_blocked_limit_sent = Some(self.send_max_offset);
+            self.pending_frames.push(Frame::StreamDataBlocked {
+                stream_id: self.id,
+                limit: self.send_max_offset,
+            });
+        }
+    }
+
+    /// Receive data from this stream (returns buffered data, if any).
+    ///
+    /// Advances `recv_bytes_consumed` and, once the unused portion of the
+    /// receive window drops below half of `recv_window`, pushes a
+    /// `Frame::WindowUpdate` onto `pending_frames` re-opening the window
+    /// (see `maybe_emit_window_update`).
+    pub fn recv(&mut self) -> Result<Option<Bytes>> {
+        let popped = match self.state {
+            StreamState::Open | StreamState::HalfClosedLocal => {
+                Ok(self.recv_buf.pop_front())
+            }
+            StreamState::HalfClosedRemote | StreamState::Closed => {
+                // Can still drain buffer even if remote closed.
+                if let Some(data) = self.recv_buf.pop_front() {
+                    Ok(Some(data))
+                } else if self.state == StreamState::Closed {
+                    match self.reset_code {
+                        Some(code) => Err(StrandStreamError::StreamReset { code }),
+                        None => Err(StrandStreamError::StreamClosed(self.id)),
+                    }
+                } else {
+                    Ok(None)
+                }
+            }
+            StreamState::Idle => Err(StrandStreamError::InvalidStateTransition {
+                from: "Idle".into(),
+                to: "recv".into(),
+            }),
+        }?;
+
+        if let Some(data) = &popped {
+            self.recv_bytes_consumed += data.len() as u64;
+            self.maybe_emit_window_update();
+            self.last_activity = Some(Instant::now());
+        }
+        Ok(popped)
+    }
+
+    /// Re-open the receive window once consumption has eaten into half of it.
+    ///
+    /// Advertises `recv_max_offset = recv_bytes_consumed + recv_window` via a
+    /// `Frame::WindowUpdate { window_increment, .. }` so the peer's
+    /// `send_max_offset` (applied through `apply_window_update`) tracks how
+    /// much room the application has actually freed up, not just how many
+    /// bytes have arrived on the wire.
+    fn maybe_emit_window_update(&mut self) {
+        let unused = self.recv_max_offset.saturating_sub(self.recv_bytes_consumed);
+        if unused >= self.recv_window / 2 {
+            return;
+        }
+        let new_max_offset = self.recv_bytes_consumed + self.recv_window;
+        if new_max_offset <= self.recv_max_offset {
+            return;
+        }
+        let increment = (new_max_offset - self.recv_max_offset).min(u32::MAX as u64) as u32;
+        self.recv_max_offset = new_max_offset;
+        self.pending_frames.push(Frame::WindowUpdate {
+            stream_id: self.id,
+            window_increment: increment,
+        });
+    }
+
+    /// Process an inbound `Frame` through the mode-specific `TransportReceiver`.
+    ///
+    /// `Frame::Data` payloads are first checked against `recv_max_offset`:
+    /// a payload whose cumulative byte offset would exceed it is rejected
+    /// with `FlowControlBlocked` rather than being buffered, bounding how
+    /// much memory a fast remote can make this stream hold. Frames that pass
+    /// go to the receiver, which applies mode semantics (in-order
+    /// reassembly for RO, deduplication for RU, probabilistic drop for PR,
+    /// unconditional delivery for BE) and returns the payloads that are
+    /// ready for the application. Those payloads are pushed onto the
+    /// application-level receive queue so that subsequent `recv()` calls
+    /// return them.
+    ///
+    /// This is the primary inbound path called by the mux layer; the legacy
+    /// `push_recv()` helper is preserved for direct testing.
+    pub fn transport_receive(&mut self, frame: &Frame) -> Result<()> {
+        if let Frame::Rst { error_code, final_size, .. } = frame {
+            return self.on_reset(*error_code, *final_size);
+        }
+
+        if let Frame::Datagram { payload, .. } = frame {
+            // Bypasses the mode-specific receiver (ordering/dedup) and the
+            // recv-side flow-control accounting entirely -- a datagram isn't
+            // part of any byte stream those mechanisms track.
+            self.push_recv(payload.clone());
+            self.last_activity = Some(Instant::now());
+            return Ok(());
+        }
+
+        if let Frame::Data { payload, .. } = frame {
+            let incoming_offset = self.recv_bytes_received.saturating_add(payload.len() as u64);
+            if incoming_offset > self.recv_max_offset {
+                return Err(StrandStreamError::FlowControlBlocked(self.id));
+            }
+            self.recv_bytes_received = incoming_offset;
+        }
+
+        let payloads = self.receiver.receive(frame)?;
+        for payload in payloads {
+            self.recv_buf.push_back(payload);
+        }
+        self.last_activity = Some(Instant::now());
+        Ok(())
+    }
+
+    /// Enqueue received data into the receive buffer directly (bypasses the
+    /// transport receiver; useful for unit tests and the pure-Go overlay path).
+    pub fn push_recv(&mut self, data: Bytes) {
+        self.recv_buf.push_back(data);
+    }
+
+    /// Drain all outbound frames produced by `send()` calls (called by the
+    /// mux layer before transmitting to the network).
+    pub fn drain_frames(&mut self) -> Vec<Frame> {
+        std::mem::take(&mut self.pending_frames)
+    }
+
+    /// Whether this stream has any outbound frame (data or control) waiting
+    /// to be sent.
+    pub fn has_pending_frames(&self) -> bool {
+        !self.pending_frames.is_empty()
+    }
+
+    /// Peek at the payload length of the next pending frame, if any.
+    ///
+    /// Used by the multiplexer's scheduler to decide whether a frame fits
+    /// within a stream's current deficit / the remaining send budget without
+    /// dequeuing it.
+    pub fn peek_pending_frame_len(&self) -> Option<usize> {
+        self.pending_frames.first().map(Self::frame_payload_len)
+    }
+
+    /// Peek at the on-wire serialized length of the next pending frame, if
+    /// any (see [`Frame::encoded_len`]).
+    ///
+    /// Used by `Multiplexer::drain_frames`'s deficit round-robin scheduler,
+    /// which -- unlike `next_sendable`'s payload-byte budget -- interleaves
+    /// every frame kind (control frames included) and so weighs fairness by
+    /// what actually goes out on the wire.
+    pub fn peek_pending_frame_encoded_len(&self) -> Option<usize> {
+        self.pending_frames.first().map(Frame::encoded_len)
+    }
+
+    /// Pop the oldest pending frame (FIFO), for the scheduler to hand off.
+    pub fn pop_pending_frame(&mut self) -> Option<Frame> {
+        if self.pending_frames.is_empty() {
+            None
+        } else {
+            Some(self.pending_frames.remove(0))
+        }
+    }
+
+    fn frame_payload_len(frame: &Frame) -> usize {
+        match frame {
+            Frame::Data { payload, .. } => payload.len(),
+            _ => 0,
+        }
+    }
+
+    /// Drain pending send data as raw `Bytes` (legacy helper; used by tests
+    /// that do not inspect frame structure).
+    ///
+    /// Each `Bytes` value is the payload from one pending `Frame::Data`.
+    /// Non-data frames (if any) are silently dropped here — callers that need
+    /// full frame access should use `drain_frames()`.
+    pub fn drain_send(&mut self) -> Vec<Bytes> {
+        self.pending_frames
+            .drain(..)
+            .filter_map(|f| {
+                if let Frame::Data { payload, .. } = f {
+                    Some(payload)
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Notify the sender that a sequence number was acknowledged.
+    ///
+    /// For RO and RU streams this removes the frame from the retransmit
+    /// buffer and reports its byte length to `congestion`, which advances
+    /// `cwnd` and may release frames waiting in `cwnd_queue`. For
+    /// Probabilistic streams this is a no-op (no bytes were ever tracked
+    /// against the no-op controller). BestEffort streams run a real `Cubic`
+    /// controller (see `default_congestion_controller`), so the updated
+    /// window is also pushed down to `self.sender` via `set_cwnd_hint` for
+    /// senders that gate their own admission (`BestEffortSender`).
+    pub fn on_ack(&mut self, seq: u32) {
+        self.sender.on_ack(seq);
+        if let Some(len) = self.inflight_lens.remove(&seq) {
+            self.congestion.on_ack(len);
+            self.release_cwnd_queue();
+            self.sender.set_cwnd_hint(self.congestion.window());
+        }
+    }
+
+    /// Retrieve any frames that need retransmission (called by the loss-
+    /// detection layer).
+    ///
+    /// Each returned frame is treated as a loss signal: `congestion.on_loss`
+    /// is charged its payload length, cutting `cwnd` back to `ssthresh`. The
+    /// reduced window is then pushed down via `set_cwnd_hint`, same as `on_ack`.
+    ///
+    /// Errs if the sender gave up on a frame (e.g. `ReliableOrderedSender`
+    /// exceeding its retransmission attempt limit) -- the caller should treat
+    /// that as fatal for the stream.
+    pub fn retransmit(&mut self) -> Result<Vec<Frame>> {
+        let frames = self.sender.retransmit()?;
+        let mut lost_any = false;
+        for frame in &frames {
+            let len = Self::frame_payload_len(frame);
+            if len > 0 {
+                self.congestion.on_loss(len);
+                lost_any = true;
+            }
+        }
+        if lost_any {
+            self.sender.set_cwnd_hint(self.congestion.window());
+        }
+        Ok(frames)
+    }
+
+    /// The current congestion window, in bytes.
+    pub fn congestion_window(&self) -> usize {
+        self.congestion.window()
+    }
+
+    /// Bytes currently in flight according to the congestion controller.
+    pub fn bytes_in_flight(&self) -> usize {
+        self.congestion.bytes_in_flight()
+    }
+
+    /// Close the local side of the stream.
+    pub fn close(&mut self) -> Result<()> {
+        match self.state {
+            StreamState::Open => {
+                self.state = StreamState::HalfClosedLocal;
+                Ok(())
+            }
+            StreamState::HalfClosedRemote => {
+                self.state = StreamState::Closed;
+                Ok(())
+            }
+            StreamState::Closed | StreamState::HalfClosedLocal => {
+                Ok(()) // idempotent
+            }
+            StreamState::Idle => Err(StrandStreamError::InvalidStateTransition {
+                from: "Idle".into(),
+                to: "Closed".into(),
+            }),
+        }
+    }
+
+    /// Mark the remote side as closed.
+    pub fn remote_close(&mut self) {
+        match self.state {
+            StreamState::Open => {
+                self.state = StreamState::HalfClosedRemote;
+            }
+            StreamState::HalfClosedLocal => {
+                self.state = StreamState::Closed;
+            }
+            _ => {} // ignore in other states
+        }
+    }
+
+    /// Abruptly reset the stream.
+    pub fn reset(&mut self) {
+        self.state = StreamState::Closed;
+        self.pending_frames.clear();
+        self.recv_buf.clear();
+        self.cwnd_queue.clear();
+        self.inflight_lens.clear();
+    }
+
+    /// Abruptly reset the stream and tell the peer why.
+    ///
+    /// Closes the stream locally (same semantics as `reset()`) and then
+    /// queues a `Frame::Rst { error_code: code, final_size, .. }` onto
+    /// `pending_frames`, where `final_size` is `send_bytes_sent` -- the
+    /// total byte offset this side had committed to sending -- so the peer
+    /// can reconcile its flow-control accounting for this stream.
+    pub fn reset_with(&mut self, code: u32) {
+        let final_size = self.send_bytes_sent;
+        self.reset();
+        self.pending_frames.push(Frame::Rst {
+            stream_id: self.id,
+            error_code: code,
+            final_size,
+        });
+    }
+
+    /// Handle an inbound `Frame::Rst`: validates that `final_size` is
+    /// consistent with the bytes we've already received, then closes the
+    /// stream and records `code` so the next `recv()` call surfaces it as
+    /// `StreamReset { code }` instead of the generic `StreamClosed`.
+    ///
+    /// Errs with `ResetFinalSizeMismatch` if `final_size` is less than
+    /// `recv_bytes_received` -- the peer can't retroactively shrink the
+    /// byte offset it already sent us.
+    pub fn on_reset(&mut self, code: u32, final_size: u64) -> Result<()> {
+        if final_size < self.recv_bytes_received {
+            return Err(StrandStreamError::ResetFinalSizeMismatch {
+                stream_id: self.id,
+                final_size,
+                received: self.recv_bytes_received,
+            });
+        }
+        self.reset();
+        self.reset_code = Some(code);
+        Ok(())
+    }
+
+    /// Handle an inbound `Frame::StopSending`: the peer is telling us it is
+    /// no longer reading this stream, so further `send()`/`send_stream()`
+    /// calls are rejected with `StreamSendStopped`. Unlike `on_reset`, this
+    /// leaves `state` and every receive-side field (`recv_buf`,
+    /// `recv_bytes_received`, `recv_bytes_consumed`) untouched -- the stream
+    /// stays valid for the local side's own FIN and for anything still
+    /// arriving from the peer.
+    ///
+    /// Only valid while the local send side is still open; errs with
+    /// `InvalidStateTransition` once it's already `HalfClosedLocal` or
+    /// `Closed`, since there is nothing left to stop.
+    pub fn on_stop_sending(&mut self, error_code: u32) -> Result<()> {
+        match self.state {
+            StreamState::Open | StreamState::HalfClosedRemote => {
+                self.stop_sending_code = Some(error_code);
+                Ok(())
+            }
+            StreamState::HalfClosedLocal | StreamState::Closed => {
+                Err(StrandStreamError::InvalidStateTransition {
+                    from: self.state.to_string(),
+                    to: "StopSending".into(),
+                })
+            }
+            StreamState::Idle => Err(StrandStreamError::InvalidStateTransition {
+                from: "Idle".into(),
+                to: "StopSending".into(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread::sleep;
+
+    #[test]
+    fn state_transitions() {
+        let mut s = Stream::new(1, TransportMode::ReliableOrdered);
+        assert_eq!(s.state(), StreamState::Idle);
+
+        s.open().unwrap();
+        assert_eq!(s.state(), StreamState::Open);
+
+        s.close().unwrap();
+        assert_eq!(s.state(), StreamState::HalfClosedLocal);
+
+        s.remote_close();
+        assert_eq!(s.state(), StreamState::Closed);
+    }
+
+    #[test]
+    fn send_recv_buffers() {
+        let mut s = Stream::new(1, TransportMode::BestEffort);
+        s.open().unwrap();
+
+        s.send(Bytes::from_static(b"hello")).unwrap();
+        // drain_send() extracts raw payloads from pending frames.
+        let drained = s.drain_send();
+        assert_eq!(drained.len(), 1);
+        assert_eq!(&drained[0][..], b"hello");
+
+        s.push_recv(Bytes::from_static(b"world"));
+        let data = s.recv().unwrap().unwrap();
+        assert_eq!(&data[..], b"world");
+    }
+
+    #[test]
+    fn send_produces_frames() {
+        let mut s = Stream::new(1, TransportMode::ReliableOrdered);
+        s.open().unwrap();
+        s.send(Bytes::from_static(b"data")).unwrap();
+
+        let frames = s.drain_frames();
+        assert_eq!(frames.len(), 1);
+        match &frames[0] {
+            Frame::Data { stream_id, seq, payload, .. } => {
+                assert_eq!(*stream_id, 1);
+                assert_eq!(*seq, 0);
+                assert_eq!(&payload[..], b"data");
+            }
+            _ => panic!("expected Data frame"),
+        }
+    }
+
+    #[test]
+    fn transport_receive_ru_dedup() {
+        use crate::frame::DataFlags;
+        let mut s = Stream::new(1, TransportMode::ReliableUnordered);
+        s.open().unwrap();
+
+        let frame = Frame::Data {
+            stream_id: 1,
+            seq: 42,
+            flags: DataFlags::NONE,
+            payload: Bytes::from_static(b"msg"),
+        };
+
+        // First delivery: payload should appear in recv buffer.
+        s.transport_receive(&frame).unwrap();
+        assert_eq!(s.recv().unwrap().unwrap().as_ref(), b"msg");
+
+        // Second delivery (duplicate): no new data.
+        s.transport_receive(&frame).unwrap();
+        assert!(s.recv().unwrap().is_none());
+    }
+
+    #[test]
+    fn transport_receive_ro_ordered() {
+        use crate::frame::DataFlags;
+        let mut s = Stream::new(1, TransportMode::ReliableOrdered);
+        s.open().unwrap();
+
+        // Deliver seq=1 before seq=0 -- should be buffered.
+        let f1 = Frame::Data {
+            stream_id: 1,
+            seq: 1,
+            flags: DataFlags::NONE,
+            payload: Bytes::from_static(b"B"),
+        };
+        let f0 = Frame::Data {
+            stream_id: 1,
+            seq: 0,
+            flags: DataFlags::NONE,
+            payload: Bytes::from_static(b"A"),
+        };
+
+        s.transport_receive(&f1).unwrap();
+        assert!(s.recv().unwrap().is_none()); // not yet -- waiting for seq 0
+
+        s.transport_receive(&f0).unwrap();
+        // Now both 0 and 1 should flush.
+        assert_eq!(s.recv().unwrap().unwrap().as_ref(), b"A");
+        assert_eq!(s.recv().unwrap().unwrap().as_ref(), b"B");
+    }
+
+    #[test]
+    fn cannot_send_when_half_closed_local() {
+        let mut s = Stream::new(1, TransportMode::ReliableOrdered);
+        s.open().unwrap();
+        s.close().unwrap();
+        assert!(s.send(Bytes::from_static(b"fail")).is_err());
+    }
+
+    #[test]
+    fn reset_clears_buffers() {
+        let mut s = Stream::new(1, TransportMode::ReliableOrdered);
+        s.open().unwrap();
+        s.send(Bytes::from_static(b"data")).unwrap();
+        s.push_recv(Bytes::from_static(b"data"));
+        s.reset();
+        assert_eq!(s.state(), StreamState::Closed);
+        assert!(s.drain_send().is_empty());
+    }
+
+    #[test]
+    fn on_ack_clears_retransmit_buffer() {
+        let mut s = Stream::new(1, TransportMode::ReliableOrdered);
+        s.open().unwrap();
+        s.send(Bytes::from_static(b"A")).unwrap();
+        s.send(Bytes::from_static(b"B")).unwrap();
+
+        sleep(Duration::from_millis(30));
+        assert_eq!(s.retransmit().unwrap().len(), 2);
+        s.on_ack(0);
+        sleep(Duration::from_millis(150));
+        assert_eq!(s.retransmit().unwrap().len(), 1);
+        s.on_ack(1);
+        assert!(s.retransmit().unwrap().is_empty());
+    }
+
+    #[test]
+    fn data_within_recv_window_accepted() {
+        use crate::frame::DataFlags;
+        let mut s = Stream::with_recv_window(1, TransportMode::BestEffort, 16);
+        s.open().unwrap();
+
+        let frame = Frame::Data {
+            stream_id: 1,
+            seq: 0,
+            flags: DataFlags::NONE,
+            payload: Bytes::from(vec![0u8; 16]),
+        };
+        s.transport_receive(&frame).unwrap();
+        assert_eq!(s.recv().unwrap().unwrap().len(), 16);
+    }
+
+    #[test]
+    fn data_exceeding_recv_window_rejected() {
+        use crate::frame::DataFlags;
+        let mut s = Stream::with_recv_window(1, TransportMode::BestEffort, 16);
+        s.open().unwrap();
+
+        let frame = Frame::Data {
+            stream_id: 1,
+            seq: 0,
+            flags: DataFlags::NONE,
+            payload: Bytes::from(vec![0u8; 17]),
+        };
+        let err = s.transport_receive(&frame).unwrap_err();
+        assert!(matches!(err, StrandStreamError::FlowControlBlocked(1)));
+    }
+
+    #[test]
+    fn recv_emits_window_update_once_half_consumed() {
+        use crate::frame::DataFlags;
+        let mut s = Stream::with_recv_window(1, TransportMode::BestEffort, 16);
+        s.open().unwrap();
+
+        let frame = Frame::Data {
+            stream_id: 1,
+            seq: 0,
+            flags: DataFlags::NONE,
+            payload: Bytes::from(vec![0u8; 9]),
+        };
+        s.transport_receive(&frame).unwrap();
+        assert!(!s.has_pending_frames());
+
+        // Consuming 9 of 16 bytes leaves 7 unused, below the half-window
+        // (8) threshold, so a window update should fire advertising the
+        // window re-opened from the consumed offset.
+        s.recv().unwrap();
+        assert!(s.has_pending_frames());
+        match s.pop_pending_frame() {
+            Some(Frame::WindowUpdate { stream_id, window_increment }) => {
+                assert_eq!(stream_id, 1);
+                assert_eq!(window_increment, 9);
+            }
+            other => panic!("expected WindowUpdate frame, got {other:?}"),
+        }
+        assert_eq!(s.recv_max_offset(), 25);
+    }
+
+    #[test]
+    fn send_blocked_once_send_window_exhausted() {
+        let mut s = Stream::with_recv_window(1, TransportMode::BestEffort, 16);
+        s.open().unwrap();
+
+        s.send(Bytes::from(vec![0u8; 16])).unwrap();
+        assert_eq!(s.send_window_remaining(), 0);
+
+        let err = s.send(Bytes::from_static(b"x")).unwrap_err();
+        assert!(matches!(err, StrandStreamError::FlowControlViolation));
+    }
+
+    #[test]
+    fn send_resumes_after_incoming_window_update() {
+        let mut s = Stream::with_recv_window(1, TransportMode::BestEffort, 16);
+        s.open().unwrap();
+
+        s.send(Bytes::from(vec![0u8; 16])).unwrap();
+        assert!(s.send(Bytes::from_static(b"x")).is_err());
+
+        s.apply_window_update(8);
+        assert_eq!(s.send_max_offset(), 24);
+        s.send(Bytes::from(vec![0u8; 8])).unwrap();
+        assert!(s.send(Bytes::from_static(b"x")).is_err());
+    }
+
+    #[test]
+    fn blocked_send_queues_stream_data_blocked_once() {
+        let mut s = Stream::with_recv_window(1, TransportMode::BestEffort, 16);
+        s.open().unwrap();
+
+        s.send(Bytes::from(vec![0u8; 16])).unwrap();
+        assert!(s.send(Bytes::from_static(b"x")).is_err());
+        assert!(s.send(Bytes::from_static(b"y")).is_err());
+
+        let frames = s.drain_frames();
+        let blocked: Vec<&Frame> = frames
+            .iter()
+            .filter(|f| matches!(f, Frame::StreamDataBlocked { .. }))
+            .collect();
+        assert_eq!(blocked.len(), 1);
+        assert!(matches!(
+            blocked[0],
+            Frame::StreamDataBlocked { stream_id: 1, limit: 16 }
+        ));
+    }
+
+    #[test]
+    fn window_update_clears_stream_data_blocked_dedup() {
+        let mut s = Stream::with_recv_window(1, TransportMode::BestEffort, 16);
+        s.open().unwrap();
+
+        s.send(Bytes::from(vec![0u8; 16])).unwrap();
+        assert!(s.send(Bytes::from_static(b"x")).is_err());
+        s.drain_frames();
+
+        s.apply_window_update(8);
+        assert!(s.send(Bytes::from(vec![0u8; 8])).is_ok());
+        assert!(s.send(Bytes::from_static(b"x")).is_err());
+
+        let frames = s.drain_frames();
+        assert_eq!(
+            frames
+                .iter()
+                .filter(|f| matches!(f, Frame::StreamDataBlocked { .. }))
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn stop_sending_rejects_further_sends_but_keeps_recv_data() {
+        use crate::frame::DataFlags;
+        let mut s = Stream::new(1, TransportMode::ReliableOrdered);
+        s.open().unwrap();
+
+        let frame = Frame::Data {
+            stream_id: 1,
+            seq: 0,
+            flags: DataFlags::NONE,
+            payload: Bytes::from_static(b"already received"),
+        };
+        s.transport_receive(&frame).unwrap();
+
+        s.on_stop_sending(0x7).unwrap();
+        let err = s.send(Bytes::from_static(b"x")).unwrap_err();
+        assert!(matches!(
+            err,
+            StrandStreamError::StreamSendStopped { stream_id: 1, error_code: 0x7 }
+        ));
+
+        assert_eq!(s.state(), StreamState::Open);
+        assert_eq!(s.recv().unwrap().unwrap(), Bytes::from_static(b"already received"));
+    }
+
+    #[test]
+    fn stop_sending_rejected_outside_open_or_half_closed_remote() {
+        let mut s = Stream::new(1, TransportMode::ReliableOrdered);
+        assert!(matches!(
+            s.on_stop_sending(0x1).unwrap_err(),
+            StrandStreamError::InvalidStateTransition { .. }
+        ));
+
+        s.open().unwrap();
+        s.close().unwrap();
+        assert!(matches!(
+            s.on_stop_sending(0x1).unwrap_err(),
+            StrandStreamError::InvalidStateTransition { .. }
+        ));
+    }
+
+    #[test]
+    fn send_datagram_emits_datagram_frame_bypassing_flow_control() {
+        let mut s = Stream::with_recv_window(1, TransportMode::BestEffort, 4);
+        s.open().unwrap();
+
+        // Larger than the tiny send window -- a regular send() would be
+        // rejected, but send_datagram() bypasses flow control entirely.
+        s.send_datagram(Bytes::from_static(b"much too big for the window"))
+            .unwrap();
+        assert_eq!(s.send_window_remaining(), 4);
+
+        let frames = s.drain_frames();
+        assert_eq!(frames.len(), 1);
+        assert!(matches!(frames[0], Frame::Datagram { .. }));
+    }
+
+    #[test]
+    fn send_datagram_rejected_outside_best_effort() {
+        let mut s = Stream::new(1, TransportMode::ReliableOrdered);
+        s.open().unwrap();
+        assert!(matches!(
+            s.send_datagram(Bytes::from_static(b"x")).unwrap_err(),
+            StrandStreamError::DatagramRequiresBestEffort(1)
+        ));
+    }
+
+    #[test]
+    fn inbound_datagram_delivered_directly_bypassing_recv_window() {
+        let mut s = Stream::with_recv_window(1, TransportMode::BestEffort, 4);
+        s.open().unwrap();
+
+        let frame = Frame::Datagram {
+            flags: DataFlags::NONE,
+            payload: Bytes::from_static(b"much too big for the recv window"),
+        };
+        // A Frame::Data this large would be FlowControlBlocked against a
+        // 4-byte recv window; Datagram skips that accounting altogether.
+        s.transport_receive(&frame).unwrap();
+        assert_eq!(
+            s.recv().unwrap().unwrap(),
+            Bytes::from_static(b"much too big for the recv window")
+        );
+    }
+
+    #[test]
+    fn congestion_window_queues_frames_once_cwnd_exhausted() {
+        let mss = 1200;
+        let mut s = Stream::new(1, TransportMode::ReliableOrdered);
+        s.open().unwrap();
+        for _ in 0..10 {
+            s.send(Bytes::from(vec![0u8; mss])).unwrap();
+        }
+        assert_eq!(s.drain_frames().len(), 10);
+        assert_eq!(s.bytes_in_flight(), 10 * mss);
+
+        // cwnd is fully utilized; an 11th frame should queue rather than send.
+        s.send(Bytes::from(vec![0u8; mss])).unwrap();
+        assert!(!s.has_pending_frames());
+
+        // Acking the first frame frees room (and grows cwnd via slow start)
+        // so the queued frame is released.
+        s.on_ack(0);
+        assert!(s.has_pending_frames());
+    }
+
+    #[test]
+    fn retransmit_signals_loss_and_cuts_congestion_window() {
+        let mut s = Stream::new(1, TransportMode::ReliableOrdered);
+        s.open().unwrap();
+        s.send(Bytes::from(vec![0u8; 1200])).unwrap();
+        let before = s.congestion_window();
+
+        sleep(Duration::from_millis(30));
+        s.retransmit().unwrap();
+        assert!(s.congestion_window() < before);
+    }
+
+    #[test]
+    fn best_effort_uses_cubic_not_noop_controller() {
+        // Unlike Probabilistic/Sequenced, BestEffort gets a real Cubic so its
+        // window can grow on acks and actually drive `set_cwnd_hint`.
+        let s = Stream::new(1, TransportMode::BestEffort);
+        assert_ne!(s.congestion_window(), usize::MAX);
+    }
+
+    #[test]
+    fn best_effort_congestion_window_grows_on_ack() {
+        let mut s = Stream::with_recv_window(1, TransportMode::BestEffort, 1_000_000);
+        s.open().unwrap();
+        s.send(Bytes::from(vec![0u8; 1200])).unwrap();
+        let before = s.congestion_window();
+
+        s.on_ack(0);
+        assert!(s.congestion_window() > before);
+    }
+
+    #[test]
+    fn best_effort_stream_is_never_congestion_gated() {
+        let mut s = Stream::new(1, TransportMode::BestEffort);
+        s.open().unwrap();
+        s.send(Bytes::from(vec![0u8; 50_000])).unwrap();
+        assert!(s.has_pending_frames());
+    }
+
+    #[test]
+    fn no_idle_timeout_by_default() {
+        let mut s = Stream::new(1, TransportMode::BestEffort);
+        s.open().unwrap();
+        s.send(Bytes::from_static(b"data")).unwrap();
+        assert_eq!(s.next_deadline(), None);
+        assert_eq!(s.poll_timeout(Instant::now() + Duration::from_secs(3600)), None);
+        assert_eq!(s.state(), StreamState::Open);
+    }
+
+    #[test]
+    fn poll_timeout_resets_after_idle_period_elapses() {
+        let mut s = Stream::new(1, TransportMode::BestEffort);
+        s.open().unwrap();
+        s.set_idle_timeout(Duration::from_secs(30));
+        s.send(Bytes::from_static(b"data")).unwrap();
+
+        let deadline = s.next_deadline().expect("deadline set after activity");
+
+        // Not yet expired just before the deadline.
+        assert_eq!(s.poll_timeout(deadline - Duration::from_millis(1)), None);
+        assert_eq!(s.state(), StreamState::Open);
+
+        // Expired once `now` reaches the deadline.
+        let event = s.poll_timeout(deadline).expect("idle timeout should fire");
+        assert_eq!(event, StreamEvent::IdleTimeout { stream_id: 1 });
+        assert_eq!(s.state(), StreamState::Closed);
+    }
+
+    #[test]
+    fn activity_postpones_idle_timeout() {
+        let mut s = Stream::new(1, TransportMode::BestEffort);
+        s.open().unwrap();
+        s.set_idle_timeout(Duration::from_secs(30));
+        s.send(Bytes::from_static(b"first")).unwrap();
+
+        let first_deadline = s.next_deadline().unwrap();
+
+        // Fresh activity just before the original deadline pushes it out,
+        // so polling at the original deadline is no longer an expiry.
+        s.send(Bytes::from_static(b"second")).unwrap();
+        assert!(s.next_deadline().unwrap() >= first_deadline);
+        assert_eq!(s.poll_timeout(first_deadline), None);
+        assert_eq!(s.state(), StreamState::Open);
+    }
+
+    #[test]
+    fn clear_idle_timeout_disables_expiry() {
+        let mut s = Stream::new(1, TransportMode::BestEffort);
+        s.open().unwrap();
+        s.set_idle_timeout(Duration::from_secs(30));
+        s.send(Bytes::from_static(b"data")).unwrap();
+        s.clear_idle_timeout();
+
+        assert_eq!(s.next_deadline(), None);
+        assert_eq!(s.poll_timeout(Instant::now() + Duration::from_secs(3600)), None);
+        assert_eq!(s.state(), StreamState::Open);
+    }
+
+    #[test]
+    fn reset_with_emits_rst_frame_carrying_final_size() {
+        let mut s = Stream::new(1, TransportMode::ReliableOrdered);
+        s.open().unwrap();
+        s.send(Bytes::from_static(b"hello")).unwrap();
+        s.drain_frames(); // clear the Data frame so only the Rst remains below
+
+        s.reset_with(7);
+
+        assert_eq!(s.state(), StreamState::Closed);
+        let frames = s.drain_frames();
+        assert_eq!(frames.len(), 1);
+        assert_eq!(
+            frames[0],
+            Frame::Rst {
+                stream_id: 1,
+                error_code: 7,
+                final_size: 5,
+            }
+        );
+    }
+
+    #[test]
+    fn inbound_reset_closes_stream_and_surfaces_code_via_recv() {
+        let mut s = Stream::new(1, TransportMode::ReliableOrdered);
+        s.open().unwrap();
+
+        let frame = Frame::Rst {
+            stream_id: 1,
+            error_code: 99,
+            final_size: 0,
+        };
+        s.transport_receive(&frame).unwrap();
+
+        assert_eq!(s.state(), StreamState::Closed);
+        let err = s.recv().unwrap_err();
+        assert!(matches!(err, StrandStreamError::StreamReset { code: 99 }));
+    }
+
+    #[test]
+    fn inbound_reset_rejects_final_size_smaller_than_bytes_received() {
+        use crate::frame::DataFlags;
+        let mut s = Stream::new(1, TransportMode::ReliableOrdered);
+        s.open().unwrap();
+
+        let data = Frame::Data {
+            stream_id: 1,
+            seq: 0,
+            flags: DataFlags::NONE,
+            payload: Bytes::from_static(b"hello"),
+        };
+        s.transport_receive(&data).unwrap();
+
+        // final_size (1) is less than the 5 bytes already received.
+        let rst = Frame::Rst {
+            stream_id: 1,
+            error_code: 1,
+            final_size: 1,
+        };
+        let err = s.transport_receive(&rst).unwrap_err();
+        assert!(matches!(
+            err,
+            StrandStreamError::ResetFinalSizeMismatch {
+                stream_id: 1,
+                final_size: 1,
+                received: 5,
+            }
+        ));
+        // Rejected resets must not close the stream out from under the data path.
+        assert_eq!(s.state(), StreamState::Open);
+    }
+
+    #[test]
+    fn send_stream_chunks_at_mss_boundaries() {
+        let mut s = Stream::new(1, TransportMode::ReliableOrdered);
+        s.open().unwrap();
+
+        let body = Bytes::from(vec![0u8; DEFAULT_MSS * 3]);
+        s.send_stream(body, false).unwrap();
+
+        let frames = s.drain_frames();
+        assert_eq!(frames.len(), 3);
+        for frame in &frames {
+            match frame {
+                Frame::Data { flags, payload, .. } => {
+                    assert_eq!(payload.len(), DEFAULT_MSS);
+                    assert_eq!(*flags, DataFlags::NONE);
+                }
+                _ => panic!("expected Data frame"),
+            }
+        }
+    }
+
+    #[test]
+    fn set_mss_changes_send_stream_chunk_size() {
+        let mut s = Stream::new(1, TransportMode::ReliableOrdered);
+        s.open().unwrap();
+        s.set_mss(500);
+        assert_eq!(s.mss(), 500);
+
+        let body = Bytes::from(vec![0u8; 1_250]);
+        s.send_stream(body, false).unwrap();
+
+        let frames = s.drain_frames();
+        assert_eq!(frames.len(), 3);
+        let lens: Vec<usize> = frames
+            .iter()
+            .map(|f| match f {
+                Frame::Data { payload, .. } => payload.len(),
+                _ => panic!("expected Data frame"),
+            })
+            .collect();
+        assert_eq!(lens, vec![500, 500, 250]);
+    }
+
+    #[test]
+    fn send_stream_marks_terminal_chunk_with_fin() {
+        let mut s = Stream::new(1, TransportMode::ReliableOrdered);
+        s.open().unwrap();
+
+        // One full MSS chunk plus a short remainder -- the remainder is the
+        // terminal chunk and should carry FIN.
+        let body = Bytes::from(vec![0u8; DEFAULT_MSS + 100]);
+        s.send_stream(body, true).unwrap();
+
+        let frames = s.drain_frames();
+        assert_eq!(frames.len(), 2);
+        match &frames[0] {
+            Frame::Data { flags, payload, .. } => {
+                assert_eq!(payload.len(), DEFAULT_MSS);
+                assert_eq!(*flags, DataFlags::NONE);
+            }
+            _ => panic!("expected Data frame"),
+        }
+        match &frames[1] {
+            Frame::Data { flags, payload, .. } => {
+                assert_eq!(payload.len(), 100);
+                assert_eq!(*flags, DataFlags::FIN);
+            }
+            _ => panic!("expected Data frame"),
+        }
+    }
+
+    #[test]
+    fn send_stream_with_no_bytes_and_fin_emits_empty_fin_chunk() {
+        let mut s = Stream::new(1, TransportMode::ReliableOrdered);
+        s.open().unwrap();
+
+        s.send_stream(Bytes::new(), true).unwrap();
+
+        let frames = s.drain_frames();
+        assert_eq!(frames.len(), 1);
+        match &frames[0] {
+            Frame::Data { flags, payload, .. } => {
+                assert!(payload.is_empty());
+                assert_eq!(*flags, DataFlags::FIN);
+            }
+            _ => panic!("expected Data frame"),
+        }
+    }
+
+    #[test]
+    fn send_stream_resumes_after_congestion_backpressure() {
+        let mut s = Stream::new(1, TransportMode::ReliableOrdered);
+        s.open().unwrap();
+
+        // Initial cwnd admits exactly 10 MSS-sized chunks; feed 11 so the
+        // 11th has to wait for backpressure to clear.
+        let body = Bytes::from(vec![0u8; DEFAULT_MSS * 11]);
+        s.send_stream(body, true).unwrap();
+
+        let frames = s.drain_frames();
+        assert_eq!(frames.len(), 10, "cwnd should admit only 10 chunks up front");
+        assert_eq!(s.can_send(), 0);
+
+        // Ack the first chunk: that frees one MSS, and slow start grows
+        // cwnd by another.
+        s.on_ack(0);
+        assert!(s.can_send() > 0);
+
+        // Nothing resumes until poll_send_ready is called again.
+        assert!(s.drain_frames().is_empty());
+        s.poll_send_ready().unwrap();
+
+        let frames = s.drain_frames();
+        assert_eq!(frames.len(), 1);
+        match &frames[0] {
+            Frame::Data { flags, payload, seq, .. } => {
+                assert_eq!(*seq, 10);
+                assert_eq!(payload.len(), DEFAULT_MSS);
+                assert_eq!(*flags, DataFlags::FIN);
+            }
+            _ => panic!("expected Data frame"),
+        }
+    }
+
+    #[test]
+    fn can_send_reflects_flow_control_window() {
+        let mut s = Stream::with_recv_window(1, TransportMode::BestEffort, 500);
+        s.open().unwrap();
+        assert_eq!(s.can_send(), 500);
+    }
+}
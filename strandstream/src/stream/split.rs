@@ -0,0 +1,543 @@
+//! Splitting a [`Stream`] into independent send/receive halves.
+//!
+//! Mirrors `crate::transport::split`: `Multiplexer::get_stream_mut` hands out
+//! a single `&mut Stream`, serializing all send and receive work for a
+//! stream behind one borrow -- unusable from a dedicated writer task and a
+//! dedicated reader task running concurrently. `Stream::split` detaches the
+//! sender's outbound queue, sequence numbering, and flow-control window into
+//! a [`StreamSender`], and the receiver's reassembly/dedup buffer and
+//! receive window into a [`StreamReceiver`], so the two can move to separate
+//! tasks with no shared `&mut`.
+//!
+//! The only state the two halves share is close/reset: each observes the
+//! other's FIN or RST through an `Arc`-wrapped atomic (`StreamCloseState`)
+//! instead of a lock. The receiver also forwards any `Frame::WindowUpdate`
+//! it needs to emit to the sender over a channel -- the same pattern
+//! `transport::split` uses to forward ack feedback -- since the outbound
+//! frame queue lives on the sender's side of the split.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+
+use bytes::{Bytes, BytesMut};
+
+use crate::congestion::CongestionController;
+use crate::error::{Result, StrandStreamError};
+use crate::frame::{DataFlags, Frame};
+use crate::stream::{Stream, DEFAULT_MSS};
+use crate::transport::{TransportMode, TransportReceiver, TransportSender};
+
+const LOCAL_FIN: u8 = 0b001;
+const REMOTE_FIN: u8 = 0b010;
+const RESET: u8 = 0b100;
+
+/// Close/reset signal shared between a stream's split [`StreamSender`] and
+/// [`StreamReceiver`] halves.
+///
+/// A plain `AtomicU8` bitmask rather than a `Mutex`-guarded enum: each half
+/// only ever needs to observe whether the *other* half closed, never to
+/// reach into the other's buffers, so a lock-free flag set is enough.
+#[derive(Default)]
+struct StreamCloseState(AtomicU8);
+
+impl StreamCloseState {
+    fn set(&self, bit: u8) {
+        self.0.fetch_or(bit, Ordering::SeqCst);
+    }
+
+    fn bits(&self) -> u8 {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    /// Whether the sender should refuse further sends: it closed locally, or
+    /// either half observed a reset.
+    fn send_blocked(&self) -> bool {
+        self.bits() & (LOCAL_FIN | RESET) != 0
+    }
+
+    /// Whether the receiver should surface a terminal error instead of
+    /// `Ok(None)` once its buffer is drained: a reset, or both sides FIN'd.
+    fn recv_terminal(&self) -> bool {
+        let bits = self.bits();
+        bits & RESET != 0 || bits & (LOCAL_FIN | REMOTE_FIN) == (LOCAL_FIN | REMOTE_FIN)
+    }
+}
+
+/// The independently-ownable send half of a stream produced by
+/// [`Stream::split`]. Owns the mode-specific `TransportSender`, the outbound
+/// frame queue, the congestion controller, and the send-side flow-control
+/// window.
+pub struct StreamSender {
+    id: u32,
+    mode: TransportMode,
+    sender: Box<dyn TransportSender>,
+    pending_frames: Vec<Frame>,
+    send_max_offset: u64,
+    send_bytes_sent: u64,
+    congestion: Box<dyn CongestionController>,
+    cwnd_queue: VecDeque<Frame>,
+    inflight_lens: HashMap<u32, usize>,
+    stream_body: Bytes,
+    stream_fin: bool,
+    close: Arc<StreamCloseState>,
+    control_frames: Receiver<Frame>,
+}
+
+impl StreamSender {
+    /// Returns the stream ID.
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// The highest cumulative byte offset the peer currently allows us to send.
+    pub fn send_max_offset(&self) -> u64 {
+        self.send_max_offset
+    }
+
+    /// Bytes remaining in the send window before `send()` would be rejected.
+    pub fn send_window_remaining(&self) -> u64 {
+        self.send_max_offset.saturating_sub(self.send_bytes_sent)
+    }
+
+    /// Current send credit, in bytes -- see `Stream::can_send`.
+    pub fn can_send(&self) -> usize {
+        let flow_credit = self.send_window_remaining().min(usize::MAX as u64) as usize;
+        let cwnd_credit = self.congestion.can_send_bytes();
+        flow_credit.min(cwnd_credit)
+    }
+
+    /// Queue data for sending on this stream. See `Stream::send`.
+    pub fn send(&mut self, data: Bytes) -> Result<()> {
+        self.send_with_flags(data, DataFlags::NONE)
+    }
+
+    fn send_with_flags(&mut self, data: Bytes, flags: DataFlags) -> Result<()> {
+        if self.close.send_blocked() {
+            return Err(StrandStreamError::StreamClosed(self.id));
+        }
+        let len = data.len() as u64;
+        if self.send_bytes_sent.saturating_add(len) > self.send_max_offset {
+            return Err(StrandStreamError::FlowControlViolation);
+        }
+        let frames = self.sender.send(self.id, data, flags)?;
+        self.send_bytes_sent += len;
+        for frame in frames {
+            self.admit_or_queue(frame);
+        }
+        if flags == DataFlags::FIN {
+            self.close.set(LOCAL_FIN);
+        }
+        Ok(())
+    }
+
+    /// Queue `data` as (a segment of) a body to stream out, chunked at
+    /// `DEFAULT_MSS`. See `Stream::send_stream`.
+    pub fn send_stream(&mut self, data: Bytes, fin: bool) -> Result<()> {
+        if self.close.send_blocked() {
+            return Err(StrandStreamError::StreamClosed(self.id));
+        }
+        if self.stream_body.is_empty() {
+            self.stream_body = data;
+        } else {
+            let mut combined = BytesMut::with_capacity(self.stream_body.len() + data.len());
+            combined.extend_from_slice(&self.stream_body);
+            combined.extend_from_slice(&data);
+            self.stream_body = combined.freeze();
+        }
+        self.stream_fin = self.stream_fin || fin;
+        self.poll_send_ready()
+    }
+
+    /// Resume chunking out any `send_stream()` body buffered so far. See
+    /// `Stream::poll_send_ready`.
+    pub fn poll_send_ready(&mut self) -> Result<()> {
+        loop {
+            if self.stream_body.is_empty() {
+                if self.stream_fin {
+                    self.send_with_flags(Bytes::new(), DataFlags::FIN)?;
+                    self.stream_fin = false;
+                }
+                return Ok(());
+            }
+
+            let credit = self.can_send();
+            if credit == 0 {
+                return Ok(());
+            }
+
+            let take = credit.min(DEFAULT_MSS).min(self.stream_body.len());
+            if take == 0 {
+                return Ok(());
+            }
+            let chunk = self.stream_body.split_to(take);
+            let is_final_chunk = self.stream_body.is_empty() && self.stream_fin;
+            let flags = if is_final_chunk { DataFlags::FIN } else { DataFlags::NONE };
+            self.send_with_flags(chunk, flags)?;
+            if is_final_chunk {
+                self.stream_fin = false;
+            }
+        }
+    }
+
+    fn admit_or_queue(&mut self, frame: Frame) {
+        let len = Self::frame_payload_len(&frame);
+        if self.mode == TransportMode::BestEffort || self.congestion.can_send(len) {
+            self.admit_frame(frame, len);
+        } else {
+            self.cwnd_queue.push_back(frame);
+        }
+    }
+
+    fn admit_frame(&mut self, frame: Frame, len: usize) {
+        self.congestion.on_packet_sent(len);
+        if let Frame::Data { seq, .. } = &frame {
+            self.inflight_lens.insert(*seq, len);
+        }
+        self.pending_frames.push(frame);
+    }
+
+    fn release_cwnd_queue(&mut self) {
+        while let Some(frame) = self.cwnd_queue.front() {
+            let len = Self::frame_payload_len(frame);
+            if !self.congestion.can_send(len) {
+                break;
+            }
+            let frame = self.cwnd_queue.pop_front().expect("front just peeked");
+            self.admit_frame(frame, len);
+        }
+    }
+
+    fn frame_payload_len(frame: &Frame) -> usize {
+        match frame {
+            Frame::Data { payload, .. } => payload.len(),
+            _ => 0,
+        }
+    }
+
+    /// Apply an inbound `Frame::WindowUpdate`. See `Stream::apply_window_update`.
+    pub fn apply_window_update(&mut self, window_increment: u32) {
+        self.send_max_offset = self.send_max_offset.saturating_add(window_increment as u64);
+    }
+
+    /// Move any `Frame::WindowUpdate` the paired `StreamReceiver` forwarded
+    /// since the last call into `pending_frames`, without draining it.
+    fn drain_control_frames(&mut self) {
+        while let Ok(frame) = self.control_frames.try_recv() {
+            self.pending_frames.push(frame);
+        }
+    }
+
+    /// Drain all outbound frames ready to hand to the network layer,
+    /// including any `Frame::WindowUpdate` the paired `StreamReceiver`
+    /// forwarded for the peer.
+    pub fn drain_frames(&mut self) -> Vec<Frame> {
+        self.drain_control_frames();
+        std::mem::take(&mut self.pending_frames)
+    }
+
+    /// Whether this stream has any outbound frame waiting to be sent.
+    pub fn has_pending_frames(&mut self) -> bool {
+        self.drain_control_frames();
+        !self.pending_frames.is_empty()
+    }
+
+    /// Notify the sender that a sequence number was acknowledged. See
+    /// `Stream::on_ack`.
+    pub fn on_ack(&mut self, seq: u32) {
+        self.sender.on_ack(seq);
+        if let Some(len) = self.inflight_lens.remove(&seq) {
+            self.congestion.on_ack(len);
+            self.release_cwnd_queue();
+            self.sender.set_cwnd_hint(self.congestion.window());
+        }
+    }
+
+    /// Retrieve any frames that need retransmission. See `Stream::retransmit`.
+    pub fn retransmit(&mut self) -> Result<Vec<Frame>> {
+        let frames = self.sender.retransmit()?;
+        let mut lost_any = false;
+        for frame in &frames {
+            let len = Self::frame_payload_len(frame);
+            if len > 0 {
+                self.congestion.on_loss(len);
+                lost_any = true;
+            }
+        }
+        if lost_any {
+            self.sender.set_cwnd_hint(self.congestion.window());
+        }
+        Ok(frames)
+    }
+
+    /// The current congestion window, in bytes.
+    pub fn congestion_window(&self) -> usize {
+        self.congestion.window()
+    }
+
+    /// Bytes currently in flight according to the congestion controller.
+    pub fn bytes_in_flight(&self) -> usize {
+        self.congestion.bytes_in_flight()
+    }
+
+    /// Close the local side of the stream: blocks further `send`/`send_stream`
+    /// calls. Idempotent.
+    pub fn close(&mut self) {
+        self.close.set(LOCAL_FIN);
+    }
+
+    /// Abruptly reset the stream and tell the peer why. See
+    /// `Stream::reset_with`.
+    pub fn reset_with(&mut self, code: u32) {
+        let final_size = self.send_bytes_sent;
+        self.close.set(RESET);
+        self.pending_frames.clear();
+        self.cwnd_queue.clear();
+        self.inflight_lens.clear();
+        self.pending_frames.push(Frame::Rst { stream_id: self.id, error_code: code, final_size });
+    }
+}
+
+/// The independently-ownable receive half of a stream produced by
+/// [`Stream::split`]. Owns the mode-specific `TransportReceiver`, the
+/// reassembly/application receive buffer, and the receive-side flow-control
+/// window.
+pub struct StreamReceiver {
+    id: u32,
+    receiver: Box<dyn TransportReceiver>,
+    recv_buf: VecDeque<Bytes>,
+    recv_window: u64,
+    recv_max_offset: u64,
+    recv_bytes_received: u64,
+    recv_bytes_consumed: u64,
+    reset_code: Option<u32>,
+    close: Arc<StreamCloseState>,
+    control_frames: Sender<Frame>,
+}
+
+impl StreamReceiver {
+    /// Returns the stream ID.
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// The highest cumulative byte offset we currently accept from the peer.
+    pub fn recv_max_offset(&self) -> u64 {
+        self.recv_max_offset
+    }
+
+    /// Receive data from this stream (returns buffered data, if any). See
+    /// `Stream::recv`.
+    pub fn recv(&mut self) -> Result<Option<Bytes>> {
+        if let Some(data) = self.recv_buf.pop_front() {
+            self.recv_bytes_consumed += data.len() as u64;
+            self.maybe_emit_window_update();
+            return Ok(Some(data));
+        }
+        if self.close.recv_terminal() {
+            return match self.reset_code {
+                Some(code) => Err(StrandStreamError::StreamReset { code }),
+                None => Err(StrandStreamError::StreamClosed(self.id)),
+            };
+        }
+        Ok(None)
+    }
+
+    /// Re-open the receive window once consumption has eaten into half of
+    /// it, forwarding the resulting `Frame::WindowUpdate` to the paired
+    /// `StreamSender` over the control-frame channel -- the outbound queue
+    /// it would otherwise land on lives on the sender's side of the split.
+    /// See `Stream::maybe_emit_window_update`.
+    fn maybe_emit_window_update(&mut self) {
+        let unused = self.recv_max_offset.saturating_sub(self.recv_bytes_consumed);
+        if unused >= self.recv_window / 2 {
+            return;
+        }
+        let new_max_offset = self.recv_bytes_consumed + self.recv_window;
+        if new_max_offset <= self.recv_max_offset {
+            return;
+        }
+        let increment = (new_max_offset - self.recv_max_offset).min(u32::MAX as u64) as u32;
+        self.recv_max_offset = new_max_offset;
+        // The paired `StreamSender` may already have been dropped; there's
+        // no one left to hand the credit to.
+        let _ = self.control_frames.send(Frame::WindowUpdate {
+            stream_id: self.id,
+            window_increment: increment,
+        });
+    }
+
+    /// Process an inbound `Frame` through the mode-specific
+    /// `TransportReceiver`. See `Stream::transport_receive`.
+    pub fn transport_receive(&mut self, frame: &Frame) -> Result<()> {
+        if let Frame::Rst { error_code, final_size, .. } = frame {
+            return self.on_reset(*error_code, *final_size);
+        }
+
+        if let Frame::Data { payload, .. } = frame {
+            let incoming_offset = self.recv_bytes_received.saturating_add(payload.len() as u64);
+            if incoming_offset > self.recv_max_offset {
+                return Err(StrandStreamError::FlowControlBlocked(self.id));
+            }
+            self.recv_bytes_received = incoming_offset;
+        }
+
+        let payloads = self.receiver.receive(frame)?;
+        for payload in payloads {
+            self.recv_buf.push_back(payload);
+        }
+        Ok(())
+    }
+
+    /// Enqueue received data into the receive buffer directly, bypassing
+    /// the transport receiver (see `Stream::push_recv`).
+    pub fn push_recv(&mut self, data: Bytes) {
+        self.recv_buf.push_back(data);
+    }
+
+    /// Mark the remote side as closed (an inbound `Frame::Fin`). See
+    /// `Stream::remote_close`.
+    pub fn remote_close(&mut self) {
+        self.close.set(REMOTE_FIN);
+    }
+
+    fn on_reset(&mut self, code: u32, final_size: u64) -> Result<()> {
+        if final_size < self.recv_bytes_received {
+            return Err(StrandStreamError::ResetFinalSizeMismatch {
+                stream_id: self.id,
+                final_size,
+                received: self.recv_bytes_received,
+            });
+        }
+        self.recv_buf.clear();
+        self.close.set(RESET);
+        self.reset_code = Some(code);
+        Ok(())
+    }
+}
+
+impl Stream {
+    /// Split this stream into independently-ownable send and receive
+    /// halves that can be driven from separate tasks -- e.g. a dedicated
+    /// send loop and a dedicated receive loop run concurrently instead of
+    /// serialized behind one `&mut Stream` (see `Multiplexer::split_stream`
+    /// and the module docs on [`StreamSender`]/[`StreamReceiver`]).
+    pub fn split(self) -> (StreamSender, StreamReceiver) {
+        let close = Arc::new(StreamCloseState::default());
+        let (tx, rx) = mpsc::channel();
+
+        let sender = StreamSender {
+            id: self.id,
+            mode: self.mode,
+            sender: self.sender,
+            pending_frames: self.pending_frames,
+            send_max_offset: self.send_max_offset,
+            send_bytes_sent: self.send_bytes_sent,
+            congestion: self.congestion,
+            cwnd_queue: self.cwnd_queue,
+            inflight_lens: self.inflight_lens,
+            stream_body: self.stream_body,
+            stream_fin: self.stream_fin,
+            close: Arc::clone(&close),
+            control_frames: rx,
+        };
+        let receiver = StreamReceiver {
+            id: self.id,
+            receiver: self.receiver,
+            recv_buf: self.recv_buf,
+            recv_window: self.recv_window,
+            recv_max_offset: self.recv_max_offset,
+            recv_bytes_received: self.recv_bytes_received,
+            recv_bytes_consumed: self.recv_bytes_consumed,
+            reset_code: self.reset_code,
+            close,
+            control_frames: tx,
+        };
+        (sender, receiver)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::TransportMode;
+
+    #[test]
+    fn halves_can_move_to_separate_threads() {
+        let mut s = Stream::new(1, TransportMode::ReliableOrdered);
+        s.open().unwrap();
+        let (mut sender, mut receiver) = s.split();
+
+        let sender_thread = std::thread::spawn(move || {
+            sender.send(Bytes::from_static(b"hello")).unwrap();
+            sender.drain_frames()
+        });
+        let receiver_thread = std::thread::spawn(move || {
+            let frame = Frame::Data {
+                stream_id: 1,
+                seq: 0,
+                flags: DataFlags::NONE,
+                payload: Bytes::from_static(b"world"),
+            };
+            receiver.transport_receive(&frame).unwrap();
+            receiver.recv().unwrap()
+        });
+
+        let frames = sender_thread.join().unwrap();
+        let received = receiver_thread.join().unwrap();
+        assert_eq!(frames.len(), 1);
+        assert_eq!(received, Some(Bytes::from_static(b"world")));
+    }
+
+    #[test]
+    fn window_update_forwarded_from_receiver_to_sender() {
+        let s = Stream::with_recv_window(1, TransportMode::BestEffort, 16);
+        let (mut sender, mut receiver) = s.split();
+
+        let frame = Frame::Data {
+            stream_id: 1,
+            seq: 0,
+            flags: DataFlags::NONE,
+            payload: Bytes::from(vec![0u8; 9]),
+        };
+        receiver.transport_receive(&frame).unwrap();
+        assert!(!sender.has_pending_frames());
+
+        // Consuming 9 of 16 bytes drops unused (7) below the half-window (8)
+        // threshold, so a WindowUpdate should cross to the sender half.
+        receiver.recv().unwrap();
+        assert!(sender.has_pending_frames());
+        match sender.drain_frames().as_slice() {
+            [Frame::WindowUpdate { stream_id, window_increment }] => {
+                assert_eq!(*stream_id, 1);
+                assert_eq!(*window_increment, 9);
+            }
+            other => panic!("expected a single WindowUpdate frame, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn reset_on_sender_blocks_further_sends() {
+        let mut s = Stream::new(1, TransportMode::ReliableOrdered);
+        s.open().unwrap();
+        let (mut sender, _receiver) = s.split();
+
+        sender.reset_with(7);
+        let err = sender.send(Bytes::from_static(b"late")).unwrap_err();
+        assert!(matches!(err, StrandStreamError::StreamClosed(1)));
+    }
+
+    #[test]
+    fn inbound_reset_surfaces_via_receiver_recv() {
+        let mut s = Stream::new(1, TransportMode::ReliableOrdered);
+        s.open().unwrap();
+        let (_sender, mut receiver) = s.split();
+
+        let rst = Frame::Rst { stream_id: 1, error_code: 99, final_size: 0 };
+        receiver.transport_receive(&rst).unwrap();
+
+        let err = receiver.recv().unwrap_err();
+        assert!(matches!(err, StrandStreamError::StreamReset { code: 99 }));
+    }
+}
@@ -10,9 +10,12 @@
 //! the Best-Effort contract: the application never blocks, but packets are
 //! discarded when the network cannot absorb them.
 //!
-//! The congestion window is updated externally by the connection's CUBIC
-//! controller via `set_cwnd()`.  Passing `None` disables window gating
-//! entirely (the default for newly created streams).
+//! The congestion window is updated externally by the stream's
+//! `CongestionController` (CUBIC by default for this mode), which calls
+//! `set_cwnd_hint()` -- the `TransportSender::set_cwnd_hint` override that
+//! forwards into `set_cwnd()` -- after every window-changing event.  Passing
+//! `None` to `set_cwnd()` directly disables window gating entirely (the
+//! default for newly created streams, until the first hint arrives).
 
 use bytes::Bytes;
 
@@ -75,7 +78,7 @@ impl TransportSender for BestEffortSender {
     /// fire-and-forget contract.  The drop is logged at `DEBUG` level so that
     /// diagnostic tooling can observe it without imposing overhead on the hot
     /// path.
-    fn send(&mut self, stream_id: u32, data: Bytes) -> Result<Vec<Frame>> {
+    fn send(&mut self, stream_id: u32, data: Bytes, flags: DataFlags) -> Result<Vec<Frame>> {
         if !self.window_open() {
             tracing::debug!(
                 stream_id,
@@ -91,7 +94,7 @@ impl TransportSender for BestEffortSender {
         Ok(vec![Frame::Data {
             stream_id,
             seq,
-            flags: DataFlags::NONE,
+            flags,
             payload: data,
         }])
     }
@@ -100,9 +103,13 @@ impl TransportSender for BestEffortSender {
         // No-op: best effort does not track acknowledgements.
     }
 
-    fn retransmit(&mut self) -> Vec<Frame> {
+    fn retransmit(&mut self) -> Result<Vec<Frame>> {
         // No retransmission for best effort.
-        Vec::new()
+        Ok(Vec::new())
+    }
+
+    fn set_cwnd_hint(&mut self, cwnd: usize) {
+        self.set_cwnd(Some(cwnd.min(u32::MAX as usize) as u32));
     }
 }
 
@@ -142,7 +149,7 @@ mod tests {
         let mut sender = BestEffortSender::new();
         let mut receiver = BestEffortReceiver::new();
 
-        let f = sender.send(1, Bytes::from_static(b"fire")).unwrap();
+        let f = sender.send(1, Bytes::from_static(b"fire"), DataFlags::NONE).unwrap();
         let d = receiver.receive(&f[0]).unwrap();
         assert_eq!(d.len(), 1);
         assert_eq!(&d[0][..], b"fire");
@@ -151,8 +158,8 @@ mod tests {
     #[test]
     fn no_retransmission() {
         let mut sender = BestEffortSender::new();
-        sender.send(1, Bytes::from_static(b"gone")).unwrap();
-        assert!(sender.retransmit().is_empty());
+        sender.send(1, Bytes::from_static(b"gone"), DataFlags::NONE).unwrap();
+        assert!(sender.retransmit().unwrap().is_empty());
     }
 
     #[test]
@@ -161,7 +168,7 @@ mod tests {
         // Exhaust the congestion window.
         sender.set_cwnd(Some(0));
 
-        let frames = sender.send(1, Bytes::from_static(b"dropped")).unwrap();
+        let frames = sender.send(1, Bytes::from_static(b"dropped"), DataFlags::NONE).unwrap();
         // With window exhausted, the send returns Ok but produces no frames.
         assert!(
             frames.is_empty(),
@@ -174,7 +181,7 @@ mod tests {
         let mut sender = BestEffortSender::new();
         sender.set_cwnd(Some(65535));
 
-        let frames = sender.send(1, Bytes::from_static(b"ok")).unwrap();
+        let frames = sender.send(1, Bytes::from_static(b"ok"), DataFlags::NONE).unwrap();
         assert_eq!(frames.len(), 1);
     }
 
@@ -182,12 +189,12 @@ mod tests {
     fn cwnd_none_disables_gating() {
         let mut sender = BestEffortSender::new();
         // Default: no window set.
-        let frames = sender.send(1, Bytes::from_static(b"ok")).unwrap();
+        let frames = sender.send(1, Bytes::from_static(b"ok"), DataFlags::NONE).unwrap();
         assert_eq!(frames.len(), 1);
 
         // Explicitly disable gating.
         sender.set_cwnd(None);
-        let frames = sender.send(1, Bytes::from_static(b"also ok")).unwrap();
+        let frames = sender.send(1, Bytes::from_static(b"also ok"), DataFlags::NONE).unwrap();
         assert_eq!(frames.len(), 1);
     }
 
@@ -197,12 +204,12 @@ mod tests {
 
         // Closed window: drop.
         sender.set_cwnd(Some(0));
-        let f = sender.send(1, Bytes::from_static(b"x")).unwrap();
+        let f = sender.send(1, Bytes::from_static(b"x"), DataFlags::NONE).unwrap();
         assert!(f.is_empty());
 
         // Reopen window: frame goes through.
         sender.set_cwnd(Some(1024));
-        let f = sender.send(1, Bytes::from_static(b"y")).unwrap();
+        let f = sender.send(1, Bytes::from_static(b"y"), DataFlags::NONE).unwrap();
         assert_eq!(f.len(), 1);
     }
 }
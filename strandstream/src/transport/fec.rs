@@ -0,0 +1,343 @@
+//! Reed-Solomon forward error correction over GF(2^8), in systematic form.
+//!
+//! Used by the Probabilistic transport mode (see
+//! [`crate::transport::probabilistic`]) to recover lost frames without a
+//! round trip: a block of `k` data shards is encoded into `k + r` shards
+//! (the first `k` pass through unchanged, "shreds" in the Solana sense),
+//! and any `k` of the resulting `k + r` shards are enough to reconstruct
+//! the original block.
+
+/// Header prefixed to every FEC-coded shard's payload, identifying which
+/// block it belongs to and how to decode it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FecGroup {
+    /// Identifies the block this shard belongs to.
+    pub group_id: u32,
+    /// This shard's position in `0..k+r`. `0..k` are data shards (systematic,
+    /// i.e. identical to the original payload); `k..k+r` are parity shards.
+    pub index: u8,
+    /// Number of data shards per block.
+    pub k: u8,
+    /// Number of parity shards per block.
+    pub r: u8,
+}
+
+/// Encoded length of a [`FecGroup`] header.
+pub const FEC_GROUP_HEADER_LEN: usize = 4 + 1 + 1 + 1;
+
+impl FecGroup {
+    /// Encode this header as a 7-byte big-endian prefix.
+    pub fn encode(&self) -> [u8; FEC_GROUP_HEADER_LEN] {
+        let mut buf = [0u8; FEC_GROUP_HEADER_LEN];
+        buf[0..4].copy_from_slice(&self.group_id.to_be_bytes());
+        buf[4] = self.index;
+        buf[5] = self.k;
+        buf[6] = self.r;
+        buf
+    }
+
+    /// Decode a header from the front of `data`, returning it along with the
+    /// remaining bytes (the shard payload).
+    pub fn decode(data: &[u8]) -> Option<(Self, &[u8])> {
+        if data.len() < FEC_GROUP_HEADER_LEN {
+            return None;
+        }
+        let group_id = u32::from_be_bytes(data[0..4].try_into().ok()?);
+        let header = FecGroup {
+            group_id,
+            index: data[4],
+            k: data[5],
+            r: data[6],
+        };
+        Some((header, &data[FEC_GROUP_HEADER_LEN..]))
+    }
+}
+
+/// GF(2^8) arithmetic via log/antilog tables, using the reduction
+/// polynomial x^8 + x^4 + x^3 + x^2 + 1 (0x11D) and generator 2.
+struct Gf256 {
+    exp: [u8; 512],
+    log: [u8; 256],
+}
+
+impl Gf256 {
+    fn new() -> Self {
+        let mut exp = [0u8; 512];
+        let mut log = [0u8; 256];
+        let mut x: u16 = 1;
+        for (i, slot) in exp.iter_mut().enumerate().take(255) {
+            *slot = x as u8;
+            log[x as usize] = i as u8;
+            x <<= 1;
+            if x & 0x100 != 0 {
+                x ^= 0x11D;
+            }
+        }
+        for i in 255..512 {
+            exp[i] = exp[i - 255];
+        }
+        Self { exp, log }
+    }
+
+    fn mul(&self, a: u8, b: u8) -> u8 {
+        if a == 0 || b == 0 {
+            return 0;
+        }
+        let sum = self.log[a as usize] as usize + self.log[b as usize] as usize;
+        self.exp[sum]
+    }
+
+    fn inv(&self, a: u8) -> u8 {
+        debug_assert!(a != 0, "GF(256) has no multiplicative inverse of zero");
+        let l = self.log[a as usize] as usize;
+        self.exp[(255 - l) % 255]
+    }
+
+    /// `base` raised to `exponent`, computed via repeated multiplication
+    /// (one field multiplication per unit of `exponent`).
+    fn pow(&self, base: u8, exponent: u8) -> u8 {
+        let mut result = 1u8;
+        for _ in 0..exponent {
+            result = self.mul(result, base);
+        }
+        result
+    }
+}
+
+/// Systematic Reed-Solomon codec for a fixed `(k, r)` shape.
+///
+/// The encoding matrix is `[I_k; C]` where `C` is an `r x k` Cauchy
+/// matrix over GF(256) (row `i`, column `j` is `1 / ((k+i) XOR j)`), so any
+/// `k` of the `k + r` shard rows form an invertible `k x k` submatrix.
+pub struct RsCodec {
+    k: usize,
+    r: usize,
+    gf: Gf256,
+    /// Parity coefficients: `parity_coeffs[i][j]` scales data shard `j` into
+    /// parity shard `i`.
+    parity_coeffs: Vec<Vec<u8>>,
+}
+
+impl RsCodec {
+    /// Create a codec for `k` data shards and `r` parity shards. `k + r`
+    /// must not exceed 255 (the field only has that many nonzero elements).
+    pub fn new(k: usize, r: usize) -> Self {
+        assert!(k > 0, "k must be at least 1");
+        assert!(k + r <= 255, "k + r must fit in GF(256)'s nonzero elements");
+        let gf = Gf256::new();
+        let parity_coeffs = (0..r)
+            .map(|i| {
+                (0..k)
+                    .map(|j| gf.inv(((k + i) as u8) ^ (j as u8)))
+                    .collect()
+            })
+            .collect();
+        Self { k, r, gf, parity_coeffs }
+    }
+
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    pub fn r(&self) -> usize {
+        self.r
+    }
+
+    /// Encode `r` parity shards from exactly `k` equal-length data shards.
+    pub fn encode(&self, data_shards: &[Vec<u8>]) -> Vec<Vec<u8>> {
+        assert_eq!(data_shards.len(), self.k, "expected k data shards");
+        let shard_len = data_shards.first().map(|s| s.len()).unwrap_or(0);
+        assert!(
+            data_shards.iter().all(|s| s.len() == shard_len),
+            "all data shards must be the same length"
+        );
+
+        (0..self.r)
+            .map(|i| {
+                (0..shard_len)
+                    .map(|byte| {
+                        let mut acc = 0u8;
+                        for (j, shard) in data_shards.iter().enumerate() {
+                            acc ^= self.gf.mul(self.parity_coeffs[i][j], shard[byte]);
+                        }
+                        acc
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Encoding-matrix row for shard `index` (`0..k` identity, `k..k+r`
+    /// Cauchy parity row).
+    fn matrix_row(&self, index: usize) -> Vec<u8> {
+        if index < self.k {
+            let mut row = vec![0u8; self.k];
+            row[index] = 1;
+            row
+        } else {
+            self.parity_coeffs[index - self.k].clone()
+        }
+    }
+
+    /// Reconstruct all `k` original data shards given at least `k` available
+    /// `(shard_index, shard_bytes)` pairs (any mix of data and parity
+    /// shards, all the same length). Returns `None` if fewer than `k`
+    /// shards were supplied or the encoding submatrix is singular (should
+    /// not happen for valid, distinct indices).
+    pub fn decode(&self, available: &[(usize, Vec<u8>)]) -> Option<Vec<Vec<u8>>> {
+        if available.len() < self.k {
+            return None;
+        }
+        let chosen = &available[..self.k];
+        let shard_len = chosen[0].1.len();
+
+        let matrix: Vec<Vec<u8>> = chosen.iter().map(|(idx, _)| self.matrix_row(*idx)).collect();
+        let inverse = self.invert(&matrix)?;
+
+        let mut recovered = vec![vec![0u8; shard_len]; self.k];
+        for (row, inv_row) in inverse.iter().enumerate() {
+            for (byte, out) in recovered[row].iter_mut().enumerate() {
+                let mut acc = 0u8;
+                for (col, &coeff) in inv_row.iter().enumerate() {
+                    acc ^= self.gf.mul(coeff, chosen[col].1[byte]);
+                }
+                *out = acc;
+            }
+        }
+        Some(recovered)
+    }
+
+    /// Invert a `k x k` matrix over GF(256) via Gauss-Jordan elimination
+    /// with partial pivoting.
+    fn invert(&self, matrix: &[Vec<u8>]) -> Option<Vec<Vec<u8>>> {
+        let n = matrix.len();
+        let mut aug: Vec<Vec<u8>> = matrix
+            .iter()
+            .enumerate()
+            .map(|(i, row)| {
+                let mut augmented = row.clone();
+                augmented.resize(2 * n, 0);
+                augmented[n + i] = 1;
+                augmented
+            })
+            .collect();
+
+        for col in 0..n {
+            let pivot = (col..n).find(|&row| aug[row][col] != 0)?;
+            aug.swap(col, pivot);
+
+            let inv = self.gf.inv(aug[col][col]);
+            for v in aug[col].iter_mut() {
+                *v = self.gf.mul(*v, inv);
+            }
+
+            for row in 0..n {
+                if row != col && aug[row][col] != 0 {
+                    let factor = aug[row][col];
+                    let pivot_row = aug[col].clone();
+                    for (c, v) in aug[row].iter_mut().enumerate() {
+                        *v ^= self.gf.mul(factor, pivot_row[c]);
+                    }
+                }
+            }
+        }
+
+        Some(aug.iter().map(|row| row[n..].to_vec()).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fec_group_roundtrips() {
+        let header = FecGroup {
+            group_id: 0xDEADBEEF,
+            index: 3,
+            k: 4,
+            r: 2,
+        };
+        let encoded = header.encode();
+        let (decoded, rest) = FecGroup::decode(&encoded).unwrap();
+        assert_eq!(decoded, header);
+        assert!(rest.is_empty());
+    }
+
+    #[test]
+    fn fec_group_decode_rejects_short_input() {
+        assert!(FecGroup::decode(&[1, 2, 3]).is_none());
+    }
+
+    #[test]
+    fn gf256_mul_inverse_round_trips() {
+        let gf = Gf256::new();
+        for a in 1u8..=255 {
+            let inv = gf.inv(a);
+            assert_eq!(gf.mul(a, inv), 1, "a={a}");
+        }
+    }
+
+    #[test]
+    fn encode_is_systematic_passthrough() {
+        let codec = RsCodec::new(3, 2);
+        let data = vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]];
+        let parity = codec.encode(&data);
+        assert_eq!(parity.len(), 2);
+        assert_eq!(parity[0].len(), 3);
+    }
+
+    #[test]
+    fn decode_with_all_data_shards_present_is_identity() {
+        let codec = RsCodec::new(3, 2);
+        let data = vec![vec![10, 20], vec![30, 40], vec![50, 60]];
+        let available: Vec<(usize, Vec<u8>)> = data.iter().cloned().enumerate().collect();
+        let recovered = codec.decode(&available).unwrap();
+        assert_eq!(recovered, data);
+    }
+
+    #[test]
+    fn decode_reconstructs_missing_data_shard_from_parity() {
+        let codec = RsCodec::new(3, 2);
+        let data = vec![vec![10, 20, 30], vec![40, 50, 60], vec![70, 80, 90]];
+        let parity = codec.encode(&data);
+
+        // Lose data shard 1, keep shard 0, 2, and both parity shards.
+        let available = vec![
+            (0usize, data[0].clone()),
+            (2usize, data[2].clone()),
+            (3usize, parity[0].clone()),
+        ];
+        let recovered = codec.decode(&available).unwrap();
+        assert_eq!(recovered, data);
+    }
+
+    #[test]
+    fn decode_reconstructs_two_missing_data_shards() {
+        let codec = RsCodec::new(4, 2);
+        let data = vec![
+            vec![1, 2],
+            vec![3, 4],
+            vec![5, 6],
+            vec![7, 8],
+        ];
+        let parity = codec.encode(&data);
+
+        // Only shards 1, 3 (data) and both parity shards survive.
+        let available = vec![
+            (1usize, data[1].clone()),
+            (3usize, data[3].clone()),
+            (4usize, parity[0].clone()),
+            (5usize, parity[1].clone()),
+        ];
+        let recovered = codec.decode(&available).unwrap();
+        assert_eq!(recovered, data);
+    }
+
+    #[test]
+    fn decode_returns_none_with_too_few_shards() {
+        let codec = RsCodec::new(3, 2);
+        let available = vec![(0usize, vec![1, 2]), (1usize, vec![3, 4])];
+        assert!(codec.decode(&available).is_none());
+    }
+}
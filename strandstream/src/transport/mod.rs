@@ -1,16 +1,21 @@
 //! Transport mode definitions and per-mode sender/receiver traits.
 
 pub mod best_effort;
+pub mod fec;
+pub mod pmtud;
 pub mod probabilistic;
 pub mod reliable_ordered;
 pub mod reliable_unordered;
+pub mod replay_filter;
+pub mod sequenced;
+pub mod split;
 
 use bytes::Bytes;
 
 use crate::error::Result;
-use crate::frame::Frame;
+use crate::frame::{DataFlags, Frame};
 
-/// The four delivery modes supported by StrandStream.
+/// The five delivery modes supported by StrandStream.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[repr(u8)]
 pub enum TransportMode {
@@ -18,6 +23,7 @@ pub enum TransportMode {
     ReliableUnordered = 0x02,
     BestEffort = 0x03,
     Probabilistic = 0x04,
+    Sequenced = 0x05,
 }
 
 impl TransportMode {
@@ -28,6 +34,7 @@ impl TransportMode {
             0x02 => Ok(TransportMode::ReliableUnordered),
             0x03 => Ok(TransportMode::BestEffort),
             0x04 => Ok(TransportMode::Probabilistic),
+            0x05 => Ok(TransportMode::Sequenced),
             other => Err(crate::error::StrandStreamError::InvalidTransportMode(other)),
         }
     }
@@ -35,12 +42,24 @@ impl TransportMode {
 
 /// Trait for the sending side of a transport mode.
 pub trait TransportSender: Send {
-    /// Enqueue data for sending. Returns the frame(s) to transmit.
-    fn send(&mut self, stream_id: u32, data: Bytes) -> Result<Vec<Frame>>;
+    /// Enqueue data for sending, with the given `Frame::Data` flags (e.g.
+    /// `DataFlags::FIN` for the terminal chunk of a streamed body; see
+    /// `crate::stream::Stream::send_stream`). Returns the frame(s) to
+    /// transmit.
+    fn send(&mut self, stream_id: u32, data: Bytes, flags: DataFlags) -> Result<Vec<Frame>>;
     /// Handle an acknowledgement for the given sequence number.
     fn on_ack(&mut self, seq: u32);
     /// Retrieve any frames that need retransmission.
-    fn retransmit(&mut self) -> Vec<Frame>;
+    ///
+    /// Errs if a mode enforces a retransmission attempt limit and a frame
+    /// has exceeded it (see `ReliableOrderedSender`/`ReliableUnorderedSender`);
+    /// modes without such a limit always return `Ok`.
+    fn retransmit(&mut self) -> Result<Vec<Frame>>;
+    /// Receive an updated congestion window hint in bytes from the stream's
+    /// `CongestionController`, for senders that gate their own admission
+    /// (e.g. `BestEffortSender`). Default is a no-op, since most senders defer
+    /// entirely to `Stream`'s generic `admit_or_queue` admission control.
+    fn set_cwnd_hint(&mut self, _cwnd: usize) {}
 }
 
 /// Trait for the receiving side of a transport mode.
@@ -0,0 +1,327 @@
+//! Datagram Packetization Layer PMTU Discovery (DPLPMTUD, RFC 8899).
+//!
+//! `congestion::cubic::Cubic`'s growth granularity and the multiplexer's
+//! framing limit both default to a conservative 1200-byte segment size,
+//! which leaves bandwidth on the table on paths that support larger
+//! packets. [`Pmtud`] probes upward from that safe floor (`base_plpmtu`)
+//! towards a configurable ceiling (`max_plpmtu`) using a binary search: each
+//! [`Pmtud::poll_probe`] call returns the next candidate size to send a
+//! padded probe frame at (e.g. a standalone `Frame::Padding`), and the
+//! caller reports the outcome back via [`Pmtud::on_probe_ack`] or
+//! [`Pmtud::on_probe_lost`].
+//!
+//! States (RFC 8899 section 5.2):
+//! - [`PmtudState::Base`]: nothing beyond `base_plpmtu` confirmed yet; the
+//!   very first probe is at `base_plpmtu` itself.
+//! - [`PmtudState::Searching`]: binary-searching between the largest
+//!   confirmed-good size and the largest not yet ruled out.
+//! - [`PmtudState::SearchComplete`]: the search has converged on a size;
+//!   probing pauses until `raise_timer` elapses (RFC 8899's
+//!   `PMTU_RAISE_TIMER`), at which point it re-enters `Searching` with the
+//!   ceiling reset to `max_plpmtu` to check whether a larger size, or a
+//!   regression, has since become usable.
+//! - [`PmtudState::Error`]: a probe at `base_plpmtu` itself was lost --
+//!   even the safe floor is blackholed -- so `effective_mtu` holds at
+//!   `base_plpmtu` and no further probes are sent.
+//!
+//! `effective_mtu()` is the value to feed into `Cubic::set_mss` and the
+//! multiplexer's max payload size; it is only ever raised by an
+//! *acknowledged* probe, never by an in-flight or merely attempted one.
+
+use std::time::{Duration, Instant};
+
+/// Safe starting PLPMTU every path is assumed to support without probing
+/// (matches `congestion::cubic::Cubic`'s default `MSS`).
+pub const BASE_PLPMTU: usize = 1200;
+
+/// Default periodic re-validation interval once a search has completed
+/// (RFC 8899's `PMTU_RAISE_TIMER`).
+pub const DEFAULT_RAISE_TIMER: Duration = Duration::from_secs(600);
+
+/// DPLPMTUD state (see module docs).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PmtudState {
+    /// Nothing beyond `base_plpmtu` confirmed yet.
+    Base,
+    /// Binary-searching for the largest usable size.
+    Searching,
+    /// Converged on a confirmed size; only re-probes after `raise_timer`.
+    SearchComplete,
+    /// A probe at `base_plpmtu` itself was lost -- the path is blackholing
+    /// even the safe floor.
+    Error,
+}
+
+/// DPLPMTUD prober and effective-MTU tracker (see module docs).
+#[derive(Debug)]
+pub struct Pmtud {
+    state: PmtudState,
+    base: usize,
+    max: usize,
+    effective_mtu: usize,
+    /// Largest size confirmed usable so far (inclusive lower bound of the
+    /// ongoing binary search).
+    floor: usize,
+    /// Largest size not yet ruled out (exclusive-ish upper bound: the
+    /// search stops once `ceiling <= floor + 1`).
+    ceiling: usize,
+    /// Size of the probe currently awaiting ack/loss, if any. Only one
+    /// probe is ever outstanding at a time.
+    probe_in_flight: Option<usize>,
+    raise_timer: Duration,
+    last_validated: Option<Instant>,
+}
+
+impl Pmtud {
+    /// Create a prober with the given base (safe floor) and max (ceiling
+    /// the binary search will not probe past) PLPMTU, and the default
+    /// `raise_timer`.
+    pub fn new(base_plpmtu: usize, max_plpmtu: usize) -> Self {
+        Self::with_raise_timer(base_plpmtu, max_plpmtu, DEFAULT_RAISE_TIMER)
+    }
+
+    /// Create a prober with an explicit re-validation interval (see
+    /// `DEFAULT_RAISE_TIMER`), e.g. for tests that don't want to wait real
+    /// minutes for `SearchComplete` to re-probe.
+    pub fn with_raise_timer(base_plpmtu: usize, max_plpmtu: usize, raise_timer: Duration) -> Self {
+        let base = base_plpmtu.max(1);
+        let max = max_plpmtu.max(base);
+        Self {
+            state: PmtudState::Base,
+            base,
+            max,
+            effective_mtu: base,
+            floor: base,
+            ceiling: max,
+            probe_in_flight: None,
+            raise_timer,
+            last_validated: None,
+        }
+    }
+
+    /// Current state.
+    pub fn state(&self) -> PmtudState {
+        self.state
+    }
+
+    /// The largest size confirmed usable by an acknowledged probe so far.
+    /// Safe to feed into `Cubic::set_mss` / the multiplexer's framing
+    /// limit at any time.
+    pub fn effective_mtu(&self) -> usize {
+        self.effective_mtu
+    }
+
+    /// The next candidate size in the binary search between `floor`
+    /// (confirmed) and `ceiling` (not yet ruled out), or `None` once
+    /// they've converged to within one byte of each other.
+    fn candidate(&self) -> Option<usize> {
+        if self.ceiling <= self.floor + 1 {
+            None
+        } else {
+            Some(self.floor + (self.ceiling - self.floor) / 2)
+        }
+    }
+
+    /// If a probe should be sent right now, returns its size; the caller
+    /// sends a padded probe frame of that size and reports the outcome via
+    /// `on_probe_ack`/`on_probe_lost`. Returns `None` while a probe is
+    /// already outstanding, in `Error`, or in `SearchComplete` before
+    /// `raise_timer` has elapsed.
+    pub fn poll_probe(&mut self, now: Instant) -> Option<usize> {
+        if self.probe_in_flight.is_some() || self.state == PmtudState::Error {
+            return None;
+        }
+
+        if self.state == PmtudState::SearchComplete {
+            let due = self
+                .last_validated
+                .map(|t| now.duration_since(t) >= self.raise_timer)
+                .unwrap_or(true);
+            if !due {
+                return None;
+            }
+            // RFC 8899 PMTU_RAISE_TIMER: periodically re-open the search in
+            // case a larger size -- or a regression -- has since appeared.
+            self.state = PmtudState::Searching;
+            self.ceiling = self.max;
+        }
+
+        let size = match self.state {
+            PmtudState::Base => self.base,
+            _ => self.candidate()?,
+        };
+        self.probe_in_flight = Some(size);
+        Some(size)
+    }
+
+    /// The outstanding probe of `size` was acknowledged: raise the
+    /// confirmed floor (and `effective_mtu`) to it, settling into
+    /// `SearchComplete` once the binary search has converged.
+    ///
+    /// A stale report for a size that isn't the current outstanding probe
+    /// (e.g. a duplicate ack after the probe already timed out and a new
+    /// one was sent) is ignored.
+    pub fn on_probe_ack(&mut self, size: usize, now: Instant) {
+        if self.probe_in_flight != Some(size) {
+            return;
+        }
+        self.probe_in_flight = None;
+        self.floor = size;
+        self.effective_mtu = size;
+        self.state = PmtudState::Searching;
+
+        if self.candidate().is_none() {
+            self.state = PmtudState::SearchComplete;
+            self.last_validated = Some(now);
+        }
+    }
+
+    /// The outstanding probe of `size` was lost: narrow the ceiling below
+    /// it and keep searching, unless the lost probe was at `base_plpmtu`
+    /// itself, in which case the path is blackholing even the safe floor
+    /// and the prober gives up (`PmtudState::Error`).
+    pub fn on_probe_lost(&mut self, size: usize, now: Instant) {
+        if self.probe_in_flight != Some(size) {
+            return;
+        }
+        self.probe_in_flight = None;
+
+        if size <= self.base {
+            self.state = PmtudState::Error;
+            self.effective_mtu = self.base;
+            return;
+        }
+
+        self.ceiling = (size - 1).max(self.floor);
+        if self.candidate().is_none() {
+            self.state = PmtudState::SearchComplete;
+            self.last_validated = Some(now);
+        } else {
+            self.state = PmtudState::Searching;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::congestion::cubic::Cubic;
+    use crate::congestion::CongestionController;
+
+    #[test]
+    fn starts_at_base_plpmtu() {
+        let p = Pmtud::new(1200, 1500);
+        assert_eq!(p.state(), PmtudState::Base);
+        assert_eq!(p.effective_mtu(), 1200);
+    }
+
+    #[test]
+    fn successful_probes_binary_search_up_to_search_complete() {
+        let mut p = Pmtud::new(1200, 1500);
+        let mut now = Instant::now();
+
+        // Acking every probe the search offers should monotonically raise
+        // effective_mtu and eventually converge.
+        let mut last_mtu = p.effective_mtu();
+        loop {
+            let Some(size) = p.poll_probe(now) else { break };
+            p.on_probe_ack(size, now);
+            assert!(p.effective_mtu() >= last_mtu);
+            last_mtu = p.effective_mtu();
+            now += Duration::from_millis(10);
+            if p.state() == PmtudState::SearchComplete {
+                break;
+            }
+        }
+
+        assert_eq!(p.state(), PmtudState::SearchComplete);
+        // With every probe acked, it should converge at (or one below) the
+        // configured ceiling.
+        assert!(p.effective_mtu() >= 1499, "expected near-ceiling convergence, got {}", p.effective_mtu());
+    }
+
+    #[test]
+    fn probe_loss_above_base_narrows_the_search_without_blackholing() {
+        let mut p = Pmtud::new(1200, 1500);
+        let now = Instant::now();
+
+        // First probe beyond base_plpmtu is lost: should narrow the
+        // ceiling and keep searching, not declare a black hole.
+        let size = p.poll_probe(now).unwrap();
+        assert_eq!(size, 1200, "the very first probe is always at base_plpmtu");
+        p.on_probe_ack(size, now);
+
+        let candidate = p.poll_probe(now).unwrap();
+        assert!(candidate > 1200);
+        p.on_probe_lost(candidate, now);
+
+        assert_ne!(p.state(), PmtudState::Error);
+        assert_eq!(p.effective_mtu(), 1200, "a lost probe must not raise effective_mtu");
+    }
+
+    #[test]
+    fn losing_the_base_probe_declares_a_blackhole() {
+        let mut p = Pmtud::new(1200, 1500);
+        let now = Instant::now();
+
+        let size = p.poll_probe(now).unwrap();
+        assert_eq!(size, 1200);
+        p.on_probe_lost(size, now);
+
+        assert_eq!(p.state(), PmtudState::Error);
+        assert_eq!(p.effective_mtu(), 1200);
+        assert_eq!(p.poll_probe(now), None, "no further probes once blackholed");
+    }
+
+    #[test]
+    fn search_complete_reprobes_only_after_the_raise_timer() {
+        let mut p = Pmtud::with_raise_timer(1200, 1260, Duration::from_secs(60));
+        let now = Instant::now();
+
+        // Confirm the base, then lose every candidate above it -- e.g. a
+        // transient black hole -- so the search converges well short of
+        // `max_plpmtu` instead of at it.
+        let base = p.poll_probe(now).unwrap();
+        p.on_probe_ack(base, now);
+        loop {
+            let Some(size) = p.poll_probe(now) else { break };
+            p.on_probe_lost(size, now);
+            if p.state() == PmtudState::SearchComplete {
+                break;
+            }
+        }
+        assert_eq!(p.state(), PmtudState::SearchComplete);
+        assert_eq!(p.effective_mtu(), 1200, "no candidate above base was ever acked");
+
+        // Too soon: still holding at SearchComplete.
+        assert_eq!(p.poll_probe(now + Duration::from_secs(1)), None);
+
+        // Once the raise timer is due, the ceiling resets to max_plpmtu,
+        // giving the earlier (possibly transient) losses another chance.
+        let reprobe = p.poll_probe(now + Duration::from_secs(61));
+        assert!(reprobe.is_some(), "raise timer should reopen the search");
+        assert_eq!(p.state(), PmtudState::Searching);
+    }
+
+    #[test]
+    fn effective_mtu_feeds_cubic_set_mss() {
+        let mut p = Pmtud::new(1200, 1500);
+        let now = Instant::now();
+        let mut cubic = Cubic::with_mss(p.effective_mtu());
+
+        loop {
+            let Some(size) = p.poll_probe(now) else { break };
+            p.on_probe_ack(size, now);
+            cubic.set_mss(p.effective_mtu());
+            if p.state() == PmtudState::SearchComplete {
+                break;
+            }
+        }
+
+        assert_eq!(cubic.mss(), p.effective_mtu());
+        let before = cubic.window();
+        cubic.on_packet_sent(cubic.mss());
+        cubic.on_ack(cubic.mss());
+        assert_eq!(cubic.window(), before + cubic.mss());
+    }
+}
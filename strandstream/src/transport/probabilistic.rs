@@ -0,0 +1,742 @@
+//! Probabilistic transport mode -- accept frames with configurable probability.
+//!
+//! The receiver accepts each incoming frame with one of two loss models:
+//!
+//! - i.i.d.: each frame is an independent Bernoulli trial with delivery
+//!   probability `p` (configured in the range 0.0..=1.0).
+//! - Gilbert-Elliott: a two-state Markov chain (see [`GilbertElliott`]) that
+//!   reproduces the bursty loss real networks exhibit, and -- given an
+//!   explicit seed -- is reproducible in tests.
+//!
+//! No retransmission, no ordering.
+//!
+//! Optionally, the sender groups data chunks into Reed-Solomon FEC blocks
+//! (see [`crate::transport::fec`]): `k` data shreds plus `r` parity shreds
+//! per block, so the receiver can reconstruct a block from any `k` of the
+//! `k + r` shreds it accepts, recovering losses with zero round trips.
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use bytes::{Bytes, BytesMut};
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+
+use crate::error::{Result, StrandStreamError};
+use crate::frame::{DataFlags, Frame};
+use crate::transport::fec::{FecGroup, RsCodec, FEC_GROUP_HEADER_LEN};
+use crate::transport::{TransportReceiver, TransportSender};
+
+/// Default per-shred payload size. App chunks shorter than this are
+/// zero-padded before FEC parity is computed, so the block's shreds are all
+/// the same length; if a lost data shred has to be reconstructed, it comes
+/// back padded to this length rather than its original (shorter) size.
+pub const DEFAULT_SHRED_SIZE: usize = 1200;
+
+/// Maximum number of incomplete FEC groups buffered by the receiver before
+/// the oldest is evicted (sliding window), bounding memory under sustained
+/// loss of entire blocks.
+const MAX_PENDING_GROUPS: usize = 64;
+
+/// Sending side for Probabilistic streams.
+///
+/// With the default redundancy (`k=1, r=0`) this behaves exactly like the
+/// best-effort sender: each chunk is sent as its own one-shred block. Use
+/// [`ProbabilisticSender::with_redundancy`] to trade bandwidth for loss
+/// resilience by grouping chunks into FEC blocks.
+pub struct ProbabilisticSender {
+    next_seq: u32,
+    codec: RsCodec,
+    shred_size: usize,
+    next_group_id: u32,
+    /// Data chunks buffered for the in-progress block.
+    pending: Vec<Bytes>,
+}
+
+impl ProbabilisticSender {
+    pub fn new() -> Self {
+        Self::with_redundancy(1, 0)
+    }
+
+    /// Create a sender that groups every `k` chunks into a block and emits
+    /// `r` additional parity shreds for it, recoverable from any `k` of the
+    /// resulting `k + r` shreds. `r = 0` disables FEC.
+    pub fn with_redundancy(k: usize, r: usize) -> Self {
+        Self {
+            next_seq: 0,
+            codec: RsCodec::new(k, r),
+            shred_size: DEFAULT_SHRED_SIZE,
+            next_group_id: 0,
+            pending: Vec::with_capacity(k),
+        }
+    }
+
+    /// The `r/k` redundancy ratio this sender is configured with.
+    pub fn redundancy_ratio(&self) -> f64 {
+        self.codec.r() as f64 / self.codec.k() as f64
+    }
+
+    fn take_seq(&mut self) -> u32 {
+        let seq = self.next_seq;
+        self.next_seq = self.next_seq.wrapping_add(1);
+        seq
+    }
+
+    fn build_frame(
+        &mut self,
+        stream_id: u32,
+        flags: DataFlags,
+        header: FecGroup,
+        shard: &[u8],
+    ) -> Frame {
+        let mut payload = BytesMut::with_capacity(FEC_GROUP_HEADER_LEN + shard.len());
+        payload.extend_from_slice(&header.encode());
+        payload.extend_from_slice(shard);
+        Frame::Data {
+            stream_id,
+            seq: self.take_seq(),
+            flags: DataFlags(flags.0 | DataFlags::FEC.0),
+            payload: payload.freeze(),
+        }
+    }
+}
+
+impl Default for ProbabilisticSender {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TransportSender for ProbabilisticSender {
+    fn send(&mut self, stream_id: u32, data: Bytes, flags: DataFlags) -> Result<Vec<Frame>> {
+        let k = self.codec.k();
+        let r = self.codec.r();
+        let group_id = self.next_group_id;
+        let index = self.pending.len();
+
+        let header = FecGroup {
+            group_id,
+            index: index as u8,
+            k: k as u8,
+            r: r as u8,
+        };
+        let mut frames = vec![self.build_frame(stream_id, flags, header, &data)];
+        self.pending.push(data);
+
+        if self.pending.len() == k {
+            let shard_len = self.shred_size;
+            let padded: Vec<Vec<u8>> = self
+                .pending
+                .iter()
+                .map(|chunk| {
+                    let mut v = chunk.to_vec();
+                    v.resize(shard_len, 0);
+                    v
+                })
+                .collect();
+            let parity = self.codec.encode(&padded);
+            for (i, shard) in parity.into_iter().enumerate() {
+                let header = FecGroup {
+                    group_id,
+                    index: (k + i) as u8,
+                    k: k as u8,
+                    r: r as u8,
+                };
+                frames.push(self.build_frame(stream_id, flags, header, &shard));
+            }
+            self.pending.clear();
+            self.next_group_id = self.next_group_id.wrapping_add(1);
+        }
+
+        Ok(frames)
+    }
+
+    fn on_ack(&mut self, _seq: u32) {
+        // No acknowledgement tracking.
+    }
+
+    fn retransmit(&mut self) -> Result<Vec<Frame>> {
+        // No retransmission -- FEC recovers losses instead.
+        Ok(Vec::new())
+    }
+}
+
+/// A single shred buffered while its FEC group fills up.
+enum PendingShard {
+    /// A data shred's true (unpadded) bytes.
+    Data(Bytes),
+    /// A parity shred, already padded to the block's shred size.
+    Parity(Vec<u8>),
+}
+
+/// Shreds accumulated so far for one in-progress FEC group.
+struct PendingGroup {
+    k: u8,
+    r: u8,
+    shards: BTreeMap<u8, PendingShard>,
+}
+
+/// Which loss model a [`ProbabilisticReceiver`] draws its per-frame delivery
+/// decision from.
+enum LossModel {
+    /// Each frame is an independent Bernoulli trial with this delivery
+    /// probability, clamped to [0.0, 1.0].
+    Iid(f64),
+    /// Two-state Markov burst-loss model; see [`GilbertElliott`].
+    GilbertElliott(GilbertElliott),
+}
+
+/// The two states of a [`GilbertElliott`] chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GeState {
+    /// Low-loss state, delivery probability `k`.
+    Good,
+    /// High-loss state, delivery probability `h`.
+    Bad,
+}
+
+/// Two-state Gilbert-Elliott burst-loss model.
+///
+/// `p` is `P(Good -> Bad)` and `r` is `P(Bad -> Good)`; each state has its own
+/// delivery probability, `k` for Good (typically close to 1.0, little or no
+/// loss) and `h` for Bad (typically small, heavy loss). This reproduces the
+/// bursty loss real networks exhibit, unlike an i.i.d. Bernoulli trial.
+///
+/// Each `step()` first draws a uniform to transition the chain, then a
+/// second uniform against the *resulting* state's loss probability -- so a
+/// draw that flips Good -> Bad can also be the one that drops that frame.
+struct GilbertElliott {
+    p: f64,
+    r: f64,
+    k: f64,
+    h: f64,
+    state: GeState,
+    rng: SmallRng,
+}
+
+impl GilbertElliott {
+    fn new(p: f64, r: f64, k: f64, h: f64, rng: SmallRng) -> Self {
+        Self {
+            p,
+            r,
+            k,
+            h,
+            state: GeState::Good,
+            rng,
+        }
+    }
+
+    /// Advance the chain one step and return `true` if the frame should be
+    /// delivered.
+    fn step(&mut self) -> bool {
+        let transition: f64 = self.rng.gen();
+        self.state = match self.state {
+            GeState::Good if transition < self.p => GeState::Bad,
+            GeState::Bad if transition < self.r => GeState::Good,
+            other => other,
+        };
+
+        let loss_probability = match self.state {
+            GeState::Good => 1.0 - self.k,
+            GeState::Bad => 1.0 - self.h,
+        };
+        let outcome: f64 = self.rng.gen();
+        outcome >= loss_probability
+    }
+}
+
+/// Receiving side for Probabilistic streams.
+///
+/// Each shred is accepted according to the configured [`LossModel`]; shreds
+/// that fail are silently dropped. Accepted shreds are buffered per FEC group
+/// (see [`FecGroup`]) until `k` of them arrive, at which point the block is
+/// reconstructed (if any data shreds were lost) and the original chunks are
+/// delivered in order. Groups that never reach `k` shreds are evicted on a
+/// sliding window so memory stays bounded.
+pub struct ProbabilisticReceiver {
+    loss: LossModel,
+    groups: HashMap<u32, PendingGroup>,
+    /// Group insertion order, for sliding-window eviction.
+    group_order: VecDeque<u32>,
+    /// Recently-completed group ids, so redundant shreds that arrive after
+    /// a group has already been reconstructed and delivered are ignored
+    /// instead of spawning a bogus new group. Bounded the same way as
+    /// `group_order`.
+    completed: VecDeque<u32>,
+}
+
+impl ProbabilisticReceiver {
+    /// Create a new probabilistic receiver using the i.i.d. Bernoulli model
+    /// with the given delivery probability.
+    ///
+    /// `probability` is clamped to the range [0.0, 1.0].
+    pub fn new(probability: f64) -> Self {
+        Self {
+            loss: LossModel::Iid(probability.clamp(0.0, 1.0)),
+            groups: HashMap::new(),
+            group_order: VecDeque::new(),
+            completed: VecDeque::new(),
+        }
+    }
+
+    /// Create a receiver driven by a Gilbert-Elliott burst-loss chain (see
+    /// [`GilbertElliott`]) instead of the i.i.d. model, seeded from OS
+    /// entropy. Use [`Self::with_gilbert_elliott_seeded`] for reproducible
+    /// loss traces, e.g. in delivery-ratio tests.
+    pub fn with_gilbert_elliott(p: f64, r: f64, k: f64, h: f64) -> Self {
+        Self::with_gilbert_elliott_seeded(p, r, k, h, rand::random())
+    }
+
+    /// Same as [`Self::with_gilbert_elliott`], but seeded explicitly from
+    /// `seed` so loss traces are reproducible.
+    pub fn with_gilbert_elliott_seeded(p: f64, r: f64, k: f64, h: f64, seed: u64) -> Self {
+        Self {
+            loss: LossModel::GilbertElliott(GilbertElliott::new(
+                p,
+                r,
+                k,
+                h,
+                SmallRng::seed_from_u64(seed),
+            )),
+            groups: HashMap::new(),
+            group_order: VecDeque::new(),
+            completed: VecDeque::new(),
+        }
+    }
+
+    /// Returns the configured delivery probability for the i.i.d. model, or
+    /// `None` if this receiver uses the Gilbert-Elliott burst model instead.
+    pub fn probability(&self) -> Option<f64> {
+        match &self.loss {
+            LossModel::Iid(p) => Some(*p),
+            LossModel::GilbertElliott(_) => None,
+        }
+    }
+
+    fn evict_oldest_if_full(&mut self) {
+        while self.group_order.len() > MAX_PENDING_GROUPS {
+            if let Some(oldest) = self.group_order.pop_front() {
+                self.groups.remove(&oldest);
+            }
+        }
+    }
+
+    /// Buffer one accepted shred, returning the block's reconstructed
+    /// chunks once `k` shreds for its group have arrived.
+    ///
+    /// `header.k`/`header.r` come straight off the wire, so before they ever
+    /// reach `RsCodec::new` (whose `k + r <= 255` is an `assert!`, not a
+    /// `Result`) this rejects a shred whose header claims a shape the field
+    /// can't represent, or one that disagrees with the shape a prior shred
+    /// already established for the same group.
+    fn accept_shard(&mut self, header: FecGroup, shard_bytes: &[u8]) -> Vec<Bytes> {
+        if self.completed.contains(&header.group_id) {
+            // Redundant shred for a group already delivered; ignore it.
+            return Vec::new();
+        }
+
+        if header.k == 0 || header.k as usize + header.r as usize > 255 {
+            return Vec::new();
+        }
+
+        if let std::collections::hash_map::Entry::Vacant(entry) = self.groups.entry(header.group_id) {
+            entry.insert(PendingGroup {
+                k: header.k,
+                r: header.r,
+                shards: BTreeMap::new(),
+            });
+            self.group_order.push_back(header.group_id);
+            self.evict_oldest_if_full();
+        }
+
+        let Some(group) = self.groups.get_mut(&header.group_id) else {
+            // Evicted before this shred could be filed; drop it.
+            return Vec::new();
+        };
+
+        if header.k != group.k || header.r != group.r {
+            // Disagrees with the shape a prior shred established for this
+            // group id; drop rather than let it corrupt reconstruction.
+            return Vec::new();
+        }
+
+        let shard = if header.index < header.k {
+            PendingShard::Data(Bytes::copy_from_slice(shard_bytes))
+        } else {
+            PendingShard::Parity(shard_bytes.to_vec())
+        };
+        group.shards.entry(header.index).or_insert(shard);
+
+        if group.shards.len() < group.k as usize {
+            return Vec::new();
+        }
+
+        let group = self.groups.remove(&header.group_id).unwrap();
+        self.group_order.retain(|&id| id != header.group_id);
+        self.completed.push_back(header.group_id);
+        while self.completed.len() > MAX_PENDING_GROUPS {
+            self.completed.pop_front();
+        }
+        Self::reconstruct(group)
+    }
+
+    /// Reconstruct a block's `k` original chunks from its buffered shreds.
+    fn reconstruct(group: PendingGroup) -> Vec<Bytes> {
+        let all_data_present =
+            (0..group.k).all(|i| matches!(group.shards.get(&i), Some(PendingShard::Data(_))));
+        if all_data_present {
+            return (0..group.k)
+                .map(|i| match group.shards.get(&i) {
+                    Some(PendingShard::Data(bytes)) => bytes.clone(),
+                    _ => unreachable!("checked above"),
+                })
+                .collect();
+        }
+
+        let shard_len = group
+            .shards
+            .values()
+            .find_map(|s| match s {
+                PendingShard::Parity(bytes) => Some(bytes.len()),
+                PendingShard::Data(_) => None,
+            })
+            .unwrap_or(0);
+
+        let codec = RsCodec::new(group.k as usize, group.r as usize);
+        let available: Vec<(usize, Vec<u8>)> = group
+            .shards
+            .iter()
+            .map(|(&index, shard)| {
+                let bytes = match shard {
+                    PendingShard::Data(bytes) => {
+                        let mut padded = bytes.to_vec();
+                        padded.resize(shard_len, 0);
+                        padded
+                    }
+                    PendingShard::Parity(bytes) => bytes.clone(),
+                };
+                (index as usize, bytes)
+            })
+            .collect();
+
+        let Some(recovered) = codec.decode(&available) else {
+            // Submatrix was singular (should not happen for valid indices);
+            // nothing better to do than drop the block.
+            return Vec::new();
+        };
+
+        (0..group.k as usize)
+            .map(|i| match group.shards.get(&(i as u8)) {
+                Some(PendingShard::Data(bytes)) => bytes.clone(),
+                _ => Bytes::from(recovered[i].clone()),
+            })
+            .collect()
+    }
+}
+
+impl TransportReceiver for ProbabilisticReceiver {
+    fn receive(&mut self, frame: &Frame) -> Result<Vec<Bytes>> {
+        match frame {
+            Frame::Data { payload, .. } => {
+                let delivered = match &mut self.loss {
+                    LossModel::Iid(p) => rand::random::<f64>() < *p,
+                    LossModel::GilbertElliott(ge) => ge.step(),
+                };
+                if !delivered {
+                    return Ok(Vec::new()); // dropped by the loss model
+                }
+
+                let Some((header, shard_bytes)) = FecGroup::decode(payload) else {
+                    return Err(StrandStreamError::Internal(
+                        "Probabilistic frame missing FEC group header".into(),
+                    ));
+                };
+                Ok(self.accept_shard(header, shard_bytes))
+            }
+            _ => Err(StrandStreamError::Internal(
+                "ProbabilisticReceiver received non-data frame".into(),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn probability_one_always_delivers() {
+        let mut sender = ProbabilisticSender::new();
+        let mut receiver = ProbabilisticReceiver::new(1.0);
+
+        for _ in 0..100 {
+            let f = sender
+                .send(1, Bytes::from_static(b"data"), DataFlags::NONE)
+                .unwrap();
+            let d = receiver.receive(&f[0]).unwrap();
+            assert_eq!(d.len(), 1);
+            assert_eq!(&d[0][..], b"data");
+        }
+    }
+
+    #[test]
+    fn probability_zero_never_delivers() {
+        let mut sender = ProbabilisticSender::new();
+        let mut receiver = ProbabilisticReceiver::new(0.0);
+
+        for _ in 0..100 {
+            let f = sender
+                .send(1, Bytes::from_static(b"data"), DataFlags::NONE)
+                .unwrap();
+            let d = receiver.receive(&f[0]).unwrap();
+            assert!(d.is_empty());
+        }
+    }
+
+    #[test]
+    fn probability_delivers_roughly_expected_ratio() {
+        let mut sender = ProbabilisticSender::new();
+        let mut receiver = ProbabilisticReceiver::new(0.5);
+
+        let trials = 10_000;
+        let mut delivered = 0usize;
+        for _ in 0..trials {
+            let f = sender
+                .send(1, Bytes::from_static(b"d"), DataFlags::NONE)
+                .unwrap();
+            let d = receiver.receive(&f[0]).unwrap();
+            delivered += d.len();
+        }
+
+        // Expect roughly 50% +/- 10% (generous tolerance for a coin flip).
+        let ratio = delivered as f64 / trials as f64;
+        assert!(
+            (0.40..=0.60).contains(&ratio),
+            "delivery ratio {ratio} outside expected range"
+        );
+    }
+
+    #[test]
+    fn fec_block_reconstructs_missing_data_shred() {
+        let mut sender = ProbabilisticSender::with_redundancy(3, 1);
+        let mut receiver = ProbabilisticReceiver::new(1.0);
+
+        let chunks: Vec<Bytes> = vec![
+            Bytes::from_static(b"alpha"),
+            Bytes::from_static(b"bravo"),
+            Bytes::from_static(b"charl"),
+        ];
+
+        let mut frames = Vec::new();
+        for chunk in &chunks {
+            frames.extend(sender.send(1, chunk.clone(), DataFlags::NONE).unwrap());
+        }
+        // 3 data shreds + 1 parity shred.
+        assert_eq!(frames.len(), 4);
+
+        // Drop the second data shred (index 1); feed the rest.
+        let mut delivered = Vec::new();
+        for (i, frame) in frames.iter().enumerate() {
+            if i == 1 {
+                continue;
+            }
+            delivered.extend(receiver.receive(frame).unwrap());
+        }
+
+        assert_eq!(delivered.len(), 3);
+        assert_eq!(&delivered[0][..], b"alpha");
+        assert_eq!(&delivered[1][..5], b"bravo");
+        assert_eq!(&delivered[2][..], b"charl");
+    }
+
+    #[test]
+    fn fec_block_passes_through_when_no_loss() {
+        let mut sender = ProbabilisticSender::with_redundancy(2, 2);
+        let mut receiver = ProbabilisticReceiver::new(1.0);
+
+        let f1 = sender
+            .send(1, Bytes::from_static(b"one"), DataFlags::NONE)
+            .unwrap();
+        let f2 = sender
+            .send(1, Bytes::from_static(b"two"), DataFlags::NONE)
+            .unwrap();
+        let frames: Vec<Frame> = f1.into_iter().chain(f2).collect();
+        assert_eq!(frames.len(), 4); // 2 data + 2 parity
+
+        let mut delivered = Vec::new();
+        for frame in &frames {
+            delivered.extend(receiver.receive(frame).unwrap());
+        }
+        assert_eq!(delivered.len(), 2);
+        assert_eq!(&delivered[0][..], b"one");
+        assert_eq!(&delivered[1][..], b"two");
+    }
+
+    /// Builds a raw FEC-coded `Data` frame without going through
+    /// `ProbabilisticSender`, so a header can claim a `(k, r)` shape the
+    /// sender itself would never construct (e.g. one `RsCodec::new` would
+    /// reject).
+    fn raw_fec_frame(header: FecGroup, shard: &[u8]) -> Frame {
+        let mut payload = BytesMut::with_capacity(FEC_GROUP_HEADER_LEN + shard.len());
+        payload.extend_from_slice(&header.encode());
+        payload.extend_from_slice(shard);
+        Frame::Data {
+            stream_id: 1,
+            seq: 0,
+            flags: DataFlags(DataFlags::FEC.0),
+            payload: payload.freeze(),
+        }
+    }
+
+    #[test]
+    fn oversized_k_plus_r_header_is_dropped_not_panicking() {
+        // k=200, r=100 are each legal u8 values but together exceed GF(256)'s
+        // 255 nonzero elements -- exactly what `RsCodec::new` asserts
+        // against. A data shred claiming this shape, with the group
+        // incomplete (so `reconstruct` would otherwise try to rebuild via
+        // `RsCodec::new`), must be dropped rather than panic the process.
+        let mut receiver = ProbabilisticReceiver::new(1.0);
+        let header = FecGroup {
+            group_id: 1,
+            index: 0,
+            k: 200,
+            r: 100,
+        };
+        let frame = raw_fec_frame(header, &[0u8; DEFAULT_SHRED_SIZE]);
+        assert_eq!(receiver.receive(&frame).unwrap(), Vec::<Bytes>::new());
+    }
+
+    #[test]
+    fn zero_k_header_is_dropped_not_panicking() {
+        let mut receiver = ProbabilisticReceiver::new(1.0);
+        let header = FecGroup {
+            group_id: 1,
+            index: 0,
+            k: 0,
+            r: 1,
+        };
+        let frame = raw_fec_frame(header, &[0u8; DEFAULT_SHRED_SIZE]);
+        assert_eq!(receiver.receive(&frame).unwrap(), Vec::<Bytes>::new());
+    }
+
+    #[test]
+    fn mismatched_k_r_for_an_established_group_is_dropped() {
+        let mut sender = ProbabilisticSender::with_redundancy(2, 1);
+        let mut receiver = ProbabilisticReceiver::new(1.0);
+
+        let frames = sender
+            .send(1, Bytes::from_static(b"one"), DataFlags::NONE)
+            .unwrap();
+        assert_eq!(receiver.receive(&frames[0]).unwrap(), Vec::<Bytes>::new());
+
+        // Same group id, but now claiming a shape that disagrees with the
+        // one the first shred established.
+        let conflicting = FecGroup {
+            group_id: 0,
+            index: 1,
+            k: 1,
+            r: 0,
+        };
+        let frame = raw_fec_frame(conflicting, &[0u8; DEFAULT_SHRED_SIZE]);
+        assert_eq!(receiver.receive(&frame).unwrap(), Vec::<Bytes>::new());
+    }
+
+    #[test]
+    fn incomplete_groups_are_evicted_on_sliding_window() {
+        let mut sender = ProbabilisticSender::with_redundancy(4, 1);
+        let mut receiver = ProbabilisticReceiver::new(1.0);
+
+        // Push far more blocks than MAX_PENDING_GROUPS, each missing enough
+        // shreds (only 3 of 5 fed) that it never completes.
+        for _ in 0..(MAX_PENDING_GROUPS + 10) {
+            let mut frames = Vec::new();
+            for i in 0..4u8 {
+                frames.extend(
+                    sender
+                        .send(1, Bytes::copy_from_slice(&[i; 4]), DataFlags::NONE)
+                        .unwrap(),
+                );
+            }
+            for frame in frames.iter().take(3) {
+                assert!(receiver.receive(frame).unwrap().is_empty());
+            }
+        }
+
+        assert!(receiver.group_order.len() <= MAX_PENDING_GROUPS);
+    }
+
+    #[test]
+    fn redundancy_ratio_reflects_k_and_r() {
+        let sender = ProbabilisticSender::with_redundancy(4, 2);
+        assert_eq!(sender.redundancy_ratio(), 0.5);
+    }
+
+    #[test]
+    fn iid_probability_getter() {
+        let receiver = ProbabilisticReceiver::new(0.5);
+        assert_eq!(receiver.probability(), Some(0.5));
+    }
+
+    #[test]
+    fn gilbert_elliott_probability_getter_is_none() {
+        let receiver = ProbabilisticReceiver::with_gilbert_elliott_seeded(0.1, 0.5, 1.0, 0.0, 1);
+        assert_eq!(receiver.probability(), None);
+    }
+
+    #[test]
+    fn gilbert_elliott_no_loss_in_good_state_with_k_one() {
+        // p=0 means the chain never leaves Good; k=1 means no loss there.
+        let mut sender = ProbabilisticSender::new();
+        let mut receiver = ProbabilisticReceiver::with_gilbert_elliott_seeded(0.0, 0.0, 1.0, 0.0, 42);
+
+        for _ in 0..100 {
+            let f = sender
+                .send(1, Bytes::from_static(b"data"), DataFlags::NONE)
+                .unwrap();
+            let d = receiver.receive(&f[0]).unwrap();
+            assert_eq!(d.len(), 1);
+        }
+    }
+
+    #[test]
+    fn gilbert_elliott_total_loss_in_bad_state() {
+        // r=0 never returns to Good, so once in Bad (guaranteed by p=1) with
+        // h=0 every subsequent frame is dropped.
+        let mut sender = ProbabilisticSender::new();
+        let mut receiver = ProbabilisticReceiver::with_gilbert_elliott_seeded(1.0, 0.0, 1.0, 0.0, 7);
+
+        // First frame transitions Good -> Bad and is evaluated against h=0.
+        let mut delivered = 0usize;
+        for _ in 0..100 {
+            let f = sender
+                .send(1, Bytes::from_static(b"data"), DataFlags::NONE)
+                .unwrap();
+            delivered += receiver.receive(&f[0]).unwrap().len();
+        }
+        assert_eq!(delivered, 0);
+    }
+
+    #[test]
+    fn gilbert_elliott_is_deterministic_given_same_seed() {
+        let mut sender_a = ProbabilisticSender::new();
+        let mut sender_b = ProbabilisticSender::new();
+        let mut receiver_a =
+            ProbabilisticReceiver::with_gilbert_elliott_seeded(0.1, 0.3, 0.9, 0.2, 99);
+        let mut receiver_b =
+            ProbabilisticReceiver::with_gilbert_elliott_seeded(0.1, 0.3, 0.9, 0.2, 99);
+
+        let mut trace_a = Vec::new();
+        let mut trace_b = Vec::new();
+        for _ in 0..200 {
+            let fa = sender_a
+                .send(1, Bytes::from_static(b"x"), DataFlags::NONE)
+                .unwrap();
+            let fb = sender_b
+                .send(1, Bytes::from_static(b"x"), DataFlags::NONE)
+                .unwrap();
+            trace_a.push(receiver_a.receive(&fa[0]).unwrap().len());
+            trace_b.push(receiver_b.receive(&fb[0]).unwrap().len());
+        }
+
+        assert_eq!(trace_a, trace_b);
+    }
+}
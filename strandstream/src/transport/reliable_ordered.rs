@@ -1,30 +1,200 @@
 //! Reliable-Ordered transport mode -- TCP-equivalent in-order delivery.
 //!
-//! Sender: sequence tracking with a send buffer for retransmission.
+//! Sender: sequence tracking with a send buffer for retransmission, gated by
+//! an RTO timer derived from a per-stream `RttEstimate` (RFC 9002 SRTT/RTTVAR
+//! smoothing), same algorithm as `RetransmissionEngine`/`LossDetector` use at
+//! the connection level. A frame is only retransmitted once its RTO has
+//! elapsed since it was last sent, and retransmitting it more than
+//! `max_retransmits` times gives up with `MaxRetransmissionsExceeded`.
+//! `AckedRanges` additionally drives fast retransmit: a buffered frame with
+//! enough higher sequences already acked around it is resent immediately
+//! rather than waiting out the RTO. A sender-side flow-control window
+//! (`send_window`/`bytes_in_flight`) bounds how much unacknowledged data the
+//! send buffer can hold, rejecting new sends with `FlowControlBlocked` once
+//! a slow or silent receiver lets it fill up, rather than letting it grow
+//! without bound.
 //! Receiver: in-order delivery buffer using a BTreeMap, delivers only
 //! when the next expected contiguous sequence is present.
 
 use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
 
 use bytes::Bytes;
 
-use crate::error::{NexStreamError, Result};
+use crate::error::{StrandStreamError, Result};
 use crate::frame::{DataFlags, Frame};
+use crate::recovery::RttEstimate;
 use crate::transport::{TransportReceiver, TransportSender};
 
+/// Default cap on retransmission attempts per frame before giving up,
+/// mirroring `RetransmissionEngine`'s `MAX_RETRIES`.
+const DEFAULT_MAX_RETRANSMITS: u32 = 3;
+
+/// Floor on the computed RTO. `RttEstimate` has no peer-reported `ack_delay`
+/// plumbed into this mode's simple per-frame tracking (unlike
+/// `RetransmissionEngine`, which gets one from the connection), so its
+/// estimate alone would otherwise retransmit too eagerly before a real RTT
+/// sample arrives.
+const MIN_RTO: Duration = Duration::from_millis(20);
+
+/// Number of higher sequences that must be acked around a gap before fast
+/// retransmit fires for it, matching TCP's classic 3-dup-ack threshold.
+const FAST_RETRANSMIT_THRESHOLD: u32 = 3;
+
+/// Default sender-side flow-control window: 1 MiB, a typical send buffer
+/// size, bounding how much unacknowledged data `send_buffer` can hold
+/// before a slow or silent receiver blocks further sends.
+const DEFAULT_SEND_WINDOW: usize = 1024 * 1024;
+
+/// A sorted set of half-open `[start, end)` acknowledged sequence ranges,
+/// merging on insert -- the SACK-style counterpart to the receiver's
+/// contiguous `expected_seq` tracking.
+///
+/// `start`/`end` are raw `u32` sequence numbers, which wrap at `u32::MAX`
+/// (see `ReliableOrderedSender::next_seq`). `insert` handles a range that
+/// itself wraps (`end < start`) by splitting it at the boundary so no acked
+/// sequence is lost; the range-counting helpers below assume all seqs of
+/// interest lie within a single non-wrapped span, which holds for any send
+/// buffer of realistic size (far fewer than `u32::MAX` frames in flight at
+/// once).
+#[derive(Debug, Default)]
+struct AckedRanges {
+    ranges: BTreeMap<u32, u32>,
+}
+
+impl AckedRanges {
+    fn new() -> Self {
+        Self {
+            ranges: BTreeMap::new(),
+        }
+    }
+
+    /// Insert the half-open range `[start, end)`, merging with any range it
+    /// overlaps or touches.
+    fn insert(&mut self, start: u32, end: u32) {
+        if start == end {
+            return;
+        }
+        if end < start {
+            // Wraps past u32::MAX -- split at the boundary.
+            self.insert_non_wrapping(start, u32::MAX);
+            self.insert_non_wrapping(0, end);
+            return;
+        }
+        self.insert_non_wrapping(start, end);
+    }
+
+    fn insert_non_wrapping(&mut self, start: u32, end: u32) {
+        if start == end {
+            return;
+        }
+
+        // First existing range that could possibly overlap or touch
+        // [start, end): every range before it ends strictly below `start`.
+        let i = self
+            .ranges
+            .range(..start)
+            .next_back()
+            .filter(|&(_, &e)| e >= start)
+            .map(|(&s, _)| s);
+
+        let mut new_start = i.unwrap_or(start).min(start);
+        let mut new_end = end;
+        let mut to_remove = Vec::new();
+
+        for (&s, &e) in self.ranges.range(new_start..) {
+            if s > new_end {
+                break;
+            }
+            new_start = new_start.min(s);
+            new_end = new_end.max(e);
+            to_remove.push(s);
+        }
+        for s in to_remove {
+            self.ranges.remove(&s);
+        }
+        self.ranges.insert(new_start, new_end);
+    }
+
+    /// Returns `true` if `seq` falls within one of the tracked ranges.
+    /// Assumes `seq` is within the non-wrapped span of the tracked ranges.
+    fn contains(&self, seq: u32) -> bool {
+        self.ranges
+            .range(..=seq)
+            .next_back()
+            .is_some_and(|(_, &end)| seq < end)
+    }
+
+    /// Count of acked sequence numbers strictly greater than `seq`. Used for
+    /// fast-retransmit dup-ack counting; assumes `seq` and the tracked
+    /// ranges are within a single non-wrapped span.
+    fn count_acked_above(&self, seq: u32) -> u32 {
+        let mut count: u32 = 0;
+        for (&start, &end) in &self.ranges {
+            let lo = start.max(seq.saturating_add(1));
+            if end > lo {
+                count = count.saturating_add(end - lo);
+            }
+        }
+        count
+    }
+}
+
+/// Per-frame bookkeeping kept alongside the buffered `Frame` so `retransmit`
+/// knows whether it's due and `on_ack` can feed a round-trip sample.
+#[derive(Debug, Clone)]
+struct SendBufferEntry {
+    frame: Frame,
+    /// Payload length, cached so `on_ack_range` can release the
+    /// flow-control window without re-matching on `frame`.
+    len: usize,
+    /// When this frame was first sent, used as the RTT sample start.
+    first_sent: Instant,
+    /// When this frame was last (re)sent, compared against `rto`.
+    last_sent: Instant,
+    /// This frame's own retransmission timeout, doubled on each resend
+    /// (exponential backoff) starting from the sender's RTT-derived `rto()`
+    /// at the time it was first buffered.
+    rto: Duration,
+    /// Number of times this frame has been retransmitted (0 = never).
+    attempts: u32,
+}
+
 /// Sending side for Reliable-Ordered streams.
 pub struct ReliableOrderedSender {
     /// Next sequence number to assign.
     next_seq: u32,
-    /// Send buffer: maps seq -> frame for potential retransmission.
-    send_buffer: BTreeMap<u32, Frame>,
+    /// Send buffer: maps seq -> frame + retransmission bookkeeping.
+    send_buffer: BTreeMap<u32, SendBufferEntry>,
+    /// RTT estimator feeding the RTO used to decide when a frame is due for
+    /// retransmission.
+    rtt: RttEstimate,
+    /// Maximum number of retransmission attempts before giving up.
+    max_retransmits: u32,
+    /// Ranges of sequence numbers acked so far, driving fast retransmit.
+    acked: AckedRanges,
+    /// Maximum bytes allowed in flight (unacknowledged) at once.
+    send_window: usize,
+    /// Bytes currently in flight, i.e. the sum of unacknowledged payload
+    /// lengths still in `send_buffer`.
+    bytes_in_flight: usize,
 }
 
 impl ReliableOrderedSender {
     pub fn new() -> Self {
+        Self::with_max_retransmits(DEFAULT_MAX_RETRANSMITS)
+    }
+
+    /// Create a sender with a non-default retransmission attempt limit.
+    pub fn with_max_retransmits(max_retransmits: u32) -> Self {
         Self {
             next_seq: 0,
             send_buffer: BTreeMap::new(),
+            rtt: RttEstimate::new(Duration::ZERO),
+            max_retransmits,
+            acked: AckedRanges::new(),
+            send_window: DEFAULT_SEND_WINDOW,
+            bytes_in_flight: 0,
         }
     }
 
@@ -32,6 +202,50 @@ impl ReliableOrderedSender {
     pub fn in_flight(&self) -> usize {
         self.send_buffer.len()
     }
+
+    /// Current retransmission timeout, derived from the RTT estimate.
+    pub fn rto(&self) -> Duration {
+        self.rtt.rto().max(MIN_RTO)
+    }
+
+    /// Bytes currently in flight (unacknowledged) in the send buffer.
+    pub fn bytes_in_flight(&self) -> usize {
+        self.bytes_in_flight
+    }
+
+    /// Set the sender-side flow-control window, in bytes -- e.g. to honor a
+    /// peer-advertised receive window.
+    pub fn set_send_window(&mut self, max: usize) {
+        self.send_window = max;
+    }
+
+    /// Acknowledge every sequence number in the half-open range
+    /// `[start, end)` in one pass, removing each from the send buffer and
+    /// feeding an RTT sample for each newly-acked frame.
+    ///
+    /// This is the SACK-style counterpart to `on_ack`: acking a contiguous
+    /// run of frames costs one call instead of one per sequence number.
+    pub fn on_ack_range(&mut self, start: u32, end: u32) {
+        self.acked.insert(start, end);
+        let newly_acked: Vec<u32> = self
+            .send_buffer
+            .keys()
+            .copied()
+            .filter(|&seq| self.acked.contains(seq))
+            .collect();
+        for seq in newly_acked {
+            if let Some(entry) = self.send_buffer.remove(&seq) {
+                self.bytes_in_flight = self.bytes_in_flight.saturating_sub(entry.len);
+                // Karn's algorithm: an ack for a retransmitted frame is
+                // ambiguous about which transmission it actually covers, so
+                // only ever-sent-once frames feed the RTT estimator.
+                if entry.attempts == 0 {
+                    self.rtt
+                        .update(entry.first_sent.elapsed(), Duration::ZERO);
+                }
+            }
+        }
+    }
 }
 
 impl Default for ReliableOrderedSender {
@@ -41,25 +255,90 @@ impl Default for ReliableOrderedSender {
 }
 
 impl TransportSender for ReliableOrderedSender {
-    fn send(&mut self, stream_id: u32, data: Bytes) -> Result<Vec<Frame>> {
+    /// Errs with `FlowControlBlocked` if admitting `data` would push
+    /// `bytes_in_flight` past `send_window` -- the caller should hold the
+    /// data and retry once enough of the buffer has been acknowledged.
+    fn send(&mut self, stream_id: u32, data: Bytes, flags: DataFlags) -> Result<Vec<Frame>> {
+        let len = data.len();
+        if self.bytes_in_flight.saturating_add(len) > self.send_window {
+            return Err(StrandStreamError::FlowControlBlocked(stream_id));
+        }
+
         let seq = self.next_seq;
         self.next_seq = self.next_seq.wrapping_add(1);
         let frame = Frame::Data {
             stream_id,
             seq,
-            flags: DataFlags::NONE,
+            flags,
             payload: data,
         };
-        self.send_buffer.insert(seq, frame.clone());
+        let now = Instant::now();
+        let rto = self.rto();
+        self.send_buffer.insert(
+            seq,
+            SendBufferEntry {
+                frame: frame.clone(),
+                len,
+                first_sent: now,
+                last_sent: now,
+                rto,
+                attempts: 0,
+            },
+        );
+        self.bytes_in_flight += len;
         Ok(vec![frame])
     }
 
     fn on_ack(&mut self, seq: u32) {
-        self.send_buffer.remove(&seq);
+        self.on_ack_range(seq, seq.wrapping_add(1));
     }
 
-    fn retransmit(&mut self) -> Vec<Frame> {
-        self.send_buffer.values().cloned().collect()
+    /// Return frames that are due for retransmission, bumping their attempt
+    /// counter, doubling their per-frame RTO (exponential backoff), and
+    /// resetting `last_sent`.
+    ///
+    /// A frame is due either because its own backed-off RTO has elapsed
+    /// since it was last sent, or because fast retransmit fires for it:
+    /// `FAST_RETRANSMIT_THRESHOLD` higher sequences are already acked while
+    /// it still isn't, a strong signal of loss rather than reordering that
+    /// lets us skip the RTO wait.
+    ///
+    /// Errs with `MaxRetransmissionsExceeded` on the first due frame that
+    /// would exceed `max_retransmits`, leaving the send buffer untouched for
+    /// that frame (and any not-yet-examined frames) so the caller can decide
+    /// how to handle a dead stream rather than silently dropping data.
+    fn retransmit(&mut self) -> Result<Vec<Frame>> {
+        let now = Instant::now();
+        let acked = &self.acked;
+        let due: Vec<u32> = self
+            .send_buffer
+            .iter()
+            .filter(|(&seq, entry)| {
+                now.duration_since(entry.last_sent) >= entry.rto
+                    || acked.count_acked_above(seq) >= FAST_RETRANSMIT_THRESHOLD
+            })
+            .map(|(&seq, _)| seq)
+            .collect();
+
+        let mut frames = Vec::with_capacity(due.len());
+        for seq in due {
+            let entry = self.send_buffer.get_mut(&seq).expect("seq just collected");
+            if entry.attempts + 1 > self.max_retransmits {
+                let stream_id = match entry.frame {
+                    Frame::Data { stream_id, .. } => stream_id,
+                    _ => unreachable!("send buffer only ever holds Frame::Data"),
+                };
+                return Err(StrandStreamError::MaxRetransmissionsExceeded(
+                    self.max_retransmits,
+                    stream_id,
+                ));
+            }
+            entry.attempts += 1;
+            entry.last_sent = now;
+            entry.rto *= 2;
+            frames.push(entry.frame.clone());
+        }
+        Ok(frames)
     }
 }
 
@@ -110,7 +389,7 @@ impl TransportReceiver for ReliableOrderedReceiver {
                 }
                 Ok(delivered)
             }
-            _ => Err(NexStreamError::Internal(
+            _ => Err(StrandStreamError::Internal(
                 "ReliableOrderedReceiver received non-data frame".into(),
             )),
         }
@@ -120,6 +399,7 @@ impl TransportReceiver for ReliableOrderedReceiver {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::thread::sleep;
 
     #[test]
     fn in_order_delivery() {
@@ -127,7 +407,7 @@ mod tests {
         let mut receiver = ReliableOrderedReceiver::new();
 
         let frames = sender
-            .send(1, Bytes::from_static(b"hello"))
+            .send(1, Bytes::from_static(b"hello"), DataFlags::NONE)
             .unwrap();
         let delivered = receiver.receive(&frames[0]).unwrap();
         assert_eq!(delivered.len(), 1);
@@ -139,9 +419,9 @@ mod tests {
         let mut sender = ReliableOrderedSender::new();
         let mut receiver = ReliableOrderedReceiver::new();
 
-        let f0 = sender.send(1, Bytes::from_static(b"A")).unwrap();
-        let f1 = sender.send(1, Bytes::from_static(b"B")).unwrap();
-        let f2 = sender.send(1, Bytes::from_static(b"C")).unwrap();
+        let f0 = sender.send(1, Bytes::from_static(b"A"), DataFlags::NONE).unwrap();
+        let f1 = sender.send(1, Bytes::from_static(b"B"), DataFlags::NONE).unwrap();
+        let f2 = sender.send(1, Bytes::from_static(b"C"), DataFlags::NONE).unwrap();
 
         // Deliver out of order: 1, 2, then 0.
         let d = receiver.receive(&f1[0]).unwrap();
@@ -160,12 +440,243 @@ mod tests {
     #[test]
     fn ack_removes_from_send_buffer() {
         let mut sender = ReliableOrderedSender::new();
-        sender.send(1, Bytes::from_static(b"A")).unwrap();
-        sender.send(1, Bytes::from_static(b"B")).unwrap();
+        sender.send(1, Bytes::from_static(b"A"), DataFlags::NONE).unwrap();
+        sender.send(1, Bytes::from_static(b"B"), DataFlags::NONE).unwrap();
         assert_eq!(sender.in_flight(), 2);
         sender.on_ack(0);
         assert_eq!(sender.in_flight(), 1);
         sender.on_ack(1);
         assert_eq!(sender.in_flight(), 0);
     }
+
+    #[test]
+    fn retransmit_withholds_frames_before_rto_elapses() {
+        let mut sender = ReliableOrderedSender::new();
+        sender.send(1, Bytes::from_static(b"A"), DataFlags::NONE).unwrap();
+        // No RTT sample yet, so rto() is the MIN_RTO floor -- far longer
+        // than the microseconds elapsed since `send`.
+        assert!(sender.retransmit().unwrap().is_empty());
+    }
+
+    #[test]
+    fn retransmit_returns_frame_once_rto_elapses() {
+        let mut sender = ReliableOrderedSender::new();
+        sender.send(1, Bytes::from_static(b"A"), DataFlags::NONE).unwrap();
+        sleep(sender.rto() + Duration::from_millis(5));
+
+        let frames = sender.retransmit().unwrap();
+        assert_eq!(frames.len(), 1);
+        match &frames[0] {
+            Frame::Data { seq, payload, .. } => {
+                assert_eq!(*seq, 0);
+                assert_eq!(&payload[..], b"A");
+            }
+            other => panic!("expected Frame::Data, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn retransmit_resets_timer_so_immediate_second_call_is_empty() {
+        let mut sender = ReliableOrderedSender::new();
+        sender.send(1, Bytes::from_static(b"A"), DataFlags::NONE).unwrap();
+        sleep(sender.rto() + Duration::from_millis(5));
+
+        assert_eq!(sender.retransmit().unwrap().len(), 1);
+        // last_sent was just reset, so the frame isn't due again immediately.
+        assert!(sender.retransmit().unwrap().is_empty());
+    }
+
+    #[test]
+    fn retransmit_gives_up_after_max_retransmits() {
+        let mut sender = ReliableOrderedSender::with_max_retransmits(2);
+        sender.send(7, Bytes::from_static(b"A"), DataFlags::NONE).unwrap();
+
+        // Each frame's own RTO doubles after every resend, so the wait
+        // before it's due again doubles too.
+        let mut wait = sender.rto();
+        for _ in 0..2 {
+            sleep(wait + Duration::from_millis(5));
+            assert_eq!(sender.retransmit().unwrap().len(), 1);
+            wait *= 2;
+        }
+
+        sleep(wait + Duration::from_millis(5));
+        let err = sender.retransmit().unwrap_err();
+        match err {
+            StrandStreamError::MaxRetransmissionsExceeded(limit, stream_id) => {
+                assert_eq!(limit, 2);
+                assert_eq!(stream_id, 7);
+            }
+            other => panic!("expected MaxRetransmissionsExceeded, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn retransmit_backs_off_exponentially_per_frame() {
+        let mut sender = ReliableOrderedSender::with_max_retransmits(3);
+        sender.send(1, Bytes::from_static(b"A"), DataFlags::NONE).unwrap();
+        let base_rto = sender.rto();
+
+        sleep(base_rto + Duration::from_millis(5));
+        assert_eq!(sender.retransmit().unwrap().len(), 1);
+
+        // Immediately after the first resend the frame's own RTO has
+        // doubled, so it isn't due again after only the original wait.
+        sleep(base_rto + Duration::from_millis(5));
+        assert!(sender.retransmit().unwrap().is_empty());
+
+        // But it is due once the doubled RTO has elapsed.
+        sleep(base_rto + Duration::from_millis(10));
+        assert_eq!(sender.retransmit().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn karns_algorithm_ignores_rtt_sample_from_retransmitted_frame() {
+        let mut sender = ReliableOrderedSender::new();
+        sender.send(1, Bytes::from_static(b"A"), DataFlags::NONE).unwrap();
+        sleep(sender.rto() + Duration::from_millis(5));
+        assert_eq!(sender.retransmit().unwrap().len(), 1);
+
+        // Ack arrives for a frame that was retransmitted: its sample is
+        // ambiguous (which transmission does the ack cover?) and must be
+        // excluded from the RTT estimator rather than skewing it with an
+        // inflated round trip.
+        sleep(Duration::from_millis(5));
+        sender.on_ack(0);
+        assert_eq!(sender.rto(), MIN_RTO);
+    }
+
+    #[test]
+    fn on_ack_feeds_rtt_estimate() {
+        let mut sender = ReliableOrderedSender::new();
+        sender.send(1, Bytes::from_static(b"A"), DataFlags::NONE).unwrap();
+        sleep(Duration::from_millis(10));
+        sender.on_ack(0);
+        // A real sample of at least 10ms gives srtt + 4 * rttvar >= 30ms,
+        // above the MIN_RTO floor an unsampled estimate sits at.
+        assert!(sender.rto() > MIN_RTO);
+    }
+
+    #[test]
+    fn acked_ranges_merges_adjacent_inserts() {
+        let mut ranges = AckedRanges::new();
+        ranges.insert(0, 5);
+        ranges.insert(5, 10);
+        assert!(ranges.contains(0));
+        assert!(ranges.contains(9));
+        assert!(!ranges.contains(10));
+        // Merged into a single range internally.
+        assert_eq!(ranges.ranges.len(), 1);
+    }
+
+    #[test]
+    fn acked_ranges_merges_overlapping_inserts() {
+        let mut ranges = AckedRanges::new();
+        ranges.insert(10, 20);
+        ranges.insert(15, 25);
+        assert_eq!(ranges.ranges.len(), 1);
+        assert!(ranges.contains(24));
+        assert!(!ranges.contains(25));
+    }
+
+    #[test]
+    fn acked_ranges_keeps_disjoint_ranges_separate() {
+        let mut ranges = AckedRanges::new();
+        ranges.insert(0, 5);
+        ranges.insert(10, 15);
+        assert_eq!(ranges.ranges.len(), 2);
+        assert!(!ranges.contains(7));
+    }
+
+    #[test]
+    fn acked_ranges_handles_u32_wraparound() {
+        let mut ranges = AckedRanges::new();
+        ranges.insert(u32::MAX - 2, 2);
+        assert!(ranges.contains(u32::MAX - 1));
+        assert!(ranges.contains(0));
+        assert!(ranges.contains(1));
+        assert!(!ranges.contains(2));
+    }
+
+    #[test]
+    fn acked_ranges_count_acked_above() {
+        let mut ranges = AckedRanges::new();
+        ranges.insert(5, 10); // acks 5,6,7,8,9
+        assert_eq!(ranges.count_acked_above(4), 5);
+        assert_eq!(ranges.count_acked_above(6), 3);
+        assert_eq!(ranges.count_acked_above(9), 0);
+    }
+
+    #[test]
+    fn on_ack_range_removes_contiguous_run_in_one_call() {
+        let mut sender = ReliableOrderedSender::new();
+        for b in [b"A", b"B", b"C", b"D"] {
+            sender.send(1, Bytes::from_static(b), DataFlags::NONE).unwrap();
+        }
+        assert_eq!(sender.in_flight(), 4);
+        sender.on_ack_range(0, 4);
+        assert_eq!(sender.in_flight(), 0);
+    }
+
+    #[test]
+    fn fast_retransmit_fires_before_rto_elapses() {
+        let mut sender = ReliableOrderedSender::new();
+        for b in [b"A", b"B", b"C", b"D"] {
+            sender.send(7, Bytes::from_static(b), DataFlags::NONE).unwrap();
+        }
+        // seq 0 stays unacked while 1, 2, 3 are acked -- 3 higher sequences
+        // acked meets FAST_RETRANSMIT_THRESHOLD, so seq 0 should be resent
+        // immediately even though its RTO hasn't elapsed.
+        sender.on_ack_range(1, 4);
+        let frames = sender.retransmit().unwrap();
+        assert_eq!(frames.len(), 1);
+        match &frames[0] {
+            Frame::Data { seq, .. } => assert_eq!(*seq, 0),
+            other => panic!("expected Frame::Data, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn fast_retransmit_does_not_fire_below_threshold() {
+        let mut sender = ReliableOrderedSender::new();
+        for b in [b"A", b"B", b"C"] {
+            sender.send(7, Bytes::from_static(b), DataFlags::NONE).unwrap();
+        }
+        // Only 2 higher sequences acked -- below FAST_RETRANSMIT_THRESHOLD.
+        sender.on_ack_range(1, 3);
+        assert!(sender.retransmit().unwrap().is_empty());
+    }
+
+    #[test]
+    fn send_blocked_once_window_exhausted() {
+        let mut sender = ReliableOrderedSender::new();
+        sender.set_send_window(10);
+
+        sender.send(1, Bytes::from_static(b"0123456789"), DataFlags::NONE).unwrap();
+        assert_eq!(sender.bytes_in_flight(), 10);
+
+        let err = sender
+            .send(1, Bytes::from_static(b"x"), DataFlags::NONE)
+            .unwrap_err();
+        match err {
+            StrandStreamError::FlowControlBlocked(stream_id) => assert_eq!(stream_id, 1),
+            other => panic!("expected FlowControlBlocked, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn send_unblocks_after_ack_frees_window() {
+        let mut sender = ReliableOrderedSender::new();
+        sender.set_send_window(10);
+
+        sender.send(1, Bytes::from_static(b"0123456789"), DataFlags::NONE).unwrap();
+        assert!(sender.send(1, Bytes::from_static(b"x"), DataFlags::NONE).is_err());
+
+        sender.on_ack(0);
+        assert_eq!(sender.bytes_in_flight(), 0);
+
+        let frames = sender.send(1, Bytes::from_static(b"x"), DataFlags::NONE).unwrap();
+        assert_eq!(frames.len(), 1);
+        assert_eq!(sender.bytes_in_flight(), 1);
+    }
 }
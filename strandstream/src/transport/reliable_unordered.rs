@@ -1,46 +1,215 @@
 //! Reliable-Unordered transport mode -- exactly-once delivery, no ordering.
 //!
-//! Sender: sequence tracking + send buffer for retransmission (same as RO).
-//! Receiver: tracks delivered seq numbers for exactly-once delivery using a
-//! `BTreeSet`.  Old entries are garbage-collected once the set exceeds
-//! `DELIVERED_GC_THRESHOLD` — the lowest `DELIVERED_GC_KEEP` entries are
-//! removed, preserving the ability to detect near-term duplicates without
-//! growing without bound.
-
-use std::collections::{BTreeMap, BTreeSet};
+//! Sender: sequence tracking with a send buffer for retransmission, gated by
+//! an RTO timer derived from a `RttEstimate` (same RFC 9002 SRTT/RTTVAR
+//! smoothing `ReliableOrderedSender` uses), so `retransmit` only returns
+//! frames whose own RTO has actually elapsed rather than cloning and
+//! returning the whole buffer on every call. Each frame's RTO doubles on
+//! every resend (exponential backoff), and an ack for a frame that was ever
+//! retransmitted is excluded from the RTT estimator (Karn's algorithm),
+//! since such an ack can't say which transmission it's acknowledging. There
+//! is no fast-retransmit/SACK machinery here (unlike RO) since an unordered
+//! stream has no contiguous sequence to reason about dup-acks against --
+//! loss is detected purely by RTO.
+//! Receiver: dedup via a fixed-memory sliding-window anti-replay bitmap
+//! (`ReplayBitmap`), modeled on IPsec/WireGuard anti-replay, rather than a
+//! set that grows for the life of the stream.
+
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
 
 use bytes::Bytes;
 
 use crate::error::{StrandStreamError, Result};
 use crate::frame::{DataFlags, Frame};
+use crate::recovery::RttEstimate;
 use crate::transport::{TransportReceiver, TransportSender};
 
-/// Maximum number of sequence numbers in the delivered set before GC runs.
-const DELIVERED_GC_THRESHOLD: usize = 1024;
+/// Default cap on retransmission attempts per frame before giving up,
+/// matching `ReliableOrderedSender`'s default.
+const DEFAULT_MAX_RETRANSMITS: u32 = 3;
+
+/// Floor on the computed RTO, for the same reason as RO's `MIN_RTO`: with no
+/// RTT sample yet, the bare estimate is zero and would retransmit eagerly.
+const MIN_RTO: Duration = Duration::from_millis(20);
+
+/// Number of `u64` words backing `ReplayBitmap`'s window, giving a window of
+/// `BITMAP_WORDS * 64` = 2048 sequence numbers.
+const BITMAP_WORDS: usize = 32;
+
+/// Window width in bits (sequence numbers), derived from `BITMAP_WORDS`.
+const WINDOW_BITS: u64 = (BITMAP_WORDS * 64) as u64;
 
-/// Number of oldest entries to discard when GC runs.
+/// Fixed-memory sliding-window anti-replay filter.
 ///
-/// We keep the most recent `DELIVERED_GC_THRESHOLD - DELIVERED_GC_DISCARD`
-/// entries so that late duplicates of recently-seen packets are still caught.
-const DELIVERED_GC_DISCARD: usize = 512;
+/// Tracks a `highest` watermark (widened to `u64`, unwrapped from the raw
+/// `u32` sequence space via signed wrapping-distance from the last-seen raw
+/// value -- the same trick TCP uses to compare sequence numbers across a
+/// wraparound) plus a bitmap of the `WINDOW_BITS` most recent sequence
+/// numbers at or below it.
+///
+/// On receiving `seq`:
+/// - If `seq` is newer than `highest`, the window shifts forward by the gap
+///   (clearing the newly-exposed low bits) and bit 0 is set for `seq`.  A
+///   gap of `WINDOW_BITS` or more (a burst of loss, or a large jump) just
+///   clears the whole bitmap rather than shifting bit-by-bit.
+/// - If `seq` falls inside the window, its bit is checked: already set means
+///   a duplicate, otherwise it's marked seen and accepted.
+/// - If `seq` is older than the window, it's rejected as too old.
+///
+/// Both "too old" and "duplicate" are reported the same way `ReplayWindow`
+/// (the connection-level equivalent) does: the caller swallows the reject
+/// silently rather than treating it as an error, since a replayed/late frame
+/// arriving on an exactly-once channel is expected background noise, not a
+/// fault.
+#[derive(Debug, Clone)]
+struct ReplayBitmap {
+    /// Unwrapped high-water mark, or `None` before the first frame.
+    highest: Option<u64>,
+    /// The raw `u32` sequence number `highest` corresponds to, used to
+    /// unwrap the next incoming raw sequence via wrapping-distance.
+    highest_raw: u32,
+    bitmap: [u64; BITMAP_WORDS],
+}
+
+impl ReplayBitmap {
+    fn new() -> Self {
+        Self {
+            highest: None,
+            highest_raw: 0,
+            bitmap: [0; BITMAP_WORDS],
+        }
+    }
+
+    /// Shift the bitmap's bit positions forward by `shift` (age of every
+    /// currently-tracked bit increases by `shift`), clearing newly-exposed
+    /// low bits. Treats the word array as a single big unsigned integer with
+    /// word 0 least-significant, so this is just a left shift on that value.
+    fn shift_forward(&mut self, shift: u64) {
+        if shift == 0 {
+            return;
+        }
+        if shift >= WINDOW_BITS {
+            self.bitmap = [0; BITMAP_WORDS];
+            return;
+        }
+        let word_shift = (shift / 64) as usize;
+        let bit_shift = (shift % 64) as u32;
+        let mut shifted = [0u64; BITMAP_WORDS];
+        for i in (0..BITMAP_WORDS).rev() {
+            let Some(src) = i.checked_sub(word_shift) else {
+                continue;
+            };
+            let mut word = self.bitmap[src] << bit_shift;
+            if bit_shift > 0 && src > 0 {
+                word |= self.bitmap[src - 1] >> (64 - bit_shift);
+            }
+            shifted[i] = word;
+        }
+        self.bitmap = shifted;
+    }
+
+    fn bit(age: u64) -> (usize, u32) {
+        ((age / 64) as usize, (age % 64) as u32)
+    }
+
+    /// Check `raw` against the window and, if accepted, record it. Returns
+    /// `true` if `raw` should be delivered, `false` if it's a duplicate or
+    /// too old.
+    fn check_and_mark(&mut self, raw: u32) -> bool {
+        let highest = match self.highest {
+            None => {
+                self.highest = Some(raw as u64);
+                self.highest_raw = raw;
+                self.bitmap[0] = 1;
+                return true;
+            }
+            Some(h) => h,
+        };
+
+        // Unwrap `raw` relative to `highest_raw` using the signed-distance
+        // trick: the wrapping difference, reinterpreted as i32, is the
+        // forward (positive) or backward (negative) distance even across a
+        // u32 wraparound, as long as the true distance fits in i32's range.
+        let delta = raw.wrapping_sub(self.highest_raw) as i32 as i64;
+        let unwrapped = (highest as i64 + delta) as u64;
+
+        if unwrapped > highest {
+            let shift = unwrapped - highest;
+            self.shift_forward(shift);
+            self.highest = Some(unwrapped);
+            self.highest_raw = raw;
+            self.bitmap[0] |= 1;
+            true
+        } else {
+            let age = highest - unwrapped;
+            if age >= WINDOW_BITS {
+                return false; // too old
+            }
+            let (word, bit) = Self::bit(age);
+            let mask = 1u64 << bit;
+            if self.bitmap[word] & mask != 0 {
+                return false; // duplicate
+            }
+            self.bitmap[word] |= mask;
+            true
+        }
+    }
+}
+
+/// Per-frame bookkeeping kept alongside the buffered `Frame` so `retransmit`
+/// knows whether it's due and `on_ack` can feed a round-trip sample. Mirrors
+/// `reliable_ordered::SendBufferEntry` minus the flow-control length field,
+/// which this mode doesn't track.
+#[derive(Debug, Clone)]
+struct SendBufferEntry {
+    frame: Frame,
+    /// When this frame was first sent, used as the RTT sample start.
+    first_sent: Instant,
+    /// When this frame was last (re)sent, compared against `rto`.
+    last_sent: Instant,
+    /// This frame's own retransmission timeout, doubled on each resend
+    /// (exponential backoff) starting from the sender's RTT-derived `rto()`
+    /// at the time it was first buffered.
+    rto: Duration,
+    /// Number of times this frame has been retransmitted (0 = never).
+    attempts: u32,
+}
 
 /// Sending side for Reliable-Unordered streams.
 pub struct ReliableUnorderedSender {
     next_seq: u32,
-    send_buffer: BTreeMap<u32, Frame>,
+    send_buffer: BTreeMap<u32, SendBufferEntry>,
+    /// RTT estimator feeding the RTO used to decide when a frame is due for
+    /// retransmission.
+    rtt: RttEstimate,
+    /// Maximum number of retransmission attempts before giving up.
+    max_retransmits: u32,
 }
 
 impl ReliableUnorderedSender {
     pub fn new() -> Self {
+        Self::with_max_retransmits(DEFAULT_MAX_RETRANSMITS)
+    }
+
+    /// Create a sender with a non-default retransmission attempt limit.
+    pub fn with_max_retransmits(max_retransmits: u32) -> Self {
         Self {
             next_seq: 0,
             send_buffer: BTreeMap::new(),
+            rtt: RttEstimate::new(Duration::ZERO),
+            max_retransmits,
         }
     }
 
     pub fn in_flight(&self) -> usize {
         self.send_buffer.len()
     }
+
+    /// Current retransmission timeout, derived from the RTT estimate.
+    pub fn rto(&self) -> Duration {
+        self.rtt.rto().max(MIN_RTO)
+    }
 }
 
 impl Default for ReliableUnorderedSender {
@@ -50,74 +219,93 @@ impl Default for ReliableUnorderedSender {
 }
 
 impl TransportSender for ReliableUnorderedSender {
-    fn send(&mut self, stream_id: u32, data: Bytes) -> Result<Vec<Frame>> {
+    fn send(&mut self, stream_id: u32, data: Bytes, flags: DataFlags) -> Result<Vec<Frame>> {
         let seq = self.next_seq;
         self.next_seq = self.next_seq.wrapping_add(1);
         let frame = Frame::Data {
             stream_id,
             seq,
-            flags: DataFlags::NONE,
+            flags,
             payload: data,
         };
-        self.send_buffer.insert(seq, frame.clone());
+        let now = Instant::now();
+        let rto = self.rto();
+        self.send_buffer.insert(
+            seq,
+            SendBufferEntry {
+                frame: frame.clone(),
+                first_sent: now,
+                last_sent: now,
+                rto,
+                attempts: 0,
+            },
+        );
         Ok(vec![frame])
     }
 
     fn on_ack(&mut self, seq: u32) {
-        self.send_buffer.remove(&seq);
+        if let Some(entry) = self.send_buffer.remove(&seq) {
+            // Karn's algorithm: an ack for a retransmitted frame can't say
+            // which transmission it covers, so only ever-sent-once frames
+            // feed the RTT estimator.
+            if entry.attempts == 0 {
+                self.rtt.update(entry.first_sent.elapsed(), Duration::ZERO);
+            }
+        }
     }
 
-    fn retransmit(&mut self) -> Vec<Frame> {
-        self.send_buffer.values().cloned().collect()
+    /// Return frames whose own backed-off RTO has elapsed since they were
+    /// last sent, bumping their attempt counter and doubling their RTO.
+    ///
+    /// Errs with `MaxRetransmissionsExceeded` on the first due frame that
+    /// would exceed `max_retransmits`, leaving the send buffer untouched for
+    /// that frame (and any not-yet-examined frames), matching
+    /// `ReliableOrderedSender::retransmit`.
+    fn retransmit(&mut self) -> Result<Vec<Frame>> {
+        let now = Instant::now();
+        let due: Vec<u32> = self
+            .send_buffer
+            .iter()
+            .filter(|(_, entry)| now.duration_since(entry.last_sent) >= entry.rto)
+            .map(|(&seq, _)| seq)
+            .collect();
+
+        let mut frames = Vec::with_capacity(due.len());
+        for seq in due {
+            let entry = self.send_buffer.get_mut(&seq).expect("seq just collected");
+            if entry.attempts + 1 > self.max_retransmits {
+                let stream_id = match entry.frame {
+                    Frame::Data { stream_id, .. } => stream_id,
+                    _ => unreachable!("send buffer only ever holds Frame::Data"),
+                };
+                return Err(StrandStreamError::MaxRetransmissionsExceeded(
+                    self.max_retransmits,
+                    stream_id,
+                ));
+            }
+            entry.attempts += 1;
+            entry.last_sent = now;
+            entry.rto *= 2;
+            frames.push(entry.frame.clone());
+        }
+        Ok(frames)
     }
 }
 
 /// Receiving side for Reliable-Unordered streams.
 ///
-/// Delivers frames immediately and uses a `BTreeSet` to ensure exactly-once
-/// delivery (duplicates are silently dropped).
-///
-/// ## Garbage collection
-///
-/// The delivered-set is bounded to prevent unbounded memory growth.  Once it
-/// reaches `DELIVERED_GC_THRESHOLD` entries the oldest `DELIVERED_GC_DISCARD`
-/// sequence numbers are removed.  This means that a very delayed retransmit
-/// whose sequence number has been GC'd will be re-delivered, but only after
-/// at least `DELIVERED_GC_THRESHOLD - DELIVERED_GC_DISCARD` newer packets
-/// have been received — an acceptable trade-off for long-running streams.
+/// Delivers frames immediately, deduplicated via a `ReplayBitmap` so memory
+/// use stays fixed regardless of how long the stream lives.
 pub struct ReliableUnorderedReceiver {
-    /// Set of sequence numbers already delivered (ordered for efficient GC).
-    delivered: BTreeSet<u32>,
+    filter: ReplayBitmap,
 }
 
 impl ReliableUnorderedReceiver {
     pub fn new() -> Self {
         Self {
-            delivered: BTreeSet::new(),
+            filter: ReplayBitmap::new(),
         }
     }
-
-    /// Remove the oldest `DELIVERED_GC_DISCARD` entries from the delivered set.
-    ///
-    /// Called automatically when the set size hits `DELIVERED_GC_THRESHOLD`.
-    fn gc(&mut self) {
-        // Collect the lowest DELIVERED_GC_DISCARD keys.
-        let to_remove: Vec<u32> = self
-            .delivered
-            .iter()
-            .copied()
-            .take(DELIVERED_GC_DISCARD)
-            .collect();
-        for seq in to_remove {
-            self.delivered.remove(&seq);
-        }
-    }
-
-    /// Returns the number of sequence numbers currently tracked.
-    #[cfg(test)]
-    pub fn delivered_count(&self) -> usize {
-        self.delivered.len()
-    }
 }
 
 impl Default for ReliableUnorderedReceiver {
@@ -130,15 +318,10 @@ impl TransportReceiver for ReliableUnorderedReceiver {
     fn receive(&mut self, frame: &Frame) -> Result<Vec<Bytes>> {
         match frame {
             Frame::Data { seq, payload, .. } => {
-                // Deduplicate: only deliver if not already seen.
-                if self.delivered.insert(*seq) {
-                    // Run GC if the set has grown too large.
-                    if self.delivered.len() >= DELIVERED_GC_THRESHOLD {
-                        self.gc();
-                    }
+                if self.filter.check_and_mark(*seq) {
                     Ok(vec![payload.clone()])
                 } else {
-                    Ok(vec![]) // duplicate, drop silently
+                    Ok(vec![]) // duplicate or too old, drop silently
                 }
             }
             _ => Err(StrandStreamError::Internal(
@@ -151,14 +334,15 @@ impl TransportReceiver for ReliableUnorderedReceiver {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::thread::sleep;
 
     #[test]
     fn immediate_delivery() {
         let mut sender = ReliableUnorderedSender::new();
         let mut receiver = ReliableUnorderedReceiver::new();
 
-        let f1 = sender.send(1, Bytes::from_static(b"B")).unwrap();
-        let f0 = sender.send(1, Bytes::from_static(b"A")).unwrap();
+        let f1 = sender.send(1, Bytes::from_static(b"B"), DataFlags::NONE).unwrap();
+        let f0 = sender.send(1, Bytes::from_static(b"A"), DataFlags::NONE).unwrap();
 
         // Deliver f1 first -- should arrive immediately.
         let d = receiver.receive(&f1[0]).unwrap();
@@ -175,7 +359,7 @@ mod tests {
         let mut sender = ReliableUnorderedSender::new();
         let mut receiver = ReliableUnorderedReceiver::new();
 
-        let f = sender.send(1, Bytes::from_static(b"X")).unwrap();
+        let f = sender.send(1, Bytes::from_static(b"X"), DataFlags::NONE).unwrap();
         let d = receiver.receive(&f[0]).unwrap();
         assert_eq!(d.len(), 1);
 
@@ -185,56 +369,157 @@ mod tests {
     }
 
     #[test]
-    fn gc_bounds_delivered_set() {
-        let mut receiver = ReliableUnorderedReceiver::new();
+    fn retransmit_withholds_frames_before_rto_elapses() {
+        let mut sender = ReliableUnorderedSender::new();
+        sender.send(1, Bytes::from_static(b"A"), DataFlags::NONE).unwrap();
+        // No RTT sample yet, so rto() is the MIN_RTO floor -- far longer
+        // than the microseconds elapsed since `send`.
+        assert!(sender.retransmit().unwrap().is_empty());
+    }
 
-        // Deliver DELIVERED_GC_THRESHOLD + 1 unique frames.
-        // After the (THRESHOLD)th insertion the GC should run, removing
-        // DELIVERED_GC_DISCARD entries.
-        let limit = DELIVERED_GC_THRESHOLD + 1;
-        for seq in 0..limit as u32 {
-            let frame = Frame::Data {
-                stream_id: 1,
-                seq,
-                flags: crate::frame::DataFlags::NONE,
-                payload: Bytes::from_static(b"x"),
-            };
-            let d = receiver.receive(&frame).unwrap();
-            assert_eq!(d.len(), 1, "frame {seq} should be delivered once");
+    #[test]
+    fn retransmit_returns_only_overdue_frames() {
+        let mut sender = ReliableUnorderedSender::new();
+        sender.send(1, Bytes::from_static(b"A"), DataFlags::NONE).unwrap();
+        sleep(sender.rto() + Duration::from_millis(5));
+        sender.send(1, Bytes::from_static(b"B"), DataFlags::NONE).unwrap();
+
+        // Only the first frame is overdue -- the second was just sent, so a
+        // naive "resend everything" sender would wrongly include it too.
+        let frames = sender.retransmit().unwrap();
+        assert_eq!(frames.len(), 1);
+        match &frames[0] {
+            Frame::Data { seq, .. } => assert_eq!(*seq, 0),
+            other => panic!("expected Frame::Data, got {other:?}"),
         }
+    }
 
-        // After GC the set must be smaller than DELIVERED_GC_THRESHOLD.
-        assert!(
-            receiver.delivered_count() < DELIVERED_GC_THRESHOLD,
-            "delivered set should be bounded after GC, got {}",
-            receiver.delivered_count()
-        );
+    #[test]
+    fn retransmit_backs_off_exponentially_per_frame() {
+        let mut sender = ReliableUnorderedSender::new();
+        sender.send(1, Bytes::from_static(b"A"), DataFlags::NONE).unwrap();
+        let base_rto = sender.rto();
+
+        sleep(base_rto + Duration::from_millis(5));
+        assert_eq!(sender.retransmit().unwrap().len(), 1);
+
+        // The frame's own RTO just doubled, so it isn't due again after
+        // only the original wait.
+        sleep(base_rto + Duration::from_millis(5));
+        assert!(sender.retransmit().unwrap().is_empty());
+
+        sleep(base_rto + Duration::from_millis(10));
+        assert_eq!(sender.retransmit().unwrap().len(), 1);
     }
 
     #[test]
-    fn gc_does_not_drop_recent_duplicates() {
-        let mut receiver = ReliableUnorderedReceiver::new();
+    fn retransmit_gives_up_after_max_retransmits() {
+        let mut sender = ReliableUnorderedSender::with_max_retransmits(2);
+        sender.send(7, Bytes::from_static(b"A"), DataFlags::NONE).unwrap();
+
+        let mut wait = sender.rto();
+        for _ in 0..2 {
+            sleep(wait + Duration::from_millis(5));
+            assert_eq!(sender.retransmit().unwrap().len(), 1);
+            wait *= 2;
+        }
 
-        // Fill past the GC threshold so that GC runs.
-        for seq in 0..DELIVERED_GC_THRESHOLD as u32 {
-            let frame = Frame::Data {
-                stream_id: 1,
-                seq,
-                flags: crate::frame::DataFlags::NONE,
-                payload: Bytes::from_static(b"x"),
-            };
-            receiver.receive(&frame).unwrap();
+        sleep(wait + Duration::from_millis(5));
+        let err = sender.retransmit().unwrap_err();
+        match err {
+            StrandStreamError::MaxRetransmissionsExceeded(limit, stream_id) => {
+                assert_eq!(limit, 2);
+                assert_eq!(stream_id, 7);
+            }
+            other => panic!("expected MaxRetransmissionsExceeded, got {other:?}"),
         }
+    }
+
+    #[test]
+    fn on_ack_feeds_rtt_estimate() {
+        let mut sender = ReliableUnorderedSender::new();
+        sender.send(1, Bytes::from_static(b"A"), DataFlags::NONE).unwrap();
+        sleep(Duration::from_millis(10));
+        sender.on_ack(0);
+        assert!(sender.rto() > MIN_RTO);
+    }
 
-        // The highest sequence numbers (most recent) should still be tracked.
-        let high_seq = (DELIVERED_GC_THRESHOLD - 1) as u32;
-        let dup_frame = Frame::Data {
+    #[test]
+    fn karns_algorithm_ignores_rtt_sample_from_retransmitted_frame() {
+        let mut sender = ReliableUnorderedSender::new();
+        sender.send(1, Bytes::from_static(b"A"), DataFlags::NONE).unwrap();
+        sleep(sender.rto() + Duration::from_millis(5));
+        assert_eq!(sender.retransmit().unwrap().len(), 1);
+
+        // This ack is ambiguous about which transmission it covers, so it
+        // must not feed the RTT estimator.
+        sleep(Duration::from_millis(5));
+        sender.on_ack(0);
+        assert_eq!(sender.rto(), MIN_RTO);
+    }
+
+    fn data(seq: u32) -> Frame {
+        Frame::Data {
             stream_id: 1,
-            seq: high_seq,
+            seq,
             flags: crate::frame::DataFlags::NONE,
-            payload: Bytes::from_static(b"dup"),
-        };
-        let d = receiver.receive(&dup_frame).unwrap();
-        assert!(d.is_empty(), "recent duplicate must still be deduplicated");
+            payload: Bytes::from_static(b"x"),
+        }
+    }
+
+    #[test]
+    fn memory_stays_bounded_across_a_very_long_stream() {
+        let mut receiver = ReliableUnorderedReceiver::new();
+
+        // Deliver far more unique frames than the window could ever hold;
+        // a BTreeSet-based dedup would grow without bound here, but the
+        // bitmap's memory footprint is fixed regardless.
+        for seq in 0..10_000u32 {
+            let d = receiver.receive(&data(seq)).unwrap();
+            assert_eq!(d.len(), 1, "frame {seq} should be delivered once");
+        }
+
+        // A duplicate of the most recent frame is still caught.
+        assert!(receiver.receive(&data(9_999)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn retransmit_outside_the_window_is_dropped_as_too_old() {
+        let mut receiver = ReliableUnorderedReceiver::new();
+        receiver.receive(&data(0)).unwrap();
+        // Jump far enough ahead that seq 0 falls outside the window.
+        receiver.receive(&data(10_000)).unwrap();
+
+        // A very late retransmit of seq 0 is now too old, not a fresh frame.
+        assert!(receiver.receive(&data(0)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn reordered_frame_inside_window_still_delivered() {
+        let mut receiver = ReliableUnorderedReceiver::new();
+        assert_eq!(receiver.receive(&data(10)).unwrap().len(), 1);
+        // Lower seq, but still within the window -- accepted once.
+        assert_eq!(receiver.receive(&data(8)).unwrap().len(), 1);
+        assert!(receiver.receive(&data(8)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn large_forward_jump_clears_the_whole_window() {
+        let mut receiver = ReliableUnorderedReceiver::new();
+        receiver.receive(&data(10)).unwrap();
+        // Jump past the window width in one step.
+        assert_eq!(receiver.receive(&data(100_000)).unwrap().len(), 1);
+        // The new high-water mark's own neighbourhood still works.
+        assert_eq!(receiver.receive(&data(99_999)).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn u32_wraparound_is_handled() {
+        let mut receiver = ReliableUnorderedReceiver::new();
+        receiver.receive(&data(u32::MAX - 1)).unwrap();
+        // Wraps past u32::MAX back to 0 -- still a forward step of 2.
+        assert_eq!(receiver.receive(&data(0)).unwrap().len(), 1);
+        // The pre-wrap sequence is still tracked and rejects a duplicate.
+        assert!(receiver.receive(&data(u32::MAX - 1)).unwrap().is_empty());
     }
 }
@@ -0,0 +1,110 @@
+//! Opt-in sliding-window anti-replay wrapper for `TransportReceiver`s.
+//!
+//! `BestEffortReceiver` delivers every frame with no deduplication at all,
+//! and `ReliableUnorderedReceiver` dedups with its own fixed 2048-sequence
+//! bitmap. This wraps either one in the same `ReplayWindow` bitmap used by
+//! the record layer
+//! (`crate::replay`), rejecting stale or duplicate sequence numbers in O(1)
+//! with no buffering, at the cost of the bounded false-negative window a
+//! fixed-size bitmap implies.
+
+use bytes::Bytes;
+
+use crate::error::Result;
+use crate::frame::Frame;
+use crate::replay::{ReplayWindow, DEFAULT_WINDOW_WIDTH};
+use crate::transport::TransportReceiver;
+
+/// Wraps a `TransportReceiver` with a `ReplayWindow` consulted before every
+/// frame is handed to the inner receiver.
+///
+/// A `seq` rejected by the window (too old, or already seen) is dropped
+/// silently -- `receive` returns `Ok(vec![])` -- matching the wrapped modes'
+/// own convention of swallowing duplicates rather than erroring.
+pub struct ReplayFilteredReceiver<R> {
+    inner: R,
+    window: ReplayWindow,
+}
+
+impl<R: TransportReceiver> ReplayFilteredReceiver<R> {
+    /// Wrap `inner` with a replay window of `DEFAULT_WINDOW_WIDTH`.
+    pub fn new(inner: R) -> Self {
+        Self::with_window_width(inner, DEFAULT_WINDOW_WIDTH)
+    }
+
+    /// Wrap `inner` with an explicit window width (clamped to `1..=64`; see
+    /// `ReplayWindow::new`).
+    pub fn with_window_width(inner: R, window_width: u32) -> Self {
+        Self {
+            inner,
+            window: ReplayWindow::new(window_width),
+        }
+    }
+}
+
+impl<R: TransportReceiver> TransportReceiver for ReplayFilteredReceiver<R> {
+    fn receive(&mut self, frame: &Frame) -> Result<Vec<Bytes>> {
+        if let Frame::Data { seq, .. } = frame {
+            if self.window.check_and_update(*seq).is_err() {
+                return Ok(vec![]);
+            }
+        }
+        self.inner.receive(frame)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frame::DataFlags;
+    use crate::transport::best_effort::BestEffortReceiver;
+    use crate::transport::reliable_unordered::ReliableUnorderedReceiver;
+
+    fn data(seq: u32) -> Frame {
+        Frame::Data {
+            stream_id: 1,
+            seq,
+            flags: DataFlags::NONE,
+            payload: Bytes::from_static(b"x"),
+        }
+    }
+
+    #[test]
+    fn best_effort_duplicate_dropped() {
+        let mut r = ReplayFilteredReceiver::new(BestEffortReceiver::new());
+        assert_eq!(r.receive(&data(5)).unwrap().len(), 1);
+        assert!(r.receive(&data(5)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn best_effort_stale_retransmit_dropped() {
+        let mut r = ReplayFilteredReceiver::with_window_width(BestEffortReceiver::new(), 8);
+        r.receive(&data(100)).unwrap();
+        // age 100 - 91 = 9 >= width 8
+        assert!(r.receive(&data(91)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn best_effort_in_order_frames_still_delivered() {
+        let mut r = ReplayFilteredReceiver::new(BestEffortReceiver::new());
+        for seq in 0..10 {
+            assert_eq!(r.receive(&data(seq)).unwrap().len(), 1);
+        }
+    }
+
+    #[test]
+    fn reliable_unordered_duplicate_dropped_without_buffering() {
+        let mut r = ReplayFilteredReceiver::new(ReliableUnorderedReceiver::new());
+        let f = data(42);
+        assert_eq!(r.receive(&f).unwrap().len(), 1);
+        assert!(r.receive(&f).unwrap().is_empty());
+    }
+
+    #[test]
+    fn reliable_unordered_reordered_frame_inside_window_accepted_once() {
+        let mut r = ReplayFilteredReceiver::new(ReliableUnorderedReceiver::new());
+        assert_eq!(r.receive(&data(10)).unwrap().len(), 1);
+        assert_eq!(r.receive(&data(8)).unwrap().len(), 1);
+        assert!(r.receive(&data(8)).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,186 @@
+//! Sequenced transport mode -- unreliable, but monotonic.
+//!
+//! No retransmission and no buffering: the receiver tracks only the highest
+//! sequence number seen and silently discards any frame that is not newer,
+//! delivering every frame that advances the high-water mark. This is the
+//! classic "only the latest matters" mode for telemetry or position updates,
+//! where a stale update arriving late is worse than no update at all.
+
+use bytes::Bytes;
+
+use crate::error::{Result, StrandStreamError};
+use crate::frame::{DataFlags, Frame};
+use crate::transport::{TransportReceiver, TransportSender};
+
+/// Sending side for Sequenced streams.
+///
+/// Assigns monotonically increasing sequence numbers (wrapping at u32::MAX)
+/// so the receiver can tell newer frames from stale ones; like Best-Effort,
+/// there is no retransmission or acknowledgement tracking.
+pub struct SequencedSender {
+    next_seq: u32,
+}
+
+impl SequencedSender {
+    pub fn new() -> Self {
+        Self { next_seq: 0 }
+    }
+}
+
+impl Default for SequencedSender {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TransportSender for SequencedSender {
+    fn send(&mut self, stream_id: u32, data: Bytes, flags: DataFlags) -> Result<Vec<Frame>> {
+        let seq = self.next_seq;
+        self.next_seq = self.next_seq.wrapping_add(1);
+        Ok(vec![Frame::Data {
+            stream_id,
+            seq,
+            flags,
+            payload: data,
+        }])
+    }
+
+    fn on_ack(&mut self, _seq: u32) {
+        // No-op: sequenced delivery does not track acknowledgements.
+    }
+
+    fn retransmit(&mut self) -> Result<Vec<Frame>> {
+        // No retransmission -- a stale retransmit would just be discarded
+        // by the receiver anyway.
+        Ok(Vec::new())
+    }
+}
+
+/// Receiving side for Sequenced streams.
+///
+/// Delivers a frame only if its `seq` is strictly newer than the highest
+/// seen so far; older or duplicate frames are silently discarded. Unlike
+/// `ReliableUnorderedReceiver`, this needs no growing dedup set -- a single
+/// high-water mark is enough, at the cost of discarding every frame of a
+/// reordered burst that arrives after a newer one.
+pub struct SequencedReceiver {
+    highest_seq: Option<u32>,
+}
+
+impl SequencedReceiver {
+    pub fn new() -> Self {
+        Self { highest_seq: None }
+    }
+
+    /// The highest sequence number delivered so far, if any.
+    pub fn highest_seq(&self) -> Option<u32> {
+        self.highest_seq
+    }
+}
+
+impl Default for SequencedReceiver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TransportReceiver for SequencedReceiver {
+    fn receive(&mut self, frame: &Frame) -> Result<Vec<Bytes>> {
+        match frame {
+            Frame::Data { seq, payload, .. } => {
+                let is_newer = match self.highest_seq {
+                    Some(highest) => *seq > highest,
+                    None => true,
+                };
+                if !is_newer {
+                    return Ok(Vec::new()); // stale or duplicate, discard
+                }
+                self.highest_seq = Some(*seq);
+                Ok(vec![payload.clone()])
+            }
+            _ => Err(StrandStreamError::Internal(
+                "SequencedReceiver received non-data frame".into(),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn in_order_frames_all_delivered() {
+        let mut sender = SequencedSender::new();
+        let mut receiver = SequencedReceiver::new();
+
+        for _ in 0..5 {
+            let f = sender.send(1, Bytes::from_static(b"x"), DataFlags::NONE).unwrap();
+            let d = receiver.receive(&f[0]).unwrap();
+            assert_eq!(d.len(), 1);
+        }
+    }
+
+    #[test]
+    fn stale_frame_discarded() {
+        let mut receiver = SequencedReceiver::new();
+
+        let newer = Frame::Data {
+            stream_id: 1,
+            seq: 10,
+            flags: DataFlags::NONE,
+            payload: Bytes::from_static(b"new"),
+        };
+        let older = Frame::Data {
+            stream_id: 1,
+            seq: 5,
+            flags: DataFlags::NONE,
+            payload: Bytes::from_static(b"old"),
+        };
+
+        assert_eq!(receiver.receive(&newer).unwrap().len(), 1);
+        assert!(receiver.receive(&older).unwrap().is_empty());
+        assert_eq!(receiver.highest_seq(), Some(10));
+    }
+
+    #[test]
+    fn duplicate_seq_discarded() {
+        let mut receiver = SequencedReceiver::new();
+        let frame = Frame::Data {
+            stream_id: 1,
+            seq: 7,
+            flags: DataFlags::NONE,
+            payload: Bytes::from_static(b"x"),
+        };
+        assert_eq!(receiver.receive(&frame).unwrap().len(), 1);
+        assert!(receiver.receive(&frame).unwrap().is_empty());
+    }
+
+    #[test]
+    fn reordered_burst_delivers_only_frames_newer_than_all_before() {
+        let mut receiver = SequencedReceiver::new();
+        let delivered: Vec<bool> = [3u32, 1, 4, 2]
+            .iter()
+            .map(|&seq| {
+                let frame = Frame::Data {
+                    stream_id: 1,
+                    seq,
+                    flags: DataFlags::NONE,
+                    payload: Bytes::from_static(b"x"),
+                };
+                !receiver.receive(&frame).unwrap().is_empty()
+            })
+            .collect();
+
+        // seq 3 delivered, 1 discarded (stale), 4 delivered, 2 discarded (stale).
+        assert_eq!(delivered, vec![true, false, true, false]);
+        assert_eq!(receiver.highest_seq(), Some(4));
+    }
+
+    #[test]
+    fn no_retransmission() {
+        let mut sender = SequencedSender::new();
+        sender.send(1, Bytes::from_static(b"x"), DataFlags::NONE).unwrap();
+        assert!(sender.retransmit().unwrap().is_empty());
+    }
+}
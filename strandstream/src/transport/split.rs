@@ -0,0 +1,184 @@
+//! Splitting a transport endpoint into independently-ownable send/receive halves.
+//!
+//! A `TransportSender`/`TransportReceiver` pair is normally driven together
+//! from one place (see `crate::stream::Stream`), which blocks running a
+//! dedicated send task and a dedicated receive task concurrently without
+//! wrapping the whole endpoint in a lock. `split()` instead hands each side
+//! its own owned handle that can move to a separate thread, with the only
+//! cross-talk being inbound ACK feedback: `RecvHalf` intercepts `Frame::Ack`
+//! and forwards the acknowledged sequence numbers to `SendHalf` over an mpsc
+//! channel instead of calling `on_ack` directly, so neither half ever
+//! touches the other's state.
+
+use std::sync::mpsc::{self, Receiver, Sender};
+
+use bytes::Bytes;
+
+use crate::error::Result;
+use crate::frame::{DataFlags, Frame};
+use crate::transport::{TransportReceiver, TransportSender};
+
+/// The independently-ownable send half of a transport endpoint produced by
+/// [`split`]. Owns the mode-specific [`TransportSender`] plus the receiving
+/// end of the ack-feedback channel.
+pub struct SendHalf<S: TransportSender> {
+    sender: S,
+    acks: Receiver<u32>,
+}
+
+impl<S: TransportSender> SendHalf<S> {
+    /// Enqueue data for sending, first applying any ack feedback that has
+    /// arrived from the paired [`RecvHalf`] so admission/retransmit state is
+    /// current.
+    pub fn send(&mut self, stream_id: u32, data: Bytes, flags: DataFlags) -> Result<Vec<Frame>> {
+        self.drain_acks();
+        self.sender.send(stream_id, data, flags)
+    }
+
+    /// Apply a single ack directly, bypassing the channel -- for a caller
+    /// that already has the seq number in hand (e.g. tests, or one that
+    /// chose not to split the receive side).
+    pub fn on_ack(&mut self, seq: u32) {
+        self.sender.on_ack(seq);
+    }
+
+    /// Retrieve any frames needing retransmission, after applying pending acks.
+    pub fn retransmit(&mut self) -> Result<Vec<Frame>> {
+        self.drain_acks();
+        self.sender.retransmit()
+    }
+
+    /// Forward an updated congestion window hint to the sender.
+    pub fn set_cwnd_hint(&mut self, cwnd: usize) {
+        self.sender.set_cwnd_hint(cwnd);
+    }
+
+    /// Apply every ack that has arrived from the paired `RecvHalf` since the
+    /// last call, without otherwise touching the sender. Send/retransmit
+    /// already do this implicitly; this is for a send loop that wants acks
+    /// applied promptly even between sends (e.g. to keep `set_cwnd_hint`
+    /// callers current).
+    pub fn poll_acks(&mut self) {
+        self.drain_acks();
+    }
+
+    fn drain_acks(&mut self) {
+        while let Ok(seq) = self.acks.try_recv() {
+            self.sender.on_ack(seq);
+        }
+    }
+
+    /// Recover the underlying sender, discarding the ack-feedback link.
+    pub fn into_inner(self) -> S {
+        self.sender
+    }
+}
+
+/// The independently-ownable receive half of a transport endpoint produced
+/// by [`split`]. Owns the mode-specific [`TransportReceiver`] plus the
+/// sending end of the ack-feedback channel.
+pub struct RecvHalf<R: TransportReceiver> {
+    receiver: R,
+    acks: Sender<u32>,
+}
+
+impl<R: TransportReceiver> RecvHalf<R> {
+    /// Process an inbound frame.
+    ///
+    /// `Frame::Ack` is intercepted here rather than handed to the inner
+    /// receiver: every sequence number covered by its ranges is forwarded to
+    /// the paired `SendHalf` over the ack-feedback channel, and an empty
+    /// payload list is returned, since an ACK never carries application
+    /// data. Every other frame is delegated to the inner `TransportReceiver`
+    /// unchanged.
+    pub fn receive(&mut self, frame: &Frame) -> Result<Vec<Bytes>> {
+        if let Frame::Ack { ranges, .. } = frame {
+            for range in ranges {
+                for seq in range.start..=range.end {
+                    // The paired `SendHalf` may already have been dropped
+                    // (e.g. its task exited); there's no one left to notify.
+                    let _ = self.acks.send(seq);
+                }
+            }
+            return Ok(Vec::new());
+        }
+        self.receiver.receive(frame)
+    }
+
+    /// Recover the underlying receiver, discarding the ack-feedback link.
+    pub fn into_inner(self) -> R {
+        self.receiver
+    }
+}
+
+/// Split a transport endpoint's sender and receiver into independently-
+/// ownable halves that can be moved to separate threads/tasks -- e.g. a
+/// dedicated send loop and a dedicated receive loop run concurrently instead
+/// of serialized behind one lock.
+///
+/// The only state shared between the halves is ACK feedback: `RecvHalf`
+/// forwards every acknowledged sequence number it observes to `SendHalf`
+/// over a channel (see [`RecvHalf::receive`]), which applies them to the
+/// sender lazily on its next `send`/`retransmit` call (or immediately via
+/// [`SendHalf::poll_acks`]). There is no shared lock.
+pub fn split<S: TransportSender, R: TransportReceiver>(
+    sender: S,
+    receiver: R,
+) -> (SendHalf<S>, RecvHalf<R>) {
+    let (tx, rx) = mpsc::channel();
+    (SendHalf { sender, acks: rx }, RecvHalf { receiver, acks: tx })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frame::SeqRange;
+    use crate::transport::reliable_unordered::{ReliableUnorderedReceiver, ReliableUnorderedSender};
+
+    #[test]
+    fn halves_can_move_to_separate_threads() {
+        let (mut send_half, mut recv_half) =
+            split(ReliableUnorderedSender::new(), ReliableUnorderedReceiver::new());
+
+        let sender_thread = std::thread::spawn(move || {
+            send_half.send(1, Bytes::from_static(b"hello"), DataFlags::NONE).unwrap()
+        });
+        let receiver_thread = std::thread::spawn(move || {
+            let frame = Frame::Data {
+                stream_id: 1,
+                seq: 0,
+                flags: DataFlags::NONE,
+                payload: Bytes::from_static(b"world"),
+            };
+            recv_half.receive(&frame).unwrap()
+        });
+
+        let sent = sender_thread.join().unwrap();
+        let received = receiver_thread.join().unwrap();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(received, vec![Bytes::from_static(b"world")]);
+    }
+
+    #[test]
+    fn ack_frame_forwards_seq_numbers_to_send_half_instead_of_receiver() {
+        let (mut send_half, mut recv_half) =
+            split(ReliableUnorderedSender::new(), ReliableUnorderedReceiver::new());
+
+        send_half.send(1, Bytes::from_static(b"a"), DataFlags::NONE).unwrap();
+        send_half.send(1, Bytes::from_static(b"b"), DataFlags::NONE).unwrap();
+        assert_eq!(send_half.sender.in_flight(), 2);
+
+        let ack = Frame::Ack {
+            stream_id: 1,
+            ack_seq: 1,
+            ranges: vec![SeqRange { start: 0, end: 1 }],
+        };
+        let payloads = recv_half.receive(&ack).unwrap();
+        assert!(payloads.is_empty(), "an ACK carries no application data");
+
+        // The ack sits in the channel, unapplied, until the send half polls.
+        assert_eq!(send_half.sender.in_flight(), 2);
+        send_half.poll_acks();
+        assert_eq!(send_half.sender.in_flight(), 0);
+    }
+}
@@ -0,0 +1,138 @@
+//! QUIC-style variable-length integer codec (RFC 9000 section 16).
+//!
+//! The two most-significant bits of the first byte select the encoded
+//! length: `00` = 1 byte (6-bit value, max 63), `01` = 2 bytes (14-bit, max
+//! 16383), `10` = 4 bytes (30-bit, max ~1.07e9), `11` = 8 bytes (62-bit, max
+//! 2^62 - 1). The remaining bits of the first byte, plus any following
+//! bytes, hold the big-endian value. Used by [`crate::frame::Frame`]'s
+//! [`crate::frame::WireVersion::V2Varint`] encoding for every length/ID
+//! field, since most stream IDs and payload lengths in a multiplexed
+//! protocol are small enough to fit in one or two bytes instead of a fixed
+//! 4 or 8.
+
+use bytes::{Buf, BufMut, BytesMut};
+
+use crate::error::{Result, StrandStreamError};
+
+/// Largest value representable by this encoding: 2^62 - 1.
+pub const MAX_VARINT: u64 = (1 << 62) - 1;
+
+/// Encode `value` as a QUIC-style varint into `buf`.
+///
+/// Panics if `value` exceeds [`MAX_VARINT`] -- every field this is used for
+/// (stream/sequence IDs, frame/payload lengths) fits comfortably within
+/// 2^62, so a larger value indicates a caller bug rather than a recoverable
+/// wire-format condition.
+pub fn put_varint(buf: &mut BytesMut, value: u64) {
+    if value <= 0x3f {
+        buf.put_u8(value as u8);
+    } else if value <= 0x3fff {
+        buf.put_u16(0x4000 | value as u16);
+    } else if value <= 0x3fff_ffff {
+        buf.put_u32(0x8000_0000 | value as u32);
+    } else if value <= MAX_VARINT {
+        buf.put_u64(0xC000_0000_0000_0000 | value);
+    } else {
+        panic!("varint value {value} exceeds MAX_VARINT ({MAX_VARINT})");
+    }
+}
+
+/// The number of bytes [`put_varint`] would write for `value`.
+pub fn varint_len(value: u64) -> usize {
+    if value <= 0x3f {
+        1
+    } else if value <= 0x3fff {
+        2
+    } else if value <= 0x3fff_ffff {
+        4
+    } else {
+        8
+    }
+}
+
+/// Decode a QUIC-style varint from the front of `data`, advancing `data`
+/// past the bytes consumed.
+pub fn get_varint(data: &mut &[u8]) -> Result<u64> {
+    if data.is_empty() {
+        return Err(StrandStreamError::FrameTooShort {
+            expected: 1,
+            actual: 0,
+        });
+    }
+    let len = 1usize << (data[0] >> 6);
+    if data.len() < len {
+        return Err(StrandStreamError::FrameTooShort {
+            expected: len,
+            actual: data.len(),
+        });
+    }
+    let value = match len {
+        1 => (data.get_u8() & 0x3f) as u64,
+        2 => (data.get_u16() & 0x3fff) as u64,
+        4 => (data.get_u32() & 0x3fff_ffff) as u64,
+        8 => data.get_u64() & 0x3fff_ffff_ffff_ffff,
+        _ => unreachable!("1 << (u8 >> 6) is always 1, 2, 4, or 8"),
+    };
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip(value: u64) {
+        let mut buf = BytesMut::new();
+        put_varint(&mut buf, value);
+        assert_eq!(buf.len(), varint_len(value));
+        let mut slice = &buf[..];
+        assert_eq!(get_varint(&mut slice).unwrap(), value);
+        assert!(slice.is_empty());
+    }
+
+    #[test]
+    fn roundtrips_one_byte_boundary() {
+        roundtrip(0);
+        roundtrip(0x3f);
+    }
+
+    #[test]
+    fn roundtrips_two_byte_boundary() {
+        roundtrip(0x40);
+        roundtrip(0x3fff);
+    }
+
+    #[test]
+    fn roundtrips_four_byte_boundary() {
+        roundtrip(0x4000);
+        roundtrip(0x3fff_ffff);
+    }
+
+    #[test]
+    fn roundtrips_eight_byte_boundary() {
+        roundtrip(0x4000_0000);
+        roundtrip(MAX_VARINT);
+    }
+
+    #[test]
+    #[should_panic]
+    fn encoding_above_max_varint_panics() {
+        let mut buf = BytesMut::new();
+        put_varint(&mut buf, MAX_VARINT + 1);
+    }
+
+    #[test]
+    fn decode_errs_on_truncated_input() {
+        // A two-byte-prefixed value with only one byte available.
+        let mut buf = BytesMut::new();
+        put_varint(&mut buf, 0x3fff);
+        let truncated = &buf[..1];
+        let mut slice = truncated;
+        assert!(get_varint(&mut slice).is_err());
+    }
+
+    #[test]
+    fn decode_errs_on_empty_input() {
+        let mut slice: &[u8] = &[];
+        assert!(get_varint(&mut slice).is_err());
+    }
+}
@@ -1,7 +1,7 @@
 //! Frame encode/decode round-trip tests.
 
 use bytes::Bytes;
-use strandstream::frame::{DataFlags, Frame, FrameType, SeqRange};
+use strandstream::frame::{DataFlags, Frame, FrameType, SeqRange, WireVersion};
 
 #[test]
 fn data_frame_roundtrip() {
@@ -99,6 +99,7 @@ fn rst_frame_roundtrip() {
     let frame = Frame::Rst {
         stream_id: 12,
         error_code: 0xDEAD,
+        final_size: 4096,
     };
     let encoded = frame.encode();
     let decoded = Frame::decode(&encoded).unwrap();
@@ -131,6 +132,18 @@ fn window_update_roundtrip() {
     assert_eq!(frame, decoded);
 }
 
+#[test]
+fn datagram_roundtrip() {
+    let frame = Frame::Datagram {
+        flags: DataFlags::NONE,
+        payload: Bytes::from_static(b"fire and forget"),
+    };
+    let encoded = frame.encode();
+    let decoded = Frame::decode(&encoded).unwrap();
+    assert_eq!(frame, decoded);
+    assert_eq!(encoded[0], 0x09);
+}
+
 #[test]
 fn frame_type_discriminant() {
     assert_eq!(
@@ -196,6 +209,7 @@ fn encoded_len_matches_encode() {
         Frame::Rst {
             stream_id: 1,
             error_code: 0,
+            final_size: 0,
         },
         Frame::Ping { ping_id: 42 },
         Frame::Pong { ping_id: 42 },
@@ -278,6 +292,79 @@ fn stream_reset_roundtrip() {
     assert_eq!(encoded[0], 0x13);
 }
 
+#[test]
+fn stream_data_blocked_roundtrip() {
+    let frame = Frame::StreamDataBlocked {
+        stream_id: 9,
+        limit: 65_536,
+    };
+    let encoded = frame.encode();
+    let decoded = Frame::decode(&encoded).unwrap();
+    assert_eq!(frame, decoded);
+    assert_eq!(encoded[0], 0x14);
+}
+
+#[test]
+fn data_blocked_roundtrip() {
+    let frame = Frame::DataBlocked { limit: 1_048_576 };
+    let encoded = frame.encode();
+    let decoded = Frame::decode(&encoded).unwrap();
+    assert_eq!(frame, decoded);
+    assert_eq!(encoded[0], 0x15);
+}
+
+#[test]
+fn streams_blocked_roundtrip() {
+    let frame = Frame::StreamsBlocked { max_streams: 128 };
+    let encoded = frame.encode();
+    let decoded = Frame::decode(&encoded).unwrap();
+    assert_eq!(frame, decoded);
+    assert_eq!(encoded[0], 0x16);
+}
+
+#[test]
+fn go_away_roundtrip() {
+    let frame = Frame::GoAway {
+        last_stream_id: 41,
+        error_code: 0,
+        debug: Bytes::from_static(b"graceful shutdown"),
+    };
+    let encoded = frame.encode();
+    let decoded = Frame::decode(&encoded).unwrap();
+    assert_eq!(frame, decoded);
+    assert_eq!(encoded[0], 0x17);
+}
+
+#[test]
+fn go_away_debug_blob_truncated_at_cap_on_decode() {
+    use strandstream::frame::MAX_GOAWAY_DEBUG_LEN;
+
+    let oversized = vec![b'x'; MAX_GOAWAY_DEBUG_LEN + 100];
+    let frame = Frame::GoAway {
+        last_stream_id: 1,
+        error_code: 0,
+        debug: Bytes::from(oversized),
+    };
+    let encoded = frame.encode();
+    let decoded = Frame::decode(&encoded).unwrap();
+    match decoded {
+        Frame::GoAway { debug, .. } => assert_eq!(debug.len(), MAX_GOAWAY_DEBUG_LEN),
+        other => panic!("expected GoAway, got {other:?}"),
+    }
+}
+
+#[test]
+fn stop_sending_roundtrip() {
+    let frame = Frame::StopSending {
+        stream_id: 9,
+        error_code: 0x42,
+    };
+    let encoded = frame.encode();
+    let decoded = Frame::decode(&encoded).unwrap();
+    assert_eq!(frame, decoded);
+    assert_eq!(encoded[0], 0x18);
+}
+
 #[test]
 fn congestion_roundtrip() {
     let frame = Frame::Congestion {
@@ -299,4 +386,123 @@ fn control_frame_wire_ids_are_correct() {
     assert_eq!(FrameType::StreamClose as u8, 0x12);
     assert_eq!(FrameType::StreamReset as u8, 0x13);
     assert_eq!(FrameType::Congestion as u8, 0x40);
+    assert_eq!(FrameType::Padding as u8, 0x41);
+}
+
+#[test]
+fn padding_frame_roundtrip() {
+    let frame = Frame::Padding { len: 37 };
+    let encoded = frame.encode();
+    let decoded = Frame::decode(&encoded).unwrap();
+    assert_eq!(frame, decoded);
+    assert_eq!(encoded[0], 0x41);
+    assert_eq!(encoded.len(), frame.encoded_len());
+}
+
+#[test]
+fn padding_frame_filler_is_zeroed() {
+    let frame = Frame::Padding { len: 8 };
+    let encoded = frame.encode();
+    assert_eq!(&encoded[5..], &[0u8; 8]);
+}
+
+#[test]
+fn padding_frame_zero_len_roundtrip() {
+    let frame = Frame::Padding { len: 0 };
+    let encoded = frame.encode();
+    let decoded = Frame::decode(&encoded).unwrap();
+    assert_eq!(frame, decoded);
+}
+
+#[test]
+fn decode_truncated_padding_filler_fails() {
+    // len says 10 bytes of filler follow, but only 2 are present.
+    let mut bytes = vec![0x41];
+    bytes.extend_from_slice(&10u32.to_be_bytes());
+    bytes.extend_from_slice(&[0u8; 2]);
+    assert!(Frame::decode(&bytes).is_err());
+}
+
+#[test]
+fn data_frame_varint_roundtrip() {
+    let frame = Frame::Data {
+        stream_id: 42,
+        seq: 7,
+        flags: DataFlags::FIN,
+        payload: Bytes::from_static(b"hello world"),
+    };
+    let encoded = frame.encode_versioned(WireVersion::V2Varint);
+    assert_eq!(encoded.len(), frame.encoded_len_versioned(WireVersion::V2Varint));
+    let decoded = Frame::decode_versioned(&encoded, WireVersion::V2Varint).unwrap();
+    assert_eq!(frame, decoded);
+}
+
+#[test]
+fn varint_encoding_is_shorter_for_small_ids() {
+    let frame = Frame::Fin { stream_id: 3 };
+    let fixed = frame.encode_versioned(WireVersion::V1Fixed);
+    let varint = frame.encode_versioned(WireVersion::V2Varint);
+    assert!(varint.len() < fixed.len());
+    assert_eq!(
+        Frame::decode_versioned(&varint, WireVersion::V2Varint).unwrap(),
+        frame
+    );
+}
+
+#[test]
+fn ack_frame_varint_roundtrip() {
+    let frame = Frame::Ack {
+        stream_id: 5,
+        ack_seq: 99,
+        ranges: vec![
+            SeqRange { start: 10, end: 20 },
+            SeqRange { start: 30, end: 40 },
+        ],
+    };
+    let encoded = frame.encode_versioned(WireVersion::V2Varint);
+    let decoded = Frame::decode_versioned(&encoded, WireVersion::V2Varint).unwrap();
+    assert_eq!(frame, decoded);
+}
+
+#[test]
+fn ack_frame_varint_with_inflated_range_count_is_rejected_not_oom() {
+    // Ack's frame type byte, then `stream_id(1) + ack_seq(1)`, then a
+    // `range_count` claiming far more ranges than the handful of trailing
+    // bytes could possibly hold. This must fail cleanly (truncated data)
+    // rather than abort the process trying to pre-allocate for the claimed
+    // count.
+    let mut bytes = vec![0x02u8, 0x01u8, 0x01u8];
+    put_varint_for_test(&mut bytes, 0x3fff_ffff);
+    bytes.extend_from_slice(&[0u8; 6]);
+    assert!(Frame::decode_versioned(&bytes, WireVersion::V2Varint).is_err());
+}
+
+fn put_varint_for_test(bytes: &mut Vec<u8>, value: u32) {
+    // Mirrors `varint::put_varint`'s 4-byte encoding for values in the
+    // `0x4000..=0x3fff_ffff` range, without depending on a crate-internal API.
+    bytes.extend_from_slice(&(0x8000_0000u32 | value).to_be_bytes());
+}
+
+#[test]
+fn congestion_frame_varint_roundtrip_with_large_stream_id() {
+    // A stream ID large enough to need the 4-byte varint prefix.
+    let frame = Frame::Congestion {
+        stream_id: 1_000_000,
+        cwnd: 1_073_741_824,
+        rtt_us: 500,
+    };
+    let encoded = frame.encode_versioned(WireVersion::V2Varint);
+    let decoded = Frame::decode_versioned(&encoded, WireVersion::V2Varint).unwrap();
+    assert_eq!(frame, decoded);
+}
+
+#[test]
+fn fixed_and_varint_decode_are_not_interchangeable() {
+    // A V1Fixed-encoded frame should not parse correctly as V2Varint (or may
+    // error outright) -- the two schemes are not self-describing and must
+    // not be mixed without an out-of-band version signal.
+    let frame = Frame::Fin { stream_id: 1 };
+    let fixed = frame.encode_versioned(WireVersion::V1Fixed);
+    let misparsed = Frame::decode_versioned(&fixed, WireVersion::V2Varint);
+    assert!(misparsed.is_err() || misparsed.unwrap() != frame);
 }
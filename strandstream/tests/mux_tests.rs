@@ -1,10 +1,11 @@
 //! Multiplexer tests: multiple streams.
 
 use bytes::Bytes;
-use nexstream::frame::{DataFlags, Frame};
-use nexstream::mux::Multiplexer;
-use nexstream::stream::StreamState;
-use nexstream::transport::TransportMode;
+use strandstream::frame::{DataFlags, Frame};
+use strandstream::mux::Multiplexer;
+use strandstream::padding::{BucketPadding, DistributionPadding};
+use strandstream::stream::StreamState;
+use strandstream::transport::TransportMode;
 
 #[test]
 fn create_multiple_streams() {
@@ -92,6 +93,7 @@ fn rst_removes_stream() {
     let rst = Frame::Rst {
         stream_id: s,
         error_code: 1,
+        final_size: 0,
     };
     mux.poll(&rst).unwrap();
 
@@ -154,6 +156,57 @@ fn close_then_fin_transitions_to_closed() {
     assert_eq!(mux.get_stream(s).unwrap().state(), StreamState::Closed);
 }
 
+#[test]
+fn drain_frames_without_padding_policy_is_unchanged() {
+    let mut mux = Multiplexer::new(100);
+    let s = mux.create_stream(TransportMode::BestEffort).unwrap();
+    mux.send(s, Bytes::from_static(b"hi")).unwrap();
+
+    let frames = mux.drain_frames();
+    assert_eq!(frames.len(), 1);
+    assert!(matches!(frames[0], Frame::Data { .. }));
+}
+
+#[test]
+fn bucket_padding_follows_real_frames_with_padding() {
+    let mut mux = Multiplexer::new(100);
+    mux.set_padding_policy(Some(Box::new(BucketPadding::power_of_two())));
+    let s = mux.create_stream(TransportMode::BestEffort).unwrap();
+    mux.send(s, Bytes::from_static(b"hi")).unwrap();
+
+    let frames = mux.drain_frames();
+    assert_eq!(frames.len(), 2);
+    assert!(matches!(frames[0], Frame::Data { .. }));
+    assert!(matches!(frames[1], Frame::Padding { .. }));
+}
+
+#[test]
+fn bucket_padding_emits_nothing_when_idle() {
+    let mut mux = Multiplexer::new(100);
+    mux.set_padding_policy(Some(Box::new(BucketPadding::power_of_two())));
+    mux.create_stream(TransportMode::BestEffort).unwrap();
+
+    assert!(mux.drain_frames().is_empty());
+}
+
+#[test]
+fn distribution_padding_fills_idle_gaps() {
+    let mut mux = Multiplexer::new(100);
+    mux.set_padding_policy(Some(Box::new(DistributionPadding::new(vec![(64, 1.0)]))));
+    mux.create_stream(TransportMode::BestEffort).unwrap();
+
+    let frames = mux.drain_frames();
+    assert_eq!(frames.len(), 1);
+    assert!(matches!(frames[0], Frame::Padding { len: 64 }));
+}
+
+#[test]
+fn poll_strips_padding_frames() {
+    let mut mux = Multiplexer::new(100);
+    let padding = Frame::Padding { len: 16 };
+    assert!(mux.poll(&padding).is_ok());
+}
+
 #[test]
 fn stream_modes_preserved() {
     let mut mux = Multiplexer::new(100);
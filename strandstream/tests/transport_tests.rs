@@ -1,7 +1,7 @@
 //! Tests for each transport mode's delivery guarantees.
 
 use bytes::Bytes;
-use strandstream::frame::Frame;
+use strandstream::frame::{DataFlags, Frame};
 use strandstream::transport::best_effort::{BestEffortReceiver, BestEffortSender};
 use strandstream::transport::probabilistic::{ProbabilisticReceiver, ProbabilisticSender};
 use strandstream::transport::reliable_ordered::{ReliableOrderedReceiver, ReliableOrderedSender};
@@ -22,7 +22,7 @@ fn ro_in_order_delivery_multiple() {
     let mut frames = Vec::new();
     for i in 0..10 {
         let f = sender
-            .send(1, Bytes::from(format!("msg-{i}")))
+            .send(1, Bytes::from(format!("msg-{i}")), DataFlags::NONE)
             .unwrap();
         frames.push(f.into_iter().next().unwrap());
     }
@@ -40,11 +40,11 @@ fn ro_reorder_delivers_all_at_once() {
     let mut sender = ReliableOrderedSender::new();
     let mut receiver = ReliableOrderedReceiver::new();
 
-    let f0 = sender.send(1, Bytes::from_static(b"0")).unwrap().remove(0);
-    let f1 = sender.send(1, Bytes::from_static(b"1")).unwrap().remove(0);
-    let f2 = sender.send(1, Bytes::from_static(b"2")).unwrap().remove(0);
-    let f3 = sender.send(1, Bytes::from_static(b"3")).unwrap().remove(0);
-    let f4 = sender.send(1, Bytes::from_static(b"4")).unwrap().remove(0);
+    let f0 = sender.send(1, Bytes::from_static(b"0"), DataFlags::NONE).unwrap().remove(0);
+    let f1 = sender.send(1, Bytes::from_static(b"1"), DataFlags::NONE).unwrap().remove(0);
+    let f2 = sender.send(1, Bytes::from_static(b"2"), DataFlags::NONE).unwrap().remove(0);
+    let f3 = sender.send(1, Bytes::from_static(b"3"), DataFlags::NONE).unwrap().remove(0);
+    let f4 = sender.send(1, Bytes::from_static(b"4"), DataFlags::NONE).unwrap().remove(0);
 
     // Deliver: 4, 2, 3, 1, 0
     assert!(receiver.receive(&f4).unwrap().is_empty());
@@ -62,14 +62,20 @@ fn ro_reorder_delivers_all_at_once() {
 #[test]
 fn ro_retransmit_returns_unacked() {
     let mut sender = ReliableOrderedSender::new();
-    sender.send(1, Bytes::from_static(b"a")).unwrap();
-    sender.send(1, Bytes::from_static(b"b")).unwrap();
+    sender.send(1, Bytes::from_static(b"a"), DataFlags::NONE).unwrap();
+    sender.send(1, Bytes::from_static(b"b"), DataFlags::NONE).unwrap();
 
-    let retx = sender.retransmit();
+    // Nothing is due yet -- the RTO hasn't elapsed since `send`.
+    assert!(sender.retransmit().unwrap().is_empty());
+    std::thread::sleep(sender.rto() + std::time::Duration::from_millis(5));
+
+    let retx = sender.retransmit().unwrap();
     assert_eq!(retx.len(), 2);
 
     sender.on_ack(0);
-    let retx = sender.retransmit();
+    // The resend doubled seq 1's own RTO, so wait out twice the base RTO.
+    std::thread::sleep(sender.rto() * 2 + std::time::Duration::from_millis(5));
+    let retx = sender.retransmit().unwrap();
     assert_eq!(retx.len(), 1);
 }
 
@@ -78,7 +84,7 @@ fn ro_duplicate_ignored() {
     let mut sender = ReliableOrderedSender::new();
     let mut receiver = ReliableOrderedReceiver::new();
 
-    let f = sender.send(1, Bytes::from_static(b"X")).unwrap().remove(0);
+    let f = sender.send(1, Bytes::from_static(b"X"), DataFlags::NONE).unwrap().remove(0);
     let d1 = receiver.receive(&f).unwrap();
     assert_eq!(d1.len(), 1);
 
@@ -96,9 +102,9 @@ fn ru_delivers_immediately_regardless_of_order() {
     let mut sender = ReliableUnorderedSender::new();
     let mut receiver = ReliableUnorderedReceiver::new();
 
-    let f0 = sender.send(1, Bytes::from_static(b"A")).unwrap().remove(0);
-    let f1 = sender.send(1, Bytes::from_static(b"B")).unwrap().remove(0);
-    let f2 = sender.send(1, Bytes::from_static(b"C")).unwrap().remove(0);
+    let f0 = sender.send(1, Bytes::from_static(b"A"), DataFlags::NONE).unwrap().remove(0);
+    let f1 = sender.send(1, Bytes::from_static(b"B"), DataFlags::NONE).unwrap().remove(0);
+    let f2 = sender.send(1, Bytes::from_static(b"C"), DataFlags::NONE).unwrap().remove(0);
 
     // Deliver out of order: 2, 0, 1
     let d = receiver.receive(&f2).unwrap();
@@ -119,7 +125,7 @@ fn ru_exactly_once_dedup() {
     let mut sender = ReliableUnorderedSender::new();
     let mut receiver = ReliableUnorderedReceiver::new();
 
-    let f = sender.send(1, Bytes::from_static(b"once")).unwrap().remove(0);
+    let f = sender.send(1, Bytes::from_static(b"once"), DataFlags::NONE).unwrap().remove(0);
     assert_eq!(receiver.receive(&f).unwrap().len(), 1);
     assert_eq!(receiver.receive(&f).unwrap().len(), 0); // duplicate
     assert_eq!(receiver.receive(&f).unwrap().len(), 0); // triple
@@ -128,14 +134,20 @@ fn ru_exactly_once_dedup() {
 #[test]
 fn ru_retransmit_tracks_unacked() {
     let mut sender = ReliableUnorderedSender::new();
-    sender.send(1, Bytes::from_static(b"X")).unwrap();
-    sender.send(1, Bytes::from_static(b"Y")).unwrap();
+    sender.send(1, Bytes::from_static(b"X"), DataFlags::NONE).unwrap();
+    sender.send(1, Bytes::from_static(b"Y"), DataFlags::NONE).unwrap();
     assert_eq!(sender.in_flight(), 2);
-    assert_eq!(sender.retransmit().len(), 2);
+
+    // Nothing is due yet -- the RTO hasn't elapsed since `send`.
+    assert!(sender.retransmit().unwrap().is_empty());
+    std::thread::sleep(sender.rto() + std::time::Duration::from_millis(5));
+    assert_eq!(sender.retransmit().unwrap().len(), 2);
 
     sender.on_ack(0);
     assert_eq!(sender.in_flight(), 1);
-    assert_eq!(sender.retransmit().len(), 1);
+    // The resend doubled seq 1's own RTO, so wait out twice the base RTO.
+    std::thread::sleep(sender.rto() * 2 + std::time::Duration::from_millis(5));
+    assert_eq!(sender.retransmit().unwrap().len(), 1);
 }
 
 // ---------------------------------------------------------------------------
@@ -149,7 +161,7 @@ fn be_fire_and_forget_delivery() {
 
     for i in 0..10 {
         let f = sender
-            .send(1, Bytes::from(format!("pkt-{i}")))
+            .send(1, Bytes::from(format!("pkt-{i}")), DataFlags::NONE)
             .unwrap()
             .remove(0);
         let d = receiver.receive(&f).unwrap();
@@ -160,14 +172,14 @@ fn be_fire_and_forget_delivery() {
 #[test]
 fn be_no_retransmission() {
     let mut sender = BestEffortSender::new();
-    sender.send(1, Bytes::from_static(b"gone")).unwrap();
-    assert!(sender.retransmit().is_empty());
+    sender.send(1, Bytes::from_static(b"gone"), DataFlags::NONE).unwrap();
+    assert!(sender.retransmit().unwrap().is_empty());
 }
 
 #[test]
 fn be_ack_is_noop() {
     let mut sender = BestEffortSender::new();
-    sender.send(1, Bytes::from_static(b"data")).unwrap();
+    sender.send(1, Bytes::from_static(b"data"), DataFlags::NONE).unwrap();
     sender.on_ack(0); // should not panic
 }
 
@@ -181,7 +193,7 @@ fn pr_probability_one_delivers_all() {
     let mut receiver = ProbabilisticReceiver::new(1.0);
 
     for _ in 0..50 {
-        let f = sender.send(1, Bytes::from_static(b"d")).unwrap().remove(0);
+        let f = sender.send(1, Bytes::from_static(b"d"), DataFlags::NONE).unwrap().remove(0);
         let d = receiver.receive(&f).unwrap();
         assert_eq!(d.len(), 1);
     }
@@ -193,7 +205,7 @@ fn pr_probability_zero_drops_all() {
     let mut receiver = ProbabilisticReceiver::new(0.0);
 
     for _ in 0..50 {
-        let f = sender.send(1, Bytes::from_static(b"d")).unwrap().remove(0);
+        let f = sender.send(1, Bytes::from_static(b"d"), DataFlags::NONE).unwrap().remove(0);
         let d = receiver.receive(&f).unwrap();
         assert!(d.is_empty());
     }
@@ -202,8 +214,8 @@ fn pr_probability_zero_drops_all() {
 #[test]
 fn pr_no_retransmission() {
     let mut sender = ProbabilisticSender::new();
-    sender.send(1, Bytes::from_static(b"x")).unwrap();
-    assert!(sender.retransmit().is_empty());
+    sender.send(1, Bytes::from_static(b"x"), DataFlags::NONE).unwrap();
+    assert!(sender.retransmit().unwrap().is_empty());
 }
 
 #[test]
@@ -0,0 +1,261 @@
+// Elligator2 encoding of Curve25519 points.
+//
+// An X25519 public key is a uniformly random-looking 32 bytes, but not every
+// byte string is a valid curve point -- a passive observer who knows this can
+// distinguish StrandTrust handshake traffic from random noise. Elligator2
+// fixes this: roughly half of all curve points have a "representative" that
+// *is* indistinguishable from uniform random bytes, and the map is efficiently
+// invertible. `X25519KeyPair::generate_representable` retries key generation
+// until it lands on such a point; `point_to_representative` and
+// `representative_to_point` below implement the forward/inverse maps themselves.
+//
+// The field arithmetic (the `Fe` representation and the `mul`/`sqrt`/`invert`
+// routines) follows the classic 16-limb, base-2^16 implementation of GF(2^255-19)
+// used throughout the NaCl/TweetNaCl family of libraries.
+
+use rand::RngCore;
+
+/// A field element of GF(p), p = 2^255 - 19, represented as 16 limbs of 16 bits each
+/// (with redundancy to avoid carrying after every operation).
+type Fe = [i64; 16];
+
+/// sqrt(-1) mod p, pre-computed and expressed in the 16-limb representation.
+const SQRT_M1: Fe = [
+    0xa0b0, 0x4a0e, 0x1b27, 0xc4ee, 0xe478, 0xad2f, 0x1806, 0x2f43, 0xd7a7, 0x3dfb, 0x0099, 0x2b4d,
+    0xdf0b, 0x4fc1, 0x2480, 0x2b83,
+];
+
+/// The Montgomery curve coefficient A = 486662, as used by Curve25519.
+const CURVE_A: i64 = 486662;
+
+fn fe_zero() -> Fe {
+    [0; 16]
+}
+
+fn fe_from_i64(v: i64) -> Fe {
+    let mut o = fe_zero();
+    o[0] = v;
+    o
+}
+
+fn car25519(o: &mut Fe) {
+    for i in 0..16 {
+        o[i] += 1 << 16;
+        let c = o[i] >> 16;
+        let next = (i + 1) % 16;
+        o[next] += (c - 1) + if i == 15 { 37 * (c - 1) } else { 0 };
+        o[i] -= c << 16;
+    }
+}
+
+fn fe_add(a: &Fe, b: &Fe) -> Fe {
+    let mut o = fe_zero();
+    for i in 0..16 {
+        o[i] = a[i] + b[i];
+    }
+    o
+}
+
+fn fe_sub(a: &Fe, b: &Fe) -> Fe {
+    let mut o = fe_zero();
+    for i in 0..16 {
+        o[i] = a[i] - b[i];
+    }
+    o
+}
+
+fn fe_mul(a: &Fe, b: &Fe) -> Fe {
+    let mut t = [0i64; 31];
+    for i in 0..16 {
+        for j in 0..16 {
+            t[i + j] += a[i] * b[j];
+        }
+    }
+    for i in 0..15 {
+        t[i] += 38 * t[i + 16];
+    }
+    let mut o = fe_zero();
+    o[..16].copy_from_slice(&t[..16]);
+    car25519(&mut o);
+    car25519(&mut o);
+    o
+}
+
+fn fe_sq(a: &Fe) -> Fe {
+    fe_mul(a, a)
+}
+
+/// Fermat inverse: a^(p-2) mod p, via the standard 254-squaring addition chain.
+fn fe_invert(i: &Fe) -> Fe {
+    let mut c = *i;
+    for a in (0..=253).rev() {
+        c = fe_sq(&c);
+        if a != 2 && a != 4 {
+            c = fe_mul(&c, i);
+        }
+    }
+    c
+}
+
+/// i^((p-5)/8) mod p, via the standard 251-squaring addition chain.
+fn fe_pow2523(i: &Fe) -> Fe {
+    let mut c = *i;
+    for a in (0..=250).rev() {
+        c = fe_sq(&c);
+        if a != 1 {
+            c = fe_mul(&c, i);
+        }
+    }
+    c
+}
+
+fn fe_pack(n: &Fe) -> [u8; 32] {
+    let mut t = *n;
+    car25519(&mut t);
+    car25519(&mut t);
+    car25519(&mut t);
+    for _ in 0..2 {
+        let mut m = fe_zero();
+        m[0] = t[0] - 0xffed;
+        for i in 1..15 {
+            m[i] = t[i] - 0xffff - ((m[i - 1] >> 16) & 1);
+            m[i - 1] &= 0xffff;
+        }
+        m[15] = t[15] - 0x7fff - ((m[14] >> 16) & 1);
+        let b = (m[15] >> 16) & 1;
+        m[14] &= 0xffff;
+        // sel25519(t, m, 1 - b): swap in m's limbs wherever b == 0.
+        let swap = 1 - b;
+        let mask = -swap; // all-ones if swap == 1, else 0
+        for i in 0..16 {
+            let x = mask & (t[i] ^ m[i]);
+            t[i] ^= x;
+        }
+    }
+    let mut o = [0u8; 32];
+    for i in 0..16 {
+        o[2 * i] = (t[i] & 0xff) as u8;
+        o[2 * i + 1] = (t[i] >> 8) as u8;
+    }
+    o
+}
+
+fn fe_unpack(n: &[u8; 32]) -> Fe {
+    let mut o = fe_zero();
+    for i in 0..16 {
+        o[i] = n[2 * i] as i64 + ((n[2 * i + 1] as i64) << 8);
+    }
+    o[15] &= 0x7fff;
+    o
+}
+
+fn fe_eq(a: &Fe, b: &Fe) -> bool {
+    fe_pack(a) == fe_pack(b)
+}
+
+/// Curve25519 field square root, for p = 5 (mod 8) via the Atkin/Tonelli-style
+/// trick used throughout the ref10/TweetNaCl code base. Returns `None` if `t`
+/// is not a quadratic residue.
+fn fe_sqrt(t: &Fe) -> Option<Fe> {
+    let v = fe_pow2523(t); // t^((p-5)/8)
+    let candidate = fe_mul(t, &v); // t^((p+3)/8)
+    let c2 = fe_sq(&candidate);
+    if fe_eq(&c2, t) {
+        return Some(candidate);
+    }
+    let neg_t = fe_sub(&fe_zero(), t);
+    if fe_eq(&c2, &neg_t) {
+        return Some(fe_mul(&candidate, &SQRT_M1));
+    }
+    None
+}
+
+/// Forward map: given the u-coordinate of a curve point, return its Elligator2
+/// representative `r` such that `representative_to_point(r) == u`, or `None` if
+/// `u` has no representative (true for roughly half of all curve points).
+///
+/// Derivation: from `u = -A / (1 + 2r^2)` we get `r^2 = -(A + u) / (2u)`; this
+/// has a solution iff the right-hand side is a quadratic residue.
+pub(crate) fn point_to_representative(u_bytes: &[u8; 32]) -> Option<[u8; 32]> {
+    let u = fe_unpack(u_bytes);
+    if fe_eq(&u, &fe_zero()) {
+        return None;
+    }
+    let a = fe_from_i64(CURVE_A);
+    let numerator = fe_sub(&fe_zero(), &fe_add(&a, &u)); // -(A + u)
+    let denominator = fe_mul(&fe_from_i64(2), &u); // 2u
+    let t = fe_mul(&numerator, &fe_invert(&denominator));
+    let r = fe_sqrt(&t)?;
+
+    // Canonicalize to the "low" root: of {r, -r}, pick the one whose packed
+    // encoding is numerically smaller, so the representative is independent
+    // of which square root our arithmetic happened to produce.
+    let neg_r = fe_sub(&fe_zero(), &r);
+    let r_bytes = fe_pack(&r);
+    let neg_r_bytes = fe_pack(&neg_r);
+    let mut out = if le_bytes_less_or_eq(&r_bytes, &neg_r_bytes) {
+        r_bytes
+    } else {
+        neg_r_bytes
+    };
+    // The low root is at most (p - 1) / 2 < 2^254, so its top two bits are
+    // always clear; fill them with random bits so the encoding is uniform.
+    let mut top = [0u8; 1];
+    rand::rngs::OsRng.fill_bytes(&mut top);
+    out[31] |= top[0] & 0xC0;
+    Some(out)
+}
+
+/// Inverse map: given an Elligator2 representative, recover the u-coordinate
+/// of the curve point it encodes. Every 32-byte string (after masking the top
+/// two bits, which the forward map fills with random bits) is a valid
+/// representative -- this never fails.
+pub(crate) fn representative_to_point(r_bytes: &[u8; 32]) -> [u8; 32] {
+    let mut masked = *r_bytes;
+    masked[31] &= 0x3F;
+    let r = fe_unpack(&masked);
+    let a = fe_from_i64(CURVE_A);
+    let r2 = fe_sq(&r);
+    let denom = fe_add(&fe_from_i64(1), &fe_add(&r2, &r2)); // 1 + 2r^2
+    let neg_a = fe_sub(&fe_zero(), &a);
+    let u = fe_mul(&neg_a, &fe_invert(&denom));
+    fe_pack(&u)
+}
+
+/// Little-endian byte-array comparison (treats the arrays as 256-bit integers).
+fn le_bytes_less_or_eq(a: &[u8; 32], b: &[u8; 32]) -> bool {
+    for i in (0..32).rev() {
+        if a[i] != b[i] {
+            return a[i] < b[i];
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::x25519::X25519KeyPair;
+
+    #[test]
+    fn representative_roundtrips_to_same_point() {
+        // Retry until we land on a representable point (~50% chance per try).
+        for _ in 0..64 {
+            let kp = X25519KeyPair::generate();
+            let u = kp.public_key_bytes();
+            if let Some(r) = point_to_representative(&u) {
+                let recovered = representative_to_point(&r);
+                assert_eq!(recovered, u);
+                return;
+            }
+        }
+        panic!("no representable point found in 64 tries");
+    }
+
+    #[test]
+    fn generate_representable_always_yields_a_representative() {
+        let kp = X25519KeyPair::generate_representable();
+        let r = kp.public_key_representative().expect("should be representable");
+        assert_eq!(representative_to_point(&r), kp.public_key_bytes());
+    }
+}
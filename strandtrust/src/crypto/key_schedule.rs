@@ -0,0 +1,260 @@
+// TLS 1.3-style HKDF key schedule deriving directional traffic secrets.
+//
+// `x25519::derive_session_keys` expands client/server write keys straight
+// off the handshake secret, keyed only by the two peers' node IDs -- two
+// handshakes between the same pair of peers derive the same keys regardless
+// of what was actually said during the exchange. `KeySchedule` instead binds
+// every derived secret to a transcript hash of the messages exchanged so
+// far, following RFC 8446 ยง7.1's `HKDF-Expand-Label(secret, label, context,
+// len)` construction with `"strand1 "` in place of TLS's `"tls13 "` label
+// prefix, so a reordered or substituted message changes every downstream key.
+
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::crypto::aead::CipherSuite;
+use crate::error::{Result, StrandTrustError};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Label prefix distinguishing StrandTrust's key schedule from TLS 1.3's.
+const LABEL_PREFIX: &[u8] = b"strand1 ";
+
+/// `HKDF-Expand-Label(secret, label, context, out.len())` per RFC 8446 ยง7.1.
+pub(crate) fn expand_label(secret: &[u8; 32], label: &[u8], context: &[u8], out: &mut [u8]) -> Result<()> {
+    let hk = Hkdf::<Sha256>::from_prk(secret)
+        .map_err(|e| StrandTrustError::Encryption(format!("HKDF from_prk error: {e}")))?;
+    // HkdfLabel: uint16 output length, then the prefixed label and the
+    // context, each behind a one-byte length.
+    let full_label_len = LABEL_PREFIX.len() + label.len();
+    let mut info = Vec::with_capacity(2 + 1 + full_label_len + 1 + context.len());
+    info.extend_from_slice(&(out.len() as u16).to_be_bytes());
+    info.push(full_label_len as u8);
+    info.extend_from_slice(LABEL_PREFIX);
+    info.extend_from_slice(label);
+    info.push(context.len() as u8);
+    info.extend_from_slice(context);
+    hk.expand(&info, out)
+        .map_err(|e| StrandTrustError::Encryption(format!("HKDF expand error: {e}")))
+}
+
+/// Directional write keys/IVs and finished-message MAC secrets for one
+/// completed handshake, sized to the negotiated [`CipherSuite`].
+pub struct KeySchedule {
+    pub client_write_key: Vec<u8>,
+    pub server_write_key: Vec<u8>,
+    pub client_write_iv: Vec<u8>,
+    pub server_write_iv: Vec<u8>,
+    client_finished_secret: [u8; 32],
+    server_finished_secret: [u8; 32],
+    /// Bound into a future `ResumptionTicket` (see
+    /// `crate::crypto::resumption`) so a later 0-RTT reconnect can derive
+    /// early-data keys without redoing the DH exchange, while the fresh
+    /// ephemeral keys that same reconnect negotiates still give the rest of
+    /// the session forward secrecy.
+    resumption_master_secret: [u8; 32],
+}
+
+impl KeySchedule {
+    /// Derive a full key schedule from the X25519 shared secret and the
+    /// SHA-256 transcript hash of the handshake messages exchanged so far.
+    pub fn derive(
+        shared_secret: &[u8; 32],
+        transcript_hash: &[u8; 32],
+        suite: CipherSuite,
+    ) -> Result<Self> {
+        let salt = [0u8; 32];
+        let hk = Hkdf::<Sha256>::new(Some(&salt), shared_secret);
+        let mut handshake_secret = [0u8; 32];
+        hk.expand(b"strand1 handshake", &mut handshake_secret)
+            .map_err(|e| StrandTrustError::Encryption(format!("HKDF expand error: {e}")))?;
+
+        let mut client_traffic_secret = [0u8; 32];
+        expand_label(
+            &handshake_secret,
+            b"c traffic",
+            transcript_hash,
+            &mut client_traffic_secret,
+        )?;
+        let mut server_traffic_secret = [0u8; 32];
+        expand_label(
+            &handshake_secret,
+            b"s traffic",
+            transcript_hash,
+            &mut server_traffic_secret,
+        )?;
+
+        let mut client_write_key = vec![0u8; suite.key_len()];
+        expand_label(&client_traffic_secret, b"key", b"", &mut client_write_key)?;
+        let mut server_write_key = vec![0u8; suite.key_len()];
+        expand_label(&server_traffic_secret, b"key", b"", &mut server_write_key)?;
+
+        let mut client_write_iv = vec![0u8; suite.nonce_len()];
+        expand_label(&client_traffic_secret, b"iv", b"", &mut client_write_iv)?;
+        let mut server_write_iv = vec![0u8; suite.nonce_len()];
+        expand_label(&server_traffic_secret, b"iv", b"", &mut server_write_iv)?;
+
+        let mut client_finished_secret = [0u8; 32];
+        expand_label(&client_traffic_secret, b"finished", b"", &mut client_finished_secret)?;
+        let mut server_finished_secret = [0u8; 32];
+        expand_label(&server_traffic_secret, b"finished", b"", &mut server_finished_secret)?;
+
+        let mut resumption_master_secret = [0u8; 32];
+        expand_label(&handshake_secret, b"res master", transcript_hash, &mut resumption_master_secret)?;
+
+        Ok(Self {
+            client_write_key,
+            server_write_key,
+            client_write_iv,
+            server_write_iv,
+            client_finished_secret,
+            server_finished_secret,
+            resumption_master_secret,
+        })
+    }
+
+    /// The secret a [`crate::crypto::resumption::ResumptionTicket`] binds to
+    /// this completed handshake, letting a later reconnect skip straight to
+    /// sealing/unsealing 0-RTT early data under a key derived from it.
+    pub fn resumption_master_secret(&self) -> &[u8; 32] {
+        &self.resumption_master_secret
+    }
+
+    /// MAC the client's Finished message: `HMAC(client_finished_secret, transcript_hash)`.
+    pub fn client_finished_mac(&self, transcript_hash: &[u8; 32]) -> [u8; 32] {
+        mac(&self.client_finished_secret, transcript_hash)
+    }
+
+    /// MAC the server's Finished message: `HMAC(server_finished_secret, transcript_hash)`.
+    pub fn server_finished_mac(&self, transcript_hash: &[u8; 32]) -> [u8; 32] {
+        mac(&self.server_finished_secret, transcript_hash)
+    }
+
+    /// Verify a received client Finished MAC in constant time.
+    pub fn verify_client_finished(&self, transcript_hash: &[u8; 32], mac_bytes: &[u8]) -> Result<()> {
+        verify_mac(&self.client_finished_secret, transcript_hash, mac_bytes)
+    }
+
+    /// Verify a received server Finished MAC in constant time.
+    pub fn verify_server_finished(&self, transcript_hash: &[u8; 32], mac_bytes: &[u8]) -> Result<()> {
+        verify_mac(&self.server_finished_secret, transcript_hash, mac_bytes)
+    }
+}
+
+/// Derive a single-use 0-RTT early-data key from a resumption ticket's
+/// `resumption_master_secret`, bound to the resuming TRUST_HELLO's fresh
+/// ephemeral public key so the key differs on every reconnect even though
+/// `master_secret` itself is replayed across all of them.
+pub(crate) fn derive_early_data_key(
+    master_secret: &[u8; 32],
+    client_ephemeral_pub: &[u8; 32],
+) -> Result<[u8; 32]> {
+    let mut key = [0u8; 32];
+    expand_label(master_secret, b"e early traffic", client_ephemeral_pub, &mut key)?;
+    Ok(key)
+}
+
+fn mac(secret: &[u8; 32], transcript_hash: &[u8; 32]) -> [u8; 32] {
+    let mut hmac = HmacSha256::new_from_slice(secret).expect("HMAC accepts any key length");
+    hmac.update(transcript_hash);
+    hmac.finalize().into_bytes().into()
+}
+
+fn verify_mac(secret: &[u8; 32], transcript_hash: &[u8; 32], mac_bytes: &[u8]) -> Result<()> {
+    let mut hmac = HmacSha256::new_from_slice(secret).expect("HMAC accepts any key length");
+    hmac.update(transcript_hash);
+    hmac.verify_slice(mac_bytes)
+        .map_err(|_| StrandTrustError::Handshake("finished MAC verification failed".into()))
+}
+
+// Manual `Debug` impl so a derived `Debug` on a containing state enum
+// (e.g. `HandshakeState`) never accidentally prints key material.
+impl std::fmt::Debug for KeySchedule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KeySchedule").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deterministic_for_same_inputs() {
+        let secret = [0x11u8; 32];
+        let transcript = [0x22u8; 32];
+        let a = KeySchedule::derive(&secret, &transcript, CipherSuite::ChaCha20Poly1305).unwrap();
+        let b = KeySchedule::derive(&secret, &transcript, CipherSuite::ChaCha20Poly1305).unwrap();
+        assert_eq!(a.client_write_key, b.client_write_key);
+        assert_eq!(a.server_write_key, b.server_write_key);
+        assert_eq!(a.client_write_iv, b.client_write_iv);
+        assert_eq!(a.server_write_iv, b.server_write_iv);
+    }
+
+    #[test]
+    fn different_transcript_gives_different_keys() {
+        let secret = [0x11u8; 32];
+        let a = KeySchedule::derive(&secret, &[0x22u8; 32], CipherSuite::ChaCha20Poly1305).unwrap();
+        let b = KeySchedule::derive(&secret, &[0x33u8; 32], CipherSuite::ChaCha20Poly1305).unwrap();
+        assert_ne!(a.client_write_key, b.client_write_key);
+    }
+
+    #[test]
+    fn client_and_server_keys_differ() {
+        let secret = [0x11u8; 32];
+        let transcript = [0x22u8; 32];
+        let ks = KeySchedule::derive(&secret, &transcript, CipherSuite::ChaCha20Poly1305).unwrap();
+        assert_ne!(ks.client_write_key, ks.server_write_key);
+        assert_ne!(ks.client_write_iv, ks.server_write_iv);
+    }
+
+    #[test]
+    fn key_and_nonce_lens_follow_suite() {
+        let secret = [0x11u8; 32];
+        let transcript = [0x22u8; 32];
+        let ks = KeySchedule::derive(&secret, &transcript, CipherSuite::Aes128Ccm).unwrap();
+        assert_eq!(ks.client_write_key.len(), CipherSuite::Aes128Ccm.key_len());
+        assert_eq!(ks.client_write_iv.len(), CipherSuite::Aes128Ccm.nonce_len());
+    }
+
+    #[test]
+    fn finished_mac_roundtrips() {
+        let secret = [0x11u8; 32];
+        let transcript = [0x22u8; 32];
+        let ks = KeySchedule::derive(&secret, &transcript, CipherSuite::ChaCha20Poly1305).unwrap();
+        let tag = ks.client_finished_mac(&transcript);
+        assert!(ks.verify_client_finished(&transcript, &tag).is_ok());
+    }
+
+    #[test]
+    fn resumption_master_secret_differs_from_traffic_keys() {
+        let secret = [0x11u8; 32];
+        let transcript = [0x22u8; 32];
+        let ks = KeySchedule::derive(&secret, &transcript, CipherSuite::ChaCha20Poly1305).unwrap();
+        assert_ne!(&ks.resumption_master_secret()[..], &ks.client_write_key[..]);
+    }
+
+    #[test]
+    fn derive_early_data_key_is_deterministic_and_ephemeral_bound() {
+        let master_secret = [0x33u8; 32];
+        let ephemeral_a = [0x44u8; 32];
+        let ephemeral_b = [0x55u8; 32];
+
+        let key_a1 = derive_early_data_key(&master_secret, &ephemeral_a).unwrap();
+        let key_a2 = derive_early_data_key(&master_secret, &ephemeral_a).unwrap();
+        let key_b = derive_early_data_key(&master_secret, &ephemeral_b).unwrap();
+
+        assert_eq!(key_a1, key_a2);
+        assert_ne!(key_a1, key_b);
+    }
+
+    #[test]
+    fn finished_mac_rejects_tampered_transcript() {
+        let secret = [0x11u8; 32];
+        let transcript = [0x22u8; 32];
+        let ks = KeySchedule::derive(&secret, &transcript, CipherSuite::ChaCha20Poly1305).unwrap();
+        let tag = ks.server_finished_mac(&transcript);
+        assert!(ks.verify_server_finished(&[0x99u8; 32], &tag).is_err());
+    }
+}
@@ -0,0 +1,15 @@
+// Crypto module declarations
+
+pub mod keys;
+pub mod x25519;
+pub mod aead;
+pub mod elligator2;
+pub mod hash;
+pub mod key_schedule;
+pub mod record;
+pub mod rekey;
+pub mod resumption;
+pub mod retry_token;
+pub mod replay;
+pub mod session;
+pub mod trust_store;
@@ -0,0 +1,228 @@
+// Forward-ratcheting key updates for long-lived StrandTrust sessions.
+//
+// `derive_session_keys` produces a single `SessionKeys` for the lifetime of a
+// handshake, which both bounds how much data can safely be encrypted under
+// one key and gives no recovery if a key is ever compromised.
+// `SessionKeys::update` ratchets every traffic secret forward one generation
+// via HKDF-Expand (no further DH required), and `KeyRing` keeps the current
+// and immediately-previous generation so frames sent just before a rekey
+// still decrypt during the transition. This mirrors QUIC's key update
+// mechanism (RFC 9001 section 6).
+
+use hkdf::Hkdf;
+use sha2::Sha256;
+use std::time::Duration;
+
+use crate::crypto::x25519::SessionKeys;
+use crate::error::{Result, StrandTrustError};
+
+/// HKDF info label for ratcheting a traffic secret forward one generation.
+const KEY_UPDATE_LABEL: &[u8] = b"strand key update";
+
+/// `next_generation(current) = HKDF-Expand(current, "strand key update", N)`.
+fn ratchet<const N: usize>(current: &[u8; N]) -> Result<[u8; N]> {
+    let hk = Hkdf::<Sha256>::new(None, current);
+    let mut next = [0u8; N];
+    hk.expand(KEY_UPDATE_LABEL, &mut next)
+        .map_err(|e| StrandTrustError::Encryption(format!("HKDF expand error: {e}")))?;
+    Ok(next)
+}
+
+/// Best-effort overwrite of superseded key material with zeros. This is not
+/// a hardened zeroization (no volatile write, no compiler fence against
+/// dead-store elimination) but it is strictly better than leaving stale key
+/// bytes sitting in memory unchanged once they are logically retired.
+fn clear<const N: usize>(buf: &mut [u8; N]) {
+    for b in buf.iter_mut() {
+        *b = 0;
+    }
+}
+
+impl SessionKeys {
+    /// Ratchet every traffic secret forward one generation and bump
+    /// `key_epoch`. The superseded key material is zeroized in place.
+    pub fn update(&mut self) -> Result<()> {
+        let next_client_write_key = ratchet(&self.client_write_key)?;
+        let next_server_write_key = ratchet(&self.server_write_key)?;
+        let next_client_write_iv = ratchet(&self.client_write_iv)?;
+        let next_server_write_iv = ratchet(&self.server_write_iv)?;
+
+        clear(&mut self.client_write_key);
+        clear(&mut self.server_write_key);
+        clear(&mut self.client_write_iv);
+        clear(&mut self.server_write_iv);
+
+        self.client_write_key = next_client_write_key;
+        self.server_write_key = next_server_write_key;
+        self.client_write_iv = next_client_write_iv;
+        self.server_write_iv = next_server_write_iv;
+        self.key_epoch = self.key_epoch.wrapping_add(1);
+        Ok(())
+    }
+}
+
+/// Decides when a connection should trigger [`SessionKeys::update`].
+///
+/// Mirrors the two triggers QUIC's key update mechanism supports: a byte
+/// count threshold (bound how much data is encrypted under one key) and an
+/// elapsed-time threshold (bound key lifetime even on idle/low-traffic
+/// connections).
+#[derive(Debug, Clone, Copy)]
+pub struct KeyUpdatePolicy {
+    max_bytes_per_epoch: u64,
+    max_age_per_epoch: Duration,
+}
+
+impl KeyUpdatePolicy {
+    /// A conservative default: rekey every 64 MiB of traffic or every hour,
+    /// whichever comes first.
+    pub const DEFAULT_MAX_BYTES: u64 = 64 * 1024 * 1024;
+    pub const DEFAULT_MAX_AGE: Duration = Duration::from_secs(3600);
+
+    /// Build a policy with explicit thresholds.
+    pub fn new(max_bytes_per_epoch: u64, max_age_per_epoch: Duration) -> Self {
+        Self {
+            max_bytes_per_epoch,
+            max_age_per_epoch,
+        }
+    }
+
+    /// Whether a new epoch should be started, given the bytes sent and time
+    /// elapsed since the current epoch began.
+    pub fn should_update(&self, bytes_sent_this_epoch: u64, elapsed_this_epoch: Duration) -> bool {
+        bytes_sent_this_epoch >= self.max_bytes_per_epoch
+            || elapsed_this_epoch >= self.max_age_per_epoch
+    }
+}
+
+impl Default for KeyUpdatePolicy {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_MAX_BYTES, Self::DEFAULT_MAX_AGE)
+    }
+}
+
+/// Holds the current and immediately-previous [`SessionKeys`] generation so a
+/// receiver can still decrypt frames sent just before a key update raced
+/// ahead of it (tolerating reordering/loss across the epoch boundary).
+pub struct KeyRing {
+    current: SessionKeys,
+    previous: Option<SessionKeys>,
+}
+
+impl KeyRing {
+    /// Start a keyring at the initial (epoch 0) session keys.
+    pub fn new(initial: SessionKeys) -> Self {
+        Self {
+            current: initial,
+            previous: None,
+        }
+    }
+
+    /// The current generation's session keys.
+    pub fn current(&self) -> &SessionKeys {
+        &self.current
+    }
+
+    /// The low 8 bits of the current key epoch, as carried on the wire.
+    pub fn epoch(&self) -> u8 {
+        self.current.key_epoch
+    }
+
+    /// Advance to the next generation. The previous generation is retained
+    /// (and whatever it in turn superseded is dropped) so in-flight frames
+    /// sent under the old epoch still decrypt.
+    pub fn update(&mut self) -> Result<()> {
+        let mut next = SessionKeys {
+            client_write_key: self.current.client_write_key,
+            server_write_key: self.current.server_write_key,
+            client_write_iv: self.current.client_write_iv,
+            server_write_iv: self.current.server_write_iv,
+            key_epoch: self.current.key_epoch,
+        };
+        next.update()?;
+        let retired = std::mem::replace(&mut self.current, next);
+        self.previous = Some(retired);
+        Ok(())
+    }
+
+    /// Find the session keys matching a received frame's epoch (its low
+    /// bits), checking the current generation first and falling back to the
+    /// previous one during a rekey transition.
+    pub fn keys_for_epoch(&self, epoch: u8) -> Option<&SessionKeys> {
+        if self.current.key_epoch == epoch {
+            return Some(&self.current);
+        }
+        self.previous
+            .as_ref()
+            .filter(|prev| prev.key_epoch == epoch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::x25519::{derive_session_keys, X25519KeyPair};
+
+    fn sample_keys() -> SessionKeys {
+        let alice = X25519KeyPair::generate();
+        let bob = X25519KeyPair::generate();
+        let shared = alice.diffie_hellman(&bob.public_key_bytes());
+        derive_session_keys(&shared, &[1u8; 16], &[2u8; 16]).unwrap()
+    }
+
+    #[test]
+    fn update_changes_keys_and_bumps_epoch() {
+        let mut keys = sample_keys();
+        let before = (keys.client_write_key, keys.server_write_key, keys.key_epoch);
+        keys.update().unwrap();
+        assert_ne!(keys.client_write_key, before.0);
+        assert_ne!(keys.server_write_key, before.1);
+        assert_eq!(keys.key_epoch, before.2 + 1);
+    }
+
+    #[test]
+    fn update_is_deterministic_given_same_input() {
+        let mut a = sample_keys();
+        let mut b = SessionKeys {
+            client_write_key: a.client_write_key,
+            server_write_key: a.server_write_key,
+            client_write_iv: a.client_write_iv,
+            server_write_iv: a.server_write_iv,
+            key_epoch: a.key_epoch,
+        };
+        a.update().unwrap();
+        b.update().unwrap();
+        assert_eq!(a.client_write_key, b.client_write_key);
+        assert_eq!(a.server_write_key, b.server_write_key);
+    }
+
+    #[test]
+    fn policy_triggers_on_bytes_or_age() {
+        let policy = KeyUpdatePolicy::new(1000, Duration::from_secs(60));
+        assert!(!policy.should_update(500, Duration::from_secs(10)));
+        assert!(policy.should_update(1000, Duration::from_secs(10)));
+        assert!(policy.should_update(0, Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn keyring_tolerates_old_epoch_during_transition() {
+        let mut ring = KeyRing::new(sample_keys());
+        let epoch0 = ring.epoch();
+        ring.update().unwrap();
+        let epoch1 = ring.epoch();
+
+        assert!(ring.keys_for_epoch(epoch0).is_some());
+        assert!(ring.keys_for_epoch(epoch1).is_some());
+        assert_ne!(epoch0, epoch1);
+    }
+
+    #[test]
+    fn keyring_drops_generations_older_than_previous() {
+        let mut ring = KeyRing::new(sample_keys());
+        let epoch0 = ring.epoch();
+        ring.update().unwrap();
+        ring.update().unwrap();
+
+        assert!(ring.keys_for_epoch(epoch0).is_none());
+    }
+}
@@ -0,0 +1,234 @@
+// 0-RTT session resumption tickets for the NexTrust handshake.
+//
+// After a handshake completes, a `Responder` can seal a `ResumptionTicket`
+// into an opaque `ResumptionToken` and hand it to the initiator. On a later
+// reconnect, `Initiator::create_init_resuming` presents the token back
+// alongside early application data sealed under a key derived from the
+// ticket's `master_secret` (see `crypto::key_schedule::derive_early_data_key`),
+// and `Responder::process_init` reopens it to validate the resumption
+// attempt and recover that key -- all before the fresh ephemeral DH exchange
+// that still runs underneath rekeys the session forward-securely.
+//
+// A responder seals many tickets over the lifetime of its process rather
+// than one key per live connection, so it can't rely on a monotonic nonce
+// counter the way `crypto::record::RecordLayer` does. AES-256-GCM-SIV's
+// synthetic-IV construction tolerates a randomly chosen, possibly repeated
+// nonce without leaking key material (see `crypto::aead`'s module docs), so
+// it -- not the handshake's own `HANDSHAKE_SUITE` -- backs ticket sealing.
+
+use rand::RngCore;
+
+use crate::crypto::aead::{AeadKey, CipherSuite};
+use crate::crypto::keys::NodeId;
+use crate::error::{Result, StrandTrustError};
+
+/// Cipher suite resumption tickets are sealed under, independent of whatever
+/// suite the resumed connection's own traffic keys end up using.
+const TICKET_SUITE: CipherSuite = CipherSuite::Aes256GcmSiv;
+
+/// Default resumption ticket lifetime: 24 hours.
+pub const DEFAULT_TICKET_LIFETIME_SECS: u64 = 24 * 3600;
+
+const TICKET_AAD: &[u8] = b"strand1 resumption ticket";
+const TICKET_PLAINTEXT_LEN: usize = 16 + 32 + 32 + 8;
+
+/// Plaintext contents of a resumption ticket: enough for a `Responder` to
+/// recognize the reconnecting peer and recover the key material a 0-RTT
+/// early-data payload was sealed under, without keeping any other
+/// per-connection state around between the original handshake and the
+/// reconnect.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResumptionTicket {
+    /// Node ID of the peer the ticket was issued to (see
+    /// `crate::crypto::keys::derive_node_id`).
+    pub peer_node_id: NodeId,
+    /// `KeySchedule::resumption_master_secret()` from the original handshake.
+    pub master_secret: [u8; 32],
+    /// SHA-256 fingerprint of the peer's MIC at issuance time, checked again
+    /// against the MIC presented on reconnect so a still-valid MIC for a
+    /// *different* identity can't redeem someone else's ticket.
+    pub mic_fingerprint: [u8; 32],
+    /// Unix timestamp (seconds) the ticket was issued at.
+    pub issued_at: u64,
+}
+
+impl ResumptionTicket {
+    fn to_bytes(&self) -> [u8; TICKET_PLAINTEXT_LEN] {
+        let mut buf = [0u8; TICKET_PLAINTEXT_LEN];
+        buf[0..16].copy_from_slice(&self.peer_node_id);
+        buf[16..48].copy_from_slice(&self.master_secret);
+        buf[48..80].copy_from_slice(&self.mic_fingerprint);
+        buf[80..88].copy_from_slice(&self.issued_at.to_be_bytes());
+        buf
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != TICKET_PLAINTEXT_LEN {
+            return Err(StrandTrustError::ResumptionTokenInvalid(
+                "malformed ticket plaintext".into(),
+            ));
+        }
+        let mut peer_node_id = [0u8; 16];
+        peer_node_id.copy_from_slice(&bytes[0..16]);
+        let mut master_secret = [0u8; 32];
+        master_secret.copy_from_slice(&bytes[16..48]);
+        let mut mic_fingerprint = [0u8; 32];
+        mic_fingerprint.copy_from_slice(&bytes[48..80]);
+        let mut issued_at_bytes = [0u8; 8];
+        issued_at_bytes.copy_from_slice(&bytes[80..88]);
+
+        Ok(Self {
+            peer_node_id,
+            master_secret,
+            mic_fingerprint,
+            issued_at: u64::from_be_bytes(issued_at_bytes),
+        })
+    }
+}
+
+/// An opaque, AEAD-sealed `ResumptionTicket`: `nonce (12 bytes) ||
+/// ciphertext`. Handed to the initiator by a `Responder` and presented back
+/// unmodified on a later reconnect; never inspected or modified in transit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResumptionToken(Vec<u8>);
+
+impl ResumptionToken {
+    /// Wrap an opaque token received from the wire.
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+
+    /// The sealed bytes, for wire transmission or storage by the initiator.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// Responder-held secret sealing and opening `ResumptionToken`s. Generate
+/// once per responder process -- or persist it to honor tickets issued
+/// before a restart -- and keep it out of the handshake transcript entirely;
+/// unlike the handshake's own traffic keys it is never agreed with a peer.
+pub struct ResumptionTicketKey {
+    key: AeadKey,
+}
+
+impl ResumptionTicketKey {
+    /// Generate a fresh, random ticket key.
+    pub fn generate() -> Result<Self> {
+        let mut secret = [0u8; 32];
+        rand::rngs::OsRng.fill_bytes(&mut secret);
+        Self::from_secret_bytes(&secret)
+    }
+
+    /// Reconstruct a ticket key from a previously persisted 32-byte secret.
+    pub fn from_secret_bytes(secret: &[u8; 32]) -> Result<Self> {
+        Ok(Self {
+            key: AeadKey::new(TICKET_SUITE, secret)?,
+        })
+    }
+
+    /// Seal a ticket into an opaque token.
+    pub fn seal(&self, ticket: &ResumptionTicket) -> Result<ResumptionToken> {
+        let mut nonce = [0u8; 12];
+        rand::rngs::OsRng.fill_bytes(&mut nonce);
+        let ciphertext = self.key.encrypt(&nonce, &ticket.to_bytes(), TICKET_AAD)?;
+
+        let mut out = Vec::with_capacity(nonce.len() + ciphertext.len());
+        out.extend_from_slice(&nonce);
+        out.extend_from_slice(&ciphertext);
+        Ok(ResumptionToken(out))
+    }
+
+    /// Open a token and check it against `lifetime_secs`, given the current
+    /// time `now` (Unix seconds). Does *not* check the embedded MIC
+    /// fingerprint against the MIC presented on reconnect -- that cross-check
+    /// belongs to the caller, which has both values in hand (see
+    /// `handshake::protocol::Responder::process_init`).
+    pub fn open(&self, token: &ResumptionToken, now: u64, lifetime_secs: u64) -> Result<ResumptionTicket> {
+        if token.0.len() <= 12 {
+            return Err(StrandTrustError::ResumptionTokenInvalid(
+                "token too short to contain a nonce and ciphertext".into(),
+            ));
+        }
+        let (nonce, ciphertext) = token.0.split_at(12);
+        let plaintext = self
+            .key
+            .decrypt(nonce, ciphertext, TICKET_AAD)
+            .map_err(|_| StrandTrustError::ResumptionTokenInvalid("failed to decrypt".into()))?;
+        let ticket = ResumptionTicket::from_bytes(&plaintext)?;
+
+        let expires_at = ticket.issued_at.saturating_add(lifetime_secs);
+        if now >= expires_at {
+            return Err(StrandTrustError::ResumptionTokenExpired {
+                issued_at: ticket.issued_at,
+                now,
+            });
+        }
+
+        Ok(ticket)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_ticket(issued_at: u64) -> ResumptionTicket {
+        ResumptionTicket {
+            peer_node_id: [0xAB; 16],
+            master_secret: [0xCD; 32],
+            mic_fingerprint: [0xEF; 32],
+            issued_at,
+        }
+    }
+
+    #[test]
+    fn seal_then_open_roundtrips() {
+        let key = ResumptionTicketKey::generate().unwrap();
+        let ticket = sample_ticket(1_000);
+        let token = key.seal(&ticket).unwrap();
+
+        let opened = key.open(&token, 1_500, DEFAULT_TICKET_LIFETIME_SECS).unwrap();
+        assert_eq!(opened, ticket);
+    }
+
+    #[test]
+    fn expired_token_is_rejected() {
+        let key = ResumptionTicketKey::generate().unwrap();
+        let ticket = sample_ticket(1_000);
+        let token = key.seal(&ticket).unwrap();
+
+        let lifetime = 100;
+        let result = key.open(&token, 1_000 + lifetime + 1, lifetime);
+        assert!(matches!(
+            result,
+            Err(StrandTrustError::ResumptionTokenExpired { issued_at: 1_000, .. })
+        ));
+    }
+
+    #[test]
+    fn tampered_token_fails_to_open() {
+        let key = ResumptionTicketKey::generate().unwrap();
+        let ticket = sample_ticket(1_000);
+        let mut token = key.seal(&ticket).unwrap();
+        let last = token.0.len() - 1;
+        token.0[last] ^= 0xFF;
+
+        assert!(matches!(
+            key.open(&token, 1_500, DEFAULT_TICKET_LIFETIME_SECS),
+            Err(StrandTrustError::ResumptionTokenInvalid(_))
+        ));
+    }
+
+    #[test]
+    fn token_sealed_under_a_different_key_fails_to_open() {
+        let key_a = ResumptionTicketKey::generate().unwrap();
+        let key_b = ResumptionTicketKey::generate().unwrap();
+        let token = key_a.seal(&sample_ticket(1_000)).unwrap();
+
+        assert!(matches!(
+            key_b.open(&token, 1_500, DEFAULT_TICKET_LIFETIME_SECS),
+            Err(StrandTrustError::ResumptionTokenInvalid(_))
+        ));
+    }
+}
@@ -0,0 +1,243 @@
+// Stateless address-validation tokens for the StrandTrust handshake's
+// optional QUIC-Retry-style round (see
+// `crate::handshake::protocol::Responder::set_require_address_validation`).
+//
+// A spoofed-source initiator can make `Responder::process_init` run an X25519
+// DH and HKDF derivation for free, at the cost of one UDP packet -- classic
+// amplification bait. Gating that work behind a `RetryToken` round trip means
+// the first `HandshakeInit` from an unrecognized source gets back only a
+// cheap AEAD-sealed token, not the expensive handshake machinery; a spoofed
+// source never sees that token to echo it back. The token itself is just the
+// observed address and an issuance timestamp, so the responder never has to
+// remember anything about a source between the two contacts -- validating a
+// retried `HandshakeInit` is a single decrypt against the current (or
+// previous) server secret.
+
+use rand::RngCore;
+use std::time::Duration;
+
+use crate::crypto::aead::{AeadKey, CipherSuite};
+use crate::error::{Result, StrandTrustError};
+
+/// Cipher suite retry tokens are sealed under, independent of the
+/// handshake's own traffic key suite.
+const RETRY_TOKEN_SUITE: CipherSuite = CipherSuite::Aes256GcmSiv;
+
+/// Default window a retry token stays fresh for (see
+/// [`RetryTokenKey::validate_token`]). Generous enough to survive a slow
+/// client round trip, tight enough that a captured token is useless shortly
+/// after. Secret rotation can end a token's validity sooner; see
+/// [`RETRY_SECRET_ROTATION`].
+pub const DEFAULT_RETRY_TOKEN_LIFETIME_SECS: u64 = 30;
+
+/// How often the server secret sealing retry tokens rotates, using the same
+/// current-and-previous-secret scheme as
+/// `handshake::rate_limit::COOKIE_SECRET_ROTATION` so a token minted just
+/// before a rotation still validates. Only those two secrets are kept, so a
+/// token stops decrypting at the second rotation after it was sealed: with
+/// this period that is 10 to 20 seconds after issuance when
+/// `rotate_if_needed` runs on every contact, before
+/// [`DEFAULT_RETRY_TOKEN_LIFETIME_SECS`] has run out.
+pub const RETRY_SECRET_ROTATION: Duration = Duration::from_secs(10);
+
+const RETRY_TOKEN_AAD: &[u8] = b"strand1 retry token";
+
+/// An opaque, AEAD-sealed address-validation token: `nonce (12 bytes) ||
+/// ciphertext`. Handed to the initiator in place of a `HandshakeResponse` and
+/// echoed back unmodified on the initiator's second `HandshakeInit`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryToken(Vec<u8>);
+
+impl RetryToken {
+    /// Wrap an opaque token received from the wire.
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        Self(bytes)
+    }
+
+    /// The sealed bytes, for wire transmission.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+fn encode_plaintext(addr: &[u8], issued_at: u64) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(2 + addr.len() + 8);
+    buf.extend_from_slice(&(addr.len() as u16).to_be_bytes());
+    buf.extend_from_slice(addr);
+    buf.extend_from_slice(&issued_at.to_be_bytes());
+    buf
+}
+
+fn decode_plaintext(bytes: &[u8]) -> Result<(Vec<u8>, u64)> {
+    if bytes.len() < 2 {
+        return Err(StrandTrustError::RetryTokenInvalid(
+            "malformed token plaintext".into(),
+        ));
+    }
+    let addr_len = u16::from_be_bytes([bytes[0], bytes[1]]) as usize;
+    if bytes.len() != 2 + addr_len + 8 {
+        return Err(StrandTrustError::RetryTokenInvalid(
+            "malformed token plaintext".into(),
+        ));
+    }
+    let addr = bytes[2..2 + addr_len].to_vec();
+    let mut issued_at_bytes = [0u8; 8];
+    issued_at_bytes.copy_from_slice(&bytes[2 + addr_len..]);
+    Ok((addr, u64::from_be_bytes(issued_at_bytes)))
+}
+
+/// Responder-held secret sealing and validating [`RetryToken`]s. Generate
+/// once per responder process; unlike `ResumptionTicketKey` this never needs
+/// to be persisted across restarts, since a dropped retry token just costs a
+/// legitimate initiator one extra round trip.
+pub struct RetryTokenKey {
+    current_secret: [u8; 32],
+    previous_secret: Option<[u8; 32]>,
+    secret_set_at: u64,
+}
+
+impl RetryTokenKey {
+    /// Build a key, seeding the secret's epoch at `now` (Unix seconds).
+    pub fn new(now: u64) -> Self {
+        Self {
+            current_secret: random_secret(),
+            previous_secret: None,
+            secret_set_at: now,
+        }
+    }
+
+    /// Rotate the secret if [`RETRY_SECRET_ROTATION`] has elapsed, retaining
+    /// the superseded secret so tokens minted just before the rotation still
+    /// validate.
+    pub fn rotate_if_needed(&mut self, now: u64) {
+        if now.saturating_sub(self.secret_set_at) >= RETRY_SECRET_ROTATION.as_secs() {
+            self.previous_secret = Some(self.current_secret);
+            self.current_secret = random_secret();
+            self.secret_set_at = now;
+        }
+    }
+
+    /// Seal `addr` (the initiator's observed source address, in whatever
+    /// byte form the transport layer identifies it by) and `now` into a
+    /// fresh [`RetryToken`].
+    pub fn seal_token(&self, addr: &[u8], now: u64) -> Result<RetryToken> {
+        let key = AeadKey::new(RETRY_TOKEN_SUITE, &self.current_secret)?;
+        let mut nonce = [0u8; 12];
+        rand::rngs::OsRng.fill_bytes(&mut nonce);
+        let ciphertext = key.encrypt(&nonce, &encode_plaintext(addr, now), RETRY_TOKEN_AAD)?;
+
+        let mut out = Vec::with_capacity(nonce.len() + ciphertext.len());
+        out.extend_from_slice(&nonce);
+        out.extend_from_slice(&ciphertext);
+        Ok(RetryToken(out))
+    }
+
+    /// Validate a [`RetryToken`] echoed back in a second `HandshakeInit`:
+    /// it must decrypt under the current or previous secret, carry an
+    /// address matching `addr`, and still be within `lifetime_secs` of its
+    /// issuance.
+    pub fn validate_token(
+        &self,
+        addr: &[u8],
+        token: &RetryToken,
+        now: u64,
+        lifetime_secs: u64,
+    ) -> Result<()> {
+        if token.0.len() <= 12 {
+            return Err(StrandTrustError::RetryTokenInvalid(
+                "token too short to contain a nonce and ciphertext".into(),
+            ));
+        }
+        let (nonce, ciphertext) = token.0.split_at(12);
+
+        let plaintext = [Some(self.current_secret), self.previous_secret]
+            .into_iter()
+            .flatten()
+            .find_map(|secret| {
+                AeadKey::new(RETRY_TOKEN_SUITE, &secret)
+                    .ok()?
+                    .decrypt(nonce, ciphertext, RETRY_TOKEN_AAD)
+                    .ok()
+            })
+            .ok_or_else(|| StrandTrustError::RetryTokenInvalid("failed to decrypt".into()))?;
+
+        let (token_addr, issued_at) = decode_plaintext(&plaintext)?;
+        if token_addr != addr {
+            return Err(StrandTrustError::RetryTokenInvalid(
+                "token was issued to a different source address".into(),
+            ));
+        }
+
+        let expires_at = issued_at.saturating_add(lifetime_secs);
+        if now >= expires_at {
+            return Err(StrandTrustError::RetryTokenExpired { issued_at, now });
+        }
+
+        Ok(())
+    }
+}
+
+fn random_secret() -> [u8; 32] {
+    let mut secret = [0u8; 32];
+    rand::rngs::OsRng.fill_bytes(&mut secret);
+    secret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seal_then_validate_roundtrips() {
+        let key = RetryTokenKey::new(0);
+        let token = key.seal_token(b"198.51.100.7:4433", 1_000).unwrap();
+        assert!(key
+            .validate_token(b"198.51.100.7:4433", &token, 1_010, DEFAULT_RETRY_TOKEN_LIFETIME_SECS)
+            .is_ok());
+    }
+
+    #[test]
+    fn mismatched_address_is_rejected() {
+        let key = RetryTokenKey::new(0);
+        let token = key.seal_token(b"198.51.100.7:4433", 1_000).unwrap();
+        assert!(matches!(
+            key.validate_token(b"203.0.113.9:4433", &token, 1_010, DEFAULT_RETRY_TOKEN_LIFETIME_SECS),
+            Err(StrandTrustError::RetryTokenInvalid(_))
+        ));
+    }
+
+    #[test]
+    fn expired_token_is_rejected() {
+        let key = RetryTokenKey::new(0);
+        let token = key.seal_token(b"198.51.100.7:4433", 1_000).unwrap();
+        let lifetime = 10;
+        assert!(matches!(
+            key.validate_token(b"198.51.100.7:4433", &token, 1_000 + lifetime + 1, lifetime),
+            Err(StrandTrustError::RetryTokenExpired { issued_at: 1_000, .. })
+        ));
+    }
+
+    #[test]
+    fn tampered_token_fails_to_validate() {
+        let key = RetryTokenKey::new(0);
+        let mut token = key.seal_token(b"198.51.100.7:4433", 1_000).unwrap();
+        let last = token.0.len() - 1;
+        token.0[last] ^= 0xFF;
+        assert!(matches!(
+            key.validate_token(b"198.51.100.7:4433", &token, 1_010, DEFAULT_RETRY_TOKEN_LIFETIME_SECS),
+            Err(StrandTrustError::RetryTokenInvalid(_))
+        ));
+    }
+
+    #[test]
+    fn rotation_still_accepts_token_from_previous_epoch() {
+        let mut key = RetryTokenKey::new(0);
+        let token = key.seal_token(b"198.51.100.7:4433", 0).unwrap();
+
+        let later = RETRY_SECRET_ROTATION.as_secs() + 1;
+        key.rotate_if_needed(later);
+        assert!(key
+            .validate_token(b"198.51.100.7:4433", &token, later, DEFAULT_RETRY_TOKEN_LIFETIME_SECS)
+            .is_ok());
+    }
+}
@@ -0,0 +1,403 @@
+// Misuse-resistant stateful AEAD session, for FFI callers.
+//
+// `AeadKey::encrypt`/`decrypt` (see `crate::crypto::aead`) and the
+// `nextrust_encrypt`/`nextrust_decrypt` FFI built directly on top of it take
+// a raw nonce from the caller -- exactly how nonce reuse happens in
+// practice, and with a counter-nonce suite a single reused nonce is
+// catastrophic. `AeadSession` instead owns the key and a monotonic 64-bit
+// message counter, derives each nonce from the counter itself, and performs
+// an HKDF ratchet (`key_{n+1} = HKDF-Expand(key_n, "rekey")`) after a
+// configurable number of messages or bytes, so long-lived sessions also get
+// forward secrecy -- the same property `RecordLayer` (`crate::crypto::
+// record`) gives the handshake-keyed record stream. Unlike `RecordLayer`,
+// `AeadSession` has no per-epoch replay window -- a caller that needs that
+// should layer one on top -- but it does tolerate messages arriving out of
+// order *across* a rekey boundary: the `RETAINED_OLD_EPOCHS` most recently
+// superseded keys are kept in a small ring, so a message encrypted just
+// before the peer rekeyed still decrypts even if it's delivered after a
+// message from the new epoch. Epochs older than that are rejected as stale.
+
+use std::collections::VecDeque;
+
+use hkdf::Hkdf;
+use sha2::Sha256;
+
+use crate::crypto::aead::{AeadKey, CipherSuite};
+use crate::error::{Result, StrandTrustError};
+
+/// HKDF info label for ratcheting an `AeadSession` key forward one epoch.
+const REKEY_LABEL: &[u8] = b"rekey";
+
+/// Number of superseded key epochs kept alive in `AeadSession::old_keys`
+/// after a rekey. Large enough to absorb ordinary network reordering around
+/// a rekey boundary without keeping arbitrarily old keys around forever.
+const RETAINED_OLD_EPOCHS: usize = 2;
+
+/// Hard ceiling on messages encrypted under one key: once the counter would
+/// reach this, refuse to encrypt further rather than ever reuse a nonce.
+/// `AeadSessionPolicy`'s rekey threshold is expected to trigger many orders
+/// of magnitude earlier; this exists purely as a last-resort backstop if a
+/// caller configures an absurdly high policy.
+const HARD_COUNTER_LIMIT: u64 = u64::MAX - 1;
+
+/// Decides when an [`AeadSession`] ratchets its key forward.
+#[derive(Debug, Clone, Copy)]
+pub struct AeadSessionPolicy {
+    max_messages_per_epoch: u64,
+    max_bytes_per_epoch: u64,
+}
+
+impl AeadSessionPolicy {
+    /// A conservative default: rekey every 2^20 messages or 16 MiB of
+    /// plaintext, whichever comes first. Tighter than `RecordLayer`'s
+    /// defaults since an FFI-driven session may run far longer than one
+    /// handshake's lifetime with no renegotiation to re-derive from.
+    pub const DEFAULT_MAX_MESSAGES: u64 = 1 << 20;
+    pub const DEFAULT_MAX_BYTES: u64 = 16 * 1024 * 1024;
+
+    /// Build a policy with explicit thresholds.
+    pub fn new(max_messages_per_epoch: u64, max_bytes_per_epoch: u64) -> Self {
+        Self {
+            max_messages_per_epoch,
+            max_bytes_per_epoch,
+        }
+    }
+
+    fn should_rekey(&self, messages_since_rekey: u64, bytes_since_rekey: u64) -> bool {
+        messages_since_rekey >= self.max_messages_per_epoch
+            || bytes_since_rekey >= self.max_bytes_per_epoch
+    }
+}
+
+impl Default for AeadSessionPolicy {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_MAX_MESSAGES, Self::DEFAULT_MAX_BYTES)
+    }
+}
+
+/// Ratchet `key` forward one epoch via `HKDF-Expand(key, "rekey", key_len)`.
+fn ratchet_key(suite: CipherSuite, key: &[u8]) -> Result<Vec<u8>> {
+    let hk = Hkdf::<Sha256>::new(None, key);
+    let mut next = vec![0u8; suite.key_len()];
+    hk.expand(REKEY_LABEL, &mut next)
+        .map_err(|e| StrandTrustError::Encryption(format!("HKDF expand error: {e}")))?;
+    Ok(next)
+}
+
+/// Best-effort overwrite of a superseded key with zeros. Not a hardened
+/// zeroization (no volatile write, no compiler fence against dead-store
+/// elimination), but strictly better than leaving stale key bytes
+/// unchanged in memory once they are logically retired. Mirrors
+/// `crypto::rekey::clear`.
+fn clear(key: &mut [u8]) {
+    for b in key.iter_mut() {
+        *b = 0;
+    }
+}
+
+/// Construct the per-message nonce by encoding `counter` big-endian into the
+/// low bytes of a zeroed, suite-width nonce. The internal counter never
+/// repeats within an epoch and every epoch uses a freshly ratcheted key, so
+/// this nonce is unique for the life of the session.
+fn session_nonce(suite: CipherSuite, counter: u64) -> Vec<u8> {
+    let mut nonce = vec![0u8; suite.nonce_len()];
+    let counter_bytes = counter.to_be_bytes();
+    let start = nonce.len() - counter_bytes.len();
+    nonce[start..].copy_from_slice(&counter_bytes);
+    nonce
+}
+
+/// A misuse-resistant stateful AEAD session.
+///
+/// Owns the key, derives each nonce from an internal counter instead of
+/// taking one from the caller, and ratchets the key forward automatically
+/// once [`AeadSessionPolicy`]'s message/byte threshold is crossed. Frames
+/// are tagged with a key epoch so the peer's `AeadSession` can ratchet in
+/// lockstep on `decrypt` rather than needing an out-of-band rekey signal.
+pub struct AeadSession {
+    suite: CipherSuite,
+    aead: AeadKey,
+    key: Vec<u8>,
+    counter: u64,
+    epoch: u8,
+    messages_since_rekey: u64,
+    bytes_since_rekey: u64,
+    policy: AeadSessionPolicy,
+    /// Raw key bytes for the `RETAINED_OLD_EPOCHS` most recently superseded
+    /// epochs, newest first, keyed by the epoch they belonged to. Consulted
+    /// by `decrypt` when `epoch` is behind the session's current one.
+    old_keys: VecDeque<(u8, Vec<u8>)>,
+}
+
+impl AeadSession {
+    /// Start a session at epoch 0 from a freshly-negotiated key.
+    pub fn new(suite: CipherSuite, key: &[u8], policy: AeadSessionPolicy) -> Result<Self> {
+        let aead = AeadKey::new(suite, key)?;
+        Ok(Self {
+            suite,
+            aead,
+            key: key.to_vec(),
+            counter: 0,
+            epoch: 0,
+            messages_since_rekey: 0,
+            bytes_since_rekey: 0,
+            policy,
+            old_keys: VecDeque::with_capacity(RETAINED_OLD_EPOCHS),
+        })
+    }
+
+    /// The session's current key epoch, bumped every automatic rekey.
+    pub fn epoch(&self) -> u8 {
+        self.epoch
+    }
+
+    /// The next message counter this session will encrypt with.
+    pub fn counter(&self) -> u64 {
+        self.counter
+    }
+
+    /// Encrypt one message, returning `(epoch, counter, ciphertext)` for the
+    /// caller to carry on the wire alongside the ciphertext. Rekeys
+    /// automatically once the configured policy is crossed, and refuses
+    /// outright rather than ever reuse a nonce if the counter is about to
+    /// be exhausted.
+    pub fn encrypt(&mut self, plaintext: &[u8], aad: &[u8]) -> Result<(u8, u64, Vec<u8>)> {
+        if self.counter >= HARD_COUNTER_LIMIT {
+            return Err(StrandTrustError::Encryption(
+                "AeadSession message counter exhausted; rekey threshold too high".into(),
+            ));
+        }
+
+        let nonce = session_nonce(self.suite, self.counter);
+        let ciphertext = self.aead.encrypt(&nonce, plaintext, aad)?;
+        let (epoch, counter) = (self.epoch, self.counter);
+
+        self.counter += 1;
+        self.messages_since_rekey += 1;
+        self.bytes_since_rekey += plaintext.len() as u64;
+        if self
+            .policy
+            .should_rekey(self.messages_since_rekey, self.bytes_since_rekey)
+        {
+            self.rekey()?;
+        }
+
+        Ok((epoch, counter, ciphertext))
+    }
+
+    /// Decrypt one message sent at `(epoch, counter)`.
+    ///
+    /// If `epoch` is ahead of this session's own, ratchet forward to match
+    /// before decrypting -- a session only ever rekeys forward, so a higher
+    /// epoch just means the peer rekeyed first. An `epoch` behind the
+    /// current one is looked up in `old_keys`: if it's one of the
+    /// `RETAINED_OLD_EPOCHS` most recently superseded epochs, the message
+    /// decrypts against that retained key without otherwise touching session
+    /// state; older than that, its key is gone and the message is rejected
+    /// as stale.
+    pub fn decrypt(
+        &mut self,
+        epoch: u8,
+        counter: u64,
+        ciphertext: &[u8],
+        aad: &[u8],
+    ) -> Result<Vec<u8>> {
+        if epoch < self.epoch {
+            let old_key = self
+                .old_keys
+                .iter()
+                .find(|(e, _)| *e == epoch)
+                .map(|(_, k)| k.clone())
+                .ok_or_else(|| {
+                    StrandTrustError::Decryption(format!(
+                        "stale key epoch: got {epoch}, current epoch is {} (key no longer retained)",
+                        self.epoch
+                    ))
+                })?;
+            let aead = AeadKey::new(self.suite, &old_key)?;
+            let nonce = session_nonce(self.suite, counter);
+            return aead.decrypt(&nonce, ciphertext, aad);
+        }
+        let caught_up = self.epoch < epoch;
+        while self.epoch < epoch {
+            self.rekey()?;
+        }
+
+        let nonce = session_nonce(self.suite, counter);
+        let plaintext = self.aead.decrypt(&nonce, ciphertext, aad)?;
+
+        self.messages_since_rekey += 1;
+        self.bytes_since_rekey += plaintext.len() as u64;
+        // A catch-up rekey above already moved us to the sender's epoch; if
+        // the per-message policy also fired on this same call we'd overshoot
+        // it by one. Only the counters carry over -- the policy itself only
+        // gets a vote on calls that didn't just catch up.
+        if !caught_up
+            && self
+                .policy
+                .should_rekey(self.messages_since_rekey, self.bytes_since_rekey)
+        {
+            self.rekey()?;
+        }
+
+        Ok(plaintext)
+    }
+
+    /// Ratchet the key forward one epoch and reset the counter. The
+    /// superseded key is pushed onto `old_keys` rather than zeroed
+    /// immediately, so a message still in flight under it can still
+    /// decrypt; once it falls off the back of the `RETAINED_OLD_EPOCHS`-deep
+    /// ring it is zeroed then.
+    fn rekey(&mut self) -> Result<()> {
+        let next_key = ratchet_key(self.suite, &self.key)?;
+        self.aead = AeadKey::new(self.suite, &next_key)?;
+        let retired_key = std::mem::replace(&mut self.key, next_key);
+        self.old_keys.push_front((self.epoch, retired_key));
+        while self.old_keys.len() > RETAINED_OLD_EPOCHS {
+            if let Some((_, mut stale_key)) = self.old_keys.pop_back() {
+                clear(&mut stale_key);
+            }
+        }
+        self.counter = 0;
+        self.messages_since_rekey = 0;
+        self.bytes_since_rekey = 0;
+        self.epoch = self.epoch.wrapping_add(1);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pair(policy: AeadSessionPolicy) -> (AeadSession, AeadSession) {
+        let key = [0x5Au8; 32];
+        let sender = AeadSession::new(CipherSuite::ChaCha20Poly1305, &key, policy).unwrap();
+        let receiver = AeadSession::new(CipherSuite::ChaCha20Poly1305, &key, policy).unwrap();
+        (sender, receiver)
+    }
+
+    #[test]
+    fn encrypt_decrypt_roundtrip() {
+        let (mut sender, mut receiver) = pair(AeadSessionPolicy::default());
+        let (epoch, counter, ct) = sender.encrypt(b"hello session", b"").unwrap();
+        let pt = receiver.decrypt(epoch, counter, &ct, b"").unwrap();
+        assert_eq!(pt, b"hello session");
+    }
+
+    #[test]
+    fn counter_advances_each_message() {
+        let (mut sender, _) = pair(AeadSessionPolicy::default());
+        assert_eq!(sender.counter(), 0);
+        sender.encrypt(b"a", b"").unwrap();
+        assert_eq!(sender.counter(), 1);
+        sender.encrypt(b"b", b"").unwrap();
+        assert_eq!(sender.counter(), 2);
+    }
+
+    #[test]
+    fn distinct_counters_give_distinct_ciphertext() {
+        let (mut sender, _) = pair(AeadSessionPolicy::default());
+        let (_, _, ct0) = sender.encrypt(b"same plaintext!!", b"").unwrap();
+        let (_, _, ct1) = sender.encrypt(b"same plaintext!!", b"").unwrap();
+        assert_ne!(ct0, ct1);
+    }
+
+    #[test]
+    fn automatic_rekey_after_message_budget() {
+        let policy = AeadSessionPolicy::new(2, u64::MAX);
+        let (mut sender, mut receiver) = pair(policy);
+
+        let (epoch0, counter0, ct0) = sender.encrypt(b"one", b"").unwrap();
+        let (epoch1, counter1, ct1) = sender.encrypt(b"two", b"").unwrap();
+        // The second message crossed the threshold, so the sender already
+        // rekeyed by the time encrypt() returns; the *next* call is epoch 1.
+        assert_eq!(epoch0, 0);
+        assert_eq!(epoch1, 0);
+        assert_eq!(sender.epoch(), 1);
+        assert_eq!(sender.counter(), 0);
+
+        receiver.decrypt(epoch0, counter0, &ct0, b"").unwrap();
+        receiver.decrypt(epoch1, counter1, &ct1, b"").unwrap();
+        assert_eq!(receiver.epoch(), 1);
+
+        // Peers rekeyed in lockstep: a fresh message under the new epoch
+        // still decrypts correctly.
+        let (epoch2, counter2, ct2) = sender.encrypt(b"three", b"").unwrap();
+        assert_eq!(epoch2, 1);
+        let pt2 = receiver.decrypt(epoch2, counter2, &ct2, b"").unwrap();
+        assert_eq!(pt2, b"three");
+    }
+
+    #[test]
+    fn automatic_rekey_after_byte_budget() {
+        let policy = AeadSessionPolicy::new(u64::MAX, 10);
+        let (mut sender, _) = pair(policy);
+
+        sender.encrypt(&[0u8; 10], b"").unwrap();
+        assert_eq!(sender.epoch(), 1);
+    }
+
+    #[test]
+    fn receiver_ratchets_ahead_on_higher_epoch_without_seeing_every_message() {
+        let (mut sender, mut receiver) = pair(AeadSessionPolicy::new(1, u64::MAX));
+
+        // Sender rekeys after every message; the receiver only ever sees
+        // the second message, so it must jump straight from epoch 0 to 1.
+        sender.encrypt(b"dropped", b"").unwrap();
+        let (epoch, counter, ct) = sender.encrypt(b"seen", b"").unwrap();
+        assert_eq!(epoch, 1);
+
+        let pt = receiver.decrypt(epoch, counter, &ct, b"").unwrap();
+        assert_eq!(pt, b"seen");
+        assert_eq!(receiver.epoch(), 1);
+    }
+
+    #[test]
+    fn late_arriving_message_from_previous_epoch_still_decrypts() {
+        let (mut sender, mut receiver) = pair(AeadSessionPolicy::new(1, u64::MAX));
+
+        let (epoch0, counter0, ct0) = sender.encrypt(b"one", b"").unwrap();
+        // Advance the receiver to epoch 1 via a second message.
+        let (epoch1, counter1, ct1) = sender.encrypt(b"two", b"").unwrap();
+        receiver.decrypt(epoch1, counter1, &ct1, b"").unwrap();
+
+        // The epoch-0 message arrives after the receiver already rekeyed --
+        // its key is retained in the ring, so it still decrypts.
+        let receiver_epoch_before = receiver.epoch();
+        let pt0 = receiver.decrypt(epoch0, counter0, &ct0, b"").unwrap();
+        assert_eq!(pt0, b"one");
+        // Decrypting a retained old-epoch message doesn't move the receiver.
+        assert_eq!(receiver.epoch(), receiver_epoch_before);
+    }
+
+    #[test]
+    fn epoch_older_than_retention_window_is_rejected() {
+        let (mut sender, mut receiver) = pair(AeadSessionPolicy::new(1, u64::MAX));
+
+        let (epoch0, counter0, ct0) = sender.encrypt(b"one", b"").unwrap();
+        // Rekey past RETAINED_OLD_EPOCHS generations so epoch 0 falls off
+        // the back of the ring.
+        for _ in 0..(RETAINED_OLD_EPOCHS + 1) {
+            let (epoch, counter, ct) = sender.encrypt(b"filler", b"").unwrap();
+            receiver.decrypt(epoch, counter, &ct, b"").unwrap();
+        }
+
+        let err = receiver.decrypt(epoch0, counter0, &ct0, b"");
+        assert!(matches!(err, Err(StrandTrustError::Decryption(_))));
+    }
+
+    #[test]
+    fn rekey_changes_ciphertext_for_same_counter_and_plaintext() {
+        let policy = AeadSessionPolicy::new(1, u64::MAX);
+        let (mut sender, _) = pair(policy);
+
+        let (_, counter0, ct0) = sender.encrypt(b"identical-plain!", b"").unwrap();
+        // Sender rekeyed and its counter reset, so this next message reuses
+        // counter 0 -- but under a different ratcheted key, so ciphertext
+        // still differs.
+        let (_, counter1, ct1) = sender.encrypt(b"identical-plain!", b"").unwrap();
+        assert_eq!(counter0, counter1);
+        assert_ne!(ct0, ct1);
+    }
+}
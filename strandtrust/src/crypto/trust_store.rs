@@ -0,0 +1,196 @@
+// Trust store: which peer identities a StrandTrust node is willing to
+// complete a handshake with.
+//
+// `derive_session_keys` turns a DH shared secret into traffic keys with no
+// opinion on whose shared secret it was — any peer willing to run the
+// handshake gets a session. `TrustStore` adds that missing authorization
+// step ahead of key derivation, in the two modes Noise-style deployments
+// commonly want: `Explicit`, an allowlist of Ed25519 public keys checked
+// against the peer's MIC issuer key; and `SharedSecret`, where a passphrase
+// deterministically derives both this node's keypair and the sole peer key
+// it trusts, so a fleet can mutually authenticate without ever exchanging
+// identities out of band.
+
+use std::collections::HashSet;
+
+use hkdf::Hkdf;
+use sha2::Sha256;
+
+use crate::crypto::keys::IdentityKeyPair;
+use crate::crypto::x25519::{derive_session_keys, SessionKeys};
+use crate::error::{Result, StrandTrustError};
+
+/// HKDF salt used to derive a shared-secret deployment's identity seed.
+const PSK_SALT: &[u8] = b"strand psk";
+
+/// HKDF info label for expanding the PSK identity seed.
+const PSK_IDENTITY_INFO: &[u8] = b"strand psk identity seed";
+
+/// Which peer Ed25519 public keys a node accepts a handshake from.
+pub enum TrustStore {
+    /// Authenticate the peer's public key against an explicit allowlist.
+    Explicit { trusted_keys: HashSet<[u8; 32]> },
+    /// Skip identity exchange entirely: this node's keypair and the one peer
+    /// key it trusts are both derived from a shared passphrase, so any two
+    /// nodes configured with the same passphrase mutually trust each other.
+    SharedSecret {
+        identity: IdentityKeyPair,
+        trusted_key: [u8; 32],
+    },
+}
+
+impl TrustStore {
+    /// Build an explicit-trust store from a set of authorized peer public keys.
+    pub fn explicit(trusted_keys: impl IntoIterator<Item = [u8; 32]>) -> Self {
+        Self::Explicit {
+            trusted_keys: trusted_keys.into_iter().collect(),
+        }
+    }
+
+    /// Build a shared-secret store: derive a deterministic identity keypair
+    /// and the sole trusted peer key from `passphrase` via
+    /// `HKDF-Extract(salt="strand psk", ikm=passphrase)`.
+    ///
+    /// Every node configured with the same passphrase derives the same
+    /// keypair, so `trusted_key` is simply that keypair's own public key —
+    /// any peer who can complete the DH handshake has proven they hold the
+    /// same passphrase.
+    pub fn shared_secret(passphrase: &[u8]) -> Result<Self> {
+        let hk = Hkdf::<Sha256>::new(Some(PSK_SALT), passphrase);
+        let mut seed = [0u8; 32];
+        hk.expand(PSK_IDENTITY_INFO, &mut seed)
+            .map_err(|e| StrandTrustError::Encryption(format!("HKDF expand error: {e}")))?;
+        let identity = IdentityKeyPair::from_seed(&seed);
+        let trusted_key = identity.public_key_bytes();
+        Ok(Self::SharedSecret {
+            identity,
+            trusted_key,
+        })
+    }
+
+    /// This node's own identity keypair, for `SharedSecret` mode where it is
+    /// derived from the passphrase rather than configured separately.
+    pub fn identity(&self) -> Option<&IdentityKeyPair> {
+        match self {
+            Self::Explicit { .. } => None,
+            Self::SharedSecret { identity, .. } => Some(identity),
+        }
+    }
+
+    /// Whether `pubkey` is an authorized peer identity.
+    ///
+    /// Checked in constant time (every candidate key is compared, with no
+    /// early exit) so handshake verification timing can't leak which, if
+    /// any, trusted key a probing peer is closest to.
+    pub fn is_trusted(&self, pubkey: &[u8; 32]) -> bool {
+        match self {
+            Self::Explicit { trusted_keys } => trusted_keys
+                .iter()
+                .fold(0u8, |acc, candidate| acc | ct_eq(candidate, pubkey))
+                != 0,
+            Self::SharedSecret { trusted_key, .. } => ct_eq(trusted_key, pubkey) != 0,
+        }
+    }
+}
+
+/// Constant-time byte comparison: ORs together the XOR of every byte pair
+/// instead of short-circuiting, so execution time doesn't depend on where
+/// (or whether) the inputs first differ. Returns nonzero iff equal.
+fn ct_eq(a: &[u8; 32], b: &[u8; 32]) -> u8 {
+    let diff = a.iter().zip(b.iter()).fold(0u8, |acc, (x, y)| acc | (x ^ y));
+    (diff == 0) as u8
+}
+
+/// Derive session keys from a DH shared secret, gated on the peer's public
+/// key being authorized by `trust`. This is [`derive_session_keys`] with the
+/// missing authorization step from the module doc comment: deployments that
+/// skip this and call `derive_session_keys` directly get working session
+/// keys with an unauthenticated peer.
+pub fn derive_session_keys_for_peer(
+    trust: &TrustStore,
+    peer_public_key: &[u8; 32],
+    shared_secret: &[u8; 32],
+    client_node_id: &[u8; 16],
+    server_node_id: &[u8; 16],
+) -> Result<SessionKeys> {
+    if !trust.is_trusted(peer_public_key) {
+        return Err(StrandTrustError::UntrustedPeer);
+    }
+    derive_session_keys(shared_secret, client_node_id, server_node_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn explicit_trusts_only_listed_keys() {
+        let trusted = IdentityKeyPair::generate();
+        let stranger = IdentityKeyPair::generate();
+        let store = TrustStore::explicit([trusted.public_key_bytes()]);
+
+        assert!(store.is_trusted(&trusted.public_key_bytes()));
+        assert!(!store.is_trusted(&stranger.public_key_bytes()));
+    }
+
+    #[test]
+    fn explicit_store_has_no_derived_identity() {
+        let store = TrustStore::explicit([[0u8; 32]]);
+        assert!(store.identity().is_none());
+    }
+
+    #[test]
+    fn shared_secret_is_deterministic_across_nodes() {
+        let a = TrustStore::shared_secret(b"fleet passphrase").unwrap();
+        let b = TrustStore::shared_secret(b"fleet passphrase").unwrap();
+
+        let a_identity = a.identity().unwrap();
+        let b_identity = b.identity().unwrap();
+        assert_eq!(a_identity.public_key_bytes(), b_identity.public_key_bytes());
+
+        // Each node trusts the other's (identical) derived key.
+        assert!(a.is_trusted(&b_identity.public_key_bytes()));
+        assert!(b.is_trusted(&a_identity.public_key_bytes()));
+    }
+
+    #[test]
+    fn shared_secret_differs_by_passphrase() {
+        let a = TrustStore::shared_secret(b"passphrase one").unwrap();
+        let b = TrustStore::shared_secret(b"passphrase two").unwrap();
+
+        assert_ne!(
+            a.identity().unwrap().public_key_bytes(),
+            b.identity().unwrap().public_key_bytes()
+        );
+        assert!(!a.is_trusted(&b.identity().unwrap().public_key_bytes()));
+    }
+
+    #[test]
+    fn derive_session_keys_for_peer_rejects_untrusted() {
+        let store = TrustStore::explicit([[0u8; 32]]);
+        let shared = [7u8; 32];
+        let result = derive_session_keys_for_peer(
+            &store,
+            &[1u8; 32],
+            &shared,
+            &[1u8; 16],
+            &[2u8; 16],
+        );
+        assert!(matches!(result, Err(StrandTrustError::UntrustedPeer)));
+    }
+
+    #[test]
+    fn derive_session_keys_for_peer_succeeds_for_trusted() {
+        let peer = IdentityKeyPair::generate();
+        let store = TrustStore::explicit([peer.public_key_bytes()]);
+        let shared = [7u8; 32];
+        let result = derive_session_keys_for_peer(
+            &store,
+            &peer.public_key_bytes(),
+            &shared,
+            &[1u8; 16],
+            &[2u8; 16],
+        );
+        assert!(result.is_ok());
+    }
+}
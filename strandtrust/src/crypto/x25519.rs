@@ -5,6 +5,7 @@ use rand::rngs::OsRng;
 use sha2::Sha256;
 use x25519_dalek::{PublicKey, StaticSecret};
 
+use crate::crypto::elligator2;
 use crate::error::{StrandTrustError, Result};
 
 /// An X25519 ephemeral keypair for one handshake.
@@ -44,6 +45,41 @@ impl X25519KeyPair {
         let shared = self.secret.diffie_hellman(&peer_pk);
         *shared.as_bytes()
     }
+
+    /// Generate a fresh ephemeral keypair whose public key has an Elligator2
+    /// representative, retrying with a new secret until one is found.
+    ///
+    /// Roughly half of all Curve25519 points are representable, so this loop
+    /// terminates after a handful of iterations in practice. Use this instead
+    /// of [`generate`](Self::generate) when the public key will be sent over
+    /// the wire as a representative (see [`public_key_representative`](Self::public_key_representative))
+    /// rather than as a raw point, to keep the handshake indistinguishable
+    /// from random noise.
+    pub fn generate_representable() -> Self {
+        loop {
+            let candidate = Self::generate();
+            if elligator2::point_to_representative(&candidate.public_key_bytes()).is_some() {
+                return candidate;
+            }
+        }
+    }
+
+    /// The Elligator2 representative of this keypair's public key.
+    ///
+    /// Returns `None` if this keypair was not produced by
+    /// [`generate_representable`](Self::generate_representable) (or otherwise
+    /// happens not to have a representative).
+    pub fn public_key_representative(&self) -> Option<[u8; 32]> {
+        elligator2::point_to_representative(&self.public_key_bytes())
+    }
+
+    /// Recover a peer's public key bytes from an Elligator2 representative
+    /// received over the wire.
+    ///
+    /// The result can be passed directly to [`diffie_hellman`](Self::diffie_hellman).
+    pub fn public_key_from_representative(representative: &[u8; 32]) -> [u8; 32] {
+        elligator2::representative_to_point(representative)
+    }
 }
 
 /// Session keys derived from the X25519 shared secret via HKDF.
@@ -52,6 +88,10 @@ pub struct SessionKeys {
     pub server_write_key: [u8; 32],
     pub client_write_iv: [u8; 12],
     pub server_write_iv: [u8; 12],
+    /// Generation counter, bumped each time [`SessionKeys::update`] ratchets
+    /// the traffic secrets forward. Carried on the wire (low bits only) so
+    /// the receiver knows which generation to decrypt a frame with.
+    pub key_epoch: u8,
 }
 
 /// Derive session keys from a shared secret following the StrandTrust spec (section 4.2).
@@ -115,6 +155,7 @@ pub fn derive_session_keys(
         server_write_key,
         client_write_iv,
         server_write_iv,
+        key_epoch: 0,
     })
 }
 
@@ -5,8 +5,10 @@
 
 use std::slice;
 
-use crate::crypto::aead::AeadCipher;
+use crate::crypto::aead::{AeadCipher, CipherSuite};
 use crate::crypto::keys::IdentityKeyPair;
+use crate::crypto::session::{AeadSession, AeadSessionPolicy};
+use crate::handshake::rate_limit::{HandshakeDecision, RateLimiter};
 use crate::mic::builder::MICBuilder;
 use crate::mic::serializer;
 use crate::mic::validator;
@@ -37,6 +39,43 @@ pub unsafe extern "C" fn nextrust_keypair_generate(
     0
 }
 
+/// Deterministically derive an Ed25519 keypair from an arbitrary shared
+/// secret (e.g. a human-memorable passphrase), via
+/// [`IdentityKeyPair::from_shared_secret`]. The same `secret` always yields
+/// the same keypair, enabling a "shared secret" provisioning mode — every
+/// node given the same secret mutually trusts the others via the common
+/// public key — and recoverable identities from a backup passphrase,
+/// without ever storing the private seed.
+///
+/// `secret_ptr`/`secret_len`: the shared secret bytes.
+/// `public_key_out`: pointer to 32-byte buffer for the public key.
+/// `secret_key_out`: pointer to 32-byte buffer for the secret key seed.
+///
+/// Returns 0 on success.
+#[no_mangle]
+pub unsafe extern "C" fn nextrust_keypair_from_secret(
+    secret_ptr: *const u8,
+    secret_len: usize,
+    public_key_out: *mut u8,
+    secret_key_out: *mut u8,
+) -> i32 {
+    if secret_ptr.is_null() || public_key_out.is_null() || secret_key_out.is_null() {
+        return -1;
+    }
+    let secret = unsafe { slice::from_raw_parts(secret_ptr, secret_len) };
+    let kp = match IdentityKeyPair::from_shared_secret(secret) {
+        Ok(kp) => kp,
+        Err(_) => return -1,
+    };
+    let pk = kp.public_key_bytes();
+    let sk = kp.secret_key_bytes();
+    unsafe {
+        std::ptr::copy_nonoverlapping(pk.as_ptr(), public_key_out, 32);
+        std::ptr::copy_nonoverlapping(sk.as_ptr(), secret_key_out, 32);
+    }
+    0
+}
+
 // ── MIC creation ─────────────────────────────────────────────────────────
 
 /// Create a self-signed MIC and write the serialized bytes to `mic_out`.
@@ -269,3 +308,257 @@ pub unsafe extern "C" fn nextrust_decrypt(
     }
     0
 }
+
+// ── Stateful AEAD session (auto nonce sequencing + rekeying) ────────────
+//
+// `nextrust_encrypt`/`nextrust_decrypt` above push nonce management onto
+// the caller, which is a catastrophic footgun with a counter-nonce suite
+// like ChaCha20-Poly1305. `nextrust_session_*` instead hands out an opaque
+// `AeadSession` (see `crate::crypto::session`) that derives nonces from its
+// own counter and rekeys itself automatically, so a C caller only ever
+// supplies plaintext/ciphertext and the wire-carried `(epoch, counter)`
+// pair -- it never touches a nonce directly.
+
+/// Create a new AEAD session from a 32-byte ChaCha20-Poly1305 key, using the
+/// default rekey policy. Returns a null pointer on error.
+///
+/// `key`: pointer to 32-byte key.
+///
+/// The returned session must be released with `nextrust_session_free`.
+#[no_mangle]
+pub unsafe extern "C" fn nextrust_session_new(key: *const u8) -> *mut AeadSession {
+    if key.is_null() {
+        return std::ptr::null_mut();
+    }
+    let key_slice = unsafe { slice::from_raw_parts(key, 32) };
+    match AeadSession::new(
+        CipherSuite::ChaCha20Poly1305,
+        key_slice,
+        AeadSessionPolicy::default(),
+    ) {
+        Ok(session) => Box::into_raw(Box::new(session)),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+/// Free a session created by `nextrust_session_new`. Safe to call with a
+/// null pointer.
+#[no_mangle]
+pub unsafe extern "C" fn nextrust_session_free(session: *mut AeadSession) {
+    if session.is_null() {
+        return;
+    }
+    unsafe {
+        drop(Box::from_raw(session));
+    }
+}
+
+/// Encrypt one message under `session`, advancing its internal counter (and
+/// rekeying it, if the policy threshold is crossed) as a side effect.
+///
+/// `plaintext`/`plaintext_len`: message to encrypt.
+/// `aad`/`aad_len`: optional associated data (`aad` may be null if `aad_len` is 0).
+/// `epoch_out`/`counter_out`: on success, the `(epoch, counter)` pair the
+/// caller must carry alongside the ciphertext on the wire, for `decrypt`.
+/// `ciphertext_out`/`ciphertext_out_len`: output buffer (must hold
+/// `plaintext_len` + 16); `ciphertext_out_len` is the capacity on input and
+/// the actual length on output.
+///
+/// Returns 0 on success, -1 on error.
+#[no_mangle]
+pub unsafe extern "C" fn nextrust_session_encrypt(
+    session: *mut AeadSession,
+    plaintext: *const u8,
+    plaintext_len: usize,
+    aad: *const u8,
+    aad_len: usize,
+    epoch_out: *mut u8,
+    counter_out: *mut u64,
+    ciphertext_out: *mut u8,
+    ciphertext_out_len: *mut usize,
+) -> i32 {
+    if session.is_null()
+        || plaintext.is_null()
+        || epoch_out.is_null()
+        || counter_out.is_null()
+        || ciphertext_out.is_null()
+        || ciphertext_out_len.is_null()
+    {
+        return -1;
+    }
+
+    let session = unsafe { &mut *session };
+    let pt = unsafe { slice::from_raw_parts(plaintext, plaintext_len) };
+    let aad_slice = if aad.is_null() || aad_len == 0 {
+        &[]
+    } else {
+        unsafe { slice::from_raw_parts(aad, aad_len) }
+    };
+
+    let (epoch, counter, ct) = match session.encrypt(pt, aad_slice) {
+        Ok(r) => r,
+        Err(_) => return -1,
+    };
+
+    let cap = unsafe { *ciphertext_out_len };
+    if ct.len() > cap {
+        return -1;
+    }
+    unsafe {
+        std::ptr::copy_nonoverlapping(ct.as_ptr(), ciphertext_out, ct.len());
+        *ciphertext_out_len = ct.len();
+        *epoch_out = epoch;
+        *counter_out = counter;
+    }
+    0
+}
+
+/// Decrypt one message under `session` at the given `(epoch, counter)`.
+/// If `epoch` is ahead of the session's own, it ratchets forward to match
+/// before decrypting. An `epoch` behind the session's own still decrypts if
+/// it is one of the two most recently superseded epochs, whose keys the
+/// session retains; anything older is rejected as stale.
+///
+/// `ciphertext`/`ciphertext_len`: ciphertext (includes the 16-byte tag).
+/// `aad`/`aad_len`: optional associated data (`aad` may be null if `aad_len` is 0).
+/// `plaintext_out`/`plaintext_out_len`: output buffer (must hold
+/// `ciphertext_len` - 16); `plaintext_out_len` is the capacity on input and
+/// the actual length on output.
+///
+/// Returns 0 on success, -1 on error.
+#[no_mangle]
+pub unsafe extern "C" fn nextrust_session_decrypt(
+    session: *mut AeadSession,
+    epoch: u8,
+    counter: u64,
+    ciphertext: *const u8,
+    ciphertext_len: usize,
+    aad: *const u8,
+    aad_len: usize,
+    plaintext_out: *mut u8,
+    plaintext_out_len: *mut usize,
+) -> i32 {
+    if session.is_null()
+        || ciphertext.is_null()
+        || plaintext_out.is_null()
+        || plaintext_out_len.is_null()
+    {
+        return -1;
+    }
+
+    let session = unsafe { &mut *session };
+    let ct = unsafe { slice::from_raw_parts(ciphertext, ciphertext_len) };
+    let aad_slice = if aad.is_null() || aad_len == 0 {
+        &[]
+    } else {
+        unsafe { slice::from_raw_parts(aad, aad_len) }
+    };
+
+    let pt = match session.decrypt(epoch, counter, ct, aad_slice) {
+        Ok(p) => p,
+        Err(_) => return -1,
+    };
+
+    let cap = unsafe { *plaintext_out_len };
+    if pt.len() > cap {
+        return -1;
+    }
+    unsafe {
+        std::ptr::copy_nonoverlapping(pt.as_ptr(), plaintext_out, pt.len());
+        *plaintext_out_len = pt.len();
+    }
+    0
+}
+
+// ── Handshake rate limiting (cookie-based DoS mitigation) ───────────────
+//
+// `nextrust_handshake_init` generates an ephemeral X25519 keypair at no
+// cost to the caller, so a responder that processes every `HandshakeInit`
+// it receives is trivially flooded. `nextrust_rate_limiter_*` exposes
+// `RateLimiter` (see `crate::handshake::rate_limit`) so a responder can
+// check each initiation before doing any key agreement.
+//
+// Return value (distinct from the 0/-1 convention above, since a rejected
+// or challenged handshake is an expected outcome, not an FFI misuse error):
+//   0 = proceed with the handshake
+//   1 = challenge the initiator; `cookie_out` holds the 16-byte cookie
+//   2 = drop the initiation
+//  -1 = invalid arguments (null pointer, bad buffer)
+
+/// Create a new rate limiter with the default policy.
+///
+/// `now`: current unix timestamp (seconds), used to seed the cookie
+/// secret's rotation clock and the global token bucket (each per-source
+/// bucket is seeded at that source's first `admit`).
+///
+/// The returned rate limiter must be released with
+/// `nextrust_rate_limiter_free`.
+#[no_mangle]
+pub unsafe extern "C" fn nextrust_rate_limiter_new(now: u64) -> *mut RateLimiter {
+    Box::into_raw(Box::new(RateLimiter::new(now)))
+}
+
+/// Free a rate limiter created by `nextrust_rate_limiter_new`. Safe to call
+/// with a null pointer.
+#[no_mangle]
+pub unsafe extern "C" fn nextrust_rate_limiter_free(limiter: *mut RateLimiter) {
+    if limiter.is_null() {
+        return;
+    }
+    unsafe {
+        drop(Box::from_raw(limiter));
+    }
+}
+
+/// Decide what to do with an incoming `HandshakeInit`.
+///
+/// `source_id`/`source_id_len`: caller-chosen identifier for the peer (e.g.
+/// a hash of the UDP source address).
+/// `message`/`message_len`: the serialized handshake message `mac2` (if
+/// any) was computed over.
+/// `mac2`: pointer to a 16-byte MAC if this is a retry after a prior
+/// challenge, or null for a first attempt.
+/// `cookie_out`: pointer to a 16-byte buffer, written only when this
+/// returns 1 (challenge).
+/// `now`: current unix timestamp (seconds).
+///
+/// Returns 0 (proceed), 1 (challenge), 2 (drop), or -1 on invalid
+/// arguments.
+#[no_mangle]
+pub unsafe extern "C" fn nextrust_rate_limiter_admit(
+    limiter: *mut RateLimiter,
+    source_id: *const u8,
+    source_id_len: usize,
+    message: *const u8,
+    message_len: usize,
+    mac2: *const u8,
+    cookie_out: *mut u8,
+    now: u64,
+) -> i32 {
+    if limiter.is_null() || source_id.is_null() || message.is_null() || cookie_out.is_null() {
+        return -1;
+    }
+
+    let limiter = unsafe { &mut *limiter };
+    let source_id = unsafe { slice::from_raw_parts(source_id, source_id_len) };
+    let message = unsafe { slice::from_raw_parts(message, message_len) };
+    let mac2_arr = if mac2.is_null() {
+        None
+    } else {
+        let mac2_slice = unsafe { slice::from_raw_parts(mac2, 16) };
+        let mut arr = [0u8; 16];
+        arr.copy_from_slice(mac2_slice);
+        Some(arr)
+    };
+
+    match limiter.admit(source_id, message, mac2_arr.as_ref(), now) {
+        HandshakeDecision::Proceed => 0,
+        HandshakeDecision::Challenge { cookie } => {
+            unsafe {
+                std::ptr::copy_nonoverlapping(cookie.as_ptr(), cookie_out, 16);
+            }
+            1
+        }
+        HandshakeDecision::Drop => 2,
+    }
+}
@@ -1,5 +1,7 @@
 // Handshake messages exchanged during the StrandTrust 1-RTT protocol.
 
+use crate::crypto::resumption::ResumptionToken;
+use crate::crypto::retry_token::RetryToken;
 use crate::mic::MIC;
 
 /// Message 1: Initiator -> Responder (TRUST_HELLO).
@@ -9,6 +11,25 @@ pub struct HandshakeInit {
     pub ephemeral_pub: [u8; 32],
     /// Initiator's MIC (proving identity and capabilities).
     pub initiator_mic: MIC,
+    /// Present when this TRUST_HELLO is a 0-RTT resumption attempt (see
+    /// [`crate::handshake::protocol::Initiator::create_init_resuming`]).
+    pub resumption: Option<ResumptionAttempt>,
+    /// Present when this TRUST_HELLO is echoing back a [`RetryToken`] the
+    /// responder issued in response to an earlier, unvalidated TRUST_HELLO
+    /// (see [`crate::handshake::protocol::Responder::set_require_address_validation`]).
+    pub retry_token: Option<RetryToken>,
+}
+
+/// A 0-RTT resumption attempt riding along with a [`HandshakeInit`]: a
+/// previously issued [`ResumptionToken`] plus early application data sealed
+/// under a key derived from the resumed master secret.
+#[derive(Debug, Clone)]
+pub struct ResumptionAttempt {
+    /// The token the responder issued after the original handshake.
+    pub token: ResumptionToken,
+    /// Early application data, AEAD-sealed under a key derived from
+    /// `crypto::key_schedule::derive_early_data_key`.
+    pub early_data: Vec<u8>,
 }
 
 /// Message 2: Responder -> Initiator (TRUST_ACCEPT).
@@ -18,15 +39,16 @@ pub struct HandshakeResponse {
     pub ephemeral_pub: [u8; 32],
     /// Responder's MIC.
     pub responder_mic: MIC,
-    /// Encrypted payload (e.g., server_finished confirmation, encrypted with
-    /// the server_write_key derived from the DH shared secret).
-    pub encrypted_payload: Vec<u8>,
+    /// `HMAC(server_finished_secret, transcript_hash)` -- proves the
+    /// responder derived the same key schedule over the same transcript,
+    /// per [`crate::crypto::key_schedule::KeySchedule`].
+    pub server_finished_mac: [u8; 32],
 }
 
 /// Message 3: Initiator -> Responder (TRUST_FINISH).
 #[derive(Debug, Clone)]
 pub struct HandshakeComplete {
-    /// Encrypted payload (e.g., client_finished confirmation, encrypted with
-    /// the client_write_key derived from the DH shared secret).
-    pub encrypted_payload: Vec<u8>,
+    /// `HMAC(client_finished_secret, transcript_hash)`, the initiator's
+    /// half of the Finished exchange.
+    pub client_finished_mac: [u8; 32],
 }
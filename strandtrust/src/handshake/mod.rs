@@ -0,0 +1,6 @@
+// Handshake module declarations.
+
+pub mod messages;
+pub mod protocol;
+pub mod rate_limit;
+pub mod state;
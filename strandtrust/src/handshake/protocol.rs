@@ -0,0 +1,1245 @@
+// StrandTrust handshake protocol: 3-message exchange with X25519 DH + HKDF session keys.
+//
+//   Initiator                         Responder
+//     |--- HandshakeInit ------->|
+//     |<-- HandshakeResponse ----|
+//     |--- HandshakeComplete --->|
+//     |==== encrypted channel ===|
+
+use crate::crypto::aead::{AeadKey, CipherSuite};
+use crate::crypto::hash::hash_sha256;
+use crate::crypto::key_schedule::{derive_early_data_key, KeySchedule};
+use crate::crypto::keys::{derive_node_id, IdentityKeyPair};
+use crate::crypto::resumption::{ResumptionTicket, ResumptionTicketKey, ResumptionToken};
+use crate::crypto::retry_token::{RetryToken, RetryTokenKey, DEFAULT_RETRY_TOKEN_LIFETIME_SECS};
+use crate::crypto::trust_store::TrustStore;
+use crate::crypto::x25519::X25519KeyPair;
+use crate::error::{Result, StrandTrustError};
+use crate::handshake::messages::{
+    HandshakeComplete, HandshakeInit, HandshakeResponse, ResumptionAttempt,
+};
+use crate::handshake::state::HandshakeState;
+use crate::mic::serializer;
+use crate::mic::validator::validate;
+use crate::mic::MIC;
+use crate::transport::Obfuscator;
+use std::collections::{HashMap, VecDeque};
+
+/// The cipher suite the handshake's own key schedule is derived for. Once
+/// suite negotiation lands on the wire this should come from the peers'
+/// advertised lists (see [`CipherSuite::benchmark_preference`]); until then
+/// every handshake uses the suite the original protocol shipped with.
+const HANDSHAKE_SUITE: CipherSuite = CipherSuite::ChaCha20Poly1305;
+
+/// Fixed nonce for 0-RTT early-data sealing: safe only because every key it's
+/// used under (`derive_early_data_key`'s output) is itself single-use, bound
+/// to one specific resuming TRUST_HELLO's fresh ephemeral public key.
+const EARLY_DATA_NONCE: [u8; 12] = [0u8; 12];
+const EARLY_DATA_AAD: &[u8] = b"strand1 early data";
+
+/// Derive the 16-byte node ID from a MIC's node_id field (which holds the 32-byte public key).
+/// This matches the IdentityKeyPair::node_id() derivation: first 16 bytes of SHA-256(pubkey).
+fn node_id_from_mic(mic: &MIC) -> [u8; 16] {
+    use ed25519_dalek::VerifyingKey;
+    // mic.node_id stores the raw 32-byte Ed25519 public key
+    if let Ok(vk) = VerifyingKey::from_bytes(&mic.node_id) {
+        derive_node_id(&vk)
+    } else {
+        // Fallback: just truncate (shouldn't happen with valid MICs)
+        let mut id = [0u8; 16];
+        id.copy_from_slice(&mic.node_id[..16]);
+        id
+    }
+}
+
+/// SHA-256 fingerprint of a MIC's full wire encoding, bound into a
+/// [`ResumptionTicket`] at issuance time so a resumption attempt can be
+/// checked against the *same* identity the ticket was issued to, not merely
+/// any identity that still validates.
+fn mic_fingerprint(mic: &MIC) -> [u8; 32] {
+    hash_sha256(&serializer::serialize(mic))
+}
+
+/// SHA-256 transcript hash over everything exchanged in TRUST_HELLO and
+/// TRUST_ACCEPT: both ephemeral public keys and both MICs, in a fixed
+/// client-then-server order so both peers compute the same value. Feeding
+/// this into [`KeySchedule::derive`] means a reordered or substituted
+/// handshake message changes every downstream key, not just the ones that
+/// reference the tampered field directly.
+fn transcript_hash(
+    client_ephemeral_pub: &[u8; 32],
+    server_ephemeral_pub: &[u8; 32],
+    client_mic: &MIC,
+    server_mic: &MIC,
+) -> [u8; 32] {
+    let mut transcript = Vec::new();
+    transcript.extend_from_slice(client_ephemeral_pub);
+    transcript.extend_from_slice(server_ephemeral_pub);
+    transcript.extend_from_slice(&serializer::serialize(client_mic));
+    transcript.extend_from_slice(&serializer::serialize(server_mic));
+    hash_sha256(&transcript)
+}
+
+// ── Initiator ────────────────────────────────────────────────────────────
+
+/// Client-side (initiator) of the StrandTrust handshake.
+pub struct Initiator {
+    #[allow(dead_code)]
+    identity: IdentityKeyPair,
+    mic: MIC,
+    state: HandshakeState,
+    trust_store: Option<TrustStore>,
+    obfuscator: Option<Box<dyn Obfuscator>>,
+}
+
+impl Initiator {
+    /// Create a new initiator with the given identity and MIC. The
+    /// responder's identity is accepted unconditionally; use
+    /// [`Initiator::with_trust_store`] to only complete handshakes against a
+    /// configured set of trusted peers.
+    pub fn new(identity: IdentityKeyPair, mic: MIC) -> Self {
+        Self {
+            identity,
+            mic,
+            state: HandshakeState::Idle,
+            trust_store: None,
+            obfuscator: None,
+        }
+    }
+
+    /// Create a new initiator that only accepts a [`HandshakeResponse`] from
+    /// a peer whose MIC-embedded identity key is authorized by `trust_store`
+    /// (see [`crate::crypto::trust_store::TrustStore`] for the explicit-peer
+    /// and shared-secret modes).
+    pub fn with_trust_store(identity: IdentityKeyPair, mic: MIC, trust_store: TrustStore) -> Self {
+        Self {
+            identity,
+            mic,
+            state: HandshakeState::Idle,
+            trust_store: Some(trust_store),
+            obfuscator: None,
+        }
+    }
+
+    /// Obfuscate ephemeral public keys on the wire via `obfuscator` (see
+    /// [`crate::transport::Obfuscator`]), e.g. to resist DPI fingerprinting
+    /// of the handshake. Disabled by default: public keys travel as raw
+    /// X25519 points.
+    pub fn set_obfuscator(&mut self, obfuscator: Option<Box<dyn Obfuscator>>) {
+        self.obfuscator = obfuscator;
+    }
+
+    /// Wire encoding of an ephemeral public key, per the configured
+    /// [`Obfuscator`] if any.
+    fn encode_ephemeral(&self, keypair: &X25519KeyPair) -> [u8; 32] {
+        match &self.obfuscator {
+            Some(obfuscator) => obfuscator.encode_public_key(keypair),
+            None => keypair.public_key_bytes(),
+        }
+    }
+
+    /// Raw curve point behind a peer's wire-encoded ephemeral public key, per
+    /// the configured [`Obfuscator`] if any.
+    fn decode_peer_ephemeral(&self, wire: &[u8; 32]) -> [u8; 32] {
+        match &self.obfuscator {
+            Some(obfuscator) => obfuscator.decode_public_key(wire),
+            None => *wire,
+        }
+    }
+
+    /// Generate a fresh ephemeral X25519 keypair for this handshake attempt.
+    /// When an [`Obfuscator`] is configured, retries (like
+    /// [`X25519KeyPair::generate_representable`]) until the public key has an
+    /// Elligator2 representative, so `encode_ephemeral` never has to fall
+    /// back to sending the raw point.
+    fn generate_ephemeral(&self) -> ([u8; 32], X25519KeyPair) {
+        loop {
+            let secret_bytes = {
+                use rand::RngCore;
+                let mut secret = [0u8; 32];
+                rand::rngs::OsRng.fill_bytes(&mut secret);
+                secret
+            };
+            let keypair = X25519KeyPair::from_secret_bytes(secret_bytes);
+            if self.obfuscator.is_none() || keypair.public_key_representative().is_some() {
+                return (secret_bytes, keypair);
+            }
+        }
+    }
+
+    /// Step 1: Generate TRUST_HELLO (HandshakeInit).
+    pub fn create_init(&mut self) -> Result<HandshakeInit> {
+        if !matches!(self.state, HandshakeState::Idle) {
+            return Err(StrandTrustError::InvalidStateTransition {
+                from: self.state.label().into(),
+                to: "InitSent".into(),
+            });
+        }
+
+        // Generate ephemeral X25519 keypair. We keep the raw secret bytes so we can
+        // perform DH once the response arrives (StaticSecret round-trips; EphemeralSecret
+        // does not, hence from_secret_bytes rather than a one-shot generate()).
+        let (secret_bytes, ephemeral) = self.generate_ephemeral();
+        let ephemeral_pub = ephemeral.public_key_bytes();
+        let wire_pub = self.encode_ephemeral(&ephemeral);
+
+        self.state = HandshakeState::InitSent {
+            ephemeral_secret: secret_bytes,
+            ephemeral_public: ephemeral_pub,
+        };
+
+        Ok(HandshakeInit {
+            ephemeral_pub: wire_pub,
+            initiator_mic: self.mic.clone(),
+            resumption: None,
+            retry_token: None,
+        })
+    }
+
+    /// Retry [`Initiator::create_init`] after the responder challenged the
+    /// first attempt with a [`RetryToken`] (see
+    /// [`Responder::set_require_address_validation`]), echoing the token
+    /// back on a fresh TRUST_HELLO. Generates a new ephemeral keypair just
+    /// like the original attempt -- the responder never kept any state from
+    /// it, so there is nothing to resume.
+    pub fn create_init_with_retry_token(&mut self, token: RetryToken) -> Result<HandshakeInit> {
+        if !matches!(self.state, HandshakeState::Idle | HandshakeState::InitSent { .. }) {
+            return Err(StrandTrustError::InvalidStateTransition {
+                from: self.state.label().into(),
+                to: "InitSent".into(),
+            });
+        }
+
+        let (secret_bytes, ephemeral) = self.generate_ephemeral();
+        let ephemeral_pub = ephemeral.public_key_bytes();
+        let wire_pub = self.encode_ephemeral(&ephemeral);
+
+        self.state = HandshakeState::InitSent {
+            ephemeral_secret: secret_bytes,
+            ephemeral_public: ephemeral_pub,
+        };
+
+        Ok(HandshakeInit {
+            ephemeral_pub: wire_pub,
+            initiator_mic: self.mic.clone(),
+            resumption: None,
+            retry_token: Some(token),
+        })
+    }
+
+    /// Like [`Initiator::create_init`], but attempts 0-RTT resumption of a
+    /// previously completed session: `token` is the [`ResumptionToken`] that
+    /// session's responder issued (see
+    /// [`Responder::issue_resumption_token`]), and `master_secret` is that
+    /// session's own `KeySchedule::resumption_master_secret()` (the
+    /// initiator's own copy -- the token's copy is opaque to us).
+    /// `early_data` is sealed under a key derived from `master_secret` and
+    /// sent alongside the token; the fresh ephemeral DH exchange still runs
+    /// underneath and rekeys the session forward-securely regardless of
+    /// whether the responder accepts the resumption.
+    ///
+    /// Because the responder may process the same sealed `early_data` more
+    /// than once (a retransmitted or replayed TRUST_HELLO has no way to be
+    /// told apart from a fresh one), `idempotent` must be `true` -- callers
+    /// must only pass data that is safe to apply repeatedly.
+    pub fn create_init_resuming(
+        &mut self,
+        token: ResumptionToken,
+        master_secret: [u8; 32],
+        early_data: &[u8],
+        idempotent: bool,
+    ) -> Result<HandshakeInit> {
+        if !idempotent {
+            return Err(StrandTrustError::Handshake(
+                "0-RTT early data is replayable; pass idempotent = true only for requests safe to process more than once".into(),
+            ));
+        }
+        if !matches!(self.state, HandshakeState::Idle) {
+            return Err(StrandTrustError::InvalidStateTransition {
+                from: self.state.label().into(),
+                to: "Resuming".into(),
+            });
+        }
+
+        let (secret_bytes, ephemeral) = self.generate_ephemeral();
+        let ephemeral_pub = ephemeral.public_key_bytes();
+        let wire_pub = self.encode_ephemeral(&ephemeral);
+
+        // The early-data key is derived from the raw ephemeral point, not its
+        // wire encoding, so it matches what `Responder::try_accept_resumption`
+        // derives after decoding the same point back out of the wire message.
+        let early_key = derive_early_data_key(&master_secret, &ephemeral_pub)?;
+        let sealed_early_data = AeadKey::new(HANDSHAKE_SUITE, &early_key)?
+            .encrypt(&EARLY_DATA_NONCE, early_data, EARLY_DATA_AAD)?;
+
+        self.state = HandshakeState::Resuming {
+            ephemeral_secret: secret_bytes,
+            ephemeral_public: ephemeral_pub,
+            resumed_master_secret: master_secret,
+        };
+
+        Ok(HandshakeInit {
+            ephemeral_pub: wire_pub,
+            initiator_mic: self.mic.clone(),
+            resumption: Some(ResumptionAttempt {
+                token,
+                early_data: sealed_early_data,
+            }),
+            retry_token: None,
+        })
+    }
+
+    /// Step 2 (initiator side): Process TRUST_ACCEPT, produce TRUST_FINISH.
+    pub fn process_response(
+        &mut self,
+        response: HandshakeResponse,
+        now: u64,
+    ) -> Result<HandshakeComplete> {
+        let (ephemeral_secret, ephemeral_public) = match &self.state {
+            HandshakeState::InitSent {
+                ephemeral_secret,
+                ephemeral_public,
+            } => (*ephemeral_secret, *ephemeral_public),
+            HandshakeState::Resuming {
+                ephemeral_secret,
+                ephemeral_public,
+                ..
+            } => (*ephemeral_secret, *ephemeral_public),
+            _ => {
+                return Err(StrandTrustError::InvalidStateTransition {
+                    from: self.state.label().into(),
+                    to: "ResponseReceived".into(),
+                });
+            }
+        };
+
+        // Validate responder's MIC
+        validate(&response.responder_mic, now)?;
+
+        // Reject responders outside the configured trust anchor, if any.
+        if let Some(trust) = &self.trust_store {
+            if !trust.is_trusted(&response.responder_mic.node_id) {
+                return Err(StrandTrustError::UntrustedPeer);
+            }
+        }
+
+        // Decode the responder's ephemeral public key out of its wire
+        // encoding immediately, before any other use -- everything
+        // downstream (DH, transcript hashing) operates on the raw point.
+        let responder_ephemeral_pub = self.decode_peer_ephemeral(&response.ephemeral_pub);
+
+        // Perform DH
+        let our_ephemeral = X25519KeyPair::from_secret_bytes(ephemeral_secret);
+        let shared_secret = our_ephemeral.diffie_hellman(&responder_ephemeral_pub);
+
+        let transcript = transcript_hash(
+            &ephemeral_public,
+            &responder_ephemeral_pub,
+            &self.mic,
+            &response.responder_mic,
+        );
+        let keys = KeySchedule::derive(&shared_secret, &transcript, HANDSHAKE_SUITE)?;
+
+        // Verify the responder's Finished MAC before trusting the channel.
+        keys.verify_server_finished(&transcript, &response.server_finished_mac)
+            .map_err(|_| StrandTrustError::Handshake("invalid server finished MAC".into()))?;
+
+        let client_finished_mac = keys.client_finished_mac(&transcript);
+
+        self.state = HandshakeState::Complete {
+            keys,
+            peer_mic: response.responder_mic,
+            my_mic: self.mic.clone(),
+        };
+
+        Ok(HandshakeComplete { client_finished_mac })
+    }
+
+    /// Get the completed handshake state (directional traffic keys and peer MIC).
+    pub fn completed_state(&self) -> Option<(&KeySchedule, &MIC)> {
+        match &self.state {
+            HandshakeState::Complete { keys, peer_mic, .. } => Some((keys, peer_mic)),
+            _ => None,
+        }
+    }
+
+    /// The completed session's resumption master secret, to keep alongside a
+    /// [`ResumptionToken`] received out-of-band for a future
+    /// [`Initiator::create_init_resuming`] call.
+    pub fn resumption_master_secret(&self) -> Option<[u8; 32]> {
+        match &self.state {
+            HandshakeState::Complete { keys, .. } => Some(*keys.resumption_master_secret()),
+            _ => None,
+        }
+    }
+}
+
+// ── Responder ────────────────────────────────────────────────────────────
+
+/// Upper bound on [`Responder::sessions`]. `source_addr` is caller-supplied
+/// (typically the UDP source address) and therefore spoofable, so without a
+/// cap a flood of `HandshakeInit`s from distinct addresses would grow the map
+/// -- and the live key-schedule/transcript state each entry holds -- without
+/// limit. Once full, the oldest session (by insertion, tracked in
+/// `session_order`) is evicted to make room, the same sliding-window
+/// eviction `ProbabilisticReceiver` uses for its FEC groups.
+const MAX_RESPONDER_SESSIONS: usize = 4096;
+
+/// Server-side (responder) of the StrandTrust handshake.
+///
+/// A single long-lived `Responder` fields every incoming connection for a
+/// process, so -- unlike `Initiator`, which only ever tracks one handshake
+/// at a time -- it keeps one [`ResponderSession`] per peer in `sessions`,
+/// keyed by the `source_addr` each method already takes. This is also what
+/// lets a peer reconnect (plain or 0-RTT resumed) from the same address
+/// after a previous connection reached `HandshakeState::Complete`: the new
+/// `process_init` simply starts a fresh session over the old one.
+///
+/// `sessions` is bounded at [`MAX_RESPONDER_SESSIONS`] (see
+/// `session_order`), since `source_addr` is attacker-controlled and
+/// otherwise a flood of distinct addresses would grow it forever.
+pub struct Responder {
+    #[allow(dead_code)]
+    identity: IdentityKeyPair,
+    mic: MIC,
+    sessions: HashMap<Vec<u8>, ResponderSession>,
+    /// `sessions` keys in insertion order, for sliding-window eviction once
+    /// `MAX_RESPONDER_SESSIONS` is reached.
+    session_order: VecDeque<Vec<u8>>,
+    trust_store: Option<TrustStore>,
+    resumption_ticket_key: Option<ResumptionTicketKey>,
+    resumption_ticket_lifetime_secs: u64,
+    require_address_validation: bool,
+    retry_token_key: Option<RetryTokenKey>,
+    retry_token_lifetime_secs: u64,
+    obfuscator: Option<Box<dyn Obfuscator>>,
+}
+
+/// One peer's handshake progress within a [`Responder`], keyed by that
+/// peer's observed source address in `Responder::sessions`.
+#[derive(Debug)]
+struct ResponderSession {
+    state: HandshakeState,
+    /// Early application data decrypted from this connection's most recent
+    /// 0-RTT resumption attempt, if any (see [`Responder::take_early_data`]).
+    pending_early_data: Option<Vec<u8>>,
+}
+
+impl Default for ResponderSession {
+    fn default() -> Self {
+        Self {
+            state: HandshakeState::Idle,
+            pending_early_data: None,
+        }
+    }
+}
+
+/// What a [`Responder`] wants to do with a `HandshakeInit`, returned from
+/// [`Responder::process_init`].
+#[derive(Debug)]
+pub enum ProcessInitOutcome {
+    /// The handshake proceeded normally; send this TRUST_ACCEPT back.
+    Response(HandshakeResponse),
+    /// Address validation is enabled and this TRUST_HELLO didn't carry a
+    /// valid [`RetryToken`]; send this token back and wait for the
+    /// initiator to retry with it echoed (see
+    /// [`Initiator::create_init_with_retry_token`]).
+    Retry(RetryToken),
+}
+
+impl Responder {
+    /// Create a new responder with the given identity and MIC. Any initiator
+    /// whose MIC passes validation is accepted; use
+    /// [`Responder::with_trust_store`] to additionally require the
+    /// initiator's identity key be in a configured trust anchor. 0-RTT
+    /// resumption is disabled until [`Responder::set_resumption_ticket_key`]
+    /// is called.
+    pub fn new(identity: IdentityKeyPair, mic: MIC) -> Self {
+        Self {
+            identity,
+            mic,
+            sessions: HashMap::new(),
+            session_order: VecDeque::new(),
+            trust_store: None,
+            resumption_ticket_key: None,
+            resumption_ticket_lifetime_secs: crate::crypto::resumption::DEFAULT_TICKET_LIFETIME_SECS,
+            require_address_validation: false,
+            retry_token_key: None,
+            retry_token_lifetime_secs: DEFAULT_RETRY_TOKEN_LIFETIME_SECS,
+            obfuscator: None,
+        }
+    }
+
+    /// Create a new responder that only accepts a [`HandshakeInit`] from a
+    /// peer whose MIC-embedded identity key is authorized by `trust_store`
+    /// (see [`crate::crypto::trust_store::TrustStore`] for the explicit-peer
+    /// and shared-secret modes).
+    pub fn with_trust_store(identity: IdentityKeyPair, mic: MIC, trust_store: TrustStore) -> Self {
+        Self {
+            identity,
+            mic,
+            sessions: HashMap::new(),
+            session_order: VecDeque::new(),
+            trust_store: Some(trust_store),
+            resumption_ticket_key: None,
+            resumption_ticket_lifetime_secs: crate::crypto::resumption::DEFAULT_TICKET_LIFETIME_SECS,
+            require_address_validation: false,
+            retry_token_key: None,
+            retry_token_lifetime_secs: DEFAULT_RETRY_TOKEN_LIFETIME_SECS,
+            obfuscator: None,
+        }
+    }
+
+    /// Obfuscate ephemeral public keys on the wire via `obfuscator` (see
+    /// [`crate::transport::Obfuscator`]), e.g. to resist DPI fingerprinting
+    /// of the handshake. Disabled by default: public keys travel as raw
+    /// X25519 points.
+    pub fn set_obfuscator(&mut self, obfuscator: Option<Box<dyn Obfuscator>>) {
+        self.obfuscator = obfuscator;
+    }
+
+    /// Wire encoding of an ephemeral public key, per the configured
+    /// [`Obfuscator`] if any.
+    fn encode_ephemeral(&self, keypair: &X25519KeyPair) -> [u8; 32] {
+        match &self.obfuscator {
+            Some(obfuscator) => obfuscator.encode_public_key(keypair),
+            None => keypair.public_key_bytes(),
+        }
+    }
+
+    /// Raw curve point behind a peer's wire-encoded ephemeral public key, per
+    /// the configured [`Obfuscator`] if any.
+    fn decode_peer_ephemeral(&self, wire: &[u8; 32]) -> [u8; 32] {
+        match &self.obfuscator {
+            Some(obfuscator) => obfuscator.decode_public_key(wire),
+            None => *wire,
+        }
+    }
+
+    /// Generate a fresh ephemeral X25519 keypair for this handshake attempt.
+    /// When an [`Obfuscator`] is configured, retries (like
+    /// [`X25519KeyPair::generate_representable`]) until the public key has an
+    /// Elligator2 representative, so `encode_ephemeral` never has to fall
+    /// back to sending the raw point.
+    fn generate_ephemeral(&self) -> ([u8; 32], X25519KeyPair) {
+        loop {
+            let secret_bytes = {
+                use rand::RngCore;
+                let mut secret = [0u8; 32];
+                rand::rngs::OsRng.fill_bytes(&mut secret);
+                secret
+            };
+            let keypair = X25519KeyPair::from_secret_bytes(secret_bytes);
+            if self.obfuscator.is_none() || keypair.public_key_representative().is_some() {
+                return (secret_bytes, keypair);
+            }
+        }
+    }
+
+    /// Require a [`RetryToken`] address-validation round before processing
+    /// any TRUST_HELLO: the first attempt from a given source gets back a
+    /// token instead of a TRUST_ACCEPT, and only a retried TRUST_HELLO
+    /// echoing that token runs the actual DH and key derivation. Disabled by
+    /// default, since it costs every legitimate initiator an extra round
+    /// trip.
+    pub fn set_require_address_validation(&mut self, enabled: bool) {
+        self.require_address_validation = enabled;
+    }
+
+    /// Override the default retry token freshness window (see
+    /// [`crate::crypto::retry_token::DEFAULT_RETRY_TOKEN_LIFETIME_SECS`]).
+    pub fn set_retry_token_lifetime_secs(&mut self, lifetime_secs: u64) {
+        self.retry_token_lifetime_secs = lifetime_secs;
+    }
+
+    /// Enable issuing and honoring 0-RTT [`ResumptionToken`]s, sealed/opened
+    /// under `key`. Without this, a [`HandshakeInit`] carrying a resumption
+    /// attempt falls back to a plain 1-RTT handshake -- the attempt's token
+    /// and early data are simply ignored.
+    pub fn set_resumption_ticket_key(&mut self, key: ResumptionTicketKey) {
+        self.resumption_ticket_key = Some(key);
+    }
+
+    /// Override the default resumption ticket lifetime (see
+    /// [`crate::crypto::resumption::DEFAULT_TICKET_LIFETIME_SECS`]).
+    pub fn set_resumption_ticket_lifetime_secs(&mut self, lifetime_secs: u64) {
+        self.resumption_ticket_lifetime_secs = lifetime_secs;
+    }
+
+    /// Early application data decrypted from `source_addr`'s 0-RTT
+    /// resumption attempt during its most recent [`Responder::process_init`],
+    /// if any. Takes the value: it's only meaningful once per handshake.
+    pub fn take_early_data(&mut self, source_addr: &[u8]) -> Option<Vec<u8>> {
+        self.sessions.get_mut(source_addr)?.pending_early_data.take()
+    }
+
+    /// Seal a [`ResumptionToken`] the now-authenticated peer can present to
+    /// [`Initiator::create_init_resuming`] on a future reconnect. Only
+    /// available once `source_addr`'s handshake has actually completed
+    /// ([`Responder::process_complete`]), since only then has the peer
+    /// proven -- via its Finished MAC -- that it holds the master secret the
+    /// ticket lets it resume.
+    pub fn issue_resumption_token(&self, source_addr: &[u8], now: u64) -> Result<ResumptionToken> {
+        let (keys, peer_mic) = match self.sessions.get(source_addr).map(|session| &session.state) {
+            Some(HandshakeState::Complete { keys, peer_mic, .. }) => (keys, peer_mic),
+            other => {
+                let label = other.map(HandshakeState::label).unwrap_or("Idle");
+                return Err(StrandTrustError::InvalidStateTransition {
+                    from: label.into(),
+                    to: "resumption-token-issuance".into(),
+                });
+            }
+        };
+        let ticket_key = self.resumption_ticket_key.as_ref().ok_or_else(|| {
+            StrandTrustError::Handshake("no resumption ticket key configured".into())
+        })?;
+
+        let ticket = ResumptionTicket {
+            peer_node_id: node_id_from_mic(peer_mic),
+            master_secret: *keys.resumption_master_secret(),
+            mic_fingerprint: mic_fingerprint(peer_mic),
+            issued_at: now,
+        };
+        ticket_key.seal(&ticket)
+    }
+
+    /// Try to redeem a 0-RTT [`ResumptionAttempt`] riding along with a
+    /// [`HandshakeInit`]. Returns the decrypted early data on success, or
+    /// `None` for anything that keeps the attempt from being honored --
+    /// no ticket key configured, an expired or tampered token, a ticket
+    /// issued to a different identity than the one presenting it now, or
+    /// tampered early data. None of these fail the handshake itself: the
+    /// fresh DH exchange still completes a normal 1-RTT handshake either way.
+    fn try_accept_resumption(
+        &self,
+        attempt: &ResumptionAttempt,
+        initiator_mic: &MIC,
+        initiator_ephemeral_pub: &[u8; 32],
+        now: u64,
+    ) -> Option<Vec<u8>> {
+        let ticket_key = self.resumption_ticket_key.as_ref()?;
+        let ticket = ticket_key
+            .open(&attempt.token, now, self.resumption_ticket_lifetime_secs)
+            .ok()?;
+
+        if ticket.peer_node_id != node_id_from_mic(initiator_mic)
+            || ticket.mic_fingerprint != mic_fingerprint(initiator_mic)
+        {
+            return None;
+        }
+
+        let early_key = derive_early_data_key(&ticket.master_secret, initiator_ephemeral_pub).ok()?;
+        AeadKey::new(HANDSHAKE_SUITE, &early_key)
+            .ok()?
+            .decrypt(&EARLY_DATA_NONCE, &attempt.early_data, EARLY_DATA_AAD)
+            .ok()
+    }
+
+    /// Take `source_addr`'s session out of `self.sessions` for a method to
+    /// work on, reporting whether it's new (so the matching
+    /// [`Responder::insert_session`] call knows whether to add it to
+    /// `session_order`).
+    fn take_session(&mut self, source_addr: &[u8]) -> (ResponderSession, bool) {
+        match self.sessions.remove(source_addr) {
+            Some(session) => (session, false),
+            None => (ResponderSession::default(), true),
+        }
+    }
+
+    /// Put `session` back into `self.sessions`. If `is_new` (from the
+    /// matching [`Responder::take_session`] call), also records it in
+    /// `session_order` and evicts the oldest session if that pushes
+    /// `sessions` past [`MAX_RESPONDER_SESSIONS`].
+    fn insert_session(&mut self, source_addr: &[u8], session: ResponderSession, is_new: bool) {
+        self.sessions.insert(source_addr.to_vec(), session);
+        if is_new {
+            self.session_order.push_back(source_addr.to_vec());
+            while self.session_order.len() > MAX_RESPONDER_SESSIONS {
+                if let Some(oldest) = self.session_order.pop_front() {
+                    self.sessions.remove(&oldest);
+                }
+            }
+        }
+    }
+
+    /// Step 1 (responder side): Process TRUST_HELLO. Produces a TRUST_ACCEPT,
+    /// unless [`Responder::set_require_address_validation`] is enabled and
+    /// `init` doesn't carry a [`RetryToken`] that validates against
+    /// `source_addr` -- in which case a fresh token is returned instead and
+    /// no DH is performed (see [`ProcessInitOutcome::Retry`]).
+    ///
+    /// `source_addr` also keys which [`ResponderSession`] this init belongs
+    /// to (see `Responder::sessions`): a fresh `HandshakeInit` is accepted
+    /// not only from an address with no session yet, but also from one
+    /// whose prior session already reached `Complete` -- that's just the
+    /// peer reconnecting, plain or 0-RTT resumed -- while one stuck mid
+    /// handshake (`ResponseReceived`) is rejected as out of order.
+    pub fn process_init(
+        &mut self,
+        init: HandshakeInit,
+        source_addr: &[u8],
+        now: u64,
+    ) -> Result<ProcessInitOutcome> {
+        let (mut session, is_new) = self.take_session(source_addr);
+        let result = self.process_init_for_session(&mut session, init, source_addr, now);
+        self.insert_session(source_addr, session, is_new);
+        result
+    }
+
+    /// The actual body of [`Responder::process_init`], operating on a
+    /// session already taken out of `self.sessions` -- so it can freely call
+    /// other `&self`/`&mut self` helpers below without fighting the borrow
+    /// checker over `self.sessions` -- with the caller responsible for
+    /// putting `session` back no matter which branch returns.
+    fn process_init_for_session(
+        &mut self,
+        session: &mut ResponderSession,
+        init: HandshakeInit,
+        source_addr: &[u8],
+        now: u64,
+    ) -> Result<ProcessInitOutcome> {
+        if !matches!(
+            session.state,
+            HandshakeState::Idle | HandshakeState::AwaitingRetry | HandshakeState::Complete { .. }
+        ) {
+            return Err(StrandTrustError::InvalidStateTransition {
+                from: session.state.label().into(),
+                to: "ResponseSent".into(),
+            });
+        }
+
+        if self.require_address_validation {
+            let key = self
+                .retry_token_key
+                .get_or_insert_with(|| RetryTokenKey::new(now));
+            key.rotate_if_needed(now);
+
+            let validated = init.retry_token.as_ref().is_some_and(|token| {
+                key.validate_token(source_addr, token, now, self.retry_token_lifetime_secs)
+                    .is_ok()
+            });
+            if !validated {
+                let token = key.seal_token(source_addr, now)?;
+                session.state = HandshakeState::AwaitingRetry;
+                return Ok(ProcessInitOutcome::Retry(token));
+            }
+        }
+
+        // Validate initiator's MIC
+        validate(&init.initiator_mic, now)?;
+
+        // Reject initiators outside the configured trust anchor, if any.
+        if let Some(trust) = &self.trust_store {
+            if !trust.is_trusted(&init.initiator_mic.node_id) {
+                return Err(StrandTrustError::UntrustedPeer);
+            }
+        }
+
+        // Decode the initiator's ephemeral public key out of its wire
+        // encoding immediately, before any other use -- everything
+        // downstream (DH, transcript hashing, 0-RTT early-data keying)
+        // operates on the raw point.
+        let initiator_ephemeral_pub = self.decode_peer_ephemeral(&init.ephemeral_pub);
+
+        // Generate ephemeral keypair
+        let (_secret_bytes, ephemeral) = self.generate_ephemeral();
+        let ephemeral_pub = ephemeral.public_key_bytes();
+        let wire_pub = self.encode_ephemeral(&ephemeral);
+
+        // Perform DH
+        let shared_secret = ephemeral.diffie_hellman(&initiator_ephemeral_pub);
+
+        let transcript = transcript_hash(
+            &initiator_ephemeral_pub,
+            &ephemeral_pub,
+            &init.initiator_mic,
+            &self.mic,
+        );
+        let keys = KeySchedule::derive(&shared_secret, &transcript, HANDSHAKE_SUITE)?;
+        let server_finished_mac = keys.server_finished_mac(&transcript);
+
+        session.pending_early_data = init.resumption.as_ref().and_then(|attempt| {
+            self.try_accept_resumption(attempt, &init.initiator_mic, &initiator_ephemeral_pub, now)
+        });
+
+        session.state = HandshakeState::ResponseReceived {
+            keys,
+            transcript_hash: transcript,
+            peer_mic: init.initiator_mic,
+        };
+
+        Ok(ProcessInitOutcome::Response(HandshakeResponse {
+            ephemeral_pub: wire_pub,
+            responder_mic: self.mic.clone(),
+            server_finished_mac,
+        }))
+    }
+
+    /// Step 3 (responder side): Process TRUST_FINISH to complete
+    /// `source_addr`'s handshake.
+    pub fn process_complete(&mut self, source_addr: &[u8], complete: HandshakeComplete) -> Result<()> {
+        let (mut session, is_new) = self.take_session(source_addr);
+        let (keys, transcript, peer_mic) = match std::mem::replace(&mut session.state, HandshakeState::Idle) {
+            HandshakeState::ResponseReceived {
+                keys,
+                transcript_hash,
+                peer_mic,
+            } => (keys, transcript_hash, peer_mic),
+            other => {
+                let from = other.label();
+                session.state = other;
+                self.insert_session(source_addr, session, is_new);
+                return Err(StrandTrustError::InvalidStateTransition {
+                    from: from.into(),
+                    to: "Complete".into(),
+                });
+            }
+        };
+
+        if keys
+            .verify_client_finished(&transcript, &complete.client_finished_mac)
+            .is_err()
+        {
+            self.insert_session(source_addr, session, is_new);
+            return Err(StrandTrustError::Handshake(
+                "invalid client finished MAC".into(),
+            ));
+        }
+
+        session.state = HandshakeState::Complete {
+            keys,
+            peer_mic,
+            my_mic: self.mic.clone(),
+        };
+        self.insert_session(source_addr, session, is_new);
+
+        Ok(())
+    }
+
+    /// Get `source_addr`'s completed handshake state (directional traffic
+    /// keys and peer MIC).
+    pub fn completed_state(&self, source_addr: &[u8]) -> Option<(&KeySchedule, &MIC)> {
+        match self.sessions.get(source_addr).map(|session| &session.state) {
+            Some(HandshakeState::Complete { keys, peer_mic, .. }) => Some((keys, peer_mic)),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mic::builder::MICBuilder;
+    use crate::mic::Capability;
+    use crate::transport::Obfs4Obfuscation;
+
+    fn make_identity_and_mic() -> (IdentityKeyPair, MIC) {
+        let kp = IdentityKeyPair::generate();
+        let mic = MICBuilder::new(&kp)
+            .model_hash([0xDD; 32])
+            .add_capability(Capability::TextGeneration)
+            .validity(1000, 9999999)
+            .build()
+            .unwrap();
+        (kp, mic)
+    }
+
+    /// Unwrap a [`ProcessInitOutcome`] that's expected to be a proceed-path
+    /// `Response`, panicking with the `Retry` token otherwise -- every test
+    /// below except the address-validation ones runs with
+    /// `require_address_validation` left at its default (off).
+    fn expect_response(outcome: ProcessInitOutcome) -> HandshakeResponse {
+        match outcome {
+            ProcessInitOutcome::Response(response) => response,
+            ProcessInitOutcome::Retry(_) => panic!("expected Response, got Retry"),
+        }
+    }
+
+    #[test]
+    fn full_handshake() {
+        let (client_kp, client_mic) = make_identity_and_mic();
+        let (server_kp, server_mic) = make_identity_and_mic();
+
+        let mut initiator = Initiator::new(client_kp, client_mic);
+        let mut responder = Responder::new(server_kp, server_mic);
+
+        let now = 5000u64;
+
+        // Step 1: client -> server
+        let init_msg = initiator.create_init().unwrap();
+
+        // Step 2: server processes init, returns response
+        let response_msg = expect_response(responder.process_init(init_msg, b"test-src", now).unwrap());
+
+        // Step 3: client processes response, returns complete
+        let complete_msg = initiator.process_response(response_msg, now).unwrap();
+
+        // Step 4: server processes complete
+        responder.process_complete(b"test-src", complete_msg).unwrap();
+
+        // Both sides should be in Complete state
+        assert!(initiator.completed_state().is_some());
+        assert!(responder.completed_state(b"test-src").is_some());
+    }
+
+    #[test]
+    fn flooding_distinct_source_addresses_keeps_sessions_bounded() {
+        // source_addr is attacker-controlled (the UDP source address), so a
+        // flood of HandshakeInits from distinct spoofed addresses must not
+        // grow `sessions` -- and the live key-schedule state each entry
+        // holds -- without bound.
+        let (client_kp, client_mic) = make_identity_and_mic();
+        let (server_kp, server_mic) = make_identity_and_mic();
+
+        let mut initiator = Initiator::new(client_kp, client_mic);
+        let mut responder = Responder::new(server_kp, server_mic);
+        let now = 5000u64;
+        let init_msg = initiator.create_init().unwrap();
+
+        let flood = MAX_RESPONDER_SESSIONS + 500;
+        for i in 0..flood {
+            let addr = (i as u32).to_be_bytes();
+            let _ = responder.process_init(init_msg.clone(), &addr, now);
+        }
+
+        assert_eq!(responder.sessions.len(), MAX_RESPONDER_SESSIONS);
+        assert_eq!(responder.session_order.len(), MAX_RESPONDER_SESSIONS);
+
+        // The oldest addresses should have been evicted, the most recent
+        // ones retained.
+        assert!(!responder.sessions.contains_key(&0u32.to_be_bytes().to_vec()));
+        let last = ((flood - 1) as u32).to_be_bytes();
+        assert!(responder.sessions.contains_key(&last.to_vec()));
+    }
+
+    #[test]
+    fn directional_keys_match_between_peers() {
+        let (client_kp, client_mic) = make_identity_and_mic();
+        let (server_kp, server_mic) = make_identity_and_mic();
+
+        let mut initiator = Initiator::new(client_kp, client_mic);
+        let mut responder = Responder::new(server_kp, server_mic);
+
+        let now = 5000u64;
+        let init_msg = initiator.create_init().unwrap();
+        let response_msg = expect_response(responder.process_init(init_msg, b"test-src", now).unwrap());
+        let complete_msg = initiator.process_response(response_msg, now).unwrap();
+        responder.process_complete(b"test-src", complete_msg).unwrap();
+
+        let (client_keys, _) = initiator.completed_state().unwrap();
+        let (server_keys, _) = responder.completed_state(b"test-src").unwrap();
+
+        assert_eq!(client_keys.client_write_key, server_keys.client_write_key);
+        assert_eq!(client_keys.server_write_key, server_keys.server_write_key);
+        assert_eq!(client_keys.client_write_iv, server_keys.client_write_iv);
+        assert_eq!(client_keys.server_write_iv, server_keys.server_write_iv);
+        // The two directions never share key material.
+        assert_ne!(client_keys.client_write_key, client_keys.server_write_key);
+    }
+
+    #[test]
+    fn tampered_finished_mac_is_rejected() {
+        let (client_kp, client_mic) = make_identity_and_mic();
+        let (server_kp, server_mic) = make_identity_and_mic();
+
+        let mut initiator = Initiator::new(client_kp, client_mic);
+        let mut responder = Responder::new(server_kp, server_mic);
+
+        let now = 5000u64;
+        let init_msg = initiator.create_init().unwrap();
+        let mut response_msg = expect_response(responder.process_init(init_msg, b"test-src", now).unwrap());
+        response_msg.server_finished_mac[0] ^= 0xFF;
+
+        assert!(initiator.process_response(response_msg, now).is_err());
+    }
+
+    #[test]
+    fn responder_rejects_initiator_outside_trust_anchor() {
+        let (client_kp, client_mic) = make_identity_and_mic();
+        let (server_kp, server_mic) = make_identity_and_mic();
+        let (stranger_kp, _stranger_mic) = make_identity_and_mic();
+
+        let mut initiator = Initiator::new(client_kp, client_mic);
+        let trust = TrustStore::explicit([stranger_kp.public_key_bytes()]);
+        let mut responder = Responder::with_trust_store(server_kp, server_mic, trust);
+
+        let init_msg = initiator.create_init().unwrap();
+        let result = responder.process_init(init_msg, b"test-src", 5000u64);
+        assert!(matches!(result, Err(StrandTrustError::UntrustedPeer)));
+    }
+
+    #[test]
+    fn responder_accepts_initiator_inside_trust_anchor() {
+        let (client_kp, client_mic) = make_identity_and_mic();
+        let (server_kp, server_mic) = make_identity_and_mic();
+
+        let trust = TrustStore::explicit([client_kp.public_key_bytes()]);
+        let mut initiator = Initiator::new(client_kp, client_mic);
+        let mut responder = Responder::with_trust_store(server_kp, server_mic, trust);
+
+        let init_msg = initiator.create_init().unwrap();
+        assert!(responder.process_init(init_msg, b"test-src", 5000u64).is_ok());
+    }
+
+    #[test]
+    fn initiator_rejects_responder_outside_trust_anchor() {
+        let (client_kp, client_mic) = make_identity_and_mic();
+        let (server_kp, server_mic) = make_identity_and_mic();
+        let (stranger_kp, _stranger_mic) = make_identity_and_mic();
+
+        let trust = TrustStore::explicit([stranger_kp.public_key_bytes()]);
+        let mut initiator = Initiator::with_trust_store(client_kp, client_mic, trust);
+        let mut responder = Responder::new(server_kp, server_mic);
+
+        let now = 5000u64;
+        let init_msg = initiator.create_init().unwrap();
+        let response_msg = expect_response(responder.process_init(init_msg, b"test-src", now).unwrap());
+        let result = initiator.process_response(response_msg, now);
+        assert!(matches!(result, Err(StrandTrustError::UntrustedPeer)));
+    }
+
+    #[test]
+    fn shared_secret_trust_store_authenticates_both_peers() {
+        // Both peers derive the same identity keypair and trusted peer key
+        // from a shared passphrase, so either side can gate the handshake
+        // without ever exchanging identity keys out of band.
+        let client_trust = TrustStore::shared_secret(b"fleet passphrase").unwrap();
+        let server_trust = TrustStore::shared_secret(b"fleet passphrase").unwrap();
+        let client_identity = client_trust.identity().unwrap().clone();
+        let server_identity = server_trust.identity().unwrap().clone();
+
+        let client_mic = MICBuilder::new(&client_identity)
+            .model_hash([0xDD; 32])
+            .add_capability(Capability::TextGeneration)
+            .validity(1000, 9999999)
+            .build()
+            .unwrap();
+        let server_mic = MICBuilder::new(&server_identity)
+            .model_hash([0xDD; 32])
+            .add_capability(Capability::TextGeneration)
+            .validity(1000, 9999999)
+            .build()
+            .unwrap();
+
+        let mut initiator = Initiator::with_trust_store(client_identity, client_mic, client_trust);
+        let mut responder = Responder::with_trust_store(server_identity, server_mic, server_trust);
+
+        let now = 5000u64;
+        let init_msg = initiator.create_init().unwrap();
+        let response_msg = expect_response(responder.process_init(init_msg, b"test-src", now).unwrap());
+        let complete_msg = initiator.process_response(response_msg, now).unwrap();
+        responder.process_complete(b"test-src", complete_msg).unwrap();
+
+        assert!(initiator.completed_state().is_some());
+        assert!(responder.completed_state(b"test-src").is_some());
+    }
+
+    fn complete_handshake(initiator: &mut Initiator, responder: &mut Responder, now: u64) {
+        let init_msg = initiator.create_init().unwrap();
+        let response_msg = expect_response(responder.process_init(init_msg, b"test-src", now).unwrap());
+        let complete_msg = initiator.process_response(response_msg, now).unwrap();
+        responder.process_complete(b"test-src", complete_msg).unwrap();
+    }
+
+    #[test]
+    fn resumption_delivers_early_data_and_rekeys_forward_securely() {
+        let (client_kp, client_mic) = make_identity_and_mic();
+        let (server_kp, server_mic) = make_identity_and_mic();
+
+        let mut initiator = Initiator::new(client_kp.clone(), client_mic.clone());
+        let mut responder = Responder::new(server_kp, server_mic);
+        responder.set_resumption_ticket_key(ResumptionTicketKey::generate().unwrap());
+
+        let now = 5000u64;
+        complete_handshake(&mut initiator, &mut responder, now);
+
+        let token = responder.issue_resumption_token(b"test-src", now).unwrap();
+        let master_secret = initiator.resumption_master_secret().unwrap();
+        let (original_client_keys, _) = initiator.completed_state().unwrap();
+        let original_client_write_key = original_client_keys.client_write_key.clone();
+
+        // Reconnect with a fresh `Initiator` carrying the same identity/MIC
+        // the ticket was issued to.
+        let mut resuming_initiator = Initiator::new(client_kp, client_mic);
+        let now2 = now + 10;
+        let early_data = b"idempotent warm-start payload".to_vec();
+        let init_msg = resuming_initiator
+            .create_init_resuming(token, master_secret, &early_data, true)
+            .unwrap();
+        assert!(init_msg.resumption.is_some());
+
+        let response_msg = expect_response(responder.process_init(init_msg, b"test-src", now2).unwrap());
+        assert_eq!(responder.take_early_data(b"test-src"), Some(early_data));
+
+        let complete_msg = resuming_initiator
+            .process_response(response_msg, now2)
+            .unwrap();
+        responder.process_complete(b"test-src", complete_msg).unwrap();
+
+        let (resumed_client_keys, _) = resuming_initiator.completed_state().unwrap();
+        // The reconnect's traffic keys come from a brand-new DH exchange,
+        // not the resumed master secret, so they differ from the original.
+        assert_ne!(resumed_client_keys.client_write_key, original_client_write_key);
+    }
+
+    #[test]
+    fn create_init_resuming_rejects_non_idempotent_callers() {
+        let (client_kp, client_mic) = make_identity_and_mic();
+        let mut initiator = Initiator::new(client_kp, client_mic);
+        let token = ResumptionTicketKey::generate()
+            .unwrap()
+            .seal(&ResumptionTicket {
+                peer_node_id: [0u8; 16],
+                master_secret: [0u8; 32],
+                mic_fingerprint: [0u8; 32],
+                issued_at: 0,
+            })
+            .unwrap();
+
+        let result = initiator.create_init_resuming(token, [0u8; 32], b"data", false);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn expired_resumption_token_falls_back_to_plain_handshake() {
+        let (client_kp, client_mic) = make_identity_and_mic();
+        let (server_kp, server_mic) = make_identity_and_mic();
+
+        let mut initiator = Initiator::new(client_kp.clone(), client_mic.clone());
+        let mut responder = Responder::new(server_kp, server_mic);
+        responder.set_resumption_ticket_key(ResumptionTicketKey::generate().unwrap());
+        responder.set_resumption_ticket_lifetime_secs(10);
+
+        let now = 5000u64;
+        complete_handshake(&mut initiator, &mut responder, now);
+        let token = responder.issue_resumption_token(b"test-src", now).unwrap();
+        let master_secret = initiator.resumption_master_secret().unwrap();
+
+        let mut resuming_initiator = Initiator::new(client_kp, client_mic);
+        let far_future = now + 1000;
+        let init_msg = resuming_initiator
+            .create_init_resuming(token, master_secret, b"data", true)
+            .unwrap();
+
+        let response_msg = expect_response(responder.process_init(init_msg, b"test-src", far_future).unwrap());
+        // The token is expired by `far_future`, so the attempt is silently
+        // ignored -- the handshake itself still completes normally.
+        assert_eq!(responder.take_early_data(b"test-src"), None);
+        let complete_msg = resuming_initiator
+            .process_response(response_msg, far_future)
+            .unwrap();
+        responder.process_complete(b"test-src", complete_msg).unwrap();
+        assert!(resuming_initiator.completed_state().is_some());
+    }
+
+    #[test]
+    fn address_validation_challenges_then_accepts_retry() {
+        let (client_kp, client_mic) = make_identity_and_mic();
+        let (server_kp, server_mic) = make_identity_and_mic();
+
+        let mut initiator = Initiator::new(client_kp, client_mic);
+        let mut responder = Responder::new(server_kp, server_mic);
+        responder.set_require_address_validation(true);
+
+        let now = 5000u64;
+        let init_msg = initiator.create_init().unwrap();
+        let token = match responder.process_init(init_msg, b"198.51.100.7:4433", now).unwrap() {
+            ProcessInitOutcome::Retry(token) => token,
+            other => panic!("expected Retry, got {other:?}"),
+        };
+        assert!(matches!(responder.completed_state(b"198.51.100.7:4433"), None));
+
+        let retry_init = initiator.create_init_with_retry_token(token).unwrap();
+        let response_msg =
+            expect_response(responder.process_init(retry_init, b"198.51.100.7:4433", now).unwrap());
+        let complete_msg = initiator.process_response(response_msg, now).unwrap();
+        responder
+            .process_complete(b"198.51.100.7:4433", complete_msg)
+            .unwrap();
+
+        assert!(initiator.completed_state().is_some());
+        assert!(responder.completed_state(b"198.51.100.7:4433").is_some());
+    }
+
+    #[test]
+    fn retry_token_from_a_different_address_is_rejected() {
+        let (client_kp, client_mic) = make_identity_and_mic();
+        let (server_kp, server_mic) = make_identity_and_mic();
+
+        let mut initiator = Initiator::new(client_kp, client_mic);
+        let mut responder = Responder::new(server_kp, server_mic);
+        responder.set_require_address_validation(true);
+
+        let now = 5000u64;
+        let init_msg = initiator.create_init().unwrap();
+        let token = match responder.process_init(init_msg, b"198.51.100.7:4433", now).unwrap() {
+            ProcessInitOutcome::Retry(token) => token,
+            other => panic!("expected Retry, got {other:?}"),
+        };
+
+        let retry_init = initiator.create_init_with_retry_token(token).unwrap();
+        // A spoofed-source retry from a different address doesn't validate,
+        // so the responder challenges again rather than running the DH.
+        match responder.process_init(retry_init, b"203.0.113.9:4433", now).unwrap() {
+            ProcessInitOutcome::Retry(_) => {}
+            other => panic!("expected Retry, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn full_handshake_with_obfuscated_ephemeral_keys() {
+        let (client_kp, client_mic) = make_identity_and_mic();
+        let (server_kp, server_mic) = make_identity_and_mic();
+
+        let mut initiator = Initiator::new(client_kp, client_mic);
+        let mut responder = Responder::new(server_kp, server_mic);
+        initiator.set_obfuscator(Some(Box::new(Obfs4Obfuscation::with_default_padding(
+            [0x77; 32],
+        ))));
+        responder.set_obfuscator(Some(Box::new(Obfs4Obfuscation::with_default_padding(
+            [0x77; 32],
+        ))));
+
+        let now = 5000u64;
+        let init_msg = initiator.create_init().unwrap();
+
+        let response_msg = expect_response(responder.process_init(init_msg, b"test-src", now).unwrap());
+        let complete_msg = initiator.process_response(response_msg, now).unwrap();
+        responder.process_complete(b"test-src", complete_msg).unwrap();
+
+        assert!(initiator.completed_state().is_some());
+        assert!(responder.completed_state(b"test-src").is_some());
+
+        let (client_keys, _) = initiator.completed_state().unwrap();
+        let (server_keys, _) = responder.completed_state(b"test-src").unwrap();
+        assert_eq!(client_keys.client_write_key, server_keys.client_write_key);
+    }
+
+    #[test]
+    fn expired_retry_token_is_challenged_again() {
+        let (client_kp, client_mic) = make_identity_and_mic();
+        let (server_kp, server_mic) = make_identity_and_mic();
+
+        let mut initiator = Initiator::new(client_kp, client_mic);
+        let mut responder = Responder::new(server_kp, server_mic);
+        responder.set_require_address_validation(true);
+        responder.set_retry_token_lifetime_secs(10);
+
+        let now = 5000u64;
+        let init_msg = initiator.create_init().unwrap();
+        let token = match responder.process_init(init_msg, b"198.51.100.7:4433", now).unwrap() {
+            ProcessInitOutcome::Retry(token) => token,
+            other => panic!("expected Retry, got {other:?}"),
+        };
+
+        let retry_init = initiator.create_init_with_retry_token(token).unwrap();
+        let far_future = now + 1000;
+        match responder
+            .process_init(retry_init, b"198.51.100.7:4433", far_future)
+            .unwrap()
+        {
+            ProcessInitOutcome::Retry(_) => {}
+            other => panic!("expected Retry, got {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,407 @@
+// WireGuard-style cookie-based rate limiting for handshake initiation.
+//
+// `Responder::process_init` (see `crate::handshake::protocol`) runs a full
+// X25519 DH and HKDF key derivation for every `HandshakeInit` it sees, with
+// no cost to the sender -- trivial to flood. `RateLimiter` gates that
+// expensive work behind two cheap checks:
+//
+// - A token bucket keyed by source identifier (e.g. a hash of the UDP
+//   source address) bounds how fast any single peer can even attempt
+//   handshakes; once exhausted, further attempts from that source are
+//   dropped outright.
+// - A second, global token bucket models "is the responder under load
+//   right now". While it has capacity, initiations are processed directly;
+//   once it's exhausted, the responder skips the expensive DH and instead
+//   challenges the initiator with `cookie = HMAC(secret, source_id)`. A
+//   legitimate initiator retries with `mac2 = HMAC(cookie, message)`
+//   attached, which the responder can verify for the mere cost of an HMAC
+//   before doing any key agreement. The secret rotates every
+//   `COOKIE_SECRET_ROTATION` so a captured cookie stops working shortly
+//   after.
+//
+// Modeled on WireGuard's cookie mechanism (WireGuard whitepaper section
+// 5.4.4): the cost asymmetry of an HMAC versus a Diffie-Hellman is exactly
+// what makes flooding uneconomical. The only per-initiator state kept before
+// an initiator has proven itself is its source's token bucket, created on
+// its first `admit`.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// How often the responder's cookie secret rotates. A cookie minted just
+/// before a rotation remains verifiable for up to two intervals (the
+/// current and previous secret are both checked), which WireGuard also
+/// accepts in exchange for not tracking exactly when each cookie was
+/// issued.
+pub const COOKIE_SECRET_ROTATION: Duration = Duration::from_secs(120);
+
+/// What the responder should do with an incoming `HandshakeInit`, as
+/// decided by [`RateLimiter::admit`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HandshakeDecision {
+    /// Proceed with the full (expensive) handshake processing.
+    Proceed,
+    /// Load is high enough that this source must first prove it can
+    /// receive a reply by attaching `mac2` on retry.
+    Challenge { cookie: [u8; 16] },
+    /// Drop the initiation outright: the source exceeded its token bucket,
+    /// or the `mac2` it already attached didn't verify.
+    Drop,
+}
+
+/// A token bucket refilled proportionally to elapsed time, keyed
+/// externally (per-source buckets live in a map; the global bucket is a
+/// single instance).
+#[derive(Debug, Clone, Copy)]
+struct TokenBucket {
+    tokens: f64,
+    last_refill: u64,
+}
+
+impl TokenBucket {
+    fn new(capacity: u32, now: u64) -> Self {
+        Self {
+            tokens: capacity as f64,
+            last_refill: now,
+        }
+    }
+
+    /// Refill for the elapsed time since the last call, then try to take
+    /// one token. Returns whether a token was available.
+    fn try_take(&mut self, capacity: u32, refill_per_sec: f64, now: u64) -> bool {
+        let elapsed = now.saturating_sub(self.last_refill) as f64;
+        self.tokens = (self.tokens + elapsed * refill_per_sec).min(capacity as f64);
+        self.last_refill = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Configuration for [`RateLimiter`]'s two token buckets.
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimiterPolicy {
+    /// Burst of handshake attempts a single source can make before later
+    /// ones are dropped.
+    pub source_bucket_capacity: u32,
+    /// Steady-state attempts per second a single source is allowed.
+    pub source_refill_per_sec: f64,
+    /// Burst of handshakes the responder processes directly before it
+    /// considers itself "under load" and starts issuing cookie challenges.
+    pub global_bucket_capacity: u32,
+    /// Steady-state handshakes per second the responder processes directly.
+    pub global_refill_per_sec: f64,
+}
+
+impl RateLimiterPolicy {
+    pub const DEFAULT_SOURCE_CAPACITY: u32 = 5;
+    pub const DEFAULT_SOURCE_REFILL_PER_SEC: f64 = 1.0;
+    pub const DEFAULT_GLOBAL_CAPACITY: u32 = 1000;
+    pub const DEFAULT_GLOBAL_REFILL_PER_SEC: f64 = 200.0;
+
+    /// Build a policy with explicit thresholds.
+    pub fn new(
+        source_bucket_capacity: u32,
+        source_refill_per_sec: f64,
+        global_bucket_capacity: u32,
+        global_refill_per_sec: f64,
+    ) -> Self {
+        Self {
+            source_bucket_capacity,
+            source_refill_per_sec,
+            global_bucket_capacity,
+            global_refill_per_sec,
+        }
+    }
+}
+
+impl Default for RateLimiterPolicy {
+    fn default() -> Self {
+        Self::new(
+            Self::DEFAULT_SOURCE_CAPACITY,
+            Self::DEFAULT_SOURCE_REFILL_PER_SEC,
+            Self::DEFAULT_GLOBAL_CAPACITY,
+            Self::DEFAULT_GLOBAL_REFILL_PER_SEC,
+        )
+    }
+}
+
+/// Cookie-based handshake admission control for a `Responder`.
+///
+/// Takes an explicit `now` (unix seconds) on every call rather than reading
+/// the system clock, matching the rest of the handshake layer (e.g.
+/// `validate(mic, now)`), so tests can drive time deterministically.
+pub struct RateLimiter {
+    policy: RateLimiterPolicy,
+    current_secret: [u8; 32],
+    previous_secret: Option<[u8; 32]>,
+    secret_set_at: u64,
+    global_bucket: TokenBucket,
+    source_buckets: HashMap<Vec<u8>, TokenBucket>,
+}
+
+impl RateLimiter {
+    /// Build a rate limiter with the default policy.
+    pub fn new(now: u64) -> Self {
+        Self::with_policy(RateLimiterPolicy::default(), now)
+    }
+
+    /// Build a rate limiter with an explicit policy.
+    pub fn with_policy(policy: RateLimiterPolicy, now: u64) -> Self {
+        Self {
+            policy,
+            current_secret: random_secret(),
+            previous_secret: None,
+            secret_set_at: now,
+            global_bucket: TokenBucket::new(policy.global_bucket_capacity, now),
+            source_buckets: HashMap::new(),
+        }
+    }
+
+    /// Rotate the cookie secret if `COOKIE_SECRET_ROTATION` has elapsed
+    /// since the last rotation, retaining the superseded secret as
+    /// `previous_secret` so cookies minted just before the rotation still
+    /// verify.
+    fn rotate_if_needed(&mut self, now: u64) {
+        if now.saturating_sub(self.secret_set_at) >= COOKIE_SECRET_ROTATION.as_secs() {
+            self.previous_secret = Some(self.current_secret);
+            self.current_secret = random_secret();
+            self.secret_set_at = now;
+        }
+    }
+
+    fn cookie_for(secret: &[u8; 32], source_id: &[u8]) -> [u8; 16] {
+        let mut mac = HmacSha256::new_from_slice(secret).expect("HMAC accepts any key length");
+        mac.update(source_id);
+        truncate(&mac.finalize().into_bytes())
+    }
+
+    fn mac2_for(cookie: &[u8; 16], message: &[u8]) -> [u8; 16] {
+        let mut mac = HmacSha256::new_from_slice(cookie).expect("HMAC accepts any key length");
+        mac.update(message);
+        truncate(&mac.finalize().into_bytes())
+    }
+
+    /// Decide what to do with a `HandshakeInit` from `source_id`.
+    ///
+    /// `message` is the bytes a retry's `mac2` (if any) was computed over;
+    /// `mac2` is `Some` when the initiator is retrying in response to a
+    /// prior [`HandshakeDecision::Challenge`] (see
+    /// [`RateLimiter::prove_cookie`] to compute it).
+    pub fn admit(
+        &mut self,
+        source_id: &[u8],
+        message: &[u8],
+        mac2: Option<&[u8; 16]>,
+        now: u64,
+    ) -> HandshakeDecision {
+        self.rotate_if_needed(now);
+
+        let source_bucket = self
+            .source_buckets
+            .entry(source_id.to_vec())
+            .or_insert_with(|| TokenBucket::new(self.policy.source_bucket_capacity, now));
+        if !source_bucket.try_take(
+            self.policy.source_bucket_capacity,
+            self.policy.source_refill_per_sec,
+            now,
+        ) {
+            return HandshakeDecision::Drop;
+        }
+
+        if let Some(mac2) = mac2 {
+            let proven = [Some(self.current_secret), self.previous_secret]
+                .into_iter()
+                .flatten()
+                .any(|secret| Self::mac2_for(&Self::cookie_for(&secret, source_id), message) == *mac2);
+            return if proven {
+                HandshakeDecision::Proceed
+            } else {
+                HandshakeDecision::Drop
+            };
+        }
+
+        if !self.global_bucket.try_take(
+            self.policy.global_bucket_capacity,
+            self.policy.global_refill_per_sec,
+            now,
+        ) {
+            return HandshakeDecision::Challenge {
+                cookie: Self::cookie_for(&self.current_secret, source_id),
+            };
+        }
+
+        HandshakeDecision::Proceed
+    }
+
+    /// Compute the `mac2` an initiator should attach when retrying after a
+    /// [`HandshakeDecision::Challenge`].
+    pub fn prove_cookie(cookie: &[u8; 16], message: &[u8]) -> [u8; 16] {
+        Self::mac2_for(cookie, message)
+    }
+}
+
+fn random_secret() -> [u8; 32] {
+    let mut secret = [0u8; 32];
+    rand::rngs::OsRng.fill_bytes(&mut secret);
+    secret
+}
+
+fn truncate(tag: &[u8]) -> [u8; 16] {
+    let mut out = [0u8; 16];
+    out.copy_from_slice(&tag[..16]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn light_policy() -> RateLimiterPolicy {
+        RateLimiterPolicy::new(3, 1.0, 2, 1.0)
+    }
+
+    #[test]
+    fn proceeds_while_under_both_budgets() {
+        let mut rl = RateLimiter::with_policy(light_policy(), 0);
+        assert_eq!(rl.admit(b"src-a", b"msg", None, 0), HandshakeDecision::Proceed);
+    }
+
+    #[test]
+    fn source_bucket_exhaustion_drops() {
+        let mut rl = RateLimiter::with_policy(light_policy(), 0);
+        // Burst past the source's capacity within the same instant.
+        for _ in 0..3 {
+            rl.admit(b"src-a", b"msg", None, 0);
+        }
+        assert_eq!(rl.admit(b"src-a", b"msg", None, 0), HandshakeDecision::Drop);
+    }
+
+    #[test]
+    fn one_source_flooding_does_not_affect_another() {
+        // Unlike `light_policy()`, the global bucket here is sized well
+        // above what a single source's own bucket could ever legitimately
+        // drain, so one flooding source exhausts only its own per-source
+        // budget rather than also starving the shared global one -- which
+        // is the only way an unrelated second source can still get through.
+        let isolating_policy = RateLimiterPolicy::new(3, 1.0, 10, 1.0);
+        let mut rl = RateLimiter::with_policy(isolating_policy, 0);
+        for _ in 0..3 {
+            rl.admit(b"flooder", b"msg", None, 0);
+        }
+        assert_eq!(rl.admit(b"flooder", b"msg", None, 0), HandshakeDecision::Drop);
+        assert_eq!(
+            rl.admit(b"someone-else", b"msg", None, 0),
+            HandshakeDecision::Proceed
+        );
+    }
+
+    #[test]
+    fn global_budget_exhaustion_triggers_challenge() {
+        let mut rl = RateLimiter::with_policy(light_policy(), 0);
+        // Each call is from a distinct source so only the global bucket,
+        // not any one source bucket, is what gets exhausted.
+        assert_eq!(rl.admit(b"s0", b"msg", None, 0), HandshakeDecision::Proceed);
+        assert_eq!(rl.admit(b"s1", b"msg", None, 0), HandshakeDecision::Proceed);
+        match rl.admit(b"s2", b"msg", None, 0) {
+            HandshakeDecision::Challenge { .. } => {}
+            other => panic!("expected Challenge, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn valid_mac2_proceeds_even_under_load() {
+        let mut rl = RateLimiter::with_policy(light_policy(), 0);
+        rl.admit(b"s0", b"msg", None, 0);
+        rl.admit(b"s1", b"msg", None, 0);
+        let cookie = match rl.admit(b"s2", b"retry-msg", None, 0) {
+            HandshakeDecision::Challenge { cookie } => cookie,
+            other => panic!("expected Challenge, got {other:?}"),
+        };
+        let mac2 = RateLimiter::prove_cookie(&cookie, b"retry-msg");
+        assert_eq!(
+            rl.admit(b"s2", b"retry-msg", Some(&mac2), 0),
+            HandshakeDecision::Proceed
+        );
+    }
+
+    #[test]
+    fn forged_mac2_is_dropped() {
+        let mut rl = RateLimiter::with_policy(light_policy(), 0);
+        let bogus = [0xAAu8; 16];
+        assert_eq!(
+            rl.admit(b"s0", b"msg", Some(&bogus), 0),
+            HandshakeDecision::Drop
+        );
+    }
+
+    #[test]
+    fn cookie_from_wrong_source_does_not_verify() {
+        let mut rl = RateLimiter::with_policy(light_policy(), 0);
+        rl.admit(b"s0", b"msg", None, 0);
+        rl.admit(b"s1", b"msg", None, 0);
+        let cookie = match rl.admit(b"s2", b"retry-msg", None, 0) {
+            HandshakeDecision::Challenge { cookie } => cookie,
+            other => panic!("expected Challenge, got {other:?}"),
+        };
+        let mac2 = RateLimiter::prove_cookie(&cookie, b"retry-msg");
+        // A different source replaying the same cookie/mac2 pair fails,
+        // since the cookie is bound to the source identifier.
+        assert_eq!(
+            rl.admit(b"someone-else", b"retry-msg", Some(&mac2), 0),
+            HandshakeDecision::Drop
+        );
+    }
+
+    #[test]
+    fn secret_rotation_still_accepts_cookie_from_previous_epoch() {
+        let mut rl = RateLimiter::with_policy(light_policy(), 0);
+        rl.admit(b"s0", b"msg", None, 0);
+        rl.admit(b"s1", b"msg", None, 0);
+        let cookie = match rl.admit(b"s2", b"retry-msg", None, 0) {
+            HandshakeDecision::Challenge { cookie } => cookie,
+            other => panic!("expected Challenge, got {other:?}"),
+        };
+
+        // Rotate the secret by advancing past one rotation interval.
+        let later = COOKIE_SECRET_ROTATION.as_secs() + 1;
+        let mac2 = RateLimiter::prove_cookie(&cookie, b"retry-msg");
+        assert_eq!(
+            rl.admit(b"s2", b"retry-msg", Some(&mac2), later),
+            HandshakeDecision::Proceed
+        );
+    }
+
+    #[test]
+    fn cookie_from_two_rotations_ago_is_rejected() {
+        let mut rl = RateLimiter::with_policy(light_policy(), 0);
+        rl.admit(b"s0", b"msg", None, 0);
+        rl.admit(b"s1", b"msg", None, 0);
+        let cookie = match rl.admit(b"s2", b"retry-msg", None, 0) {
+            HandshakeDecision::Challenge { cookie } => cookie,
+            other => panic!("expected Challenge, got {other:?}"),
+        };
+        let mac2 = RateLimiter::prove_cookie(&cookie, b"retry-msg");
+
+        let one_rotation = COOKIE_SECRET_ROTATION.as_secs() + 1;
+        let two_rotations = 2 * COOKIE_SECRET_ROTATION.as_secs() + 2;
+        // Force a first rotation (any source's call advances the shared
+        // rotation clock), leaving the original cookie's secret one slot
+        // away from falling out of the tracked (current, previous) pair...
+        rl.admit(b"filler", b"msg", None, one_rotation);
+        // ...then a second rotation pushes it out entirely.
+        assert_eq!(
+            rl.admit(b"s2", b"retry-msg", Some(&mac2), two_rotations),
+            HandshakeDecision::Drop
+        );
+    }
+}
@@ -1,5 +1,6 @@
 // Handshake state machine.
 
+use crate::crypto::key_schedule::KeySchedule;
 use crate::mic::MIC;
 
 /// The current state of a StrandTrust handshake.
@@ -16,26 +17,52 @@ pub enum HandshakeState {
         ephemeral_public: [u8; 32],
     },
 
-    /// Initiator has received TRUST_ACCEPT with session key and peer MIC.
+    /// Initiator has sent a TRUST_HELLO carrying a 0-RTT resumption attempt
+    /// (see [`crate::handshake::protocol::Initiator::create_init_resuming`]),
+    /// waiting for TRUST_ACCEPT. Distinct from `InitSent` only in that it
+    /// also remembers the resumed master secret; `process_response` never
+    /// needs it again once the fresh DH exchange derives a new, forward-secure
+    /// key schedule over the completed transcript -- 0-RTT only fills the
+    /// wait before that exchange finishes, it never replaces it.
+    Resuming {
+        /// The initiator's ephemeral X25519 secret bytes (kept for DH).
+        ephemeral_secret: [u8; 32],
+        /// The initiator's ephemeral X25519 public bytes (sent in TRUST_HELLO).
+        ephemeral_public: [u8; 32],
+        /// `KeySchedule::resumption_master_secret()` from the session being resumed.
+        resumed_master_secret: [u8; 32],
+    },
+
+    /// Responder has challenged a TRUST_HELLO with a `RetryToken` instead of
+    /// processing it, waiting for the initiator to retry with the token
+    /// echoed back (see
+    /// [`crate::handshake::protocol::Responder::set_require_address_validation`]).
+    /// Carries no per-source state of its own -- the token itself is what
+    /// lets the retried TRUST_HELLO be validated -- so a responder in this
+    /// state is exactly as stateless as one in `Idle`; the variant exists so
+    /// callers can tell a challenge was issued from a handshake that never
+    /// started.
+    AwaitingRetry,
+
+    /// Responder has sent TRUST_ACCEPT, waiting for TRUST_FINISH.
     ResponseReceived {
-        /// Derived session key (client_write_key).
-        session_key: [u8; 32],
-        /// The responder's MIC.
+        /// Directional traffic keys/IVs derived from the transcript so far.
+        keys: KeySchedule,
+        /// SHA-256 transcript hash covering TRUST_HELLO and TRUST_ACCEPT.
+        transcript_hash: [u8; 32],
+        /// The initiator's MIC.
         peer_mic: MIC,
-        /// Server's write key (for decrypting server messages).
-        server_write_key: [u8; 32],
     },
 
     /// Handshake is complete â€” both sides authenticated.
     Complete {
-        /// Symmetric session key for encrypting outbound data.
-        session_key: [u8; 32],
+        /// Directional traffic keys/IVs, ready to hand to a
+        /// [`crate::crypto::record::RecordLayer`] pair.
+        keys: KeySchedule,
         /// The peer's MIC.
         peer_mic: MIC,
         /// Our own MIC (sent to the peer).
         my_mic: MIC,
-        /// Server's write key.
-        server_write_key: [u8; 32],
     },
 }
 
@@ -45,6 +72,8 @@ impl HandshakeState {
         match self {
             HandshakeState::Idle => "Idle",
             HandshakeState::InitSent { .. } => "InitSent",
+            HandshakeState::Resuming { .. } => "Resuming",
+            HandshakeState::AwaitingRetry => "AwaitingRetry",
             HandshakeState::ResponseReceived { .. } => "ResponseReceived",
             HandshakeState::Complete { .. } => "Complete",
         }
@@ -1,12 +1,12 @@
 // Integration tests for the NexTrust 3-way handshake protocol.
 
-use nextrust::crypto::keys::IdentityKeyPair;
-use nextrust::handshake::protocol::{Initiator, Responder};
-use nextrust::mic::builder::MICBuilder;
-use nextrust::mic::Capability;
+use strandtrust::crypto::keys::IdentityKeyPair;
+use strandtrust::handshake::protocol::{Initiator, Responder};
+use strandtrust::mic::builder::MICBuilder;
+use strandtrust::mic::Capability;
 
 /// Helper: create an identity keypair and a self-signed MIC for testing.
-fn make_identity_and_mic(caps: Vec<Capability>) -> (IdentityKeyPair, nextrust::mic::MIC) {
+fn make_identity_and_mic(caps: Vec<Capability>) -> (IdentityKeyPair, strandtrust::mic::MIC) {
     let kp = IdentityKeyPair::generate();
     let mut builder = MICBuilder::new(&kp)
         .model_hash([0xEE; 32])
@@ -45,16 +45,18 @@ fn full_handshake_succeeds() {
     responder.process_complete(complete_msg).unwrap();
 
     // Both sides should now be in the Complete state.
-    let (client_session_key, peer_mic_from_client) = initiator.completed_state().unwrap();
-    let (server_session_key, peer_mic_from_server) = responder.completed_state().unwrap();
+    let (client_keys, peer_mic_from_client) = initiator.completed_state().unwrap();
+    let (server_keys, peer_mic_from_server) = responder.completed_state().unwrap();
 
     // The client's "peer MIC" should be the server's MIC and vice versa.
     assert_eq!(peer_mic_from_client.model_hash, [0xEE; 32]);
     assert_eq!(peer_mic_from_server.model_hash, [0xEE; 32]);
 
-    // Session keys should be non-zero.
-    assert_ne!(client_session_key, &[0u8; 32]);
-    assert_ne!(server_session_key, &[0u8; 32]);
+    // Both peers derived the same directional traffic keys, and neither is all-zero.
+    assert_eq!(client_keys.client_write_key, server_keys.client_write_key);
+    assert_eq!(client_keys.server_write_key, server_keys.server_write_key);
+    assert_ne!(client_keys.client_write_key, vec![0u8; client_keys.client_write_key.len()]);
+    assert_ne!(client_keys.server_write_key, vec![0u8; client_keys.server_write_key.len()]);
 }
 
 #[test]
@@ -131,10 +133,8 @@ fn tampered_response_payload_rejected() {
     let init_msg = initiator.create_init().unwrap();
     let mut response_msg = responder.process_init(init_msg, now).unwrap();
 
-    // Tamper with the encrypted payload
-    if !response_msg.encrypted_payload.is_empty() {
-        response_msg.encrypted_payload[0] ^= 0xFF;
-    }
+    // Tamper with the server's Finished MAC
+    response_msg.server_finished_mac[0] ^= 0xFF;
 
     // Client should reject the tampered response
     assert!(initiator.process_response(response_msg, now).is_err());
@@ -154,10 +154,8 @@ fn tampered_complete_payload_rejected() {
     let response_msg = responder.process_init(init_msg, now).unwrap();
     let mut complete_msg = initiator.process_response(response_msg, now).unwrap();
 
-    // Tamper with the client's finished payload
-    if !complete_msg.encrypted_payload.is_empty() {
-        complete_msg.encrypted_payload[0] ^= 0xFF;
-    }
+    // Tamper with the client's Finished MAC
+    complete_msg.client_finished_mac[0] ^= 0xFF;
 
     // Server should reject the tampered complete
     assert!(responder.process_complete(complete_msg).is_err());